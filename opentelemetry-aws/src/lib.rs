@@ -0,0 +1,61 @@
+//! # OpenTelemetry AWS
+//!
+//! This crate provides the [AWS X-Ray] integration of OpenTelemetry:
+//!
+//! * [`trace::XrayPropagator`] propagates span contexts in the
+//!   `X-Amzn-Trace-Id` header, as set by AWS load balancers and services.
+//! * [`trace::XrayIdGenerator`] generates trace ids which can be ingested by
+//!   X-Ray, starting with the epoch seconds of the start of the trace.
+//!
+//! ## Examples
+//! ```
+//! use opentelemetry::global;
+//! use opentelemetry_aws::trace::{XrayIdGenerator, XrayPropagator};
+//! use opentelemetry_sdk::trace::SdkTracerProvider;
+//!
+//! global::set_text_map_propagator(XrayPropagator::default());
+//!
+//! let provider = SdkTracerProvider::builder()
+//!     .with_id_generator(XrayIdGenerator::default())
+//!     .build();
+//! global::set_tracer_provider(provider);
+//! ```
+//!
+//! [AWS X-Ray]: https://docs.aws.amazon.com/xray/latest/devguide/aws-xray.html
+//!
+//! *[Supported Rust Versions](#supported-rust-versions)*
+//!
+//! # Supported Rust Versions
+//!
+//! OpenTelemetry is built against the latest stable release. The minimum
+//! supported version is 1.75.0. The current OpenTelemetry version is not
+//! guaranteed to build on Rust versions earlier than the minimum supported
+//! version.
+//!
+//! The current stable Rust compiler and the three most recent minor versions
+//! before it will always be supported. For example, if the current stable
+//! compiler version is 1.49, the minimum supported version will not be
+//! increased past 1.46, three minor versions prior. Increasing the minimum
+//! supported compiler version is not considered a semver breaking change as
+//! long as doing so complies with this policy.
+#![warn(
+    future_incompatible,
+    missing_debug_implementations,
+    missing_docs,
+    nonstandard_style,
+    rust_2018_idioms,
+    unreachable_pub,
+    unused
+)]
+#![cfg_attr(
+    docsrs,
+    feature(doc_cfg, doc_auto_cfg),
+    deny(rustdoc::broken_intra_doc_links)
+)]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/open-telemetry/opentelemetry-rust/main/assets/logo.svg"
+)]
+#![cfg_attr(test, deny(warnings))]
+
+#[cfg(feature = "trace")]
+pub mod trace;
@@ -0,0 +1,89 @@
+use opentelemetry::trace::{SpanId, TraceId};
+use opentelemetry_sdk::trace::{IdGenerator, RandomIdGenerator};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The random part of the trace id, its last 96 bits.
+const RANDOM_MASK: u128 = (1 << 96) - 1;
+
+/// Generates trace ids which can be ingested by AWS X-Ray.
+///
+/// X-Ray rejects the traces whose trace id does not start with the epoch
+/// seconds of the start of the trace, within the last 30 days. The first 32
+/// bits of the trace ids generated by this generator hold the current epoch
+/// seconds, the remaining 96 bits and the span ids are random.
+///
+/// ## Examples
+/// ```
+/// use opentelemetry_aws::trace::XrayIdGenerator;
+/// use opentelemetry_sdk::trace::SdkTracerProvider;
+///
+/// let provider = SdkTracerProvider::builder()
+///     .with_id_generator(XrayIdGenerator::default())
+///     .build();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct XrayIdGenerator {
+    random: RandomIdGenerator,
+}
+
+impl IdGenerator for XrayIdGenerator {
+    fn new_trace_id(&self) -> TraceId {
+        let random = u128::from_be_bytes(self.random.new_trace_id().to_bytes());
+        let epoch_seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default() as u32;
+        TraceId::from((u128::from(epoch_seconds) << 96) | (random & RANDOM_MASK))
+    }
+
+    fn new_span_id(&self) -> SpanId {
+        self.random.new_span_id()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn epoch_seconds() -> u128 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+            .into()
+    }
+
+    #[test]
+    fn trace_id_starts_with_epoch_seconds() {
+        let generator = XrayIdGenerator::default();
+
+        let before = epoch_seconds();
+        let trace_id = generator.new_trace_id();
+        let after = epoch_seconds();
+
+        let start = u128::from_be_bytes(trace_id.to_bytes()) >> 96;
+        assert!((before..=after).contains(&start));
+        assert_eq!(
+            trace_id.to_string()[..8],
+            format!("{:08x}", start),
+            "the first 8 hex digits are the epoch seconds"
+        );
+    }
+
+    #[test]
+    fn ids_are_random() {
+        let generator = XrayIdGenerator::default();
+
+        let first = generator.new_trace_id();
+        let second = generator.new_trace_id();
+        assert_ne!(first, TraceId::INVALID);
+        assert_ne!(
+            u128::from_be_bytes(first.to_bytes()) & RANDOM_MASK,
+            u128::from_be_bytes(second.to_bytes()) & RANDOM_MASK
+        );
+
+        let span_id = generator.new_span_id();
+        assert_ne!(span_id, SpanId::INVALID);
+        assert_ne!(span_id, generator.new_span_id());
+    }
+}
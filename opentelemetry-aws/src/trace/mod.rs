@@ -0,0 +1,6 @@
+//! AWS X-Ray propagation and trace ids.
+mod id_generator;
+mod xray_propagator;
+
+pub use id_generator::XrayIdGenerator;
+pub use xray_propagator::XrayPropagator;
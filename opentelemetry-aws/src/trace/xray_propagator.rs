@@ -0,0 +1,391 @@
+use opentelemetry::{
+    propagation::{text_map_propagator::FieldIter, Extractor, Injector, TextMapPropagator},
+    trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState},
+    Context,
+};
+use std::sync::OnceLock;
+
+const AWS_XRAY_TRACE_HEADER: &str = "x-amzn-trace-id";
+
+const HEADER_ROOT_KEY: &str = "Root";
+const HEADER_PARENT_KEY: &str = "Parent";
+const HEADER_SAMPLED_KEY: &str = "Sampled";
+const HEADER_LINEAGE_KEY: &str = "Lineage";
+
+const SAMPLED: &str = "1";
+const NOT_SAMPLED: &str = "0";
+const REQUESTED_SAMPLE_DECISION: &str = "?";
+
+const TRACE_ID_VERSION: &str = "1";
+
+/// Key of the trace state entry holding the `Lineage` field of the header.
+const LINEAGE_TRACE_STATE_KEY: &str = "xray-lineage";
+const MAX_LINEAGE_REQUEST_COUNTER: u16 = 32767;
+
+/// The sampling decision is left to the receiver, with `Sampled=?`.
+const TRACE_FLAG_DEFERRED: TraceFlags = TraceFlags::new(0x02);
+
+// TODO Replace this with LazyLock once it is stable.
+static AWS_XRAY_HEADER_FIELDS: OnceLock<[String; 1]> = OnceLock::new();
+
+fn aws_xray_header_fields() -> &'static [String; 1] {
+    AWS_XRAY_HEADER_FIELDS.get_or_init(|| [AWS_XRAY_TRACE_HEADER.to_owned()])
+}
+
+/// Extracts and injects `SpanContext`s into `Extractor`s or `Injector`s using
+/// the [AWS X-Ray] tracing header.
+///
+/// The `X-Amzn-Trace-Id` header is a `;` separated list of `key=value` fields.
+/// Here's an example of a `X-Amzn-Trace-Id` header.
+///
+/// `X-Amzn-Trace-Id: Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1`
+///
+/// - `Root` is the trace id, made of the version `1`, the epoch seconds of the
+///   start of the trace as 8 hex digits, and 24 random hex digits.
+/// - `Parent` is the span id of the caller.
+/// - `Sampled` is the sampling decision: `1` if sampled, `0` if not and `?` if
+///   the decision is left to the receiver.
+/// - `Lineage` counts the calls of the request through AWS services, to
+///   detect loops. It is kept in the trace state of the extracted span context
+///   under the `xray-lineage` key, and injected again from there.
+///
+/// The other fields, like the `Self` field added by load balancers, are
+/// ignored. Headers without a `Parent`, as sent by load balancers to the first
+/// traced service, do not identify a span and are ignored as well.
+///
+/// Trace ids which do not start with the epoch seconds of the start of the
+/// trace are rejected by X-Ray. Use [`XrayIdGenerator`] to generate them.
+///
+/// ## Examples
+/// ```
+/// use opentelemetry::{global, propagation::TextMapPropagator, trace::TraceContextExt};
+/// use opentelemetry_aws::trace::XrayPropagator;
+/// use std::collections::HashMap;
+///
+/// let propagator = XrayPropagator::default();
+///
+/// let mut headers = HashMap::new();
+/// headers.insert(
+///     "x-amzn-trace-id".to_string(),
+///     "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1".to_string(),
+/// );
+/// let cx = propagator.extract(&headers);
+/// assert!(cx.span().span_context().is_sampled());
+///
+/// // or install it globally
+/// global::set_text_map_propagator(propagator);
+/// ```
+///
+/// [AWS X-Ray]: https://docs.aws.amazon.com/xray/latest/devguide/xray-concepts.html#xray-concepts-tracingheader
+/// [`XrayIdGenerator`]: crate::trace::XrayIdGenerator
+#[derive(Clone, Debug, Default)]
+pub struct XrayPropagator {
+    _private: (),
+}
+
+impl XrayPropagator {
+    /// Create a new `XrayPropagator`.
+    pub fn new() -> Self {
+        XrayPropagator::default()
+    }
+
+    /// Extract trace id from the `Root` field, `1-{epoch seconds}-{random}`.
+    fn extract_trace_id(&self, root: &str) -> Result<TraceId, ()> {
+        let mut parts = root.split('-');
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(TRACE_ID_VERSION), Some(epoch), Some(random), None)
+                if epoch.len() == 8
+                    && random.len() == 24
+                    && is_lower_hex(epoch)
+                    && is_lower_hex(random) =>
+            {
+                TraceId::from_hex(&format!("{}{}", epoch, random)).map_err(|_| ())
+            }
+            _ => Err(()),
+        }
+    }
+
+    /// Extract span id from the hex encoded `Parent` field.
+    fn extract_span_id(&self, parent: &str) -> Result<SpanId, ()> {
+        // Only allow lower case hex string
+        if parent.len() != 16 || !is_lower_hex(parent) {
+            Err(())
+        } else {
+            SpanId::from_hex(parent).map_err(|_| ())
+        }
+    }
+
+    /// Extract sampled state from the `Sampled` field.
+    fn extract_sampled_state(&self, sampled: &str) -> Result<TraceFlags, ()> {
+        match sampled {
+            SAMPLED => Ok(TraceFlags::SAMPLED),
+            NOT_SAMPLED => Ok(TraceFlags::default()),
+            REQUESTED_SAMPLE_DECISION => Ok(TRACE_FLAG_DEFERRED),
+            _ => Err(()),
+        }
+    }
+
+    /// Extract a `SpanContext` from the `X-Amzn-Trace-Id` header.
+    fn extract_span_context(&self, extractor: &dyn Extractor) -> Result<SpanContext, ()> {
+        let header_value = extractor.get(AWS_XRAY_TRACE_HEADER).unwrap_or("");
+
+        let mut trace_id = TraceId::INVALID;
+        let mut span_id = SpanId::INVALID;
+        // A missing sampling decision is left to the receiver.
+        let mut trace_flags = TRACE_FLAG_DEFERRED;
+        let mut trace_state = TraceState::default();
+
+        for field in header_value.split(';').map(str::trim) {
+            if field.is_empty() {
+                continue;
+            }
+            let (key, value) = field.split_once('=').ok_or(())?;
+            match key.trim() {
+                HEADER_ROOT_KEY => trace_id = self.extract_trace_id(value.trim())?,
+                HEADER_PARENT_KEY => span_id = self.extract_span_id(value.trim())?,
+                HEADER_SAMPLED_KEY => trace_flags = self.extract_sampled_state(value.trim())?,
+                HEADER_LINEAGE_KEY => {
+                    // An invalid lineage does not invalidate the span context.
+                    let lineage = value.trim();
+                    if is_valid_lineage(lineage) {
+                        trace_state =
+                            TraceState::from_key_value([(LINEAGE_TRACE_STATE_KEY, lineage)])
+                                .map_err(|_| ())?;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let span_context = SpanContext::new(trace_id, span_id, trace_flags, true, trace_state);
+
+        // Ensure span is valid
+        if !span_context.is_valid() {
+            return Err(());
+        }
+
+        Ok(span_context)
+    }
+}
+
+/// Checks that a `Lineage` field is `{request counter}:{hash}:{loop counter}`,
+/// with a request counter up to 32767, a hash of 8 hex digits and a loop
+/// counter up to 255.
+fn is_valid_lineage(lineage: &str) -> bool {
+    let is_decimal = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    let mut parts = lineage.split(':');
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(request_counter), Some(hash), Some(loop_counter), None) => {
+            is_decimal(request_counter)
+                && request_counter
+                    .parse::<u16>()
+                    .is_ok_and(|counter| counter <= MAX_LINEAGE_REQUEST_COUNTER)
+                && hash.len() == 8
+                && is_lower_hex(hash)
+                && is_decimal(loop_counter)
+                && loop_counter.parse::<u8>().is_ok()
+        }
+        _ => false,
+    }
+}
+
+fn is_lower_hex(value: &str) -> bool {
+    value
+        .bytes()
+        .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+impl TextMapPropagator for XrayPropagator {
+    /// Properly encodes the values of the `Context`'s `SpanContext` and injects
+    /// them into the `Injector`.
+    fn inject_context(&self, context: &Context, injector: &mut dyn Injector) {
+        let span = context.span();
+        let span_context = span.span_context();
+        if !span_context.is_valid() {
+            return;
+        }
+
+        let trace_id = span_context.trace_id().to_string();
+        let sampled = if span_context.trace_flags() & TRACE_FLAG_DEFERRED == TRACE_FLAG_DEFERRED {
+            REQUESTED_SAMPLE_DECISION
+        } else if span_context.is_sampled() {
+            SAMPLED
+        } else {
+            NOT_SAMPLED
+        };
+
+        let mut value = format!(
+            "{}={}-{}-{};{}={};{}={}",
+            HEADER_ROOT_KEY,
+            TRACE_ID_VERSION,
+            &trace_id[..8],
+            &trace_id[8..],
+            HEADER_PARENT_KEY,
+            span_context.span_id(),
+            HEADER_SAMPLED_KEY,
+            sampled
+        );
+        if let Some(lineage) = span_context.trace_state().get(LINEAGE_TRACE_STATE_KEY) {
+            value.push_str(&format!(";{}={}", HEADER_LINEAGE_KEY, lineage));
+        }
+
+        injector.set(AWS_XRAY_TRACE_HEADER, value);
+    }
+
+    /// Retrieves encoded data using the provided `Extractor`. If no data for this
+    /// format was retrieved OR if the retrieved data is invalid, then the current
+    /// `Context` is returned.
+    fn extract_with_context(&self, cx: &Context, extractor: &dyn Extractor) -> Context {
+        self.extract_span_context(extractor)
+            .map(|span_context| cx.with_remote_span_context(span_context))
+            .unwrap_or_else(|_| cx.clone())
+    }
+
+    fn fields(&self) -> FieldIter<'_> {
+        FieldIter::new(aws_xray_header_fields())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::testing::trace::TestSpan;
+    use std::collections::HashMap;
+
+    const TRACE_ID_HEX: u128 = 0x5759_e988_bd86_2e3f_e1be_46a9_9427_2793;
+    const SPAN_ID_HEX: u64 = 0x5399_5c3f_42cd_8ad8;
+
+    fn lineage(value: &str) -> TraceState {
+        TraceState::from_key_value([(LINEAGE_TRACE_STATE_KEY, value)]).unwrap()
+    }
+
+    #[rustfmt::skip]
+    fn extract_data() -> Vec<(&'static str, SpanContext)> {
+        vec![
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1", SpanContext::new(TraceId::from_u128(TRACE_ID_HEX), SpanId::from_u64(SPAN_ID_HEX), TraceFlags::SAMPLED, true, TraceState::default())), // sampled
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=0", SpanContext::new(TraceId::from_u128(TRACE_ID_HEX), SpanId::from_u64(SPAN_ID_HEX), TraceFlags::default(), true, TraceState::default())), // not sampled
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=?", SpanContext::new(TraceId::from_u128(TRACE_ID_HEX), SpanId::from_u64(SPAN_ID_HEX), TRACE_FLAG_DEFERRED, true, TraceState::default())), // requested
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8", SpanContext::new(TraceId::from_u128(TRACE_ID_HEX), SpanId::from_u64(SPAN_ID_HEX), TRACE_FLAG_DEFERRED, true, TraceState::default())), // missing sampled state
+            ("Parent=53995c3f42cd8ad8; Sampled=1; Root=1-5759e988-bd862e3fe1be46a994272793;", SpanContext::new(TraceId::from_u128(TRACE_ID_HEX), SpanId::from_u64(SPAN_ID_HEX), TraceFlags::SAMPLED, true, TraceState::default())), // any order, whitespaces and trailing separator
+            ("Self=1-67891234-12456789abcdef012345678;Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1;CalledFrom=app", SpanContext::new(TraceId::from_u128(TRACE_ID_HEX), SpanId::from_u64(SPAN_ID_HEX), TraceFlags::SAMPLED, true, TraceState::default())), // unknown fields are ignored
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1;Lineage=32767:e65a2c4c:255", SpanContext::new(TraceId::from_u128(TRACE_ID_HEX), SpanId::from_u64(SPAN_ID_HEX), TraceFlags::SAMPLED, true, lineage("32767:e65a2c4c:255"))), // with lineage
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1;Lineage=1:e65a2c4c", SpanContext::new(TraceId::from_u128(TRACE_ID_HEX), SpanId::from_u64(SPAN_ID_HEX), TraceFlags::SAMPLED, true, TraceState::default())), // lineage without loop counter is ignored
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1;Lineage=32768:e65a2c4c:1", SpanContext::new(TraceId::from_u128(TRACE_ID_HEX), SpanId::from_u64(SPAN_ID_HEX), TraceFlags::SAMPLED, true, TraceState::default())), // request counter out of range
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1;Lineage=1:e65a2c4c:256", SpanContext::new(TraceId::from_u128(TRACE_ID_HEX), SpanId::from_u64(SPAN_ID_HEX), TraceFlags::SAMPLED, true, TraceState::default())), // loop counter out of range
+            ("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1;Lineage=1:e65a2c4:1", SpanContext::new(TraceId::from_u128(TRACE_ID_HEX), SpanId::from_u64(SPAN_ID_HEX), TraceFlags::SAMPLED, true, TraceState::default())), // wrong hash length
+        ]
+    }
+
+    #[rustfmt::skip]
+    fn extract_invalid_data() -> Vec<&'static str> {
+        vec![
+            "", // missing header
+            "Parent=53995c3f42cd8ad8;Sampled=1", // missing root
+            "Root=1-5759e988-bd862e3fe1be46a994272793;Sampled=1", // missing parent, as sent by load balancers
+            "Root=2-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1", // unknown version
+            "Root=1-5759e98-bd862e3fe1be46a9942727930;Parent=53995c3f42cd8ad8;Sampled=1", // wrong epoch length
+            "Root=1-5759e988-bd862e3fe1be46a99427279;Parent=53995c3f42cd8ad8;Sampled=1", // wrong random length
+            "Root=1-5759e988-bd862e3fe1be46a994272793-1;Parent=53995c3f42cd8ad8;Sampled=1", // extra trace id part
+            "Root=5759e988bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1", // w3c trace id
+            "Root=1-5759E988-BD862E3FE1BE46A994272793;Parent=53995c3f42cd8ad8;Sampled=1", // upper case trace id
+            "Root=1-5759e988-bd862e3fe1be46a99427279q;Parent=53995c3f42cd8ad8;Sampled=1", // trace id with bug
+            "Root=1-00000000-000000000000000000000000;Parent=53995c3f42cd8ad8;Sampled=1", // invalid trace id
+            "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995C3F42CD8AD8;Sampled=1", // upper case span id
+            "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad;Sampled=1", // wrong span id length
+            "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8adq;Sampled=1", // span id with bug
+            "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=0000000000000000;Sampled=1", // invalid span id
+            "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=2", // sample flag bug
+            "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=true", // invalid sample flag(set to true)
+            "Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled", // field without value
+        ]
+    }
+
+    #[rustfmt::skip]
+    fn inject_data() -> Vec<(Option<&'static str>, SpanContext)> {
+        vec![
+            (Some("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1"), SpanContext::new(TraceId::from_u128(TRACE_ID_HEX), SpanId::from_u64(SPAN_ID_HEX), TraceFlags::SAMPLED, true, TraceState::default())),
+            (Some("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=0"), SpanContext::new(TraceId::from_u128(TRACE_ID_HEX), SpanId::from_u64(SPAN_ID_HEX), TraceFlags::default(), true, TraceState::default())),
+            (Some("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=?"), SpanContext::new(TraceId::from_u128(TRACE_ID_HEX), SpanId::from_u64(SPAN_ID_HEX), TRACE_FLAG_DEFERRED, true, TraceState::default())),
+            (Some("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1;Lineage=1:e65a2c4c:0"), SpanContext::new(TraceId::from_u128(TRACE_ID_HEX), SpanId::from_u64(SPAN_ID_HEX), TraceFlags::SAMPLED, true, lineage("1:e65a2c4c:0"))),
+            (Some("Root=1-5759e988-bd862e3fe1be46a994272793;Parent=53995c3f42cd8ad8;Sampled=1"), SpanContext::new(TraceId::from_u128(TRACE_ID_HEX), SpanId::from_u64(SPAN_ID_HEX), TraceFlags::SAMPLED, true, TraceState::from_key_value([("foo", "bar")]).unwrap())), // other trace state entries are not injected
+            (Some("Root=1-00000001-000000000000000000000002;Parent=0000000000000003;Sampled=1"), SpanContext::new(TraceId::from_u128(0x0000_0001_0000_0000_0000_0000_0000_0002), SpanId::from_u64(0x03), TraceFlags::SAMPLED, true, TraceState::default())), // zero padded ids
+            (None, SpanContext::new(TraceId::INVALID, SpanId::INVALID, TraceFlags::SAMPLED, true, TraceState::default())),
+            (None, SpanContext::empty_context()),
+        ]
+    }
+
+    #[test]
+    fn extract_xray() {
+        let propagator = XrayPropagator::default();
+
+        for (header, expected_context) in extract_data() {
+            let mut extractor: HashMap<String, String> = HashMap::new();
+            extractor.insert(AWS_XRAY_TRACE_HEADER.to_string(), header.to_owned());
+            assert_eq!(
+                propagator.extract(&extractor).span().span_context().clone(),
+                expected_context,
+                "{}",
+                header
+            )
+        }
+
+        for invalid_header in extract_invalid_data() {
+            let mut extractor: HashMap<String, String> = HashMap::new();
+            extractor.insert(AWS_XRAY_TRACE_HEADER.to_string(), invalid_header.to_owned());
+            assert_eq!(
+                propagator.extract(&extractor).span().span_context(),
+                &SpanContext::empty_context(),
+                "{}",
+                invalid_header
+            )
+        }
+    }
+
+    #[test]
+    fn inject_xray() {
+        let propagator = XrayPropagator::default();
+
+        for (expected_header, context) in inject_data() {
+            let mut injector = HashMap::new();
+            propagator.inject_context(
+                &Context::current_with_span(TestSpan(context)),
+                &mut injector,
+            );
+
+            assert_eq!(
+                injector.get(AWS_XRAY_TRACE_HEADER).map(String::as_str),
+                expected_header
+            )
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        let propagator = XrayPropagator::default();
+
+        for (header, _) in extract_data() {
+            let mut extractor: HashMap<String, String> = HashMap::new();
+            extractor.insert(AWS_XRAY_TRACE_HEADER.to_string(), header.to_owned());
+            let cx = propagator.extract(&extractor);
+
+            let mut injector = HashMap::new();
+            propagator.inject_context(&cx, &mut injector);
+            let mut extractor = HashMap::new();
+            extractor.insert(
+                AWS_XRAY_TRACE_HEADER.to_string(),
+                injector.remove(AWS_XRAY_TRACE_HEADER).unwrap(),
+            );
+            assert_eq!(
+                propagator.extract(&extractor).span().span_context(),
+                cx.span().span_context()
+            );
+        }
+    }
+
+    #[test]
+    fn test_fields() {
+        let propagator = XrayPropagator::new();
+        let fields = propagator.fields().collect::<Vec<_>>();
+        assert_eq!(fields, vec![AWS_XRAY_TRACE_HEADER]);
+    }
+}
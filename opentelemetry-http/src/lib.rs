@@ -1,10 +1,10 @@
 use async_trait::async_trait;
-use std::fmt::Debug;
+use std::fmt::{self, Debug, Display};
 
 #[doc(no_inline)]
 pub use bytes::Bytes;
 #[doc(no_inline)]
-pub use http::{Request, Response};
+pub use http::{HeaderMap, Request, Response, StatusCode};
 use opentelemetry::propagation::{Extractor, Injector};
 
 /// Helper for injecting headers into HTTP Requests. This is used for OpenTelemetry context
@@ -72,53 +72,123 @@ pub trait HttpClient: Debug + Send + Sync {
     ///
     /// Returns an error if it can't connect to the server or the request could not be completed,
     /// e.g. because of a timeout, infinite redirects, or a loss of connection.
+    ///
+    /// Implementations should return an [`HttpStatusError`] when the server answers with an
+    /// unsuccessful status code, so that callers can act on the status code and headers of the
+    /// response, e.g. to only retry the requests which may succeed later.
     async fn send_bytes(&self, request: Request<Bytes>) -> Result<Response<Bytes>, HttpError>;
 }
 
+/// Error returned by [`HttpClient::send_bytes`] when the server answered with a status code that
+/// does not indicate success (200 - 299).
+///
+/// It holds the response, so that callers can inspect its status code, headers (e.g.
+/// `Retry-After`) and body. Find it in an [`HttpError`] with
+/// [`downcast_ref`](std::error::Error::downcast_ref).
+#[derive(Debug)]
+pub struct HttpStatusError {
+    response: Response<Bytes>,
+}
+
+impl HttpStatusError {
+    /// Create an error for the unsuccessful `response`.
+    pub fn new(response: Response<Bytes>) -> Self {
+        HttpStatusError { response }
+    }
+
+    /// The status code of the response.
+    pub fn status(&self) -> StatusCode {
+        self.response.status()
+    }
+
+    /// The headers of the response.
+    pub fn headers(&self) -> &HeaderMap {
+        self.response.headers()
+    }
+
+    /// The response.
+    pub fn response(&self) -> &Response<Bytes> {
+        &self.response
+    }
+
+    /// Take the response out of the error.
+    pub fn into_response(self) -> Response<Bytes> {
+        self.response
+    }
+}
+
+impl Display for HttpStatusError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "request failed with status {}", self.status())
+    }
+}
+
+impl std::error::Error for HttpStatusError {}
+
+/// Turn `response` into an [`HttpStatusError`] if its status does not indicate success.
+#[cfg(any(feature = "reqwest", feature = "hyper", test))]
+fn check_status(response: Response<Bytes>) -> Result<Response<Bytes>, HttpError> {
+    if response.status().is_success() {
+        Ok(response)
+    } else {
+        Err(HttpStatusError::new(response).into())
+    }
+}
+
 #[cfg(feature = "reqwest")]
 mod reqwest {
     use opentelemetry::otel_debug;
 
-    use super::{async_trait, Bytes, HttpClient, HttpError, Request, Response};
+    use super::{async_trait, check_status, Bytes, HttpClient, HttpError, Request, Response};
 
     #[async_trait]
     impl HttpClient for reqwest::Client {
         async fn send_bytes(&self, request: Request<Bytes>) -> Result<Response<Bytes>, HttpError> {
             otel_debug!(name: "ReqwestClient.Send");
             let request = request.try_into()?;
-            let mut response = self.execute(request).await?.error_for_status()?;
-            let headers = std::mem::take(response.headers_mut());
-            let mut http_response = Response::builder()
-                .status(response.status())
-                .body(response.bytes().await?)?;
-            *http_response.headers_mut() = headers;
-
-            Ok(http_response)
+            check_status(into_http_response(self.execute(request).await?).await?)
         }
     }
 
+    async fn into_http_response(
+        mut response: reqwest::Response,
+    ) -> Result<Response<Bytes>, HttpError> {
+        let headers = std::mem::take(response.headers_mut());
+        let mut http_response = Response::builder()
+            .status(response.status())
+            .body(response.bytes().await?)?;
+        *http_response.headers_mut() = headers;
+
+        Ok(http_response)
+    }
+
     #[cfg(not(target_arch = "wasm32"))]
     #[async_trait]
     impl HttpClient for reqwest::blocking::Client {
         async fn send_bytes(&self, request: Request<Bytes>) -> Result<Response<Bytes>, HttpError> {
             otel_debug!(name: "ReqwestBlockingClient.Send");
             let request = request.try_into()?;
-            let mut response = self.execute(request)?.error_for_status()?;
-            let headers = std::mem::take(response.headers_mut());
-            let mut http_response = Response::builder()
-                .status(response.status())
-                .body(response.bytes()?)?;
-            *http_response.headers_mut() = headers;
-
-            Ok(http_response)
+            check_status(into_http_response_blocking(self.execute(request)?)?)
         }
     }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn into_http_response_blocking(
+        mut response: reqwest::blocking::Response,
+    ) -> Result<Response<Bytes>, HttpError> {
+        let headers = std::mem::take(response.headers_mut());
+        let mut http_response = Response::builder()
+            .status(response.status())
+            .body(response.bytes()?)?;
+        *http_response.headers_mut() = headers;
+
+        Ok(http_response)
+    }
 }
 
 #[cfg(feature = "hyper")]
 pub mod hyper {
-    use super::{async_trait, Bytes, HttpClient, HttpError, Request, Response};
-    use crate::ResponseExt;
+    use super::{async_trait, check_status, Bytes, HttpClient, HttpError, Request, Response};
     use http::HeaderValue;
     use http_body_util::{BodyExt, Full};
     use hyper::body::{Body as HttpBody, Frame};
@@ -128,6 +198,7 @@ pub mod hyper {
     };
     use opentelemetry::otel_debug;
     use std::fmt::Debug;
+    use std::io;
     use std::pin::Pin;
     use std::task::{self, Poll};
     use std::time::Duration;
@@ -179,7 +250,9 @@ pub mod hyper {
                     .headers_mut()
                     .insert(http::header::AUTHORIZATION, authorization.clone());
             }
-            let mut response = time::timeout(self.timeout, self.inner.request(request)).await??;
+            let mut response = time::timeout(self.timeout, self.inner.request(request))
+                .await
+                .map_err(|elapsed| io::Error::new(io::ErrorKind::TimedOut, elapsed))??;
             let headers = std::mem::take(response.headers_mut());
 
             let mut http_response = Response::builder()
@@ -187,7 +260,7 @@ pub mod hyper {
                 .body(response.into_body().collect().await?.to_bytes())?;
             *http_response.headers_mut() = headers;
 
-            Ok(http_response.error_for_status()?)
+            check_status(http_response)
         }
     }
 
@@ -221,6 +294,9 @@ pub mod hyper {
 /// Methods to make working with responses from the [`HttpClient`] trait easier.
 pub trait ResponseExt: Sized {
     /// Turn a response into an error if the HTTP status does not indicate success (200 - 299).
+    ///
+    /// The error is an [`HttpStatusError`] holding the status code and headers of the response,
+    /// but not its body.
     fn error_for_status(self) -> Result<Self, HttpError>;
 }
 
@@ -229,7 +305,10 @@ impl<T> ResponseExt for Response<T> {
         if self.status().is_success() {
             Ok(self)
         } else {
-            Err(format!("request failed with status {}", self.status()).into())
+            let mut response = Response::new(Bytes::new());
+            *response.status_mut() = self.status();
+            *response.headers_mut() = self.headers().clone();
+            Err(HttpStatusError::new(response).into())
         }
     }
 }
@@ -250,6 +329,36 @@ mod tests {
         )
     }
 
+    #[test]
+    fn error_for_status() {
+        let response = Response::builder()
+            .status(StatusCode::SERVICE_UNAVAILABLE)
+            .header(http::header::RETRY_AFTER, "7")
+            .body(Bytes::from_static(b"later"))
+            .unwrap();
+
+        let err = check_status(response).unwrap_err();
+        let err = err.downcast_ref::<HttpStatusError>().unwrap();
+        assert_eq!(err.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(err.headers()[http::header::RETRY_AFTER], "7");
+        assert_eq!(err.response().body(), "later");
+        assert_eq!(
+            err.to_string(),
+            "request failed with status 503 Service Unavailable"
+        );
+
+        let err = Response::builder()
+            .status(StatusCode::BAD_REQUEST)
+            .body(())
+            .unwrap()
+            .error_for_status()
+            .unwrap_err();
+        let err = err.downcast_ref::<HttpStatusError>().unwrap();
+        assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+
+        assert!(check_status(Response::new(Bytes::new())).is_ok());
+    }
+
     #[test]
     fn http_headers_keys() {
         let mut carrier = http::HeaderMap::new();
@@ -1,11 +1,15 @@
 use std::sync::Arc;
 
-use http::{header::CONTENT_TYPE, Method};
 use opentelemetry::otel_debug;
+use opentelemetry_proto::tonic::collector::logs::v1::ExportLogsServiceResponse;
+use opentelemetry_sdk::error::OTelSdkError;
 use opentelemetry_sdk::logs::{LogBatch, LogExporter};
 use opentelemetry_sdk::logs::{LogError, LogResult};
 
-use super::OtlpHttpClient;
+use super::{build_request, decode_partial_success, retry::send_with_retry, OtlpHttpClient};
+use crate::exporter::partial_success::Signal;
+use crate::exporter::spool::Payload;
+use opentelemetry_sdk::util::timer::timeout;
 
 impl LogExporter for OtlpHttpClient {
     #[allow(clippy::manual_async_fn)]
@@ -23,21 +27,40 @@ impl LogExporter for OtlpHttpClient {
                     _ => Err(LogError::Other("exporter is already shut down".into())),
                 })?;
 
-            let (body, content_type) = { self.build_logs_export_body(batch)? };
-            let mut request = http::Request::builder()
-                .method(Method::POST)
-                .uri(&self.collector_endpoint)
-                .header(CONTENT_TYPE, content_type)
-                .body(body.into())
-                .map_err(|e| crate::Error::RequestFailed(Box::new(e)))?;
+            let (body, content_type, content_encoding) = { self.build_logs_export_body(batch)? };
+            let payload = Payload {
+                body,
+                content_type,
+                content_encoding,
+            };
 
-            for (k, v) in &self.headers {
-                request.headers_mut().insert(k.clone(), v.clone());
+            if let Some(spool) = &self.spool {
+                return self
+                    .export_spooled::<ExportLogsServiceResponse>(
+                        Arc::clone(spool),
+                        client,
+                        payload,
+                        Signal::Logs,
+                    )
+                    .await
+                    .map_err(|e| match e {
+                        OTelSdkError::Timeout(timeout) => LogError::ExportTimedOut(timeout),
+                        OTelSdkError::InternalFailure(e) => LogError::Other(e.into()),
+                        e => LogError::Other(e.to_string().into()),
+                    });
             }
 
+            let request = build_request(&self.collector_endpoint, &self.headers, payload)
+                .map_err(|e| crate::Error::RequestFailed(Box::new(e)))?;
+
             let request_uri = request.uri().to_string();
             otel_debug!(name: "HttpLogsClient.CallingExport");
-            let response = client.send_bytes(request).await?;
+            let response = timeout(
+                self.timeout,
+                send_with_retry(client.as_ref(), request, self.retry_config),
+            )
+            .await
+            .map_err(|_| LogError::ExportTimedOut(self.timeout))??;
 
             if !response.status().is_success() {
                 let error = format!(
@@ -49,6 +72,13 @@ impl LogExporter for OtlpHttpClient {
                 return Err(LogError::Other(error.into()));
             }
 
+            self.partial_success
+                .handle(decode_partial_success::<ExportLogsServiceResponse>(
+                    self.protocol,
+                    Signal::Logs,
+                    response.body(),
+                ));
+
             Ok(())
         }
     }
@@ -2,12 +2,15 @@ use std::sync::Arc;
 
 use crate::metric::MetricsClient;
 use async_trait::async_trait;
-use http::{header::CONTENT_TYPE, Method};
 use opentelemetry::otel_debug;
+use opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceResponse;
 use opentelemetry_sdk::error::{OTelSdkError, OTelSdkResult};
 use opentelemetry_sdk::metrics::data::ResourceMetrics;
 
-use super::OtlpHttpClient;
+use super::{build_request, decode_partial_success, retry::send_with_retry, OtlpHttpClient};
+use crate::exporter::partial_success::Signal;
+use crate::exporter::spool::Payload;
+use opentelemetry_sdk::util::timer::timeout;
 
 #[async_trait]
 impl MetricsClient for OtlpHttpClient {
@@ -21,25 +24,44 @@ impl MetricsClient for OtlpHttpClient {
                 _ => Err(OTelSdkError::AlreadyShutdown),
             })?;
 
-        let (body, content_type) = self.build_metrics_export_body(metrics).map_err(|e| {
-            OTelSdkError::InternalFailure(format!("Failed to serialize metrics: {e:?}"))
-        })?;
-        let mut request = http::Request::builder()
-            .method(Method::POST)
-            .uri(&self.collector_endpoint)
-            .header(CONTENT_TYPE, content_type)
-            .body(body.into())
+        let (body, content_type, content_encoding) =
+            self.build_metrics_export_body(metrics).map_err(|e| {
+                OTelSdkError::InternalFailure(format!("Failed to serialize metrics: {e:?}"))
+            })?;
+        let payload = Payload {
+            body,
+            content_type,
+            content_encoding,
+        };
+        let request = build_request(&self.collector_endpoint, &self.headers, payload)
             .map_err(|e| OTelSdkError::InternalFailure(format!("{e:?}")))?;
 
-        for (k, v) in &self.headers {
-            request.headers_mut().insert(k.clone(), v.clone());
+        let request_uri = request.uri().to_string();
+        otel_debug!(name: "HttpMetricsClient.CallingExport");
+        let response = timeout(
+            self.timeout,
+            send_with_retry(client.as_ref(), request, self.retry_config),
+        )
+        .await
+        .map_err(|_| OTelSdkError::Timeout(self.timeout))?
+        .map_err(|e| OTelSdkError::InternalFailure(format!("{e:?}")))?;
+
+        if !response.status().is_success() {
+            let error = format!(
+                "OpenTelemetry metrics export failed. Url: {}, Status Code: {}, Response: {:?}",
+                request_uri,
+                response.status().as_u16(),
+                response.body()
+            );
+            return Err(OTelSdkError::InternalFailure(error));
         }
 
-        otel_debug!(name: "HttpMetricsClient.CallingExport");
-        client
-            .send_bytes(request)
-            .await
-            .map_err(|e| OTelSdkError::InternalFailure(format!("{e:?}")))?;
+        self.partial_success
+            .handle(decode_partial_success::<ExportMetricsServiceResponse>(
+                self.protocol,
+                Signal::Metrics,
+                response.body(),
+            ));
 
         Ok(())
     }
@@ -1,25 +1,36 @@
+use super::partial_success::{
+    exporter_meter, ExportResponse, PartialSuccess, PartialSuccessHandler, Signal,
+};
+use super::spool::{Payload, SendError, Spool};
 use super::{
-    default_headers, default_protocol, parse_header_string,
+    default_headers, default_protocol, parse_header_string, Compression, RetryConfig, SpoolConfig,
     OTEL_EXPORTER_OTLP_HTTP_ENDPOINT_DEFAULT,
 };
 use crate::{
-    ExportConfig, Protocol, OTEL_EXPORTER_OTLP_ENDPOINT, OTEL_EXPORTER_OTLP_HEADERS,
-    OTEL_EXPORTER_OTLP_TIMEOUT,
+    ExportConfig, Protocol, OTEL_EXPORTER_OTLP_COMPRESSION, OTEL_EXPORTER_OTLP_ENDPOINT,
+    OTEL_EXPORTER_OTLP_HEADERS, OTEL_EXPORTER_OTLP_TIMEOUT,
+};
+use http::{
+    header::{CONTENT_ENCODING, CONTENT_TYPE},
+    HeaderName, HeaderValue, Method, Uri,
 };
-use http::{HeaderName, HeaderValue, Uri};
+use opentelemetry::metrics::{Meter, MeterProvider};
 use opentelemetry_http::HttpClient;
 use opentelemetry_proto::transform::common::tonic::ResourceAttributesWithSchema;
 #[cfg(feature = "logs")]
 use opentelemetry_proto::transform::logs::tonic::group_logs_by_resource_and_scope;
 #[cfg(feature = "trace")]
 use opentelemetry_proto::transform::trace::tonic::group_spans_by_resource_and_scope;
+use opentelemetry_sdk::error::{OTelSdkError, OTelSdkResult};
 #[cfg(feature = "logs")]
 use opentelemetry_sdk::logs::LogBatch;
 #[cfg(feature = "trace")]
 use opentelemetry_sdk::trace::SpanData;
+use opentelemetry_sdk::util::timer::timeout;
 use prost::Message;
 use std::collections::HashMap;
 use std::env;
+use std::future::Future;
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
@@ -36,6 +47,9 @@ pub(crate) mod logs;
 #[cfg(feature = "trace")]
 mod trace;
 
+mod retry;
+use retry::{is_retryable, send_with_retry};
+
 #[cfg(all(
     not(feature = "reqwest-client"),
     not(feature = "reqwest-blocking-client"),
@@ -51,6 +65,18 @@ pub struct HttpConfig {
 
     /// Additional headers to send to the collector.
     headers: Option<HashMap<String, String>>,
+
+    /// Retry behavior for failed exports.
+    retry_config: RetryConfig,
+
+    /// The compression algorithm to use when communicating with the collector.
+    compression: Option<Compression>,
+
+    /// Meter recording the exporter's own metrics.
+    meter: Option<Meter>,
+
+    /// Disk spool for the span and log exports.
+    spool: Option<SpoolConfig>,
 }
 
 /// Configuration for the OTLP HTTP exporter.
@@ -108,6 +134,8 @@ impl HttpExporterBuilder {
         signal_endpoint_path: &str,
         signal_timeout_var: &str,
         signal_http_headers_var: &str,
+        signal_compression_var: &str,
+        signal: Signal,
     ) -> Result<OtlpHttpClient, crate::Error> {
         let endpoint = resolve_http_endpoint(
             signal_endpoint_var,
@@ -115,6 +143,8 @@ impl HttpExporterBuilder {
             self.exporter_config.endpoint.clone(),
         )?;
 
+        let compression = self.resolve_compression(signal_compression_var)?;
+
         let timeout = match env::var(signal_timeout_var)
             .ok()
             .or(env::var(OTEL_EXPORTER_OTLP_TIMEOUT).ok())
@@ -197,31 +227,74 @@ impl HttpExporterBuilder {
             add_header_from_string(&input, &mut headers);
         }
 
+        let mut partial_success = PartialSuccessHandler::new(signal);
+        if let Some(meter) = &self.http_config.meter {
+            partial_success = partial_success.with_meter(meter);
+        }
+
         Ok(OtlpHttpClient::new(
             http_client,
             endpoint,
             headers,
             self.exporter_config.protocol,
             timeout,
+            self.http_config.retry_config,
+            compression,
+            partial_success,
         ))
     }
 
+    fn resolve_compression(&self, env_override: &str) -> Result<Option<Compression>, crate::Error> {
+        let compression = if let Some(compression) = self.http_config.compression {
+            compression
+        } else if let Ok(compression) = env::var(env_override) {
+            compression.parse::<Compression>()?
+        } else if let Ok(compression) = env::var(OTEL_EXPORTER_OTLP_COMPRESSION) {
+            compression.parse::<Compression>()?
+        } else {
+            return Ok(None);
+        };
+
+        match compression {
+            #[cfg(not(feature = "gzip-http"))]
+            Compression::Gzip => Err(crate::Error::FeatureRequiredForCompressionAlgorithm(
+                "gzip-http",
+                Compression::Gzip,
+            )),
+            #[cfg(not(feature = "zstd-http"))]
+            Compression::Zstd => Err(crate::Error::FeatureRequiredForCompressionAlgorithm(
+                "zstd-http",
+                Compression::Zstd,
+            )),
+            #[allow(unreachable_patterns)]
+            _ => Ok(Some(compression)),
+        }
+    }
+
     /// Create a log exporter with the current configuration
     #[cfg(feature = "trace")]
     pub fn build_span_exporter(
         mut self,
     ) -> Result<crate::SpanExporter, opentelemetry::trace::TraceError> {
         use crate::{
-            OTEL_EXPORTER_OTLP_TRACES_ENDPOINT, OTEL_EXPORTER_OTLP_TRACES_HEADERS,
-            OTEL_EXPORTER_OTLP_TRACES_TIMEOUT,
+            OTEL_EXPORTER_OTLP_TRACES_COMPRESSION, OTEL_EXPORTER_OTLP_TRACES_ENDPOINT,
+            OTEL_EXPORTER_OTLP_TRACES_HEADERS, OTEL_EXPORTER_OTLP_TRACES_TIMEOUT,
         };
 
-        let client = self.build_client(
+        let mut client = self.build_client(
             OTEL_EXPORTER_OTLP_TRACES_ENDPOINT,
             "/v1/traces",
             OTEL_EXPORTER_OTLP_TRACES_TIMEOUT,
             OTEL_EXPORTER_OTLP_TRACES_HEADERS,
+            OTEL_EXPORTER_OTLP_TRACES_COMPRESSION,
+            Signal::Traces,
         )?;
+        client.spool = self
+            .http_config
+            .spool
+            .as_ref()
+            .map(|spool| spool.open("traces"))
+            .transpose()?;
 
         Ok(crate::SpanExporter::new(client))
     }
@@ -230,16 +303,24 @@ impl HttpExporterBuilder {
     #[cfg(feature = "logs")]
     pub fn build_log_exporter(mut self) -> opentelemetry_sdk::logs::LogResult<crate::LogExporter> {
         use crate::{
-            OTEL_EXPORTER_OTLP_LOGS_ENDPOINT, OTEL_EXPORTER_OTLP_LOGS_HEADERS,
-            OTEL_EXPORTER_OTLP_LOGS_TIMEOUT,
+            OTEL_EXPORTER_OTLP_LOGS_COMPRESSION, OTEL_EXPORTER_OTLP_LOGS_ENDPOINT,
+            OTEL_EXPORTER_OTLP_LOGS_HEADERS, OTEL_EXPORTER_OTLP_LOGS_TIMEOUT,
         };
 
-        let client = self.build_client(
+        let mut client = self.build_client(
             OTEL_EXPORTER_OTLP_LOGS_ENDPOINT,
             "/v1/logs",
             OTEL_EXPORTER_OTLP_LOGS_TIMEOUT,
             OTEL_EXPORTER_OTLP_LOGS_HEADERS,
+            OTEL_EXPORTER_OTLP_LOGS_COMPRESSION,
+            Signal::Logs,
         )?;
+        client.spool = self
+            .http_config
+            .spool
+            .as_ref()
+            .map(|spool| spool.open("logs"))
+            .transpose()?;
 
         Ok(crate::LogExporter::from_http(client))
     }
@@ -251,8 +332,8 @@ impl HttpExporterBuilder {
         temporality: opentelemetry_sdk::metrics::Temporality,
     ) -> opentelemetry_sdk::metrics::MetricResult<crate::MetricExporter> {
         use crate::{
-            OTEL_EXPORTER_OTLP_METRICS_ENDPOINT, OTEL_EXPORTER_OTLP_METRICS_HEADERS,
-            OTEL_EXPORTER_OTLP_METRICS_TIMEOUT,
+            OTEL_EXPORTER_OTLP_METRICS_COMPRESSION, OTEL_EXPORTER_OTLP_METRICS_ENDPOINT,
+            OTEL_EXPORTER_OTLP_METRICS_HEADERS, OTEL_EXPORTER_OTLP_METRICS_TIMEOUT,
         };
 
         let client = self.build_client(
@@ -260,6 +341,8 @@ impl HttpExporterBuilder {
             "/v1/metrics",
             OTEL_EXPORTER_OTLP_METRICS_TIMEOUT,
             OTEL_EXPORTER_OTLP_METRICS_HEADERS,
+            OTEL_EXPORTER_OTLP_METRICS_COMPRESSION,
+            Signal::Metrics,
         )?;
 
         Ok(crate::MetricExporter::new(client, temporality))
@@ -272,7 +355,11 @@ pub(crate) struct OtlpHttpClient {
     collector_endpoint: Uri,
     headers: HashMap<HeaderName, HeaderValue>,
     protocol: Protocol,
-    _timeout: Duration,
+    timeout: Duration,
+    retry_config: RetryConfig,
+    compression: Option<Compression>,
+    partial_success: PartialSuccessHandler,
+    spool: Option<Arc<Spool>>,
     #[allow(dead_code)]
     // <allow dead> would be removed once we support set_resource for metrics and traces.
     resource: opentelemetry_proto::transform::common::tonic::ResourceAttributesWithSchema,
@@ -280,83 +367,234 @@ pub(crate) struct OtlpHttpClient {
 
 impl OtlpHttpClient {
     #[allow(clippy::mutable_key_type)] // http headers are not mutated
+    #[allow(clippy::too_many_arguments)]
     fn new(
         client: Arc<dyn HttpClient>,
         collector_endpoint: Uri,
         headers: HashMap<HeaderName, HeaderValue>,
         protocol: Protocol,
         timeout: Duration,
+        retry_config: RetryConfig,
+        compression: Option<Compression>,
+        partial_success: PartialSuccessHandler,
     ) -> Self {
         OtlpHttpClient {
             client: Mutex::new(Some(client)),
             collector_endpoint,
             headers,
             protocol,
-            _timeout: timeout,
+            timeout,
+            retry_config,
+            compression,
+            partial_success,
+            spool: None,
             resource: ResourceAttributesWithSchema::default(),
         }
     }
 
+    /// Export `payload` through `spool`, sending the spooled requests with
+    /// `client`.
+    ///
+    /// The export timeout applies to the whole export, spooled requests
+    /// included: each request gets the time left. Requests which were not
+    /// sent in time stay in the spool.
+    fn export_spooled<R>(
+        &self,
+        spool: Arc<Spool>,
+        client: Arc<dyn HttpClient>,
+        payload: Payload,
+        signal: Signal,
+    ) -> impl Future<Output = OTelSdkResult> + Send + 'static
+    where
+        R: ExportResponse + Message + Default,
+    {
+        let collector_endpoint = self.collector_endpoint.clone();
+        #[allow(clippy::mutable_key_type)] // http headers are not mutated
+        let headers = self.headers.clone();
+        let export_timeout = self.timeout;
+        let retry_config = self.retry_config;
+        let protocol = self.protocol;
+        let partial_success = self.partial_success.clone();
+        async move {
+            let export = spool.export(payload, |payload| {
+                let request = build_request(&collector_endpoint, &headers, payload);
+                let client = Arc::clone(&client);
+                let partial_success = partial_success.clone();
+                async move {
+                    let request = request.map_err(|e| SendError::Rejected(e.to_string()))?;
+                    let request_uri = request.uri().to_string();
+                    let response = send_with_retry(client.as_ref(), request, retry_config)
+                        .await
+                        .map_err(|e| SendError::Transient(format!("{e:?}")))?;
+
+                    if !response.status().is_success() {
+                        let error = format!(
+                            "OpenTelemetry export failed. Url: {}, Status Code: {}, Response: {:?}",
+                            request_uri,
+                            response.status().as_u16(),
+                            response.body()
+                        );
+                        return Err(if is_retryable(response.status()) {
+                            SendError::Transient(error)
+                        } else {
+                            SendError::Rejected(error)
+                        });
+                    }
+
+                    partial_success.handle(decode_partial_success::<R>(
+                        protocol,
+                        signal,
+                        response.body(),
+                    ));
+                    Ok(())
+                }
+            });
+            timeout(export_timeout, export)
+                .await
+                .map_err(|_| OTelSdkError::Timeout(export_timeout))?
+                .map_err(OTelSdkError::InternalFailure)
+        }
+    }
+
     #[cfg(feature = "trace")]
     fn build_trace_export_body(
         &self,
         spans: Vec<SpanData>,
-    ) -> opentelemetry::trace::TraceResult<(Vec<u8>, &'static str)> {
+    ) -> opentelemetry::trace::TraceResult<(Vec<u8>, &'static str, Option<&'static str>)> {
         use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
         let resource_spans = group_spans_by_resource_and_scope(spans, &self.resource);
 
         let req = ExportTraceServiceRequest { resource_spans };
-        match self.protocol {
+        let (body, content_type) = match self.protocol {
             #[cfg(feature = "http-json")]
             Protocol::HttpJson => match serde_json::to_string_pretty(&req) {
-                Ok(json) => Ok((json.into(), "application/json")),
-                Err(e) => Err(opentelemetry::trace::TraceError::from(e.to_string())),
+                Ok(json) => (json.into(), "application/json"),
+                Err(e) => return Err(opentelemetry::trace::TraceError::from(e.to_string())),
             },
-            _ => Ok((req.encode_to_vec(), "application/x-protobuf")),
-        }
+            _ => (req.encode_to_vec(), "application/x-protobuf"),
+        };
+        let (body, content_encoding) = self
+            .compress_body(body)
+            .map_err(|e| opentelemetry::trace::TraceError::from(e.to_string()))?;
+        Ok((body, content_type, content_encoding))
     }
 
     #[cfg(feature = "logs")]
     fn build_logs_export_body(
         &self,
         logs: LogBatch<'_>,
-    ) -> opentelemetry_sdk::logs::LogResult<(Vec<u8>, &'static str)> {
+    ) -> opentelemetry_sdk::logs::LogResult<(Vec<u8>, &'static str, Option<&'static str>)> {
         use opentelemetry_proto::tonic::collector::logs::v1::ExportLogsServiceRequest;
         let resource_logs = group_logs_by_resource_and_scope(logs, &self.resource);
         let req = ExportLogsServiceRequest { resource_logs };
 
-        match self.protocol {
+        let (body, content_type) = match self.protocol {
             #[cfg(feature = "http-json")]
             Protocol::HttpJson => match serde_json::to_string_pretty(&req) {
-                Ok(json) => Ok((json.into(), "application/json")),
-                Err(e) => Err(opentelemetry_sdk::logs::LogError::from(e.to_string())),
+                Ok(json) => (json.into(), "application/json"),
+                Err(e) => return Err(opentelemetry_sdk::logs::LogError::from(e.to_string())),
             },
-            _ => Ok((req.encode_to_vec(), "application/x-protobuf")),
-        }
+            _ => (req.encode_to_vec(), "application/x-protobuf"),
+        };
+        let (body, content_encoding) = self
+            .compress_body(body)
+            .map_err(|e| opentelemetry_sdk::logs::LogError::from(e.to_string()))?;
+        Ok((body, content_type, content_encoding))
     }
 
     #[cfg(feature = "metrics")]
     fn build_metrics_export_body(
         &self,
         metrics: &mut ResourceMetrics,
-    ) -> opentelemetry_sdk::metrics::MetricResult<(Vec<u8>, &'static str)> {
+    ) -> opentelemetry_sdk::metrics::MetricResult<(Vec<u8>, &'static str, Option<&'static str>)>
+    {
         use opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceRequest;
 
         let req: ExportMetricsServiceRequest = (&*metrics).into();
 
-        match self.protocol {
+        let (body, content_type) = match self.protocol {
             #[cfg(feature = "http-json")]
             Protocol::HttpJson => match serde_json::to_string_pretty(&req) {
-                Ok(json) => Ok((json.into(), "application/json")),
-                Err(e) => Err(opentelemetry_sdk::metrics::MetricError::Other(
-                    e.to_string(),
-                )),
+                Ok(json) => (json.into(), "application/json"),
+                Err(e) => {
+                    return Err(opentelemetry_sdk::metrics::MetricError::Other(
+                        e.to_string(),
+                    ))
+                }
             },
-            _ => Ok((req.encode_to_vec(), "application/x-protobuf")),
+            _ => (req.encode_to_vec(), "application/x-protobuf"),
+        };
+        let (body, content_encoding) = self
+            .compress_body(body)
+            .map_err(|e| opentelemetry_sdk::metrics::MetricError::Other(e.to_string()))?;
+        Ok((body, content_type, content_encoding))
+    }
+
+    /// Compresses `body` with the configured algorithm, returning it along with
+    /// the value of the `Content-Encoding` header to send.
+    fn compress_body(&self, body: Vec<u8>) -> std::io::Result<(Vec<u8>, Option<&'static str>)> {
+        match self.compression {
+            #[cfg(feature = "gzip-http")]
+            Some(Compression::Gzip) => {
+                use flate2::{write::GzEncoder, Compression as GzCompression};
+                use std::io::Write;
+
+                let mut encoder = GzEncoder::new(Vec::new(), GzCompression::default());
+                encoder.write_all(&body)?;
+                Ok((encoder.finish()?, Some("gzip")))
+            }
+            #[cfg(feature = "zstd-http")]
+            Some(Compression::Zstd) => Ok((zstd::bulk::compress(&body, 0)?, Some("zstd"))),
+            // unsupported algorithms are rejected when building the client
+            _ => Ok((body, None)),
         }
     }
 }
 
+/// Builds the request sending `payload` to the collector.
+#[allow(clippy::mutable_key_type)] // http headers are not mutated
+fn build_request(
+    collector_endpoint: &Uri,
+    headers: &HashMap<HeaderName, HeaderValue>,
+    payload: Payload,
+) -> http::Result<http::Request<opentelemetry_http::Bytes>> {
+    let mut request = http::Request::builder()
+        .method(Method::POST)
+        .uri(collector_endpoint)
+        .header(CONTENT_TYPE, payload.content_type)
+        .body(payload.body.into())?;
+
+    for (k, v) in headers {
+        request.headers_mut().insert(k.clone(), v.clone());
+    }
+    if let Some(content_encoding) = payload.content_encoding {
+        request
+            .headers_mut()
+            .insert(CONTENT_ENCODING, HeaderValue::from_static(content_encoding));
+    }
+    Ok(request)
+}
+
+/// Decodes the `partial_success` of the collector's response to an export request.
+///
+/// Responses that can't be decoded, e.g. because the body is empty, are treated as
+/// full successes.
+#[cfg_attr(not(feature = "http-json"), allow(unused_variables))]
+fn decode_partial_success<R>(
+    protocol: Protocol,
+    signal: Signal,
+    body: &[u8],
+) -> Option<PartialSuccess>
+where
+    R: ExportResponse + Message + Default,
+{
+    match protocol {
+        #[cfg(feature = "http-json")]
+        Protocol::HttpJson => PartialSuccess::from_json(body, signal),
+        _ => R::decode(body).ok()?.partial_success(),
+    }
+}
+
 fn build_endpoint_uri(endpoint: &str, path: &str) -> Result<Uri, crate::Error> {
     let path = if endpoint.ends_with('/') && path.starts_with('/') {
         path.strip_prefix('/').unwrap()
@@ -439,6 +677,30 @@ pub trait WithHttpConfig {
 
     /// Set additional headers to send to the collector.
     fn with_headers(self, headers: HashMap<String, String>) -> Self;
+
+    /// Set the retry behavior for failed exports.
+    ///
+    /// Retries are enabled by default, see [`RetryConfig`] for the defaults.
+    fn with_retry_config(self, retry_config: RetryConfig) -> Self;
+
+    /// Set the compression algorithm to use when communicating with the collector.
+    ///
+    /// Requires the `gzip-http` or `zstd-http` feature for the respective algorithm.
+    fn with_compression(self, compression: Compression) -> Self;
+
+    /// Set the meter provider used to record the exporter's own metrics.
+    ///
+    /// The exporter counts the spans, log records or data points rejected by the
+    /// collector in a `partial_success` response. Without a meter provider, rejected
+    /// items are only reported through internal logs.
+    fn with_meter_provider<P: MeterProvider + ?Sized>(self, meter_provider: &P) -> Self;
+
+    /// Persist the span and log export requests in a disk spool until the
+    /// collector accepted them, see [`SpoolConfig`].
+    ///
+    /// The spool is opened when building the exporter. The metric exporter
+    /// does not use the spool.
+    fn with_spool(self, spool: SpoolConfig) -> Self;
 }
 
 impl<B: HasHttpConfig> WithHttpConfig for B {
@@ -458,6 +720,26 @@ impl<B: HasHttpConfig> WithHttpConfig for B {
             });
         self
     }
+
+    fn with_retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.http_client_config().retry_config = retry_config;
+        self
+    }
+
+    fn with_compression(mut self, compression: Compression) -> Self {
+        self.http_client_config().compression = Some(compression);
+        self
+    }
+
+    fn with_meter_provider<P: MeterProvider + ?Sized>(mut self, meter_provider: &P) -> Self {
+        self.http_client_config().meter = Some(exporter_meter(meter_provider));
+        self
+    }
+
+    fn with_spool(mut self, spool: SpoolConfig) -> Self {
+        self.http_client_config().spool = Some(spool);
+        self
+    }
 }
 
 #[cfg(test)]
@@ -687,6 +969,10 @@ mod tests {
             http_config: HttpConfig {
                 client: None,
                 headers: Some(initial_headers),
+                retry_config: Default::default(),
+                compression: None,
+                meter: None,
+                spool: None,
             },
             exporter_config: crate::ExportConfig::default(),
         };
@@ -736,4 +1022,646 @@ mod tests {
             assert_eq!(url, "http://localhost:4318/v1/tracesbutnotreally");
         });
     }
+    /// A span named `name`, without any other data.
+    #[cfg(all(feature = "trace", feature = "reqwest-blocking-client"))]
+    fn span(name: &'static str) -> opentelemetry_sdk::trace::SpanData {
+        use opentelemetry::trace::{SpanContext, SpanId, SpanKind, Status};
+        use opentelemetry_sdk::trace::{SpanData, SpanEvents, SpanLinks};
+        use std::time::SystemTime;
+
+        SpanData {
+            span_context: SpanContext::empty_context(),
+            parent_span_id: SpanId::INVALID,
+            span_kind: SpanKind::Internal,
+            name: std::borrow::Cow::Borrowed(name),
+            start_time: SystemTime::now(),
+            end_time: SystemTime::now(),
+            attributes: vec![],
+            dropped_attributes_count: 0,
+            events: SpanEvents::default(),
+            links: SpanLinks::default(),
+            status: Status::Unset,
+            instrumentation_scope: Default::default(),
+        }
+    }
+
+    /// A request received by the mock collector.
+    #[cfg(all(feature = "trace", feature = "reqwest-blocking-client"))]
+    #[derive(Debug)]
+    struct ReceivedRequest {
+        headers: std::collections::HashMap<String, String>,
+        body: Vec<u8>,
+    }
+
+    /// Starts a collector that answers the n-th request with the n-th of `responses`,
+    /// repeating the last one once exhausted. Returns its endpoint and the received requests.
+    #[cfg(all(feature = "trace", feature = "reqwest-blocking-client"))]
+    fn start_mock_collector(
+        responses: Vec<(u16, Option<&'static str>)>,
+    ) -> (
+        String,
+        std::sync::Arc<std::sync::Mutex<Vec<ReceivedRequest>>>,
+    ) {
+        use std::io::{BufRead, BufReader, Read, Write};
+        use std::sync::{Arc, Mutex};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("http://{}/v1/traces", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let received = Arc::clone(&requests);
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = stream.unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut headers = std::collections::HashMap::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        headers.insert(name.to_ascii_lowercase(), value.trim().to_string());
+                    }
+                }
+                let content_length = headers
+                    .get("content-length")
+                    .map_or(0, |len| len.parse().unwrap());
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+
+                let n = {
+                    let mut received = received.lock().unwrap();
+                    received.push(ReceivedRequest { headers, body });
+                    received.len() - 1
+                };
+                let (status, retry_after) = responses[n.min(responses.len() - 1)];
+                let retry_after = retry_after
+                    .map(|v| format!("Retry-After: {v}\r\n"))
+                    .unwrap_or_default();
+                write!(
+                    stream,
+                    "HTTP/1.1 {status} Mock\r\n{retry_after}Content-Length: 0\r\nConnection: close\r\n\r\n"
+                )
+                .unwrap();
+            }
+        });
+        (endpoint, requests)
+    }
+
+    #[cfg(all(feature = "trace", feature = "reqwest-blocking-client"))]
+    mod retry {
+        use super::start_mock_collector;
+        use crate::{RetryConfig, SpanExporter, WithExportConfig, WithHttpConfig};
+        use opentelemetry_http::{
+            Bytes, HttpClient, HttpError, HttpStatusError, Request, Response,
+        };
+        use opentelemetry_sdk::trace::SpanExporter as _;
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        use std::sync::Arc;
+        use std::time::Duration;
+
+        fn export(
+            endpoint: &str,
+            retry_config: RetryConfig,
+        ) -> opentelemetry_sdk::error::OTelSdkResult {
+            let mut exporter = SpanExporter::builder()
+                .with_http()
+                .with_http_client(reqwest::blocking::Client::new())
+                .with_endpoint(endpoint)
+                .with_retry_config(retry_config)
+                .build()
+                .unwrap();
+            futures_executor::block_on(exporter.export(vec![]))
+        }
+
+        fn fast_retries() -> RetryConfig {
+            RetryConfig {
+                enabled: true,
+                initial_interval: Duration::from_millis(10),
+                max_interval: Duration::from_millis(20),
+                max_elapsed_time: Duration::from_secs(5),
+            }
+        }
+
+        #[test]
+        fn test_retries_retryable_responses_until_success() {
+            let (endpoint, requests) = start_mock_collector(vec![
+                (503, None),
+                (429, Some("0")),
+                (502, None),
+                (200, None),
+            ]);
+
+            assert!(export(&endpoint, fast_retries()).is_ok());
+            assert_eq!(requests.lock().unwrap().len(), 4);
+        }
+
+        #[test]
+        fn test_does_not_retry_non_retryable_responses() {
+            let (endpoint, requests) = start_mock_collector(vec![(400, None), (200, None)]);
+
+            assert!(export(&endpoint, fast_retries()).is_err());
+            assert_eq!(requests.lock().unwrap().len(), 1);
+        }
+
+        #[test]
+        fn test_gives_up_after_max_elapsed_time() {
+            let (endpoint, requests) = start_mock_collector(vec![(503, None)]);
+            let retry_config = RetryConfig {
+                max_elapsed_time: Duration::from_millis(100),
+                ..fast_retries()
+            };
+
+            assert!(export(&endpoint, retry_config).is_err());
+            assert!(requests.lock().unwrap().len() > 1);
+        }
+
+        #[test]
+        fn test_retry_after_exceeding_max_elapsed_time_is_not_retried() {
+            let (endpoint, requests) = start_mock_collector(vec![(429, Some("60")), (200, None)]);
+
+            assert!(export(&endpoint, fast_retries()).is_err());
+            assert_eq!(requests.lock().unwrap().len(), 1);
+        }
+
+        #[test]
+        fn test_disabled_retries() {
+            let (endpoint, requests) = start_mock_collector(vec![(503, None), (200, None)]);
+
+            assert!(export(&endpoint, RetryConfig::disabled()).is_err());
+            assert_eq!(requests.lock().unwrap().len(), 1);
+        }
+
+        /// A custom client failing every request with the error made by `error`.
+        #[derive(Debug)]
+        struct FailingClient {
+            attempts: Arc<AtomicUsize>,
+            error: fn() -> HttpError,
+        }
+
+        #[async_trait::async_trait]
+        impl HttpClient for FailingClient {
+            async fn send_bytes(
+                &self,
+                _request: Request<Bytes>,
+            ) -> Result<Response<Bytes>, HttpError> {
+                self.attempts.fetch_add(1, Ordering::Relaxed);
+                Err((self.error)())
+            }
+        }
+
+        /// Exports with a [`FailingClient`], and returns the number of attempts.
+        fn attempts(error: fn() -> HttpError) -> usize {
+            let attempts = Arc::new(AtomicUsize::new(0));
+            let mut exporter = SpanExporter::builder()
+                .with_http()
+                .with_http_client(FailingClient {
+                    attempts: Arc::clone(&attempts),
+                    error,
+                })
+                .with_retry_config(RetryConfig {
+                    max_elapsed_time: Duration::from_millis(100),
+                    ..fast_retries()
+                })
+                .build()
+                .unwrap();
+            assert!(futures_executor::block_on(exporter.export(vec![])).is_err());
+            attempts.load(Ordering::Relaxed)
+        }
+
+        fn status_error(status: u16) -> HttpError {
+            let response = Response::builder()
+                .status(status)
+                .body(Bytes::new())
+                .unwrap();
+            Box::new(HttpStatusError::new(response))
+        }
+
+        #[test]
+        fn test_custom_client_errors() {
+            assert!(attempts(|| status_error(503)) > 1);
+            assert!(
+                attempts(|| std::io::Error::from(std::io::ErrorKind::ConnectionReset).into()) > 1
+            );
+            assert_eq!(attempts(|| status_error(400)), 1);
+            assert_eq!(attempts(|| "invalid request".into()), 1);
+        }
+    }
+
+    #[cfg(all(feature = "trace", feature = "reqwest-blocking-client"))]
+    mod spool {
+        use super::{span, start_mock_collector};
+        use crate::exporter::spool::tests::TempDir;
+        use crate::{
+            Protocol, RetryConfig, SpanExporter, SpoolConfig, WithExportConfig, WithHttpConfig,
+        };
+        use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+        use opentelemetry_sdk::trace::SpanExporter as _;
+        use prost::Message;
+
+        fn spooling_exporter(endpoint: &str, directory: &std::path::Path) -> SpanExporter {
+            SpanExporter::builder()
+                .with_http()
+                .with_http_client(reqwest::blocking::Client::new())
+                .with_endpoint(endpoint)
+                .with_protocol(Protocol::HttpBinary)
+                .with_retry_config(RetryConfig::disabled())
+                .with_spool(SpoolConfig::new(directory))
+                .build()
+                .unwrap()
+        }
+
+        #[test]
+        fn test_replays_spooled_spans_after_restart() {
+            let dir = TempDir::new("http");
+            let directory = &dir.0;
+            let (endpoint, requests) =
+                start_mock_collector(vec![(503, None), (503, None), (200, None)]);
+
+            let mut exporter = spooling_exporter(&endpoint, directory);
+            assert!(futures_executor::block_on(exporter.export(vec![span("first")])).is_err());
+            assert!(futures_executor::block_on(exporter.export(vec![span("second")])).is_err());
+            drop(exporter);
+
+            let mut exporter = spooling_exporter(&endpoint, directory);
+            futures_executor::block_on(exporter.export(vec![span("third")])).unwrap();
+
+            let received = requests
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|request| {
+                    let request = ExportTraceServiceRequest::decode(&*request.body).unwrap();
+                    request.resource_spans[0].scope_spans[0].spans[0]
+                        .name
+                        .clone()
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(received, ["first", "first", "first", "second", "third"]);
+            assert_eq!(
+                std::fs::read_dir(directory.join("traces")).unwrap().count(),
+                0
+            );
+        }
+    }
+
+    #[cfg(all(feature = "trace", feature = "reqwest-blocking-client"))]
+    mod compression {
+        use super::{span, start_mock_collector};
+        use crate::exporter::tests::run_env_test;
+        use crate::{
+            Compression, Protocol, SpanExporter, WithExportConfig, WithHttpConfig,
+            OTEL_EXPORTER_OTLP_COMPRESSION,
+        };
+        use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
+        use opentelemetry_sdk::trace::SpanExporter as _;
+        use prost::Message;
+
+        /// Exports a span and returns the `Content-Encoding` and body received by the collector.
+        fn export(
+            builder: crate::span::SpanExporterBuilder<crate::HttpExporterBuilderSet>,
+        ) -> (Option<String>, Vec<u8>) {
+            let (endpoint, requests) = start_mock_collector(vec![(200, None)]);
+            // the payload is decoded as protobuf, even if `http-json` is the default protocol
+            let mut exporter = builder
+                .with_http_client(reqwest::blocking::Client::new())
+                .with_endpoint(endpoint)
+                .with_protocol(Protocol::HttpBinary)
+                .build()
+                .unwrap();
+            futures_executor::block_on(exporter.export(vec![span("compressed")])).unwrap();
+
+            let request = requests.lock().unwrap().remove(0);
+            (
+                request.headers.get("content-encoding").cloned(),
+                request.body,
+            )
+        }
+
+        fn assert_span_received(body: &[u8]) {
+            let request = ExportTraceServiceRequest::decode(body).unwrap();
+            assert_eq!(
+                request.resource_spans[0].scope_spans[0].spans[0].name,
+                "compressed"
+            );
+        }
+
+        #[test]
+        fn test_no_compression_by_default() {
+            run_env_test(vec![], || {
+                let (content_encoding, body) = export(SpanExporter::builder().with_http());
+
+                assert_eq!(content_encoding, None);
+                assert_span_received(&body);
+            })
+        }
+
+        #[cfg(feature = "gzip-http")]
+        #[test]
+        fn test_gzip_compression() {
+            use std::io::Read;
+
+            run_env_test(vec![], || {
+                let (content_encoding, body) = export(
+                    SpanExporter::builder()
+                        .with_http()
+                        .with_compression(Compression::Gzip),
+                );
+
+                assert_eq!(content_encoding.as_deref(), Some("gzip"));
+                let mut decompressed = Vec::new();
+                flate2::read::GzDecoder::new(body.as_slice())
+                    .read_to_end(&mut decompressed)
+                    .unwrap();
+                assert_span_received(&decompressed);
+            })
+        }
+
+        #[cfg(feature = "zstd-http")]
+        #[test]
+        fn test_zstd_compression_from_env() {
+            use crate::OTEL_EXPORTER_OTLP_TRACES_COMPRESSION;
+
+            run_env_test(
+                vec![
+                    (OTEL_EXPORTER_OTLP_TRACES_COMPRESSION, "zstd"),
+                    (OTEL_EXPORTER_OTLP_COMPRESSION, "gzip"),
+                ],
+                || {
+                    let (content_encoding, body) = export(SpanExporter::builder().with_http());
+
+                    assert_eq!(content_encoding.as_deref(), Some("zstd"));
+                    assert_span_received(&zstd::decode_all(body.as_slice()).unwrap());
+                },
+            )
+        }
+
+        #[cfg(not(feature = "gzip-http"))]
+        #[test]
+        fn test_gzip_compression_requires_feature() {
+            run_env_test(vec![], || {
+                let result = SpanExporter::builder()
+                    .with_http()
+                    .with_http_client(reqwest::blocking::Client::new())
+                    .with_compression(Compression::Gzip)
+                    .build();
+                assert!(result.is_err());
+            })
+        }
+
+        #[test]
+        fn test_unsupported_compression_from_env() {
+            run_env_test(vec![(OTEL_EXPORTER_OTLP_COMPRESSION, "brotli")], || {
+                let result = SpanExporter::builder()
+                    .with_http()
+                    .with_http_client(reqwest::blocking::Client::new())
+                    .build();
+                assert!(result.is_err());
+            })
+        }
+    }
+
+    mod timeout {
+        use super::super::OtlpHttpClient;
+        use crate::exporter::partial_success::{PartialSuccessHandler, Signal};
+        use crate::{Protocol, RetryConfig};
+        use opentelemetry_http::{Bytes, HttpClient, HttpError, Request, Response};
+        use std::collections::HashMap;
+        use std::sync::Arc;
+        use std::time::{Duration, Instant};
+
+        const TIMEOUT: Duration = Duration::from_millis(100);
+
+        /// A client that never completes a request, regardless of its own configuration.
+        #[derive(Debug)]
+        struct HangingClient;
+
+        #[async_trait::async_trait]
+        impl HttpClient for HangingClient {
+            async fn send_bytes(
+                &self,
+                _request: Request<Bytes>,
+            ) -> Result<Response<Bytes>, HttpError> {
+                std::future::pending().await
+            }
+        }
+
+        fn hanging_client() -> OtlpHttpClient {
+            OtlpHttpClient::new(
+                Arc::new(HangingClient),
+                "http://localhost:4318".parse().unwrap(),
+                HashMap::new(),
+                Protocol::HttpBinary,
+                TIMEOUT,
+                RetryConfig::default(),
+                None,
+                PartialSuccessHandler::new(Signal::Traces),
+            )
+        }
+
+        fn assert_timely(start: Instant) {
+            assert!(start.elapsed() >= TIMEOUT);
+            assert!(start.elapsed() < Duration::from_secs(5));
+        }
+
+        #[cfg(feature = "trace")]
+        #[test]
+        fn test_trace_export_times_out() {
+            use opentelemetry_sdk::error::OTelSdkError;
+            use opentelemetry_sdk::trace::SpanExporter;
+
+            let mut client = hanging_client();
+            let start = Instant::now();
+            let result = futures_executor::block_on(client.export(vec![]));
+
+            assert!(matches!(result, Err(OTelSdkError::Timeout(t)) if t == TIMEOUT));
+            assert_timely(start);
+        }
+
+        #[cfg(feature = "logs")]
+        #[test]
+        fn test_logs_export_times_out() {
+            use opentelemetry_sdk::logs::{LogBatch, LogError, LogExporter};
+
+            let client = hanging_client();
+            let start = Instant::now();
+            let result = futures_executor::block_on(client.export(LogBatch::new(&[])));
+
+            assert!(matches!(result, Err(LogError::ExportTimedOut(t)) if t == TIMEOUT));
+            assert_timely(start);
+        }
+
+        #[cfg(feature = "metrics")]
+        #[test]
+        fn test_metrics_export_times_out() {
+            use crate::metric::MetricsClient;
+            use opentelemetry_sdk::error::OTelSdkError;
+            use opentelemetry_sdk::metrics::data::ResourceMetrics;
+            use opentelemetry_sdk::Resource;
+
+            let client = hanging_client();
+            let mut metrics = ResourceMetrics {
+                resource: Resource::builder_empty().build(),
+                scope_metrics: vec![],
+            };
+            let start = Instant::now();
+            let result = futures_executor::block_on(client.export(&mut metrics));
+
+            assert!(matches!(result, Err(OTelSdkError::Timeout(t)) if t == TIMEOUT));
+            assert_timely(start);
+        }
+
+        /// A client answering every request successfully, after `delay`.
+        #[cfg(feature = "trace")]
+        #[derive(Debug)]
+        struct SlowClient {
+            delay: Duration,
+        }
+
+        #[cfg(feature = "trace")]
+        #[async_trait::async_trait]
+        impl HttpClient for SlowClient {
+            async fn send_bytes(
+                &self,
+                _request: Request<Bytes>,
+            ) -> Result<Response<Bytes>, HttpError> {
+                opentelemetry_sdk::util::timer::sleep(self.delay).await;
+                Ok(Response::new(Bytes::new()))
+            }
+        }
+
+        #[cfg(feature = "trace")]
+        #[test]
+        fn test_spooled_export_times_out_once() {
+            use crate::exporter::spool::tests::TempDir;
+            use crate::SpoolConfig;
+            use opentelemetry_sdk::error::OTelSdkError;
+            use opentelemetry_sdk::trace::SpanExporter;
+
+            let dir = TempDir::new("deadline");
+            let directory = dir.0.join("traces");
+            std::fs::create_dir_all(&directory).unwrap();
+            for id in 0..3 {
+                std::fs::write(directory.join(format!("{id:020}.pb")), "").unwrap();
+            }
+
+            // each request fits in the timeout, but not the four of them
+            let mut client = OtlpHttpClient::new(
+                Arc::new(SlowClient { delay: TIMEOUT / 2 }),
+                "http://localhost:4318".parse().unwrap(),
+                HashMap::new(),
+                Protocol::HttpBinary,
+                TIMEOUT,
+                RetryConfig::default(),
+                None,
+                PartialSuccessHandler::new(Signal::Traces),
+            );
+            client.spool = Some(SpoolConfig::new(&dir.0).open("traces").unwrap());
+            let start = Instant::now();
+            let result = futures_executor::block_on(client.export(vec![]));
+
+            assert!(matches!(result, Err(OTelSdkError::Timeout(t)) if t == TIMEOUT));
+            assert_timely(start);
+        }
+    }
+
+    #[cfg(feature = "trace")]
+    mod partial_success {
+        use crate::{Protocol, SpanExporter, WithExportConfig, WithHttpConfig};
+        use opentelemetry_http::{Bytes, HttpClient, HttpError, Request, Response};
+        use opentelemetry_proto::tonic::collector::trace::v1::{
+            ExportTracePartialSuccess, ExportTraceServiceResponse,
+        };
+        use opentelemetry_sdk::metrics::data::Sum;
+        use opentelemetry_sdk::metrics::{
+            InMemoryMetricExporter, PeriodicReader, SdkMeterProvider,
+        };
+        use opentelemetry_sdk::trace::SpanExporter as _;
+        use prost::Message;
+
+        /// A client answering every request with a successful response carrying `body`.
+        #[derive(Debug)]
+        struct RespondingClient {
+            body: Bytes,
+        }
+
+        #[async_trait::async_trait]
+        impl HttpClient for RespondingClient {
+            async fn send_bytes(
+                &self,
+                _request: Request<Bytes>,
+            ) -> Result<Response<Bytes>, HttpError> {
+                Ok(Response::new(self.body.clone()))
+            }
+        }
+
+        fn rejected_spans(protocol: Protocol, body: Vec<u8>) -> Vec<u64> {
+            let metric_exporter = InMemoryMetricExporter::default();
+            let meter_provider = SdkMeterProvider::builder()
+                .with_reader(PeriodicReader::builder(metric_exporter.clone()).build())
+                .build();
+
+            let mut exporter = SpanExporter::builder()
+                .with_http()
+                .with_protocol(protocol)
+                .with_http_client(RespondingClient { body: body.into() })
+                .with_meter_provider(&meter_provider)
+                .build()
+                .unwrap();
+            // partial successes are successes
+            futures_executor::block_on(exporter.export(vec![])).unwrap();
+            meter_provider.force_flush().unwrap();
+
+            metric_exporter
+                .get_finished_metrics()
+                .unwrap()
+                .iter()
+                .flat_map(|rm| &rm.scope_metrics)
+                .flat_map(|sm| &sm.metrics)
+                .filter(|m| m.name == "otlp.exporter.spans.rejected")
+                .flat_map(|m| {
+                    let sum = m.data.as_any().downcast_ref::<Sum<u64>>().unwrap();
+                    sum.data_points
+                        .iter()
+                        .map(|dp| dp.value)
+                        .collect::<Vec<_>>()
+                })
+                .collect()
+        }
+
+        #[test]
+        fn test_partial_success_is_counted() {
+            let response = ExportTraceServiceResponse {
+                partial_success: Some(ExportTracePartialSuccess {
+                    rejected_spans: 3,
+                    error_message: "spans too old".into(),
+                }),
+            };
+
+            assert_eq!(
+                rejected_spans(Protocol::HttpBinary, response.encode_to_vec()),
+                vec![3]
+            );
+        }
+
+        #[test]
+        fn test_full_success_is_not_counted() {
+            assert!(rejected_spans(Protocol::HttpBinary, vec![]).is_empty());
+            assert!(rejected_spans(
+                Protocol::HttpBinary,
+                ExportTraceServiceResponse::default().encode_to_vec()
+            )
+            .is_empty());
+        }
+
+        #[cfg(feature = "http-json")]
+        #[test]
+        fn test_json_partial_success_is_counted() {
+            let body =
+                br#"{"partialSuccess":{"rejectedSpans":"2","errorMessage":"spans too old"}}"#;
+
+            assert_eq!(rejected_spans(Protocol::HttpJson, body.to_vec()), vec![2]);
+        }
+    }
 }
@@ -0,0 +1,171 @@
+use crate::exporter::retry::{Backoff, RetryConfig};
+use http::{header::RETRY_AFTER, HeaderMap, Request, Response, StatusCode};
+use opentelemetry::otel_debug;
+use opentelemetry_http::{Bytes, HttpClient, HttpError, HttpStatusError};
+use opentelemetry_sdk::util::timer::sleep;
+use std::error::Error;
+use std::time::{Duration, SystemTime};
+
+/// Sends `request`, retrying transient failures according to `retry_config`.
+///
+/// Requests that failed because of the connection to the collector, and
+/// responses with a status code the OTLP specification marks as retryable, are
+/// retried. Unsuccessful responses reported by the client as an
+/// [`HttpStatusError`] are returned as responses, so callers still have to
+/// check the status of the response of the last attempt.
+pub(crate) async fn send_with_retry(
+    client: &dyn HttpClient,
+    request: Request<Bytes>,
+    retry_config: RetryConfig,
+) -> Result<Response<Bytes>, HttpError> {
+    let mut backoff = Backoff::new(retry_config);
+    loop {
+        let result = match client.send_bytes(clone_request(&request)).await {
+            Err(err) => match err.downcast::<HttpStatusError>() {
+                Ok(err) => Ok(err.into_response()),
+                Err(err) => Err(err),
+            },
+            result => result,
+        };
+        let server_delay = match &result {
+            Ok(response) if is_retryable(response.status()) => retry_after(response.headers()),
+            Err(err) if is_connection_error(err.as_ref()) => None,
+            _ => return result,
+        };
+
+        match backoff.next_delay(server_delay) {
+            Some(delay) => {
+                otel_debug!(
+                    name: "HttpClient.RetryingExport",
+                    delay_ms = delay.as_millis() as u64,
+                    reason = match &result {
+                        Ok(response) => format!("status code {}", response.status().as_u16()),
+                        Err(err) => format!("{err:?}"),
+                    }
+                );
+                sleep(delay).await;
+            }
+            None => return result,
+        }
+    }
+}
+
+/// Status codes that indicate a transient failure, see
+/// <https://github.com/open-telemetry/opentelemetry-proto/blob/main/docs/specification.md#retryable-response-codes>
+pub(super) fn is_retryable(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Whether `err` means the request could not be completed because of the
+/// connection to the collector, e.g. it could not be established, was lost or
+/// timed out, as opposed to an invalid request or configuration.
+fn is_connection_error(err: &(dyn Error + 'static)) -> bool {
+    let mut source = Some(err);
+    while let Some(err) = source {
+        if err.is::<std::io::Error>() {
+            return true;
+        }
+        #[cfg(feature = "reqwest")]
+        if let Some(err) = err.downcast_ref::<reqwest::Error>() {
+            if err.is_connect() || err.is_timeout() {
+                return true;
+            }
+        }
+        source = err.source();
+    }
+    false
+}
+
+/// Parses the `Retry-After` header, which is either a number of seconds or an HTTP date.
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    httpdate::parse_http_date(value)
+        .ok()
+        .map(|date| date.duration_since(SystemTime::now()).unwrap_or_default())
+}
+
+fn clone_request(request: &Request<Bytes>) -> Request<Bytes> {
+    let mut cloned = Request::new(request.body().clone());
+    *cloned.method_mut() = request.method().clone();
+    *cloned.uri_mut() = request.uri().clone();
+    *cloned.version_mut() = request.version();
+    *cloned.headers_mut() = request.headers().clone();
+    cloned
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::HeaderValue;
+
+    #[test]
+    fn test_retryable_status_codes() {
+        for code in [429, 502, 503, 504] {
+            assert!(is_retryable(StatusCode::from_u16(code).unwrap()), "{code}");
+        }
+        for code in [200, 400, 401, 403, 404, 413, 500] {
+            assert!(!is_retryable(StatusCode::from_u16(code).unwrap()), "{code}");
+        }
+    }
+
+    #[test]
+    fn test_connection_errors() {
+        let refused = std::io::Error::from(std::io::ErrorKind::ConnectionRefused);
+        assert!(is_connection_error(&refused));
+
+        let wrapped: HttpError = Box::new(Wrapped(refused));
+        assert!(is_connection_error(wrapped.as_ref()));
+
+        let invalid: HttpError = "invalid header value".into();
+        assert!(!is_connection_error(invalid.as_ref()));
+    }
+
+    /// An error caused by another one.
+    #[derive(Debug)]
+    struct Wrapped(std::io::Error);
+
+    impl std::fmt::Display for Wrapped {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "request failed")
+        }
+    }
+
+    impl Error for Wrapped {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn test_retry_after_header() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("7"));
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(7)));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("not a delay"));
+        assert_eq!(retry_after(&headers), None);
+
+        // dates in the past mean "retry now"
+        headers.insert(
+            RETRY_AFTER,
+            HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT"),
+        );
+        assert_eq!(retry_after(&headers), Some(Duration::ZERO));
+
+        let date = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(120));
+        headers.insert(RETRY_AFTER, HeaderValue::from_str(&date).unwrap());
+        let delay = retry_after(&headers).unwrap();
+        assert!(delay > Duration::from_secs(100) && delay <= Duration::from_secs(120));
+    }
+}
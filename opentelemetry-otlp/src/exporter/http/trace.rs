@@ -1,9 +1,12 @@
 use std::sync::Arc;
 
-use super::OtlpHttpClient;
+use super::{build_request, decode_partial_success, retry::send_with_retry, OtlpHttpClient};
+use crate::exporter::partial_success::Signal;
+use crate::exporter::spool::Payload;
 use futures_core::future::BoxFuture;
-use http::{header::CONTENT_TYPE, Method};
 use opentelemetry::otel_debug;
+use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceResponse;
+use opentelemetry_sdk::util::timer::timeout;
 use opentelemetry_sdk::{
     error::{OTelSdkError, OTelSdkResult},
     trace::{SpanData, SpanExporter},
@@ -23,7 +26,7 @@ impl SpanExporter for OtlpHttpClient {
             Err(err) => return Box::pin(std::future::ready(Err(err))),
         };
 
-        let (body, content_type) = match self.build_trace_export_body(batch) {
+        let (body, content_type, content_encoding) = match self.build_trace_export_body(batch) {
             Ok(body) => body,
             Err(e) => {
                 return Box::pin(std::future::ready(Err(OTelSdkError::InternalFailure(
@@ -32,12 +35,22 @@ impl SpanExporter for OtlpHttpClient {
             }
         };
 
-        let mut request = match http::Request::builder()
-            .method(Method::POST)
-            .uri(&self.collector_endpoint)
-            .header(CONTENT_TYPE, content_type)
-            .body(body.into())
-        {
+        let payload = Payload {
+            body,
+            content_type,
+            content_encoding,
+        };
+
+        if let Some(spool) = &self.spool {
+            return Box::pin(self.export_spooled::<ExportTraceServiceResponse>(
+                Arc::clone(spool),
+                client,
+                payload,
+                Signal::Traces,
+            ));
+        }
+
+        let request = match build_request(&self.collector_endpoint, &self.headers, payload) {
             Ok(req) => req,
             Err(e) => {
                 return Box::pin(std::future::ready(Err(OTelSdkError::InternalFailure(
@@ -46,17 +59,20 @@ impl SpanExporter for OtlpHttpClient {
             }
         };
 
-        for (k, v) in &self.headers {
-            request.headers_mut().insert(k.clone(), v.clone());
-        }
-
+        let retry_config = self.retry_config;
+        let export_timeout = self.timeout;
+        let protocol = self.protocol;
+        let partial_success = self.partial_success.clone();
         Box::pin(async move {
             let request_uri = request.uri().to_string();
             otel_debug!(name: "HttpTracesClient.CallingExport");
-            let response = client
-                .send_bytes(request)
-                .await
-                .map_err(|e| OTelSdkError::InternalFailure(format!("{e:?}")))?;
+            let response = timeout(
+                export_timeout,
+                send_with_retry(client.as_ref(), request, retry_config),
+            )
+            .await
+            .map_err(|_| OTelSdkError::Timeout(export_timeout))?
+            .map_err(|e| OTelSdkError::InternalFailure(format!("{e:?}")))?;
 
             if !response.status().is_success() {
                 let error = format!(
@@ -68,6 +84,12 @@ impl SpanExporter for OtlpHttpClient {
                 return Err(OTelSdkError::InternalFailure(error));
             }
 
+            partial_success.handle(decode_partial_success::<ExportTraceServiceResponse>(
+                protocol,
+                Signal::Traces,
+                response.body(),
+            ));
+
             Ok(())
         })
     }
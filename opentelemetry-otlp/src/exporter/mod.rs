@@ -61,9 +61,26 @@ const OTEL_EXPORTER_OTLP_HTTP_ENDPOINT_DEFAULT: &str = "http://localhost:4318";
 
 #[cfg(any(feature = "http-proto", feature = "http-json"))]
 pub(crate) mod http;
+#[cfg(any(feature = "grpc-tonic", feature = "http-proto", feature = "http-json"))]
+pub(crate) mod partial_success;
+#[cfg(any(feature = "grpc-tonic", feature = "http-proto", feature = "http-json"))]
+pub(crate) mod retry;
+#[cfg(all(
+    any(feature = "grpc-tonic", feature = "http-proto", feature = "http-json"),
+    any(feature = "trace", feature = "logs")
+))]
+pub(crate) mod spool;
 #[cfg(feature = "grpc-tonic")]
 pub(crate) mod tonic;
 
+#[cfg(any(feature = "grpc-tonic", feature = "http-proto", feature = "http-json"))]
+pub use retry::RetryConfig;
+#[cfg(all(
+    any(feature = "grpc-tonic", feature = "http-proto", feature = "http-json"),
+    any(feature = "trace", feature = "logs")
+))]
+pub use spool::{FsyncPolicy, SpoolConfig};
+
 /// Configuration for the OTLP exporter.
 #[derive(Debug)]
 pub struct ExportConfig {
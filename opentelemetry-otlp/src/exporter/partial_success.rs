@@ -0,0 +1,304 @@
+//! Handling of the `partial_success` field of OTLP export responses.
+//!
+//! A collector that accepts an export request may still reject part of its
+//! content, and reports so in the `partial_success` field of the response. Such
+//! responses are not errors and must not be retried, but the rejected items are
+//! lost. See <https://opentelemetry.io/docs/specs/otlp/#partial-success>.
+
+#[cfg(feature = "metrics")]
+use opentelemetry::metrics::{Counter, Meter};
+use opentelemetry::otel_warn;
+
+/// Creates the meter the exporters record their own metrics with.
+#[cfg(feature = "metrics")]
+pub(crate) fn exporter_meter<P>(meter_provider: &P) -> Meter
+where
+    P: opentelemetry::metrics::MeterProvider + ?Sized,
+{
+    meter_provider.meter_with_scope(
+        opentelemetry::InstrumentationScope::builder("opentelemetry-otlp")
+            .with_version(env!("CARGO_PKG_VERSION"))
+            .build(),
+    )
+}
+
+/// The signal an exporter sends, used to label its diagnostics.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Signal {
+    #[cfg(feature = "trace")]
+    Traces,
+    #[cfg(feature = "logs")]
+    Logs,
+    #[cfg(feature = "metrics")]
+    Metrics,
+}
+
+impl Signal {
+    fn as_str(self) -> &'static str {
+        match self {
+            #[cfg(feature = "trace")]
+            Signal::Traces => "traces",
+            #[cfg(feature = "logs")]
+            Signal::Logs => "logs",
+            #[cfg(feature = "metrics")]
+            Signal::Metrics => "metrics",
+        }
+    }
+
+    /// Name of the field holding the number of rejected items in JSON encoded responses.
+    #[cfg(feature = "http-json")]
+    fn rejected_field(self) -> &'static str {
+        match self {
+            #[cfg(feature = "trace")]
+            Signal::Traces => "rejectedSpans",
+            #[cfg(feature = "logs")]
+            Signal::Logs => "rejectedLogRecords",
+            #[cfg(feature = "metrics")]
+            Signal::Metrics => "rejectedDataPoints",
+        }
+    }
+}
+
+/// The content of the `partial_success` field of an export response.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct PartialSuccess {
+    /// The number of spans, log records or data points rejected by the collector.
+    pub(crate) rejected: i64,
+    /// The explanation of the collector, possibly set even if nothing was rejected.
+    pub(crate) error_message: String,
+}
+
+impl PartialSuccess {
+    /// Extracts the `partial_success` of a JSON encoded export response.
+    ///
+    /// The number of rejected items is an `int64`, which the protobuf JSON mapping
+    /// encodes as a string, but plain numbers are accepted as well.
+    #[cfg(feature = "http-json")]
+    pub(crate) fn from_json(body: &[u8], signal: Signal) -> Option<PartialSuccess> {
+        let response: serde_json::Value = serde_json::from_slice(body).ok()?;
+        let partial_success = response.get("partialSuccess")?;
+        let rejected = match partial_success.get(signal.rejected_field()) {
+            Some(serde_json::Value::Number(n)) => n.as_i64()?,
+            Some(serde_json::Value::String(s)) => s.parse().ok()?,
+            _ => 0,
+        };
+        let error_message = partial_success
+            .get("errorMessage")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        Some(PartialSuccess {
+            rejected,
+            error_message,
+        })
+    }
+
+    fn is_empty(&self) -> bool {
+        self.rejected <= 0 && self.error_message.is_empty()
+    }
+}
+
+/// An export service response, which may carry a `partial_success`.
+pub(crate) trait ExportResponse {
+    fn partial_success(self) -> Option<PartialSuccess>;
+}
+
+#[cfg(feature = "trace")]
+impl ExportResponse
+    for opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceResponse
+{
+    fn partial_success(self) -> Option<PartialSuccess> {
+        self.partial_success.map(|p| PartialSuccess {
+            rejected: p.rejected_spans,
+            error_message: p.error_message,
+        })
+    }
+}
+
+#[cfg(feature = "logs")]
+impl ExportResponse for opentelemetry_proto::tonic::collector::logs::v1::ExportLogsServiceResponse {
+    fn partial_success(self) -> Option<PartialSuccess> {
+        self.partial_success.map(|p| PartialSuccess {
+            rejected: p.rejected_log_records,
+            error_message: p.error_message,
+        })
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl ExportResponse
+    for opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceResponse
+{
+    fn partial_success(self) -> Option<PartialSuccess> {
+        self.partial_success.map(|p| PartialSuccess {
+            rejected: p.rejected_data_points,
+            error_message: p.error_message,
+        })
+    }
+}
+
+/// Reports the partial successes of an exporter.
+///
+/// Every partial success is logged as a warning. If the exporter was given a
+/// meter provider, the rejected items are also counted.
+#[derive(Clone, Debug)]
+pub(crate) struct PartialSuccessHandler {
+    signal: Signal,
+    #[cfg(feature = "metrics")]
+    rejected: Option<Counter<u64>>,
+}
+
+impl PartialSuccessHandler {
+    pub(crate) fn new(signal: Signal) -> Self {
+        PartialSuccessHandler {
+            signal,
+            #[cfg(feature = "metrics")]
+            rejected: None,
+        }
+    }
+
+    /// Counts the rejected items with an instrument created from `meter`.
+    #[cfg(feature = "metrics")]
+    pub(crate) fn with_meter(mut self, meter: &Meter) -> Self {
+        let (name, description, unit) = match self.signal {
+            #[cfg(feature = "trace")]
+            Signal::Traces => (
+                "otlp.exporter.spans.rejected",
+                "Number of spans rejected by the collector",
+                "{span}",
+            ),
+            #[cfg(feature = "logs")]
+            Signal::Logs => (
+                "otlp.exporter.log_records.rejected",
+                "Number of log records rejected by the collector",
+                "{log_record}",
+            ),
+            #[cfg(feature = "metrics")]
+            Signal::Metrics => (
+                "otlp.exporter.data_points.rejected",
+                "Number of metric data points rejected by the collector",
+                "{data_point}",
+            ),
+        };
+        self.rejected = Some(
+            meter
+                .u64_counter(name)
+                .with_description(description)
+                .with_unit(unit)
+                .build(),
+        );
+        self
+    }
+
+    /// Reports `partial_success`, unless it is absent or empty.
+    pub(crate) fn handle(&self, partial_success: Option<PartialSuccess>) {
+        let Some(partial_success) = partial_success.filter(|p| !p.is_empty()) else {
+            return;
+        };
+
+        otel_warn!(
+            name: "OtlpExporter.PartialSuccess",
+            signal = self.signal.as_str(),
+            rejected = partial_success.rejected,
+            error_message = partial_success.error_message.as_str()
+        );
+
+        #[cfg(feature = "metrics")]
+        if let (Some(counter), Ok(rejected)) =
+            (&self.rejected, u64::try_from(partial_success.rejected))
+        {
+            if rejected > 0 {
+                counter.add(rejected, &[]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_partial_success() {
+        assert!(PartialSuccess::default().is_empty());
+        assert!(!PartialSuccess {
+            rejected: 3,
+            error_message: String::new(),
+        }
+        .is_empty());
+        assert!(!PartialSuccess {
+            rejected: 0,
+            error_message: "deprecated attribute".into(),
+        }
+        .is_empty());
+    }
+
+    #[cfg(feature = "http-json")]
+    #[test]
+    fn test_partial_success_from_json() {
+        assert_eq!(PartialSuccess::from_json(b"{}", Signal::Traces), None);
+        assert_eq!(PartialSuccess::from_json(b"", Signal::Traces), None);
+        assert_eq!(
+            PartialSuccess::from_json(
+                br#"{"partialSuccess":{"rejectedSpans":"3","errorMessage":"too old"}}"#,
+                Signal::Traces
+            ),
+            Some(PartialSuccess {
+                rejected: 3,
+                error_message: "too old".into(),
+            })
+        );
+        assert_eq!(
+            PartialSuccess::from_json(
+                br#"{"partialSuccess":{"rejectedDataPoints":5}}"#,
+                Signal::Metrics
+            ),
+            Some(PartialSuccess {
+                rejected: 5,
+                error_message: String::new(),
+            })
+        );
+        assert_eq!(
+            PartialSuccess::from_json(br#"{"partialSuccess":{}}"#, Signal::Traces),
+            Some(PartialSuccess::default())
+        );
+    }
+
+    #[cfg(all(feature = "trace", feature = "metrics"))]
+    #[test]
+    fn test_rejected_items_are_counted() {
+        use opentelemetry::metrics::MeterProvider;
+        use opentelemetry_sdk::metrics::data::Sum;
+        use opentelemetry_sdk::metrics::{
+            InMemoryMetricExporter, PeriodicReader, SdkMeterProvider,
+        };
+
+        let exporter = InMemoryMetricExporter::default();
+        let meter_provider = SdkMeterProvider::builder()
+            .with_reader(PeriodicReader::builder(exporter.clone()).build())
+            .build();
+        let handler =
+            PartialSuccessHandler::new(Signal::Traces).with_meter(&meter_provider.meter("test"));
+
+        handler.handle(None);
+        handler.handle(Some(PartialSuccess {
+            rejected: 0,
+            error_message: "warning only".into(),
+        }));
+        handler.handle(Some(PartialSuccess {
+            rejected: 3,
+            error_message: "too old".into(),
+        }));
+        handler.handle(Some(PartialSuccess {
+            rejected: 4,
+            error_message: String::new(),
+        }));
+        meter_provider.force_flush().unwrap();
+
+        let metrics = exporter.get_finished_metrics().unwrap();
+        let metric = &metrics[0].scope_metrics[0].metrics[0];
+        assert_eq!(metric.name, "otlp.exporter.spans.rejected");
+        let sum = metric.data.as_any().downcast_ref::<Sum<u64>>().unwrap();
+        assert_eq!(sum.data_points[0].value, 7);
+    }
+}
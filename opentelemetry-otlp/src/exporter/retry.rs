@@ -0,0 +1,206 @@
+//! Retry policy shared by the OTLP exporters.
+//!
+//! The OTLP specification requires exporters to retry transient failures using an
+//! exponential backoff strategy with jitter. See
+//! <https://github.com/open-telemetry/opentelemetry-specification/blob/main/specification/protocol/exporter.md#retry>
+//! and <https://github.com/open-telemetry/opentelemetry-proto/blob/main/docs/specification.md#failures>.
+
+use rand::Rng;
+use std::time::{Duration, Instant};
+
+/// Default delay before the first retry.
+const DEFAULT_INITIAL_INTERVAL: Duration = Duration::from_secs(1);
+/// Default upper bound for the delay between two retries.
+const DEFAULT_MAX_INTERVAL: Duration = Duration::from_secs(30);
+/// Default upper bound for the total time spent retrying a single export.
+const DEFAULT_MAX_ELAPSED_TIME: Duration = Duration::from_secs(60);
+/// Factor the delay is multiplied by after each attempt.
+const MULTIPLIER: f64 = 1.5;
+/// Fraction of the delay used as random jitter in both directions.
+const RANDOMIZATION_FACTOR: f64 = 0.5;
+
+/// Configuration of the retry behavior of the OTLP exporters.
+///
+/// Failed exports that the collector reports as transient (or that failed to reach the
+/// collector at all) are retried with an exponentially growing, randomized delay until
+/// either the export succeeds or [`max_elapsed_time`](RetryConfig::max_elapsed_time) is
+/// exceeded. A delay requested by the server (`Retry-After` for HTTP, `RetryInfo` for gRPC)
+/// takes precedence over the computed delay.
+///
+/// The export timeout of the HTTP exporters (see
+/// [`WithExportConfig::with_timeout`]) bounds each export as a whole, including
+/// all its attempts and the delays between them: with the default 10 seconds
+/// timeout, an export is attempted about 5 times before giving up, regardless
+/// of `max_elapsed_time`. The gRPC exporters apply the timeout to each attempt.
+///
+/// The same configuration is used by the HTTP ([`WithHttpConfig::with_retry_config`]) and
+/// gRPC ([`WithTonicConfig::with_retry_config`]) exporters.
+///
+/// [`WithExportConfig::with_timeout`]: crate::WithExportConfig::with_timeout
+/// [`WithHttpConfig::with_retry_config`]: crate::WithHttpConfig::with_retry_config
+/// [`WithTonicConfig::with_retry_config`]: crate::WithTonicConfig::with_retry_config
+///
+/// ## Examples
+///
+/// ```
+/// # #[cfg(all(feature = "trace", feature = "http-proto"))]
+/// # {
+/// use opentelemetry_otlp::{RetryConfig, WithHttpConfig};
+/// use std::time::Duration;
+///
+/// let exporter_builder = opentelemetry_otlp::SpanExporter::builder()
+///     .with_http()
+///     .with_retry_config(RetryConfig {
+///         max_elapsed_time: Duration::from_secs(10),
+///         ..RetryConfig::default()
+///     });
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryConfig {
+    /// Whether failed exports are retried at all.
+    pub enabled: bool,
+    /// The delay before the first retry.
+    pub initial_interval: Duration,
+    /// The upper bound for the delay between two retries.
+    pub max_interval: Duration,
+    /// The upper bound for the total time spent on one export, including retries.
+    ///
+    /// No retry is attempted if its delay would exceed this budget.
+    pub max_elapsed_time: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        RetryConfig {
+            enabled: true,
+            initial_interval: DEFAULT_INITIAL_INTERVAL,
+            max_interval: DEFAULT_MAX_INTERVAL,
+            max_elapsed_time: DEFAULT_MAX_ELAPSED_TIME,
+        }
+    }
+}
+
+impl RetryConfig {
+    /// A configuration that never retries failed exports.
+    pub fn disabled() -> Self {
+        RetryConfig {
+            enabled: false,
+            ..RetryConfig::default()
+        }
+    }
+}
+
+/// Tracks the state of the retries of a single export.
+#[derive(Debug)]
+pub(crate) struct Backoff {
+    config: RetryConfig,
+    start: Instant,
+    current_interval: Duration,
+}
+
+impl Backoff {
+    pub(crate) fn new(config: RetryConfig) -> Self {
+        Backoff {
+            config,
+            start: Instant::now(),
+            current_interval: config.initial_interval,
+        }
+    }
+
+    /// Whether an attempt made now may still be followed by a retry.
+    #[cfg_attr(not(feature = "grpc-tonic"), allow(dead_code))]
+    pub(crate) fn may_retry(&self) -> bool {
+        self.config.enabled && self.start.elapsed() < self.config.max_elapsed_time
+    }
+
+    /// Returns how long to wait before the next attempt, or `None` if the export
+    /// should not be retried anymore.
+    ///
+    /// `server_delay` is the delay requested by the server, if any, and is used
+    /// instead of the computed delay.
+    pub(crate) fn next_delay(&mut self, server_delay: Option<Duration>) -> Option<Duration> {
+        if !self.config.enabled {
+            return None;
+        }
+
+        let delay = server_delay.unwrap_or_else(|| jitter(self.current_interval));
+        if self.start.elapsed() + delay > self.config.max_elapsed_time {
+            return None;
+        }
+
+        self.current_interval = self
+            .current_interval
+            .mul_f64(MULTIPLIER)
+            .min(self.config.max_interval);
+
+        Some(delay)
+    }
+}
+
+fn jitter(interval: Duration) -> Duration {
+    let delta = interval.as_secs_f64() * RANDOMIZATION_FACTOR;
+    if delta <= 0.0 {
+        return interval;
+    }
+    let secs = interval.as_secs_f64();
+    Duration::from_secs_f64(rand::thread_rng().gen_range(secs - delta..=secs + delta))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(max_elapsed_time: Duration) -> RetryConfig {
+        RetryConfig {
+            enabled: true,
+            initial_interval: Duration::from_millis(100),
+            max_interval: Duration::from_millis(200),
+            max_elapsed_time,
+        }
+    }
+
+    #[test]
+    fn test_backoff_grows_and_is_capped() {
+        let mut backoff = Backoff::new(config(Duration::from_secs(60)));
+
+        let expected = [100.0, 150.0, 200.0, 200.0];
+        for expected in expected {
+            let delay = backoff.next_delay(None).unwrap().as_secs_f64() * 1000.0;
+            assert!(
+                delay >= expected * 0.5 && delay <= expected * 1.5,
+                "delay {delay}ms is outside of the jitter range of {expected}ms"
+            );
+        }
+    }
+
+    #[test]
+    fn test_backoff_uses_server_delay() {
+        let mut backoff = Backoff::new(config(Duration::from_secs(60)));
+
+        assert_eq!(
+            backoff.next_delay(Some(Duration::from_secs(3))),
+            Some(Duration::from_secs(3))
+        );
+    }
+
+    #[test]
+    fn test_backoff_respects_max_elapsed_time() {
+        let mut backoff = Backoff::new(config(Duration::from_secs(1)));
+
+        assert_eq!(backoff.next_delay(Some(Duration::from_secs(2))), None);
+        assert!(backoff.next_delay(None).is_some());
+        assert!(backoff.may_retry());
+        assert!(!Backoff::new(config(Duration::ZERO)).may_retry());
+    }
+
+    #[test]
+    fn test_disabled_backoff_never_retries() {
+        let mut backoff = Backoff::new(RetryConfig::disabled());
+
+        assert!(!backoff.may_retry());
+
+        assert_eq!(backoff.next_delay(None), None);
+        assert_eq!(backoff.next_delay(Some(Duration::ZERO)), None);
+    }
+}
@@ -0,0 +1,762 @@
+//! Write-ahead spool persisting the export requests of the OTLP exporters on
+//! disk.
+//!
+//! Each export request is written to its own file before being sent, and the
+//! file is deleted once the collector accepted the request. Requests are sent
+//! oldest first, so requests left over by a collector outage or a restart of
+//! the process are replayed with the next exports, a few of them at a time so
+//! that an export never blocks its processor for the whole backlog.
+//!
+//! The requests are written to the disk by a dedicated thread, so that the
+//! exports don't block the async tasks running them while the files are
+//! written and flushed.
+
+use futures_channel::oneshot;
+use opentelemetry::{otel_info, otel_warn};
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::future::Future;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex, Weak};
+use std::thread;
+
+/// Default upper bound for the total size of the spooled requests of a signal.
+const DEFAULT_MAX_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Default upper bound for the number of requests sent by each export.
+const DEFAULT_MAX_REQUESTS_PER_EXPORT: usize = 8;
+
+/// File extensions of the supported content types.
+const CONTENT_TYPES: [(&str, &str); 2] = [
+    ("application/x-protobuf", "pb"),
+    ("application/json", "json"),
+];
+
+/// File extensions of the supported content encodings.
+const CONTENT_ENCODINGS: [(&str, &str); 2] = [("gzip", "gz"), ("zstd", "zst")];
+
+/// Extension of the files being written, which are ignored when recovering.
+const PARTIAL_EXTENSION: &str = "tmp";
+
+/// When the spooled export requests are flushed to the disk.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FsyncPolicy {
+    /// Flush each request, and the spool directory, to the disk before sending
+    /// the request, so that it survives power losses.
+    #[default]
+    Always,
+    /// Leave flushing to the operating system. Spooled requests survive
+    /// restarts of the process, but may be lost on power losses.
+    Never,
+}
+
+/// Configuration of the disk spool of the OTLP span and log exporters.
+///
+/// With a spool, the exporter writes each export request to the spool
+/// directory before sending it, and deletes it once the collector accepted
+/// it. Requests that could not be delivered, because the collector was
+/// unreachable or the process stopped, stay in the spool and are sent again,
+/// oldest first, with the next exports, including the first export after a
+/// restart.
+///
+/// The spooled requests are only sent by exports: they are not sent as soon as
+/// the collector becomes reachable again, but with the next spans or log
+/// records. Each export sends at most [`max_requests_per_export`] requests, so
+/// that replaying a large backlog does not block the processor calling the
+/// exporter, and the backlog is drained over the following exports. An export
+/// whose request was spooled but not sent yet succeeds.
+///
+/// Requests rejected by the collector with a non-retryable error are dropped
+/// from the spool. When the spool is full, the oldest requests are evicted to
+/// make room for the new ones.
+///
+/// The spans and log records are spooled as encoded OTLP export requests, in
+/// the `traces` and `logs` subdirectories of the spool directory, so several
+/// exporters can share the same configuration. A directory must not be used
+/// by several processes at once.
+///
+/// The same configuration is used by the HTTP ([`WithHttpConfig::with_spool`])
+/// and gRPC ([`WithTonicConfig::with_spool`]) exporters.
+///
+/// [`WithHttpConfig::with_spool`]: crate::WithHttpConfig::with_spool
+/// [`WithTonicConfig::with_spool`]: crate::WithTonicConfig::with_spool
+/// [`max_requests_per_export`]: SpoolConfig::max_requests_per_export
+///
+/// ## Examples
+///
+/// ```
+/// # #[cfg(all(feature = "trace", feature = "http-proto"))]
+/// # {
+/// use opentelemetry_otlp::{SpoolConfig, WithHttpConfig};
+///
+/// let exporter_builder = opentelemetry_otlp::SpanExporter::builder()
+///     .with_http()
+///     .with_spool(SpoolConfig {
+///         max_size: 16 * 1024 * 1024,
+///         ..SpoolConfig::new("/var/lib/my-service/otlp")
+///     });
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SpoolConfig {
+    /// The directory of the spool, created if missing.
+    pub directory: PathBuf,
+    /// The upper bound for the total size of the spooled requests of each
+    /// signal, in bytes.
+    pub max_size: u64,
+    /// When the spooled requests are flushed to the disk.
+    pub fsync: FsyncPolicy,
+    /// The upper bound for the number of spooled requests sent by each export,
+    /// including the request of the export itself. Values below 1 are
+    /// treated as 1.
+    pub max_requests_per_export: usize,
+}
+
+impl SpoolConfig {
+    /// A spool in `directory`, holding up to 64 MiB of requests per signal,
+    /// flushing each request to the disk and sending up to 8 requests per
+    /// export.
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        SpoolConfig {
+            directory: directory.into(),
+            max_size: DEFAULT_MAX_SIZE,
+            fsync: FsyncPolicy::default(),
+            max_requests_per_export: DEFAULT_MAX_REQUESTS_PER_EXPORT,
+        }
+    }
+
+    /// Open the spool of `signal`.
+    pub(crate) fn open(&self, signal: &str) -> Result<Arc<Spool>, crate::Error> {
+        Spool::open(self, signal).map_err(crate::Error::Spool)
+    }
+}
+
+/// The body of an export request, as sent to the collector.
+#[derive(Debug)]
+pub(crate) struct Payload {
+    pub(crate) body: Vec<u8>,
+    pub(crate) content_type: &'static str,
+    pub(crate) content_encoding: Option<&'static str>,
+}
+
+/// Why sending a spooled request failed.
+#[derive(Debug)]
+pub(crate) enum SendError {
+    /// The collector could not be reached, or asked to retry later: the
+    /// request stays in the spool.
+    Transient(String),
+    /// The collector rejected the request: sending it again would fail the
+    /// same way, so it is dropped from the spool.
+    Rejected(String),
+}
+
+/// A request to write to the spool, and where to send its id to.
+type WriteJob = (Payload, oneshot::Sender<Result<u64, (io::Error, Payload)>>);
+
+/// The spooled requests of one signal.
+#[derive(Debug)]
+pub(crate) struct Spool {
+    directory: PathBuf,
+    max_size: u64,
+    fsync: FsyncPolicy,
+    max_requests_per_export: usize,
+    state: Mutex<State>,
+    /// Whether an export is sending the spooled requests.
+    draining: AtomicBool,
+    /// Sends the requests to write to the writer thread, or `None` if the
+    /// thread could not be spawned and the exports write them.
+    writer: Option<mpsc::Sender<WriteJob>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// The spooled requests, oldest first.
+    entries: VecDeque<Entry>,
+    size: u64,
+    next_id: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    id: u64,
+    file_name: String,
+    size: u64,
+    content_type: &'static str,
+    content_encoding: Option<&'static str>,
+}
+
+impl Spool {
+    /// Open the spool of `signal`, recovering the requests left over by a
+    /// previous process, and start its writer thread.
+    pub(crate) fn open(config: &SpoolConfig, signal: &str) -> io::Result<Arc<Self>> {
+        let directory = config.directory.join(signal);
+        fs::create_dir_all(&directory)?;
+
+        let mut entries = Vec::new();
+        for dir_entry in fs::read_dir(&directory)? {
+            let dir_entry = dir_entry?;
+            let Ok(file_name) = dir_entry.file_name().into_string() else {
+                continue;
+            };
+            if file_name.ends_with(PARTIAL_EXTENSION) {
+                // interrupted while being written, it was never sent
+                let _ = fs::remove_file(dir_entry.path());
+                continue;
+            }
+            let Some((id, content_type, content_encoding)) = parse_file_name(&file_name) else {
+                continue;
+            };
+            entries.push(Entry {
+                id,
+                file_name,
+                size: dir_entry.metadata()?.len(),
+                content_type,
+                content_encoding,
+            });
+        }
+        entries.sort_by_key(|entry| entry.id);
+
+        let mut state = State {
+            next_id: entries.last().map_or(0, |entry| entry.id + 1),
+            size: entries.iter().map(|entry| entry.size).sum(),
+            entries: entries.into(),
+        };
+        if !state.entries.is_empty() {
+            otel_info!(
+                name: "OtlpSpool.Recovered",
+                directory = format!("{}", directory.display()),
+                requests = state.entries.len(),
+                bytes = state.size
+            );
+        }
+
+        state.evict(&directory, config.max_size, 0);
+
+        Ok(Arc::new_cyclic(|spool| Spool {
+            writer: spawn_writer(signal, Weak::clone(spool)),
+            directory,
+            max_size: config.max_size,
+            fsync: config.fsync,
+            max_requests_per_export: config.max_requests_per_export.max(1),
+            state: Mutex::new(state),
+            draining: AtomicBool::new(false),
+        }))
+    }
+
+    /// Spool `payload`, then send the spooled requests with `send`, oldest
+    /// first, until they are all sent, one of them fails transiently, or
+    /// `max_requests_per_export` of them were sent.
+    ///
+    /// Returns the error of the first transient failure, or the error of
+    /// `payload` if the collector rejected it. If another export is already
+    /// sending the spooled requests, or if older requests used up the limit,
+    /// `payload` is left in the spool for the next exports to send.
+    ///
+    /// If the returned future is dropped, `payload` is still spooled and sent
+    /// by the next exports.
+    pub(crate) async fn export<F, Fut>(&self, payload: Payload, mut send: F) -> Result<(), String>
+    where
+        F: FnMut(Payload) -> Fut,
+        Fut: Future<Output = Result<(), SendError>>,
+    {
+        let id = match self.write(payload).await {
+            Ok(id) => id,
+            Err((err, payload)) => {
+                otel_warn!(
+                    name: "OtlpSpool.WriteFailed",
+                    directory = format!("{}", self.directory.display()),
+                    error = err.to_string()
+                );
+                // still try to deliver it, without persisting it
+                return match payload {
+                    Some(payload) => send(payload).await.map_err(SendError::into_message),
+                    None => Err(format!("failed to spool the request: {err}")),
+                };
+            }
+        };
+
+        let Some(_draining) = DrainingGuard::acquire(&self.draining) else {
+            return Ok(());
+        };
+
+        let mut result = Ok(());
+        for _ in 0..self.max_requests_per_export {
+            let (entry_id, payload) = match self.oldest() {
+                Ok(Some(oldest)) => oldest,
+                Ok(None) => return result,
+                Err(err) => return Err(format!("failed to read the spooled request: {err}")),
+            };
+            match send(payload).await {
+                Ok(()) => self.remove(entry_id),
+                Err(SendError::Rejected(err)) => {
+                    self.remove(entry_id);
+                    if entry_id == id {
+                        result = Err(err);
+                    } else {
+                        otel_warn!(
+                            name: "OtlpSpool.RequestRejected",
+                            directory = format!("{}", self.directory.display()),
+                            error = err
+                        );
+                    }
+                }
+                Err(SendError::Transient(err)) => return Err(err),
+            }
+        }
+        result
+    }
+
+    /// Write `payload` to the spool on the writer thread, and return its id.
+    ///
+    /// On failure, `payload` is given back, unless the writer thread stopped
+    /// while writing it.
+    async fn write(&self, payload: Payload) -> Result<u64, (io::Error, Option<Payload>)> {
+        let payload = match &self.writer {
+            Some(writer) => {
+                let (sender, written) = oneshot::channel();
+                match writer.send((payload, sender)) {
+                    Ok(()) => {
+                        return match written.await {
+                            Ok(result) => result.map_err(|(err, payload)| (err, Some(payload))),
+                            Err(_) => Err((io::Error::other("the writer thread stopped"), None)),
+                        };
+                    }
+                    // the writer thread stopped, write it from here
+                    Err(mpsc::SendError((payload, _))) => payload,
+                }
+            }
+            None => payload,
+        };
+        self.push(&payload).map_err(|err| (err, Some(payload)))
+    }
+
+    /// Write `payload` to the spool, evicting the oldest requests if needed,
+    /// and return its id.
+    fn push(&self, payload: &Payload) -> io::Result<u64> {
+        let size = payload.body.len() as u64;
+        if size > self.max_size {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "the request of {size} bytes exceeds the spool size of {} bytes",
+                    self.max_size
+                ),
+            ));
+        }
+        let file_name = file_name_of(payload).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unsupported content type {}", payload.content_type),
+            )
+        })?;
+
+        let mut state = self.lock()?;
+        state.evict(&self.directory, self.max_size, size);
+
+        let id = state.next_id;
+        let file_name = format!("{id:020}.{file_name}");
+        let partial = self.directory.join(format!("{id:020}.{PARTIAL_EXTENSION}"));
+        let mut file = File::create(&partial)?;
+        file.write_all(&payload.body)?;
+        if self.fsync == FsyncPolicy::Always {
+            file.sync_all()?;
+        }
+        fs::rename(&partial, self.directory.join(&file_name))?;
+        if self.fsync == FsyncPolicy::Always {
+            sync_directory(&self.directory)?;
+        }
+
+        state.next_id += 1;
+        state.size += size;
+        state.entries.push_back(Entry {
+            id,
+            file_name,
+            size,
+            content_type: payload.content_type,
+            content_encoding: payload.content_encoding,
+        });
+        Ok(id)
+    }
+
+    /// Read the oldest spooled request.
+    fn oldest(&self) -> io::Result<Option<(u64, Payload)>> {
+        let mut state = self.lock()?;
+        while let Some(entry) = state.entries.front() {
+            match fs::read(self.directory.join(&entry.file_name)) {
+                Ok(body) => {
+                    let payload = Payload {
+                        body,
+                        content_type: entry.content_type,
+                        content_encoding: entry.content_encoding,
+                    };
+                    return Ok(Some((entry.id, payload)));
+                }
+                // deleted behind our back, nothing left to send
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                    let size = entry.size;
+                    state.entries.pop_front();
+                    state.size -= size;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Delete the spooled request `id`.
+    fn remove(&self, id: u64) {
+        let Ok(mut state) = self.lock() else {
+            return;
+        };
+        if let Some(index) = state.entries.iter().position(|entry| entry.id == id) {
+            if let Some(entry) = state.entries.remove(index) {
+                state.size -= entry.size;
+                let _ = fs::remove_file(self.directory.join(entry.file_name));
+            }
+        }
+    }
+
+    fn lock(&self) -> io::Result<std::sync::MutexGuard<'_, State>> {
+        self.state
+            .lock()
+            .map_err(|err| io::Error::other(err.to_string()))
+    }
+}
+
+impl State {
+    /// Delete the oldest requests until `incoming` more bytes fit in
+    /// `max_size`.
+    fn evict(&mut self, directory: &Path, max_size: u64, incoming: u64) {
+        let mut evicted = 0;
+        while self.size + incoming > max_size {
+            let Some(entry) = self.entries.pop_front() else {
+                break;
+            };
+            self.size -= entry.size;
+            let _ = fs::remove_file(directory.join(entry.file_name));
+            evicted += 1;
+        }
+        if evicted > 0 {
+            otel_warn!(
+                name: "OtlpSpool.RequestsEvicted",
+                directory = format!("{}", directory.display()),
+                requests = evicted
+            );
+        }
+    }
+}
+
+impl SendError {
+    fn into_message(self) -> String {
+        match self {
+            SendError::Transient(err) | SendError::Rejected(err) => err,
+        }
+    }
+}
+
+/// Spawn the thread writing the requests of `spool` to the disk.
+///
+/// The thread stops once the spool is dropped.
+fn spawn_writer(signal: &str, spool: Weak<Spool>) -> Option<mpsc::Sender<WriteJob>> {
+    let (sender, jobs) = mpsc::channel::<WriteJob>();
+    let spawned = thread::Builder::new()
+        .name(format!("OpenTelemetry.OtlpSpool.{signal}"))
+        .spawn(move || {
+            for (payload, written) in jobs {
+                let Some(spool) = spool.upgrade() else {
+                    break;
+                };
+                let result = spool.push(&payload).map_err(|err| (err, payload));
+                let _ = written.send(result);
+            }
+        });
+    match spawned {
+        Ok(_) => Some(sender),
+        Err(err) => {
+            otel_warn!(
+                name: "OtlpSpool.WriterNotStarted",
+                error = err.to_string()
+            );
+            None
+        }
+    }
+}
+
+/// Marks the spooled requests as being sent until dropped.
+struct DrainingGuard<'a>(&'a AtomicBool);
+
+impl<'a> DrainingGuard<'a> {
+    fn acquire(draining: &'a AtomicBool) -> Option<Self> {
+        draining
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Acquire)
+            .ok()
+            .map(|_| DrainingGuard(draining))
+    }
+}
+
+impl Drop for DrainingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(false, Ordering::Release);
+    }
+}
+
+/// The extensions of the file holding `payload`, e.g. `pb.gz`.
+fn file_name_of(payload: &Payload) -> Option<String> {
+    let (_, content_type) = CONTENT_TYPES
+        .iter()
+        .find(|(content_type, _)| *content_type == payload.content_type)?;
+    match payload.content_encoding {
+        None => Some(content_type.to_string()),
+        Some(content_encoding) => {
+            let (_, encoding) = CONTENT_ENCODINGS
+                .iter()
+                .find(|(encoding, _)| *encoding == content_encoding)?;
+            Some(format!("{content_type}.{encoding}"))
+        }
+    }
+}
+
+/// Parse the id, content type and content encoding of a spooled request from
+/// its file name.
+fn parse_file_name(file_name: &str) -> Option<(u64, &'static str, Option<&'static str>)> {
+    let mut parts = file_name.split('.');
+    let id = parts.next()?.parse().ok()?;
+    let extension = parts.next()?;
+    let (content_type, _) = CONTENT_TYPES
+        .iter()
+        .find(|(_, content_type)| *content_type == extension)?;
+    let content_encoding = match parts.next() {
+        None => None,
+        Some(extension) => Some(
+            CONTENT_ENCODINGS
+                .iter()
+                .find(|(_, encoding)| *encoding == extension)?
+                .0,
+        ),
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((id, content_type, content_encoding))
+}
+
+#[cfg(unix)]
+fn sync_directory(directory: &Path) -> io::Result<()> {
+    File::open(directory)?.sync_all()
+}
+
+#[cfg(not(unix))]
+fn sync_directory(_directory: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    /// A temporary directory, removed when dropped.
+    pub(crate) struct TempDir(pub(crate) PathBuf);
+
+    impl TempDir {
+        pub(crate) fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "otlp-spool-{name}-{}-{}",
+                std::process::id(),
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_nanos()
+            ));
+            TempDir(path)
+        }
+
+        fn config(&self, max_size: u64) -> SpoolConfig {
+            SpoolConfig {
+                max_size,
+                ..SpoolConfig::new(&self.0)
+            }
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn payload(body: &str) -> Payload {
+        Payload {
+            body: body.as_bytes().to_vec(),
+            content_type: "application/x-protobuf",
+            content_encoding: None,
+        }
+    }
+
+    fn export(
+        spool: &Spool,
+        body: &str,
+        outcome: fn(&str) -> Result<(), SendError>,
+    ) -> (Result<(), String>, Vec<String>) {
+        let mut sent = Vec::new();
+        let result = futures_executor::block_on(spool.export(payload(body), |payload| {
+            let body = String::from_utf8(payload.body).unwrap();
+            let result = outcome(&body);
+            sent.push(body);
+            std::future::ready(result)
+        }));
+        (result, sent)
+    }
+
+    fn unavailable(_body: &str) -> Result<(), SendError> {
+        Err(SendError::Transient("unavailable".into()))
+    }
+
+    fn accepted(_body: &str) -> Result<(), SendError> {
+        Ok(())
+    }
+
+    #[test]
+    fn test_replays_spooled_requests_after_restart() {
+        let dir = TempDir::new("replay");
+        {
+            let spool = Spool::open(&dir.config(1024), "traces").unwrap();
+            assert_eq!(
+                export(&spool, "first", unavailable),
+                (Err("unavailable".into()), vec!["first".into()])
+            );
+            assert_eq!(
+                export(&spool, "second", unavailable),
+                (Err("unavailable".into()), vec!["first".into()])
+            );
+        }
+
+        let spool = Spool::open(&dir.config(1024), "traces").unwrap();
+        assert_eq!(
+            export(&spool, "third", accepted),
+            (
+                Ok(()),
+                vec!["first".into(), "second".into(), "third".into()]
+            )
+        );
+        assert!(spool.oldest().unwrap().is_none());
+        assert_eq!(fs::read_dir(dir.0.join("traces")).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_evicts_oldest_requests_when_full() {
+        let dir = TempDir::new("evict");
+        let spool = Spool::open(&dir.config(10), "logs").unwrap();
+        for body in ["aaaa", "bbbb", "cccc"] {
+            let _ = export(&spool, body, unavailable);
+        }
+
+        let (result, sent) = export(&spool, "dddd", accepted);
+        assert_eq!(result, Ok(()));
+        assert_eq!(sent, vec!["cccc".to_string(), "dddd".to_string()]);
+
+        // requests larger than the spool are sent without being spooled
+        let (result, sent) = export(&spool, "larger than the spool", accepted);
+        assert_eq!(result, Ok(()));
+        assert_eq!(sent, vec!["larger than the spool".to_string()]);
+    }
+
+    #[test]
+    fn test_limits_requests_sent_per_export() {
+        let dir = TempDir::new("limit");
+        let config = SpoolConfig {
+            max_requests_per_export: 2,
+            ..dir.config(1024)
+        };
+        let spool = Spool::open(&config, "traces").unwrap();
+        for body in ["a", "b", "c"] {
+            let _ = export(&spool, body, unavailable);
+        }
+
+        assert_eq!(
+            export(&spool, "d", accepted),
+            (Ok(()), vec!["a".into(), "b".into()])
+        );
+        assert_eq!(
+            export(&spool, "e", accepted),
+            (Ok(()), vec!["c".into(), "d".into()])
+        );
+        assert_eq!(
+            export(&spool, "f", accepted),
+            (Ok(()), vec!["e".into(), "f".into()])
+        );
+        assert!(spool.oldest().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_drops_rejected_requests() {
+        let dir = TempDir::new("reject");
+        let spool = Spool::open(&dir.config(1024), "traces").unwrap();
+        let _ = export(&spool, "invalid", unavailable);
+
+        let (result, sent) = export(&spool, "valid", |body| match body {
+            "invalid" => Err(SendError::Rejected("bad request".into())),
+            _ => Ok(()),
+        });
+        assert_eq!(result, Ok(()));
+        assert_eq!(sent, vec!["invalid".to_string(), "valid".to_string()]);
+
+        let (result, _) = export(&spool, "invalid", |_| {
+            Err(SendError::Rejected("bad request".into()))
+        });
+        assert_eq!(result, Err("bad request".into()));
+        assert!(spool.oldest().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_ignores_partial_and_unknown_files() {
+        let dir = TempDir::new("recover");
+        let directory = dir.0.join("logs");
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("00000000000000000003.json.gz"), "json").unwrap();
+        fs::write(directory.join("00000000000000000004.tmp"), "partial").unwrap();
+        fs::write(directory.join("README"), "unrelated").unwrap();
+
+        let spool = Spool::open(&dir.config(1024), "logs").unwrap();
+        assert!(!directory.join("00000000000000000004.tmp").exists());
+        let (id, payload) = spool.oldest().unwrap().unwrap();
+        assert_eq!(id, 3);
+        assert_eq!(payload.content_type, "application/json");
+        assert_eq!(payload.content_encoding, Some("gzip"));
+
+        let attempts = AtomicUsize::new(0);
+        let result = futures_executor::block_on(spool.export(payload_with_encoding(), |_| {
+            attempts.fetch_add(1, Ordering::Relaxed);
+            std::future::ready(Ok(()))
+        }));
+        assert_eq!(result, Ok(()));
+        assert_eq!(attempts.load(Ordering::Relaxed), 2);
+        assert!(directory.join("README").exists());
+    }
+
+    fn payload_with_encoding() -> Payload {
+        Payload {
+            content_encoding: Some("zstd"),
+            ..payload("zstd")
+        }
+    }
+
+    #[test]
+    fn test_file_names() {
+        let payload = payload_with_encoding();
+        assert_eq!(file_name_of(&payload), Some("pb.zst".to_string()));
+        assert_eq!(
+            parse_file_name("00000000000000000042.pb.zst"),
+            Some((42, "application/x-protobuf", Some("zstd")))
+        );
+        assert_eq!(
+            parse_file_name("00000000000000000042.json"),
+            Some((42, "application/json", None))
+        );
+        assert_eq!(parse_file_name("00000000000000000042.pb.br"), None);
+        assert_eq!(parse_file_name("00000000000000000042.pb.gz.old"), None);
+        assert_eq!(parse_file_name("latest.pb"), None);
+    }
+}
@@ -5,15 +5,23 @@ use opentelemetry_proto::tonic::collector::logs::v1::{
 };
 use opentelemetry_sdk::logs::{LogBatch, LogExporter};
 use opentelemetry_sdk::logs::{LogError, LogResult};
+use std::sync::Arc;
 use tonic::{codegen::CompressionEncoding, service::Interceptor, transport::Channel, Request};
 
 use opentelemetry_proto::transform::logs::tonic::group_logs_by_resource_and_scope;
 
-use super::BoxInterceptor;
+use super::{
+    decode_spooled, retry::retry_with_backoff, send_error, spooled_payload, BoxInterceptor,
+};
+use crate::exporter::partial_success::{ExportResponse, PartialSuccessHandler};
+use crate::exporter::spool::Spool;
+use crate::RetryConfig;
 use tokio::sync::Mutex;
 
 pub(crate) struct TonicLogsClient {
     inner: Option<ClientInner>,
+    partial_success: PartialSuccessHandler,
+    spool: Option<Arc<Spool>>,
     #[allow(dead_code)]
     // <allow dead> would be removed once we support set_resource for metrics.
     resource: opentelemetry_proto::transform::common::tonic::ResourceAttributesWithSchema,
@@ -22,6 +30,7 @@ pub(crate) struct TonicLogsClient {
 struct ClientInner {
     client: LogsServiceClient<Channel>,
     interceptor: Mutex<BoxInterceptor>,
+    retry_config: RetryConfig,
 }
 
 impl fmt::Debug for TonicLogsClient {
@@ -35,6 +44,9 @@ impl TonicLogsClient {
         channel: Channel,
         interceptor: BoxInterceptor,
         compression: Option<CompressionEncoding>,
+        retry_config: RetryConfig,
+        partial_success: PartialSuccessHandler,
+        spool: Option<Arc<Spool>>,
     ) -> Self {
         let mut client = LogsServiceClient::new(channel);
         if let Some(compression) = compression {
@@ -49,7 +61,10 @@ impl TonicLogsClient {
             inner: Some(ClientInner {
                 client,
                 interceptor: Mutex::new(interceptor),
+                retry_config,
             }),
+            partial_success,
+            spool,
             resource: Default::default(),
         }
     }
@@ -62,7 +77,7 @@ impl LogExporter for TonicLogsClient {
         batch: LogBatch<'_>,
     ) -> impl std::future::Future<Output = LogResult<()>> + Send {
         async move {
-            let (mut client, metadata, extensions) = match &self.inner {
+            let (client, metadata, extensions, retry_config) = match &self.inner {
                 Some(inner) => {
                     let (m, e, _) = inner
                         .interceptor
@@ -71,24 +86,46 @@ impl LogExporter for TonicLogsClient {
                         .call(Request::new(()))
                         .map_err(|e| LogError::Other(Box::new(e)))?
                         .into_parts();
-                    (inner.client.clone(), m, e)
+                    (inner.client.clone(), m, e, inner.retry_config)
                 }
                 None => return Err(LogError::Other("exporter is already shut down".into())),
             };
 
-            let resource_logs = group_logs_by_resource_and_scope(batch, &self.resource);
+            let request = ExportLogsServiceRequest {
+                resource_logs: group_logs_by_resource_and_scope(batch, &self.resource),
+            };
 
             otel_debug!(name: "TonicsLogsClient.CallingExport");
 
-            client
-                .export(Request::from_parts(
-                    metadata,
-                    extensions,
-                    ExportLogsServiceRequest { resource_logs },
-                ))
-                .await
-                .map_err(crate::Error::from)?;
-            Ok(())
+            let send = |request: ExportLogsServiceRequest| {
+                let client = client.clone();
+                let metadata = metadata.clone();
+                let extensions = extensions.clone();
+                async move {
+                    let response = retry_with_backoff(retry_config, request, |request| {
+                        let mut client = client.clone();
+                        let request =
+                            Request::from_parts(metadata.clone(), extensions.clone(), request);
+                        async move { client.export(request).await }
+                    })
+                    .await?;
+
+                    self.partial_success
+                        .handle(response.into_inner().partial_success());
+                    Ok(())
+                }
+            };
+
+            match &self.spool {
+                Some(spool) => spool
+                    .export(spooled_payload(&request), |payload| {
+                        let export = decode_spooled(payload).map(&send);
+                        async move { export?.await.map_err(send_error) }
+                    })
+                    .await
+                    .map_err(|e| LogError::Other(e.into())),
+                None => Ok(send(request).await.map_err(crate::Error::from)?),
+            }
         }
     }
 
@@ -10,16 +10,20 @@ use opentelemetry_sdk::error::{OTelSdkError, OTelSdkResult};
 use opentelemetry_sdk::metrics::data::ResourceMetrics;
 use tonic::{codegen::CompressionEncoding, service::Interceptor, transport::Channel, Request};
 
-use super::BoxInterceptor;
+use super::{retry::retry_with_backoff, BoxInterceptor};
+use crate::exporter::partial_success::{ExportResponse, PartialSuccessHandler};
 use crate::metric::MetricsClient;
+use crate::RetryConfig;
 
 pub(crate) struct TonicMetricsClient {
     inner: Mutex<Option<ClientInner>>,
+    partial_success: PartialSuccessHandler,
 }
 
 struct ClientInner {
     client: MetricsServiceClient<Channel>,
     interceptor: BoxInterceptor,
+    retry_config: RetryConfig,
 }
 
 impl fmt::Debug for TonicMetricsClient {
@@ -33,6 +37,8 @@ impl TonicMetricsClient {
         channel: Channel,
         interceptor: BoxInterceptor,
         compression: Option<CompressionEncoding>,
+        retry_config: RetryConfig,
+        partial_success: PartialSuccessHandler,
     ) -> Self {
         let mut client = MetricsServiceClient::new(channel);
         if let Some(compression) = compression {
@@ -47,7 +53,9 @@ impl TonicMetricsClient {
             inner: Mutex::new(Some(ClientInner {
                 client,
                 interceptor,
+                retry_config,
             })),
+            partial_success,
         }
     }
 }
@@ -55,7 +63,7 @@ impl TonicMetricsClient {
 #[async_trait]
 impl MetricsClient for TonicMetricsClient {
     async fn export(&self, metrics: &mut ResourceMetrics) -> OTelSdkResult {
-        let (mut client, metadata, extensions) = self
+        let (client, metadata, extensions, retry_config) = self
             .inner
             .lock()
             .map_err(|e| OTelSdkError::InternalFailure(format!("Failed to acquire lock: {e:?}")))
@@ -70,7 +78,7 @@ impl MetricsClient for TonicMetricsClient {
                             ))
                         })?
                         .into_parts();
-                    Ok((inner.client.clone(), m, e))
+                    Ok((inner.client.clone(), m, e, inner.retry_config))
                 }
                 None => Err(OTelSdkError::InternalFailure(
                     "exporter is already shut down".into(),
@@ -79,15 +87,17 @@ impl MetricsClient for TonicMetricsClient {
 
         otel_debug!(name: "TonicsMetricsClient.CallingExport");
 
-        client
-            .export(Request::from_parts(
-                metadata,
-                extensions,
-                ExportMetricsServiceRequest::from(&*metrics),
-            ))
-            .await
-            .map_err(|e| OTelSdkError::InternalFailure(format!("{e:?}")))?;
+        let request = ExportMetricsServiceRequest::from(&*metrics);
+        let response = retry_with_backoff(retry_config, request, |request| {
+            let mut client = client.clone();
+            let request = Request::from_parts(metadata.clone(), extensions.clone(), request);
+            async move { client.export(request).await }
+        })
+        .await
+        .map_err(|e| OTelSdkError::InternalFailure(format!("{e:?}")))?;
 
+        self.partial_success
+            .handle(response.into_inner().partial_success());
         Ok(())
     }
 
@@ -12,7 +12,12 @@ use tonic::transport::Channel;
 #[cfg(feature = "tls")]
 use tonic::transport::ClientTlsConfig;
 
-use super::{default_headers, parse_header_string, OTEL_EXPORTER_OTLP_GRPC_ENDPOINT_DEFAULT};
+use super::partial_success::{PartialSuccessHandler, Signal};
+#[cfg(any(feature = "trace", feature = "logs"))]
+use super::spool::{Payload, SendError, SpoolConfig};
+use super::{
+    default_headers, parse_header_string, RetryConfig, OTEL_EXPORTER_OTLP_GRPC_ENDPOINT_DEFAULT,
+};
 use crate::exporter::Compression;
 use crate::{
     ExportConfig, OTEL_EXPORTER_OTLP_COMPRESSION, OTEL_EXPORTER_OTLP_ENDPOINT,
@@ -28,6 +33,8 @@ mod metrics;
 #[cfg(feature = "trace")]
 pub(crate) mod trace;
 
+pub(crate) mod retry;
+
 /// Configuration for [tonic]
 ///
 /// [tonic]: https://github.com/hyperium/tonic
@@ -43,6 +50,14 @@ pub struct TonicConfig {
     pub(crate) compression: Option<Compression>,
     pub(crate) channel: Option<tonic::transport::Channel>,
     pub(crate) interceptor: Option<BoxInterceptor>,
+    /// Retry behavior for failed exports.
+    pub(crate) retry_config: RetryConfig,
+    /// Meter recording the exporter's own metrics.
+    #[cfg(feature = "metrics")]
+    pub(crate) meter: Option<opentelemetry::metrics::Meter>,
+    /// Disk spool for the span and log exports.
+    #[cfg(any(feature = "trace", feature = "logs"))]
+    pub(crate) spool: Option<SpoolConfig>,
 }
 
 impl TryFrom<Compression> for tonic::codec::CompressionEncoding {
@@ -135,6 +150,11 @@ impl Default for TonicExporterBuilder {
                 compression: None,
                 channel: Option::default(),
                 interceptor: Option::default(),
+                retry_config: RetryConfig::default(),
+                #[cfg(feature = "metrics")]
+                meter: None,
+                #[cfg(any(feature = "trace", feature = "logs"))]
+                spool: None,
             },
             exporter_config: ExportConfig {
                 protocol: crate::Protocol::Grpc,
@@ -145,6 +165,15 @@ impl Default for TonicExporterBuilder {
 }
 
 impl TonicExporterBuilder {
+    fn partial_success_handler(&self, signal: Signal) -> PartialSuccessHandler {
+        let handler = PartialSuccessHandler::new(signal);
+        #[cfg(feature = "metrics")]
+        if let Some(meter) = &self.tonic_config.meter {
+            return handler.with_meter(meter);
+        }
+        handler
+    }
+
     fn build_channel(
         self,
         signal_endpoint_var: &str,
@@ -264,6 +293,14 @@ impl TonicExporterBuilder {
 
         otel_debug!(name: "LogsTonicChannelBuilding");
 
+        let retry_config = self.tonic_config.retry_config;
+        let partial_success = self.partial_success_handler(Signal::Logs);
+        let spool = self
+            .tonic_config
+            .spool
+            .as_ref()
+            .map(|spool| spool.open("logs"))
+            .transpose()?;
         let (channel, interceptor, compression) = self.build_channel(
             crate::logs::OTEL_EXPORTER_OTLP_LOGS_ENDPOINT,
             crate::logs::OTEL_EXPORTER_OTLP_LOGS_TIMEOUT,
@@ -271,7 +308,14 @@ impl TonicExporterBuilder {
             crate::logs::OTEL_EXPORTER_OTLP_LOGS_HEADERS,
         )?;
 
-        let client = TonicLogsClient::new(channel, interceptor, compression);
+        let client = TonicLogsClient::new(
+            channel,
+            interceptor,
+            compression,
+            retry_config,
+            partial_success,
+            spool,
+        );
 
         Ok(crate::logs::LogExporter::from_tonic(client))
     }
@@ -287,6 +331,8 @@ impl TonicExporterBuilder {
 
         otel_debug!(name: "MetricsTonicChannelBuilding");
 
+        let retry_config = self.tonic_config.retry_config;
+        let partial_success = self.partial_success_handler(Signal::Metrics);
         let (channel, interceptor, compression) = self.build_channel(
             crate::metric::OTEL_EXPORTER_OTLP_METRICS_ENDPOINT,
             crate::metric::OTEL_EXPORTER_OTLP_METRICS_TIMEOUT,
@@ -294,7 +340,13 @@ impl TonicExporterBuilder {
             crate::metric::OTEL_EXPORTER_OTLP_METRICS_HEADERS,
         )?;
 
-        let client = TonicMetricsClient::new(channel, interceptor, compression);
+        let client = TonicMetricsClient::new(
+            channel,
+            interceptor,
+            compression,
+            retry_config,
+            partial_success,
+        );
 
         Ok(MetricExporter::new(client, temporality))
     }
@@ -308,6 +360,14 @@ impl TonicExporterBuilder {
 
         otel_debug!(name: "TracesTonicChannelBuilding");
 
+        let retry_config = self.tonic_config.retry_config;
+        let partial_success = self.partial_success_handler(Signal::Traces);
+        let spool = self
+            .tonic_config
+            .spool
+            .as_ref()
+            .map(|spool| spool.open("traces"))
+            .transpose()?;
         let (channel, interceptor, compression) = self.build_channel(
             crate::span::OTEL_EXPORTER_OTLP_TRACES_ENDPOINT,
             crate::span::OTEL_EXPORTER_OTLP_TRACES_TIMEOUT,
@@ -315,12 +375,54 @@ impl TonicExporterBuilder {
             crate::span::OTEL_EXPORTER_OTLP_TRACES_HEADERS,
         )?;
 
-        let client = TonicTracesClient::new(channel, interceptor, compression);
+        let client = TonicTracesClient::new(
+            channel,
+            interceptor,
+            compression,
+            retry_config,
+            partial_success,
+            spool,
+        );
 
         Ok(crate::SpanExporter::new(client))
     }
 }
 
+/// The payload spooling an export `request`.
+#[cfg(any(feature = "trace", feature = "logs"))]
+fn spooled_payload(request: &impl prost::Message) -> Payload {
+    Payload {
+        body: request.encode_to_vec(),
+        content_type: "application/x-protobuf",
+        content_encoding: None,
+    }
+}
+
+/// Decodes the export request of a spooled `payload`.
+///
+/// Requests spooled by the HTTP exporter with another encoding can't be sent
+/// over gRPC, and are rejected.
+#[cfg(any(feature = "trace", feature = "logs"))]
+fn decode_spooled<M: prost::Message + Default>(payload: Payload) -> Result<M, SendError> {
+    if payload.content_type != "application/x-protobuf" || payload.content_encoding.is_some() {
+        return Err(SendError::Rejected(format!(
+            "spooled {} requests can't be sent over gRPC",
+            payload.content_encoding.unwrap_or(payload.content_type)
+        )));
+    }
+    M::decode(payload.body.as_slice())
+        .map_err(|e| SendError::Rejected(format!("invalid spooled request: {e}")))
+}
+
+#[cfg(any(feature = "trace", feature = "logs"))]
+fn send_error(status: tonic::Status) -> SendError {
+    if retry::is_transient(&status) {
+        SendError::Transient(crate::Error::from(status).to_string())
+    } else {
+        SendError::Rejected(crate::Error::from(status).to_string())
+    }
+}
+
 fn merge_metadata_with_headers_from_env(
     metadata: MetadataMap,
     headers_from_env: HeaderMap,
@@ -409,6 +511,29 @@ pub trait WithTonicConfig {
     fn with_interceptor<I>(self, interceptor: I) -> Self
     where
         I: tonic::service::Interceptor + Clone + Send + Sync + 'static;
+
+    /// Set the retry behavior for failed exports.
+    ///
+    /// Retries are enabled by default, see [`RetryConfig`] for the defaults.
+    fn with_retry_config(self, retry_config: RetryConfig) -> Self;
+
+    /// Set the meter provider used to record the exporter's own metrics.
+    ///
+    /// The exporter counts the spans, log records or data points rejected by the
+    /// collector in a `partial_success` response. Without a meter provider, rejected
+    /// items are only reported through internal logs.
+    #[cfg(feature = "metrics")]
+    fn with_meter_provider<P>(self, meter_provider: &P) -> Self
+    where
+        P: opentelemetry::metrics::MeterProvider + ?Sized;
+
+    /// Persist the span and log export requests in a disk spool until the
+    /// collector accepted them, see [`SpoolConfig`].
+    ///
+    /// The spool is opened when building the exporter. The metric exporter
+    /// does not use the spool.
+    #[cfg(any(feature = "trace", feature = "logs"))]
+    fn with_spool(self, spool: SpoolConfig) -> Self;
 }
 
 impl<B: HasTonicConfig> WithTonicConfig for B {
@@ -450,6 +575,26 @@ impl<B: HasTonicConfig> WithTonicConfig for B {
         self.tonic_config().interceptor = Some(BoxInterceptor(Box::new(interceptor)));
         self
     }
+
+    fn with_retry_config(mut self, retry_config: RetryConfig) -> Self {
+        self.tonic_config().retry_config = retry_config;
+        self
+    }
+
+    #[cfg(feature = "metrics")]
+    fn with_meter_provider<P>(mut self, meter_provider: &P) -> Self
+    where
+        P: opentelemetry::metrics::MeterProvider + ?Sized,
+    {
+        self.tonic_config().meter = Some(super::partial_success::exporter_meter(meter_provider));
+        self
+    }
+
+    #[cfg(any(feature = "trace", feature = "logs"))]
+    fn with_spool(mut self, spool: SpoolConfig) -> Self {
+        self.tonic_config().spool = Some(spool);
+        self
+    }
 }
 
 #[cfg(test)]
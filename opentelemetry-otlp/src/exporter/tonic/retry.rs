@@ -0,0 +1,397 @@
+use crate::exporter::retry::{Backoff, RetryConfig};
+use opentelemetry::otel_debug;
+use opentelemetry_sdk::util::timer::sleep;
+use prost::Message;
+use std::future::Future;
+use std::time::Duration;
+use tonic::{Code, Status};
+
+const RETRY_INFO_TYPE_URL: &str = "type.googleapis.com/google.rpc.RetryInfo";
+
+/// Runs `call` with `request` until it succeeds or fails with a status that
+/// must not be retried, waiting between attempts according to `retry_config`.
+///
+/// `request` is only cloned for the attempts that may be followed by a retry.
+/// Otherwise, like when retries are disabled, it is moved into the attempt.
+pub(crate) async fn retry_with_backoff<R, T, F, Fut>(
+    retry_config: RetryConfig,
+    request: R,
+    mut call: F,
+) -> Result<T, Status>
+where
+    R: Clone,
+    F: FnMut(R) -> Fut,
+    Fut: Future<Output = Result<T, Status>>,
+{
+    let mut backoff = Backoff::new(retry_config);
+    loop {
+        if !backoff.may_retry() {
+            return call(request).await;
+        }
+        let status = match call(request.clone()).await {
+            Ok(response) => return Ok(response),
+            Err(status) => status,
+        };
+
+        let server_delay = retry_info_delay(&status);
+        if !is_retryable(status.code(), server_delay.is_some()) {
+            return Err(status);
+        }
+
+        match backoff.next_delay(server_delay) {
+            Some(delay) => {
+                otel_debug!(
+                    name: "TonicClient.RetryingExport",
+                    delay_ms = delay.as_millis() as u64,
+                    code = format!("{:?}", status.code())
+                );
+                sleep(delay).await;
+            }
+            None => return Err(status),
+        }
+    }
+}
+
+/// Whether `status` is a transient failure, that may succeed if retried later.
+#[cfg(any(feature = "trace", feature = "logs"))]
+pub(super) fn is_transient(status: &Status) -> bool {
+    is_retryable(status.code(), retry_info_delay(status).is_some())
+}
+
+/// Status codes that indicate a transient failure, see
+/// <https://github.com/open-telemetry/opentelemetry-proto/blob/main/docs/specification.md#failures>
+///
+/// `RESOURCE_EXHAUSTED` is only retryable if the server signals it can recover,
+/// by sending a `RetryInfo`.
+fn is_retryable(code: Code, has_retry_info: bool) -> bool {
+    match code {
+        Code::Cancelled
+        | Code::DeadlineExceeded
+        | Code::Aborted
+        | Code::OutOfRange
+        | Code::Unavailable
+        | Code::DataLoss => true,
+        Code::ResourceExhausted => has_retry_info,
+        _ => false,
+    }
+}
+
+/// Extracts the delay of the `google.rpc.RetryInfo` detail of `status`, if any.
+fn retry_info_delay(status: &Status) -> Option<Duration> {
+    if status.details().is_empty() {
+        return None;
+    }
+    let details = RpcStatus::decode(status.details()).ok()?;
+    details
+        .details
+        .iter()
+        .find(|any| any.type_url == RETRY_INFO_TYPE_URL)
+        .and_then(|any| RetryInfo::decode(any.value.as_slice()).ok())
+        .and_then(|info| info.retry_delay)
+        .map(|delay| {
+            Duration::from_secs(delay.seconds.max(0) as u64)
+                + Duration::from_nanos(delay.nanos.max(0) as u64)
+        })
+}
+
+/// Builds a status carrying a `google.rpc.RetryInfo` detail, as sent by
+/// collectors throttling their clients.
+#[cfg(test)]
+pub(crate) fn status_with_retry_info(code: Code, message: &str, delay: Duration) -> Status {
+    let retry_info = RetryInfo {
+        retry_delay: Some(ProtoDuration {
+            seconds: delay.as_secs() as i64,
+            nanos: delay.subsec_nanos() as i32,
+        }),
+    };
+    let details = RpcStatus {
+        code: code as i32,
+        message: message.into(),
+        details: vec![ProtoAny {
+            type_url: RETRY_INFO_TYPE_URL.into(),
+            value: retry_info.encode_to_vec(),
+        }],
+    };
+    Status::with_details(code, message, details.encode_to_vec().into())
+}
+
+// Minimal definitions of the `google.rpc` messages carried in the status details.
+// See https://github.com/googleapis/googleapis/blob/master/google/rpc/error_details.proto
+
+/// `google.rpc.Status`
+#[derive(Clone, PartialEq, Message)]
+struct RpcStatus {
+    #[prost(int32, tag = "1")]
+    code: i32,
+    #[prost(string, tag = "2")]
+    message: String,
+    #[prost(message, repeated, tag = "3")]
+    details: Vec<ProtoAny>,
+}
+
+/// `google.protobuf.Any`
+#[derive(Clone, PartialEq, Message)]
+struct ProtoAny {
+    #[prost(string, tag = "1")]
+    type_url: String,
+    #[prost(bytes = "vec", tag = "2")]
+    value: Vec<u8>,
+}
+
+/// `google.rpc.RetryInfo`
+#[derive(Clone, PartialEq, Message)]
+struct RetryInfo {
+    #[prost(message, optional, tag = "1")]
+    retry_delay: Option<ProtoDuration>,
+}
+
+/// `google.protobuf.Duration`
+#[derive(Clone, PartialEq, Message)]
+struct ProtoDuration {
+    #[prost(int64, tag = "1")]
+    seconds: i64,
+    #[prost(int32, tag = "2")]
+    nanos: i32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retryable_codes() {
+        for code in [
+            Code::Cancelled,
+            Code::DeadlineExceeded,
+            Code::Aborted,
+            Code::OutOfRange,
+            Code::Unavailable,
+            Code::DataLoss,
+        ] {
+            assert!(is_retryable(code, false), "{code:?}");
+        }
+        for code in [
+            Code::Unknown,
+            Code::InvalidArgument,
+            Code::NotFound,
+            Code::AlreadyExists,
+            Code::PermissionDenied,
+            Code::FailedPrecondition,
+            Code::Unimplemented,
+            Code::Internal,
+            Code::Unauthenticated,
+        ] {
+            assert!(!is_retryable(code, false), "{code:?}");
+            assert!(!is_retryable(code, true), "{code:?}");
+        }
+        assert!(!is_retryable(Code::ResourceExhausted, false));
+        assert!(is_retryable(Code::ResourceExhausted, true));
+    }
+
+    #[test]
+    fn test_request_is_not_cloned_without_retries() {
+        #[derive(Debug)]
+        struct Request<'a>(&'a std::cell::Cell<usize>);
+
+        impl Clone for Request<'_> {
+            fn clone(&self) -> Self {
+                self.0.set(self.0.get() + 1);
+                Request(self.0)
+            }
+        }
+
+        let clones = std::cell::Cell::new(0);
+        let result = futures_executor::block_on(retry_with_backoff(
+            RetryConfig::disabled(),
+            Request(&clones),
+            |_request| async { Err::<(), _>(Status::unavailable("down")) },
+        ));
+        assert_eq!(result.unwrap_err().code(), Code::Unavailable);
+        assert_eq!(clones.get(), 0);
+
+        let result = futures_executor::block_on(retry_with_backoff(
+            RetryConfig::default(),
+            Request(&clones),
+            |_request| async { Ok(()) },
+        ));
+        assert!(result.is_ok());
+        // the request is kept for a retry
+        assert_eq!(clones.get(), 1);
+    }
+
+    #[test]
+    fn test_retry_info_delay() {
+        assert_eq!(retry_info_delay(&Status::unavailable("down")), None);
+        assert_eq!(
+            retry_info_delay(&status_with_retry_info(
+                Code::ResourceExhausted,
+                "retry later",
+                Duration::from_millis(1500)
+            )),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(
+            retry_info_delay(&Status::with_details(
+                Code::Unavailable,
+                "garbage",
+                vec![0xff, 0xff].into()
+            )),
+            None
+        );
+    }
+
+    /// Exports through a stub collector answering with scripted statuses.
+    #[cfg(feature = "trace")]
+    mod collector {
+        use super::status_with_retry_info;
+        use crate::{RetryConfig, WithExportConfig, WithTonicConfig};
+        use opentelemetry_proto::tonic::collector::trace::v1::{
+            trace_service_server::{TraceService, TraceServiceServer},
+            ExportTraceServiceRequest, ExportTraceServiceResponse,
+        };
+        use opentelemetry_sdk::trace::SpanExporter;
+        use std::net::SocketAddr;
+        use std::sync::{Arc, Mutex};
+        use std::time::Duration;
+        use tokio_stream::wrappers::TcpListenerStream;
+        use tonic::{Code, Status};
+
+        /// Collector answering the n-th export with the n-th scripted status, and
+        /// accepting all exports once the script is exhausted.
+        struct StubCollector {
+            script: Mutex<Vec<Status>>,
+            calls: Arc<Mutex<Vec<Code>>>,
+        }
+
+        #[tonic::async_trait]
+        impl TraceService for StubCollector {
+            async fn export(
+                &self,
+                _request: tonic::Request<ExportTraceServiceRequest>,
+            ) -> Result<tonic::Response<ExportTraceServiceResponse>, tonic::Status> {
+                let mut script = self.script.lock().unwrap();
+                let result = if script.is_empty() {
+                    Ok(tonic::Response::new(ExportTraceServiceResponse {
+                        partial_success: None,
+                    }))
+                } else {
+                    Err(script.remove(0))
+                };
+                self.calls.lock().unwrap().push(match &result {
+                    Ok(_) => Code::Ok,
+                    Err(status) => status.code(),
+                });
+                result
+            }
+        }
+
+        async fn start_collector(script: Vec<Status>) -> (SocketAddr, Arc<Mutex<Vec<Code>>>) {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+                .await
+                .expect("failed to bind");
+            let addr = listener.local_addr().unwrap();
+            let stream = TcpListenerStream::new(listener);
+
+            let calls = Arc::new(Mutex::new(Vec::new()));
+            let service = TraceServiceServer::new(StubCollector {
+                script: Mutex::new(script),
+                calls: Arc::clone(&calls),
+            });
+            tokio::task::spawn(async move {
+                tonic::transport::Server::builder()
+                    .add_service(service)
+                    .serve_with_incoming(stream)
+                    .await
+                    .expect("Server failed");
+            });
+            (addr, calls)
+        }
+
+        async fn export(addr: SocketAddr, retry_config: RetryConfig) -> Result<(), String> {
+            let mut exporter = crate::SpanExporter::builder()
+                .with_tonic()
+                .with_endpoint(format!("http://{}", addr))
+                .with_retry_config(retry_config)
+                .build()
+                .unwrap();
+            exporter.export(vec![]).await.map_err(|e| e.to_string())
+        }
+
+        fn fast_retries() -> RetryConfig {
+            RetryConfig {
+                enabled: true,
+                initial_interval: Duration::from_millis(10),
+                max_interval: Duration::from_millis(20),
+                max_elapsed_time: Duration::from_secs(5),
+            }
+        }
+
+        fn resource_exhausted_with_retry_info(delay: Duration) -> Status {
+            status_with_retry_info(Code::ResourceExhausted, "throttled", delay)
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn retries_transient_failures_until_success() {
+            let (addr, calls) = start_collector(vec![
+                Status::unavailable("collector restarting"),
+                Status::deadline_exceeded("slow"),
+                resource_exhausted_with_retry_info(Duration::from_millis(10)),
+            ])
+            .await;
+
+            export(addr, fast_retries()).await.unwrap();
+
+            assert_eq!(
+                *calls.lock().unwrap(),
+                vec![
+                    Code::Unavailable,
+                    Code::DeadlineExceeded,
+                    Code::ResourceExhausted,
+                    Code::Ok
+                ]
+            );
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn does_not_retry_invalid_argument() {
+            let (addr, calls) = start_collector(vec![Status::invalid_argument("bad data")]).await;
+
+            assert!(export(addr, fast_retries()).await.is_err());
+            assert_eq!(*calls.lock().unwrap(), vec![Code::InvalidArgument]);
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn does_not_retry_resource_exhausted_without_retry_info() {
+            let (addr, calls) = start_collector(vec![Status::resource_exhausted("quota")]).await;
+
+            assert!(export(addr, fast_retries()).await.is_err());
+            assert_eq!(*calls.lock().unwrap(), vec![Code::ResourceExhausted]);
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn honors_retry_info_delay() {
+            let (addr, calls) = start_collector(vec![resource_exhausted_with_retry_info(
+                Duration::from_millis(300),
+            )])
+            .await;
+
+            let start = std::time::Instant::now();
+            export(addr, fast_retries()).await.unwrap();
+
+            assert!(start.elapsed() >= Duration::from_millis(300));
+            assert_eq!(
+                *calls.lock().unwrap(),
+                vec![Code::ResourceExhausted, Code::Ok]
+            );
+        }
+
+        #[tokio::test(flavor = "multi_thread")]
+        async fn does_not_retry_when_disabled() {
+            let (addr, calls) =
+                start_collector(vec![Status::unavailable("collector restarting")]).await;
+
+            assert!(export(addr, RetryConfig::disabled()).await.is_err());
+            assert_eq!(*calls.lock().unwrap(), vec![Code::Unavailable]);
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use core::fmt;
+use std::sync::Arc;
 
 use futures_core::future::BoxFuture;
 use opentelemetry::otel_debug;
@@ -13,10 +14,17 @@ use opentelemetry_sdk::{
 };
 use tonic::{codegen::CompressionEncoding, service::Interceptor, transport::Channel, Request};
 
-use super::BoxInterceptor;
+use super::{
+    decode_spooled, retry::retry_with_backoff, send_error, spooled_payload, BoxInterceptor,
+};
+use crate::exporter::partial_success::{ExportResponse, PartialSuccessHandler};
+use crate::exporter::spool::Spool;
+use crate::RetryConfig;
 
 pub(crate) struct TonicTracesClient {
     inner: Option<ClientInner>,
+    partial_success: PartialSuccessHandler,
+    spool: Option<Arc<Spool>>,
     #[allow(dead_code)]
     // <allow dead> would be removed once we support set_resource for metrics.
     resource: opentelemetry_proto::transform::common::tonic::ResourceAttributesWithSchema,
@@ -25,6 +33,7 @@ pub(crate) struct TonicTracesClient {
 struct ClientInner {
     client: TraceServiceClient<Channel>,
     interceptor: BoxInterceptor,
+    retry_config: RetryConfig,
 }
 
 impl fmt::Debug for TonicTracesClient {
@@ -38,6 +47,9 @@ impl TonicTracesClient {
         channel: Channel,
         interceptor: BoxInterceptor,
         compression: Option<CompressionEncoding>,
+        retry_config: RetryConfig,
+        partial_success: PartialSuccessHandler,
+        spool: Option<Arc<Spool>>,
     ) -> Self {
         let mut client = TraceServiceClient::new(channel);
         if let Some(compression) = compression {
@@ -52,7 +64,10 @@ impl TonicTracesClient {
             inner: Some(ClientInner {
                 client,
                 interceptor,
+                retry_config,
             }),
+            partial_success,
+            spool,
             resource: Default::default(),
         }
     }
@@ -60,7 +75,7 @@ impl TonicTracesClient {
 
 impl SpanExporter for TonicTracesClient {
     fn export(&mut self, batch: Vec<SpanData>) -> BoxFuture<'static, OTelSdkResult> {
-        let (mut client, metadata, extensions) = match &mut self.inner {
+        let (client, metadata, extensions, retry_config) = match &mut self.inner {
             Some(inner) => {
                 let (m, e, _) = match inner.interceptor.call(Request::new(())) {
                     Ok(res) => res.into_parts(),
@@ -70,28 +85,59 @@ impl SpanExporter for TonicTracesClient {
                         ))))
                     }
                 };
-                (inner.client.clone(), m, e)
+                (inner.client.clone(), m, e, inner.retry_config)
             }
             None => {
                 return Box::pin(std::future::ready(Err(OTelSdkError::AlreadyShutdown)));
             }
         };
 
-        let resource_spans = group_spans_by_resource_and_scope(batch, &self.resource);
+        let request = ExportTraceServiceRequest {
+            resource_spans: group_spans_by_resource_and_scope(batch, &self.resource),
+        };
 
         otel_debug!(name: "TonicsTracesClient.CallingExport");
 
-        Box::pin(async move {
-            client
-                .export(Request::from_parts(
-                    metadata,
-                    extensions,
-                    ExportTraceServiceRequest { resource_spans },
-                ))
-                .await
-                .map_err(|e| OTelSdkError::InternalFailure(e.to_string()))?;
-            Ok(())
-        })
+        let partial_success = self.partial_success.clone();
+        let send = move |request: ExportTraceServiceRequest| {
+            let client = client.clone();
+            let metadata = metadata.clone();
+            let extensions = extensions.clone();
+            let partial_success = partial_success.clone();
+            async move {
+                let response = retry_with_backoff(retry_config, request, |request| {
+                    let mut client = client.clone();
+                    let request =
+                        Request::from_parts(metadata.clone(), extensions.clone(), request);
+                    async move { client.export(request).await }
+                })
+                .await?;
+
+                partial_success.handle(response.into_inner().partial_success());
+                Ok(())
+            }
+        };
+
+        match &self.spool {
+            Some(spool) => {
+                let spool = Arc::clone(spool);
+                let payload = spooled_payload(&request);
+                Box::pin(async move {
+                    spool
+                        .export(payload, |payload| {
+                            let export = decode_spooled(payload).map(&send);
+                            async move { export?.await.map_err(send_error) }
+                        })
+                        .await
+                        .map_err(OTelSdkError::InternalFailure)
+                })
+            }
+            None => Box::pin(async move {
+                send(request)
+                    .await
+                    .map_err(|e| OTelSdkError::InternalFailure(e.to_string()))
+            }),
+        }
     }
 
     fn shutdown(&mut self) -> OTelSdkResult {
@@ -115,6 +115,8 @@
 //! * `reqwest-client`: Use reqwest http client.
 //! * `reqwest-rustls`: Use reqwest with TLS with system trust roots via `rustls-native-certs` crate.
 //! * `reqwest-rustls-webpki-roots`: Use reqwest with TLS with Mozilla's trust roots via `webpki-roots` crate.
+//! * `gzip-http`: Use gzip compression for the http request body.
+//! * `zstd-http`: Use zstd compression for the http request body.
 //!
 //! # Kitchen Sink Full Configuration
 //!
@@ -228,6 +230,13 @@ mod span;
 
 pub use crate::exporter::Compression;
 pub use crate::exporter::ExportConfig;
+#[cfg(any(feature = "http-proto", feature = "http-json", feature = "grpc-tonic"))]
+pub use crate::exporter::RetryConfig;
+#[cfg(all(
+    any(feature = "grpc-tonic", feature = "http-proto", feature = "http-json"),
+    any(feature = "trace", feature = "logs")
+))]
+pub use crate::exporter::{FsyncPolicy, SpoolConfig};
 #[cfg(feature = "trace")]
 #[cfg(any(feature = "http-proto", feature = "http-json", feature = "grpc-tonic"))]
 pub use crate::span::{
@@ -354,9 +363,22 @@ pub enum Error {
     UnsupportedCompressionAlgorithm(String),
 
     /// Feature required to use the specified compression algorithm.
-    #[cfg(any(not(feature = "gzip-tonic"), not(feature = "zstd-tonic")))]
+    #[cfg(any(
+        not(feature = "gzip-tonic"),
+        not(feature = "zstd-tonic"),
+        not(feature = "gzip-http"),
+        not(feature = "zstd-http")
+    ))]
     #[error("feature '{0}' is required to use the compression algorithm '{1}'")]
     FeatureRequiredForCompressionAlgorithm(&'static str, Compression),
+
+    /// The spool directory could not be opened.
+    #[cfg(all(
+        any(feature = "grpc-tonic", feature = "http-proto", feature = "http-json"),
+        any(feature = "trace", feature = "logs")
+    ))]
+    #[error("failed to open the spool directory: {0}")]
+    Spool(std::io::Error),
 }
 
 #[cfg(feature = "grpc-tonic")]
@@ -0,0 +1,95 @@
+//! Propagates span contexts and baggage in the [OT Trace] format, used by
+//! OpenTracing and LightStep tracers.
+//!
+//! This helps services instrumented with OpenTelemetry to join the traces of
+//! services still instrumented with OpenTracing. Combine it with the W3C
+//! propagators in a [`TextMapCompositePropagator`] to keep a single trace
+//! across a fleet in the middle of a migration. As the propagators of a
+//! composite propagator are applied in order, list it before the W3C
+//! propagators.
+//!
+//! [OT Trace]: https://github.com/opentracing/basictracer-go/blob/master/propagation_ot.go
+//! [`TextMapCompositePropagator`]: opentelemetry::propagation::TextMapCompositePropagator
+//!
+//! *[Supported Rust Versions](#supported-rust-versions)*
+//!
+//! # Supported Rust Versions
+//!
+//! OpenTelemetry is built against the latest stable release. The minimum
+//! supported version is 1.75.0. The current OpenTelemetry version is not
+//! guaranteed to build on Rust versions earlier than the minimum supported
+//! version.
+//!
+//! The current stable Rust compiler and the three most recent minor versions
+//! before it will always be supported. For example, if the current stable
+//! compiler version is 1.49, the minimum supported version will not be
+//! increased past 1.46, three minor versions prior. Increasing the minimum
+//! supported compiler version is not considered a semver breaking change as
+//! long as doing so complies with this policy.
+#![warn(
+    future_incompatible,
+    missing_debug_implementations,
+    missing_docs,
+    nonstandard_style,
+    rust_2018_idioms,
+    unreachable_pub,
+    unused
+)]
+#![cfg_attr(
+    docsrs,
+    feature(doc_cfg, doc_auto_cfg),
+    deny(rustdoc::broken_intra_doc_links)
+)]
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/open-telemetry/opentelemetry-rust/main/assets/logo.svg"
+)]
+#![cfg_attr(test, deny(warnings))]
+
+/// The OT Trace propagator propagates span contexts in the `ot-tracer-*`
+/// headers and baggage in the `ot-baggage-*` headers.
+///
+/// Cross-cutting concerns send their state to the next process using `Propagator`s,
+/// which are defined as objects used to read and write context data to and from messages
+/// exchanged by the applications. Each concern creates a set of `Propagator`s for every
+/// supported `Propagator` type.
+///
+/// ## Examples
+/// ```
+/// # use opentelemetry::{global, propagation::TextMapCompositePropagator, trace::{Tracer, TraceContextExt}, Context};
+/// # use opentelemetry_ottrace_propagator::Propagator as OtTracePropagator;
+/// # use opentelemetry_sdk::propagation::{BaggagePropagator, TraceContextPropagator};
+/// # fn send_request() {
+/// // setup the OT Trace propagator, along with the W3C propagators. It comes
+/// // first, so that the full 128-bit trace id of the `traceparent` header is
+/// // extracted when both headers are present.
+/// global::set_text_map_propagator(TextMapCompositePropagator::new(vec![
+///     Box::new(OtTracePropagator::new()),
+///     Box::new(TraceContextPropagator::new()),
+///     Box::new(BaggagePropagator::new()),
+/// ]));
+///
+/// // before sending requests to downstream services.
+/// let mut headers = std::collections::HashMap::new(); // replace by http header of the outgoing request
+/// let caller_span = global::tracer("caller").start("say hello");
+/// let cx = Context::current_with_span(caller_span);
+/// global::get_text_map_propagator(|propagator| {
+///     propagator.inject_context(&cx, &mut headers); // propagator serialize the tracing context
+/// });
+/// // Send the request..
+/// # }
+///
+///
+/// # fn receive_request() {
+/// // Receive the request sent above on the other service...
+/// let headers = std::collections::HashMap::new(); // replace this with http header map from incoming requests.
+/// let parent_context = global::get_text_map_propagator(|propagator| {
+///      propagator.extract(&headers)
+/// });
+///
+/// // this span's parent span will be caller_span in send_request functions.
+/// let receiver_span = global::tracer("receiver").start_with_context("hello", &parent_context);
+/// # }
+/// ```
+pub mod propagator;
+
+pub use propagator::Propagator;
@@ -0,0 +1,424 @@
+use opentelemetry::{
+    baggage::BaggageExt,
+    otel_warn,
+    propagation::{text_map_propagator::FieldIter, Extractor, Injector, TextMapPropagator},
+    trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState},
+    Context, KeyValue,
+};
+
+const OT_TRACE_ID_HEADER: &str = "ot-tracer-traceid";
+const OT_SPAN_ID_HEADER: &str = "ot-tracer-spanid";
+const OT_SAMPLED_HEADER: &str = "ot-tracer-sampled";
+const OT_BAGGAGE_PREFIX: &str = "ot-baggage-";
+
+/// `Propagator` implements the [OT Trace] format.
+///
+/// A span context is propagated in three headers:
+///
+/// - `ot-tracer-traceid`: the trace id, as 16 or 32 lower case hex digits.
+///   64-bit trace ids are padded with zeros on the left. As OpenTracing
+///   tracers only support 64-bit trace ids, only the right-most 64 bits of the
+///   trace id are injected.
+/// - `ot-tracer-spanid`: the span id, as 16 lower case hex digits.
+/// - `ot-tracer-sampled`: `true` if the span is sampled, `false` otherwise.
+///
+/// The entries of the [`Baggage`] are propagated along with a valid span
+/// context, in one `ot-baggage-{key}` header per entry.
+///
+/// See [module level documentation](self#Examples) for examples.
+///
+/// [OT Trace]: https://github.com/opentracing/basictracer-go/blob/master/propagation_ot.go
+/// [`Baggage`]: opentelemetry::baggage::Baggage
+#[derive(Clone, Debug)]
+pub struct Propagator {
+    fields: [String; 3],
+}
+
+impl Default for Propagator {
+    fn default() -> Self {
+        Propagator::new()
+    }
+}
+
+impl Propagator {
+    /// Create an OT Trace propagator
+    pub fn new() -> Self {
+        Propagator {
+            fields: [
+                OT_TRACE_ID_HEADER.to_owned(),
+                OT_SPAN_ID_HEADER.to_owned(),
+                OT_SAMPLED_HEADER.to_owned(),
+            ],
+        }
+    }
+
+    /// Extract span context from the headers
+    fn extract_span_context(&self, extractor: &dyn Extractor) -> Option<SpanContext> {
+        // A missing header is not worth a warning, other propagators may apply.
+        let trace_id = extractor.get(OT_TRACE_ID_HEADER)?;
+        let span_id = extractor.get(OT_SPAN_ID_HEADER).unwrap_or("");
+        let sampled = extractor.get(OT_SAMPLED_HEADER).unwrap_or("");
+
+        match (
+            self.extract_trace_id(trace_id),
+            self.extract_span_id(span_id),
+            self.extract_trace_flags(sampled),
+        ) {
+            (Ok(trace_id), Ok(span_id), Ok(flags)) => Some(SpanContext::new(
+                trace_id,
+                span_id,
+                flags,
+                true,
+                TraceState::default(),
+            ))
+            .filter(SpanContext::is_valid),
+            _ => {
+                otel_warn!(
+                    name: "OtTracePropagator.InvalidHeader",
+                    message = "Invalid ot-tracer headers",
+                    trace_id = trace_id.to_string(),
+                    span_id = span_id.to_string(),
+                    sampled = sampled.to_string(),
+                );
+                None
+            }
+        }
+    }
+
+    /// Extract trace id from the header.
+    ///
+    /// 64-bit trace ids are padded on the left.
+    fn extract_trace_id(&self, trace_id: &str) -> Result<TraceId, ()> {
+        // Only allow lower case hex string
+        if (trace_id.len() != 16 && trace_id.len() != 32) || !is_lower_hex(trace_id) {
+            return Err(());
+        }
+
+        TraceId::from_hex(trace_id).map_err(|_| ())
+    }
+
+    /// Extract span id from the header.
+    fn extract_span_id(&self, span_id: &str) -> Result<SpanId, ()> {
+        // Only allow lower case hex string
+        if span_id.len() != 16 || !is_lower_hex(span_id) {
+            return Err(());
+        }
+
+        SpanId::from_hex(span_id).map_err(|_| ())
+    }
+
+    /// Extract flag from the header
+    ///
+    /// For interop purposes, `1` and `0` are accepted along with `true` and
+    /// `false`. A missing sampled header means not sampled.
+    fn extract_trace_flags(&self, sampled: &str) -> Result<TraceFlags, ()> {
+        match sampled {
+            "true" | "1" => Ok(TraceFlags::SAMPLED),
+            "false" | "0" | "" => Ok(TraceFlags::default()),
+            _ => Err(()),
+        }
+    }
+
+    /// Extract the baggage entries from the `ot-baggage-*` headers.
+    fn extract_baggage(&self, extractor: &dyn Extractor) -> Vec<KeyValue> {
+        extractor
+            .keys()
+            .into_iter()
+            .filter_map(|key| {
+                let name = key
+                    .strip_prefix(OT_BAGGAGE_PREFIX)
+                    .filter(|name| !name.is_empty())?;
+                extractor
+                    .get(key)
+                    .map(|value| KeyValue::new(name.to_string(), value.to_string()))
+            })
+            .collect()
+    }
+}
+
+fn is_lower_hex(value: &str) -> bool {
+    value
+        .bytes()
+        .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+}
+
+impl TextMapPropagator for Propagator {
+    fn inject_context(&self, cx: &Context, injector: &mut dyn Injector) {
+        let span = cx.span();
+        let span_context = span.span_context();
+        if span_context.is_valid() {
+            // OpenTracing tracers only support 64-bit trace ids, keep the
+            // right-most 64 bits.
+            let trace_id = span_context.trace_id().to_string();
+            injector.set(OT_TRACE_ID_HEADER, trace_id[16..].to_string());
+            injector.set(OT_SPAN_ID_HEADER, span_context.span_id().to_string());
+            injector.set(OT_SAMPLED_HEADER, span_context.is_sampled().to_string());
+
+            for (key, (value, _)) in cx.baggage() {
+                injector.set(
+                    &format!("{}{}", OT_BAGGAGE_PREFIX, key),
+                    value.as_str().into_owned(),
+                );
+            }
+        }
+    }
+
+    fn extract_with_context(&self, cx: &Context, extractor: &dyn Extractor) -> Context {
+        self.extract_span_context(extractor)
+            .map(|sc| {
+                cx.with_baggage(self.extract_baggage(extractor))
+                    .with_remote_span_context(sc)
+            })
+            .unwrap_or_else(|| cx.clone())
+    }
+
+    fn fields(&self) -> FieldIter<'_> {
+        FieldIter::new(self.fields.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::{
+        propagation::TextMapCompositePropagator, testing::trace::TestSpan, StringValue,
+    };
+    use opentelemetry_sdk::propagation::{BaggagePropagator, TraceContextPropagator};
+    use std::collections::HashMap;
+
+    const TRACE_ID_STR: &str = "4bf92f3577b34da6a3ce929d0e0e4736";
+    const SHORT_TRACE_ID_STR: &str = "a3ce929d0e0e4736";
+    const SPAN_ID_STR: &str = "00f067aa0ba902b7";
+    const TRACE_ID: u128 = 0x4bf9_2f35_77b3_4da6_a3ce_929d_0e0e_4736;
+    const SHORT_TRACE_ID: u128 = 0x0000_0000_0000_0000_a3ce_929d_0e0e_4736;
+    const SPAN_ID: u64 = 0x00f0_67aa_0ba9_02b7;
+
+    #[rustfmt::skip]
+    #[allow(clippy::type_complexity)]
+    fn extract_data() -> Vec<((Option<&'static str>, Option<&'static str>, Option<&'static str>), SpanContext)> {
+        // (TraceId, SpanId, Sampled)
+        vec![
+            ((Some(TRACE_ID_STR), Some(SPAN_ID_STR), Some("true")), SpanContext::new(TraceId::from_u128(TRACE_ID), SpanId::from_u64(SPAN_ID), TraceFlags::SAMPLED, true, TraceState::default())), // sampled
+            ((Some(TRACE_ID_STR), Some(SPAN_ID_STR), Some("false")), SpanContext::new(TraceId::from_u128(TRACE_ID), SpanId::from_u64(SPAN_ID), TraceFlags::default(), true, TraceState::default())), // not sampled
+            ((Some(TRACE_ID_STR), Some(SPAN_ID_STR), Some("1")), SpanContext::new(TraceId::from_u128(TRACE_ID), SpanId::from_u64(SPAN_ID), TraceFlags::SAMPLED, true, TraceState::default())),
+            ((Some(TRACE_ID_STR), Some(SPAN_ID_STR), Some("0")), SpanContext::new(TraceId::from_u128(TRACE_ID), SpanId::from_u64(SPAN_ID), TraceFlags::default(), true, TraceState::default())), // use 1/0 to set sample
+            ((Some(TRACE_ID_STR), Some(SPAN_ID_STR), None), SpanContext::new(TraceId::from_u128(TRACE_ID), SpanId::from_u64(SPAN_ID), TraceFlags::default(), true, TraceState::default())), // missing sampled state
+            ((Some(SHORT_TRACE_ID_STR), Some(SPAN_ID_STR), Some("true")), SpanContext::new(TraceId::from_u128(SHORT_TRACE_ID), SpanId::from_u64(SPAN_ID), TraceFlags::SAMPLED, true, TraceState::default())), // padding 64 bit traceID
+        ]
+    }
+
+    #[rustfmt::skip]
+    #[allow(clippy::type_complexity)]
+    fn extract_invalid_data() -> Vec<(Option<&'static str>, Option<&'static str>, Option<&'static str>)> {
+        vec![
+            (None, None, None),
+            (None, Some(SPAN_ID_STR), Some("true")), // missing trace id
+            (Some(TRACE_ID_STR), None, Some("true")), // missing span id
+            (Some("4bf92f3577b34da6a3ce929d0e0e47360"), Some(SPAN_ID_STR), Some("true")), // trace ID length > 32
+            (Some("4bf92f3577b34da6a3ce929d0e0e"), Some(SPAN_ID_STR), Some("true")), // trace ID length > 16 and < 32
+            (Some("a3ce929d0e0e473"), Some(SPAN_ID_STR), Some("true")), // trace ID length < 16
+            (Some("4bf92f3577b34da6a3ce929d0e0e4hhh"), Some(SPAN_ID_STR), Some("true")), // hex contains illegal char
+            (Some("4BF92F3577B34DA6A3CE929D0E0E4736"), Some(SPAN_ID_STR), Some("true")), // trace id is upper case hex string
+            (Some("00000000000000000000000000000000"), Some(SPAN_ID_STR), Some("true")), // invalid trace id
+            (Some(TRACE_ID_STR), Some("00f067aa0ba902b"), Some("true")), // span id length < 16
+            (Some(TRACE_ID_STR), Some("00f067aa0ba902b70"), Some("true")), // span id length > 16
+            (Some(TRACE_ID_STR), Some("00F067AA0BA902B7"), Some("true")), // span id is upper case hex string
+            (Some(TRACE_ID_STR), Some("0000000000000000"), Some("true")), // invalid span id
+            (Some(TRACE_ID_STR), Some(SPAN_ID_STR), Some("yes")), // invalid sampled state
+        ]
+    }
+
+    #[rustfmt::skip]
+    #[allow(clippy::type_complexity)]
+    fn inject_data() -> Vec<((Option<&'static str>, Option<&'static str>, Option<&'static str>), SpanContext)> {
+        // (TraceId, SpanId, Sampled)
+        vec![
+            ((Some(SHORT_TRACE_ID_STR), Some(SPAN_ID_STR), Some("true")), SpanContext::new(TraceId::from_u128(TRACE_ID), SpanId::from_u64(SPAN_ID), TraceFlags::SAMPLED, true, TraceState::default())), // 128-bit trace id is truncated
+            ((Some(SHORT_TRACE_ID_STR), Some(SPAN_ID_STR), Some("false")), SpanContext::new(TraceId::from_u128(TRACE_ID), SpanId::from_u64(SPAN_ID), TraceFlags::default(), true, TraceState::default())), // not sampled
+            ((Some(SHORT_TRACE_ID_STR), Some(SPAN_ID_STR), Some("true")), SpanContext::new(TraceId::from_u128(SHORT_TRACE_ID), SpanId::from_u64(SPAN_ID), TraceFlags::SAMPLED, true, TraceState::default())), // 64-bit trace id
+            ((Some("0000000000000001"), Some("0000000000000002"), Some("true")), SpanContext::new(TraceId::from_u128(1), SpanId::from_u64(2), TraceFlags::SAMPLED, true, TraceState::default())), // zero padded ids
+            ((None, None, None), SpanContext::new(TraceId::INVALID, SpanId::INVALID, TraceFlags::SAMPLED, true, TraceState::default())),
+            ((None, None, None), SpanContext::empty_context()),
+        ]
+    }
+
+    fn extractor_from_test_data(
+        trace: Option<&'static str>,
+        span: Option<&'static str>,
+        sampled: Option<&'static str>,
+    ) -> HashMap<String, String> {
+        let mut extractor = HashMap::new();
+        if let Some(trace_id) = trace {
+            extractor.insert(OT_TRACE_ID_HEADER.to_string(), trace_id.to_owned());
+        }
+        if let Some(span_id) = span {
+            extractor.insert(OT_SPAN_ID_HEADER.to_string(), span_id.to_owned());
+        }
+        if let Some(sampled) = sampled {
+            extractor.insert(OT_SAMPLED_HEADER.to_string(), sampled.to_owned());
+        }
+        extractor
+    }
+
+    #[test]
+    fn extract_ottrace() {
+        let propagator = Propagator::new();
+
+        for ((trace, span, sampled), expected_context) in extract_data() {
+            let extractor = extractor_from_test_data(trace, span, sampled);
+            assert_eq!(
+                propagator.extract(&extractor).span().span_context(),
+                &expected_context
+            )
+        }
+
+        for (trace, span, sampled) in extract_invalid_data() {
+            let extractor = extractor_from_test_data(trace, span, sampled);
+            assert_eq!(
+                propagator.extract(&extractor).span().span_context(),
+                &SpanContext::empty_context(),
+            )
+        }
+    }
+
+    #[test]
+    fn inject_ottrace() {
+        let propagator = Propagator::new();
+
+        for ((trace_id, span_id, sampled), context) in inject_data() {
+            let mut injector = HashMap::new();
+            propagator.inject_context(
+                &Context::current_with_span(TestSpan(context)),
+                &mut injector,
+            );
+
+            assert_eq!(
+                injector.get(OT_TRACE_ID_HEADER).map(String::as_str),
+                trace_id
+            );
+            assert_eq!(injector.get(OT_SPAN_ID_HEADER).map(String::as_str), span_id);
+            assert_eq!(injector.get(OT_SAMPLED_HEADER).map(String::as_str), sampled);
+        }
+    }
+
+    #[test]
+    fn extract_baggage() {
+        let propagator = Propagator::new();
+
+        let mut extractor =
+            extractor_from_test_data(Some(TRACE_ID_STR), Some(SPAN_ID_STR), Some("true"));
+        extractor.insert("ot-baggage-user-id".to_string(), "42".to_string());
+        extractor.insert("ot-baggage-tenant".to_string(), "acme".to_string());
+        extractor.insert("ot-baggage-".to_string(), "no key".to_string());
+        extractor.insert("uberctx-other".to_string(), "ignored".to_string());
+
+        let cx = propagator.extract_with_context(
+            &Context::new().with_baggage(vec![KeyValue::new("existing", "kept")]),
+            &extractor,
+        );
+        let baggage = cx.baggage();
+        assert_eq!(baggage.len(), 3);
+        assert_eq!(baggage.get("user-id"), Some(&"42".into()));
+        assert_eq!(baggage.get("tenant"), Some(&"acme".into()));
+        assert_eq!(baggage.get("existing"), Some(&"kept".into()));
+
+        // baggage is only extracted along with a valid span context
+        extractor.remove(OT_SPAN_ID_HEADER);
+        let cx = propagator.extract(&extractor);
+        assert!(cx.baggage().is_empty());
+    }
+
+    #[test]
+    fn inject_baggage() {
+        let propagator = Propagator::new();
+        let span_context = SpanContext::new(
+            TraceId::from_u128(TRACE_ID),
+            SpanId::from_u64(SPAN_ID),
+            TraceFlags::SAMPLED,
+            true,
+            TraceState::default(),
+        );
+        let baggage = vec![
+            KeyValue::new("user-id", 42),
+            KeyValue::new("tenant", StringValue::from("acme")),
+        ];
+
+        let mut injector = HashMap::new();
+        propagator.inject_context(
+            &Context::current_with_span(TestSpan(span_context)).with_baggage(baggage.clone()),
+            &mut injector,
+        );
+        assert_eq!(injector.len(), 5);
+        assert_eq!(
+            injector.get("ot-baggage-user-id").map(String::as_str),
+            Some("42")
+        );
+        assert_eq!(
+            injector.get("ot-baggage-tenant").map(String::as_str),
+            Some("acme")
+        );
+
+        // baggage is only injected along with a valid span context
+        let mut injector = HashMap::new();
+        propagator.inject_context(&Context::new().with_baggage(baggage), &mut injector);
+        assert!(injector.is_empty());
+    }
+
+    #[test]
+    fn composite_with_trace_context() {
+        let propagator = TextMapCompositePropagator::new(vec![
+            Box::new(Propagator::new()),
+            Box::new(TraceContextPropagator::new()),
+            Box::new(BaggagePropagator::new()),
+        ]);
+        let span_context = SpanContext::new(
+            TraceId::from_u128(TRACE_ID),
+            SpanId::from_u64(SPAN_ID),
+            TraceFlags::SAMPLED,
+            true,
+            TraceState::default(),
+        );
+
+        let mut injector = HashMap::new();
+        propagator.inject_context(
+            &Context::current_with_span(TestSpan(span_context.clone()))
+                .with_baggage(vec![KeyValue::new("user-id", "42")]),
+            &mut injector,
+        );
+        assert_eq!(
+            injector.get("traceparent").map(String::as_str),
+            Some("00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01")
+        );
+        assert_eq!(
+            injector.get(OT_TRACE_ID_HEADER).map(String::as_str),
+            Some(SHORT_TRACE_ID_STR)
+        );
+
+        // a legacy service only sends the ot-tracer headers
+        let legacy = injector
+            .iter()
+            .filter(|(key, _)| key.starts_with("ot-"))
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect::<HashMap<_, _>>();
+        let cx = propagator.extract(&legacy);
+        assert_eq!(
+            cx.span().span_context().trace_id(),
+            TraceId::from_u128(SHORT_TRACE_ID)
+        );
+        assert_eq!(cx.baggage().get("user-id"), Some(&"42".into()));
+
+        // the 128-bit trace id of the w3c headers wins when both are present
+        let cx = propagator.extract(&injector);
+        assert_eq!(cx.span().span_context(), &span_context);
+    }
+
+    #[test]
+    fn test_fields() {
+        let propagator = Propagator::new();
+        let fields = propagator.fields().collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![OT_TRACE_ID_HEADER, OT_SPAN_ID_HEADER, OT_SAMPLED_HEADER]
+        );
+    }
+}
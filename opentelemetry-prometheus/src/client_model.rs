@@ -0,0 +1,289 @@
+//! Fields of the Prometheus [client data model] which are missing from the
+//! messages generated by the `prometheus` crate.
+//!
+//! They are stored as unknown fields of the messages, with the field numbers of
+//! the data model, so the protobuf exposition format encodes them as Prometheus
+//! expects, and the OpenMetrics encoder reads them back. The exemplars of the
+//! implicit `+Inf` buckets of histograms, which have no field of their own, are
+//! kept beside the messages, see [`InfBucketExemplars`].
+//!
+//! [client data model]: https://github.com/prometheus/client_model/blob/master/io/prometheus/client/metrics.proto
+
+use prometheus::proto::{LabelPair, Metric, MetricFamily};
+use protobuf::{
+    well_known_types::{Empty, Timestamp},
+    Message,
+};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// `unit` field of `MetricFamily`.
+pub(crate) const METRIC_FAMILY_UNIT: u32 = 5;
+/// `exemplar` field of `Counter`.
+pub(crate) const COUNTER_EXEMPLAR: u32 = 2;
+/// `created_timestamp` field of `Counter`.
+pub(crate) const COUNTER_CREATED_TIMESTAMP: u32 = 3;
+/// `exemplar` field of `Bucket`.
+pub(crate) const BUCKET_EXEMPLAR: u32 = 3;
+/// `created_timestamp` field of `Histogram`.
+pub(crate) const HISTOGRAM_CREATED_TIMESTAMP: u32 = 15;
+
+// Fields of the `Exemplar` message.
+const EXEMPLAR_LABEL: u32 = 1;
+const EXEMPLAR_VALUE: u32 = 2;
+const EXEMPLAR_TIMESTAMP: u32 = 3;
+
+/// An exemplar of a counter or histogram bucket.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Exemplar {
+    pub(crate) labels: Vec<LabelPair>,
+    pub(crate) value: f64,
+    pub(crate) timestamp: Option<SystemTime>,
+}
+
+/// Sets the string field `field` of `message`.
+pub(crate) fn set_string(message: &mut impl Message, field: u32, value: &str) {
+    message
+        .mut_unknown_fields()
+        .add_length_delimited(field, value.as_bytes().to_vec());
+}
+
+/// Returns the string field `field` of `message`, if set.
+pub(crate) fn get_string(message: &impl Message, field: u32) -> Option<String> {
+    let value = message
+        .get_unknown_fields()
+        .get(field)?
+        .length_delimited
+        .last()?;
+    String::from_utf8(value.clone()).ok()
+}
+
+/// Sets the `google.protobuf.Timestamp` field `field` of `message`.
+pub(crate) fn set_timestamp(message: &mut impl Message, field: u32, time: SystemTime) {
+    if let Ok(bytes) = encode_timestamp(time).write_to_bytes() {
+        message
+            .mut_unknown_fields()
+            .add_length_delimited(field, bytes);
+    }
+}
+
+/// Returns the `google.protobuf.Timestamp` field `field` of `message`, if set.
+pub(crate) fn get_timestamp(message: &impl Message, field: u32) -> Option<SystemTime> {
+    let value = message
+        .get_unknown_fields()
+        .get(field)?
+        .length_delimited
+        .last()?;
+    Timestamp::parse_from_bytes(value)
+        .ok()
+        .map(|timestamp| decode_timestamp(&timestamp))
+}
+
+/// Sets the `Exemplar` field `field` of `message`.
+pub(crate) fn set_exemplar(message: &mut impl Message, field: u32, exemplar: &Exemplar) {
+    // `Empty` has no fields of its own, its unknown fields are used to encode
+    // the fields of the exemplar.
+    let mut encoded = Empty::new();
+    let fields = encoded.mut_unknown_fields();
+    for label in &exemplar.labels {
+        if let Ok(bytes) = label.write_to_bytes() {
+            fields.add_length_delimited(EXEMPLAR_LABEL, bytes);
+        }
+    }
+    fields.add_fixed64(EXEMPLAR_VALUE, exemplar.value.to_bits());
+    if let Some(time) = exemplar.timestamp {
+        if let Ok(bytes) = encode_timestamp(time).write_to_bytes() {
+            fields.add_length_delimited(EXEMPLAR_TIMESTAMP, bytes);
+        }
+    }
+
+    if let Ok(bytes) = encoded.write_to_bytes() {
+        message
+            .mut_unknown_fields()
+            .add_length_delimited(field, bytes);
+    }
+}
+
+/// Returns the `Exemplar` field `field` of `message`, if set.
+pub(crate) fn get_exemplar(message: &impl Message, field: u32) -> Option<Exemplar> {
+    let value = message
+        .get_unknown_fields()
+        .get(field)?
+        .length_delimited
+        .last()?;
+    let encoded = Empty::parse_from_bytes(value).ok()?;
+    let fields = encoded.get_unknown_fields();
+
+    let labels = fields
+        .get(EXEMPLAR_LABEL)
+        .map(|labels| {
+            labels
+                .length_delimited
+                .iter()
+                .filter_map(|label| LabelPair::parse_from_bytes(label).ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    let value = fields
+        .get(EXEMPLAR_VALUE)
+        .and_then(|value| value.fixed64.last())
+        .map(|bits| f64::from_bits(*bits))?;
+    let timestamp = fields
+        .get(EXEMPLAR_TIMESTAMP)
+        .and_then(|timestamp| timestamp.length_delimited.last())
+        .and_then(|timestamp| Timestamp::parse_from_bytes(timestamp).ok())
+        .map(|timestamp| decode_timestamp(&timestamp));
+
+    Some(Exemplar {
+        labels,
+        value,
+        timestamp,
+    })
+}
+
+/// The name of a metric family and the labels of one of its metrics.
+type SeriesKey = (String, Vec<(String, String)>);
+
+fn series_key(family: &str, labels: &[LabelPair]) -> SeriesKey {
+    let mut labels: Vec<_> = labels
+        .iter()
+        .map(|label| (label.get_name().to_string(), label.get_value().to_string()))
+        .collect();
+    labels.sort_unstable();
+    (family.to_string(), labels)
+}
+
+/// The exemplars of the implicit `+Inf` buckets of the histograms of the last
+/// collection of an exporter.
+///
+/// The data model has no field for them: they belong to an explicit `+Inf`
+/// bucket, which the text encoder of the `prometheus` crate would write as
+/// `le="inf"`, changing the series of the bucket. They are kept here instead,
+/// keyed by the name of the family and the labels of the histogram, and only
+/// turned into buckets by the encoders writing them correctly, see
+/// [`with_inf_buckets`]. Histograms renamed or relabelled by the registry, e.g.
+/// with a prefix, are not found.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct InfBucketExemplars(Arc<Mutex<HashMap<SeriesKey, Exemplar>>>);
+
+/// The `+Inf` bucket exemplars of a collection being made.
+#[derive(Debug, Default)]
+pub(crate) struct InfBucketExemplarsBuilder(HashMap<SeriesKey, Exemplar>);
+
+impl InfBucketExemplarsBuilder {
+    pub(crate) fn insert(&mut self, family: &str, labels: &[LabelPair], exemplar: Exemplar) {
+        self.0.insert(series_key(family, labels), exemplar);
+    }
+}
+
+impl InfBucketExemplars {
+    /// Replaces the exemplars with the ones of a new collection.
+    pub(crate) fn replace(&self, exemplars: InfBucketExemplarsBuilder) {
+        if let Ok(mut current) = self.0.lock() {
+            *current = exemplars.0;
+        }
+    }
+
+    /// Returns the `+Inf` bucket exemplar of the histogram `metric`.
+    pub(crate) fn get(&self, family: &MetricFamily, metric: &Metric) -> Option<Exemplar> {
+        let exemplars = self.0.lock().ok()?;
+        if exemplars.is_empty() {
+            return None;
+        }
+        exemplars
+            .get(&series_key(family.get_name(), metric.get_label()))
+            .cloned()
+    }
+}
+
+/// Returns a copy of `families` where the histograms with a `+Inf` bucket
+/// exemplar have an explicit `+Inf` bucket holding it, as expected by the
+/// protobuf exposition format.
+#[cfg(any(feature = "http-server", test))]
+pub(crate) fn with_inf_buckets(
+    families: &[MetricFamily],
+    exemplars: &InfBucketExemplars,
+) -> Vec<MetricFamily> {
+    use prometheus::proto::Bucket;
+
+    let mut families = families.to_vec();
+    for family in &mut families {
+        let mut metrics = family.take_metric();
+        for metric in metrics.iter_mut().filter(|metric| metric.has_histogram()) {
+            let Some(exemplar) = exemplars.get(family, metric) else {
+                continue;
+            };
+            let histogram = metric.mut_histogram();
+            let mut bucket = Bucket::default();
+            bucket.set_upper_bound(f64::INFINITY);
+            bucket.set_cumulative_count(histogram.get_sample_count());
+            set_exemplar(&mut bucket, BUCKET_EXEMPLAR, &exemplar);
+            histogram.mut_bucket().push(bucket);
+        }
+        family.set_metric(metrics);
+    }
+    families
+}
+
+fn encode_timestamp(time: SystemTime) -> Timestamp {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let mut timestamp = Timestamp::new();
+    timestamp.set_seconds(since_epoch.as_secs() as i64);
+    timestamp.set_nanos(since_epoch.subsec_nanos() as i32);
+    timestamp
+}
+
+fn decode_timestamp(timestamp: &Timestamp) -> SystemTime {
+    UNIX_EPOCH
+        + Duration::new(
+            timestamp.get_seconds().max(0) as u64,
+            timestamp.get_nanos().max(0) as u32,
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::proto::{Counter, MetricFamily};
+
+    #[test]
+    fn string_field() {
+        let mut family = MetricFamily::default();
+        assert_eq!(get_string(&family, METRIC_FAMILY_UNIT), None);
+        set_string(&mut family, METRIC_FAMILY_UNIT, "seconds");
+        assert_eq!(
+            get_string(&family, METRIC_FAMILY_UNIT),
+            Some("seconds".to_string())
+        );
+    }
+
+    #[test]
+    fn timestamp_field() {
+        let time = UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789);
+        let mut counter = Counter::default();
+        assert_eq!(get_timestamp(&counter, COUNTER_CREATED_TIMESTAMP), None);
+        set_timestamp(&mut counter, COUNTER_CREATED_TIMESTAMP, time);
+        assert_eq!(
+            get_timestamp(&counter, COUNTER_CREATED_TIMESTAMP),
+            Some(time)
+        );
+    }
+
+    #[test]
+    fn exemplar_field() {
+        let mut label = LabelPair::default();
+        label.set_name("trace_id".to_string());
+        label.set_value("0af7651916cd43dd8448eb211c80319c".to_string());
+        let exemplar = Exemplar {
+            labels: vec![label],
+            value: 2.5,
+            timestamp: Some(UNIX_EPOCH + Duration::from_millis(1_700_000_000_500)),
+        };
+
+        let mut counter = Counter::default();
+        assert_eq!(get_exemplar(&counter, COUNTER_EXEMPLAR), None);
+        set_exemplar(&mut counter, COUNTER_EXEMPLAR, &exemplar);
+        assert_eq!(get_exemplar(&counter, COUNTER_EXEMPLAR), Some(exemplar));
+    }
+}
@@ -1,6 +1,8 @@
 use core::fmt;
 use once_cell::sync::OnceCell;
 use opentelemetry_sdk::metrics::{ManualReaderBuilder, MetricError, MetricResult};
+#[cfg(feature = "http-server")]
+use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
 
 use crate::{Collector, PrometheusExporter, ResourceSelector};
@@ -16,18 +18,23 @@ pub struct ExporterBuilder {
     disable_scope_info: bool,
     reader: ManualReaderBuilder,
     resource_selector: ResourceSelector,
+    #[cfg(feature = "http-server")]
+    http_server_addr: Option<SocketAddr>,
 }
 
 impl fmt::Debug for ExporterBuilder {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        f.debug_struct("ExporterBuilder")
+        let mut debug = f.debug_struct("ExporterBuilder");
+        debug
             .field("registry", &self.registry)
             .field("disable_target_info", &self.disable_target_info)
             .field("without_units", &self.without_units)
             .field("without_counter_suffixes", &self.without_counter_suffixes)
             .field("namespace", &self.namespace)
-            .field("disable_scope_info", &self.disable_scope_info)
-            .finish()
+            .field("disable_scope_info", &self.disable_scope_info);
+        #[cfg(feature = "http-server")]
+        debug.field("http_server_addr", &self.http_server_addr);
+        debug.finish()
     }
 }
 
@@ -114,9 +121,31 @@ impl ExporterBuilder {
         self
     }
 
+    /// Configures the exporter to serve its metrics over HTTP on `addr`.
+    ///
+    /// The server is started by [build], on a dedicated thread, and serves the
+    /// metrics of the exporter's registry on the `/metrics` path until the
+    /// exporter is shut down, along with its [MeterProvider]. Use port `0` to
+    /// bind any available port, see [PrometheusExporter::server_addr].
+    ///
+    /// The format of the response is negotiated from the `Accept` header of each
+    /// scrape request, between the Prometheus text format, the OpenMetrics text
+    /// format and the Prometheus protobuf format. The text format is used if the
+    /// header is missing. The response is compressed with gzip if the
+    /// `Accept-Encoding` header allows it.
+    ///
+    /// [build]: ExporterBuilder::build
+    /// [MeterProvider]: opentelemetry_sdk::metrics::SdkMeterProvider
+    #[cfg(feature = "http-server")]
+    pub fn with_http_server(mut self, addr: SocketAddr) -> Self {
+        self.http_server_addr = Some(addr);
+        self
+    }
+
     /// Creates a new [PrometheusExporter] from this configuration.
     pub fn build(self) -> MetricResult<PrometheusExporter> {
         let reader = Arc::new(self.reader.build());
+        let inf_bucket_exemplars = crate::client_model::InfBucketExemplars::default();
 
         let collector = Collector {
             reader: Arc::clone(&reader),
@@ -129,6 +158,7 @@ impl ExporterBuilder {
             inner: Mutex::new(Default::default()),
             resource_selector: self.resource_selector,
             resource_labels_once: OnceCell::new(),
+            inf_bucket_exemplars: inf_bucket_exemplars.clone(),
         };
 
         let registry = self.registry.unwrap_or_default();
@@ -136,6 +166,20 @@ impl ExporterBuilder {
             .register(Box::new(collector))
             .map_err(|e| MetricError::Other(e.to_string()))?;
 
-        Ok(PrometheusExporter { reader })
+        #[cfg(feature = "http-server")]
+        let server = self
+            .http_server_addr
+            .map(|addr| {
+                crate::server::HttpServer::start(addr, registry, inf_bucket_exemplars.clone())
+            })
+            .transpose()
+            .map_err(|e| MetricError::Other(format!("failed to start HTTP server: {e}")))?;
+
+        Ok(PrometheusExporter {
+            reader,
+            inf_bucket_exemplars,
+            #[cfg(feature = "http-server")]
+            server,
+        })
     }
 }
@@ -130,11 +130,17 @@ const SCOPE_INFO_KEYS: [&str; 2] = ["otel_scope_name", "otel_scope_version"];
 // https://github.com/open-telemetry/opentelemetry-specification/blob/v1.20.0/specification/compatibility/prometheus_and_openmetrics.md
 const COUNTER_SUFFIX: &str = "_total";
 
+mod client_model;
 mod config;
+mod native_histogram;
+mod open_metrics;
 mod resource_selector;
+#[cfg(feature = "http-server")]
+mod server;
 mod utils;
 
 pub use config::ExporterBuilder;
+pub use open_metrics::{OpenMetricsEncoder, OPENMETRICS_FORMAT};
 pub use resource_selector::ResourceSelector;
 
 /// Creates a builder to configure a [PrometheusExporter]
@@ -146,6 +152,25 @@ pub fn exporter() -> ExporterBuilder {
 #[derive(Debug)]
 pub struct PrometheusExporter {
     reader: Arc<ManualReader>,
+    inf_bucket_exemplars: client_model::InfBucketExemplars,
+    #[cfg(feature = "http-server")]
+    server: Option<server::HttpServer>,
+}
+
+impl PrometheusExporter {
+    /// Returns an [OpenMetricsEncoder] which, unlike [OpenMetricsEncoder::new],
+    /// also encodes the exemplars of the `+Inf` buckets of the histograms
+    /// collected by this exporter.
+    pub fn open_metrics_encoder(&self) -> OpenMetricsEncoder {
+        OpenMetricsEncoder::with_inf_bucket_exemplars(self.inf_bucket_exemplars.clone())
+    }
+
+    /// Returns the address the built-in HTTP server is listening on, if it was
+    /// enabled with [ExporterBuilder::with_http_server].
+    #[cfg(feature = "http-server")]
+    pub fn server_addr(&self) -> Option<std::net::SocketAddr> {
+        self.server.as_ref().map(server::HttpServer::local_addr)
+    }
 }
 
 impl MetricReader for PrometheusExporter {
@@ -162,6 +187,10 @@ impl MetricReader for PrometheusExporter {
     }
 
     fn shutdown(&self) -> MetricResult<()> {
+        #[cfg(feature = "http-server")]
+        if let Some(server) = &self.server {
+            server.shutdown();
+        }
         self.reader.shutdown()
     }
 
@@ -183,6 +212,7 @@ struct Collector {
     namespace: Option<String>,
     inner: Mutex<CollectorInner>,
     resource_selector: ResourceSelector,
+    inf_bucket_exemplars: client_model::InfBucketExemplars,
 }
 
 #[derive(Default)]
@@ -200,6 +230,13 @@ static HISTOGRAM_TYPES: Lazy<[TypeId; 3]> = Lazy::new(|| {
         TypeId::of::<data::Histogram<f64>>(),
     ]
 });
+static EXPONENTIAL_HISTOGRAM_TYPES: Lazy<[TypeId; 3]> = Lazy::new(|| {
+    [
+        TypeId::of::<data::ExponentialHistogram<i64>>(),
+        TypeId::of::<data::ExponentialHistogram<u64>>(),
+        TypeId::of::<data::ExponentialHistogram<f64>>(),
+    ]
+});
 static SUM_TYPES: Lazy<[TypeId; 3]> = Lazy::new(|| {
     [
         TypeId::of::<data::Sum<i64>>(),
@@ -222,7 +259,7 @@ impl Collector {
         let data = m.data.as_any();
         let type_id = data.type_id();
 
-        if HISTOGRAM_TYPES.contains(&type_id) {
+        if HISTOGRAM_TYPES.contains(&type_id) || EXPONENTIAL_HISTOGRAM_TYPES.contains(&type_id) {
             Some((MetricType::HISTOGRAM, name))
         } else if GAUGE_TYPES.contains(&type_id) {
             Some((MetricType::GAUGE, name))
@@ -252,11 +289,7 @@ impl Collector {
 
     fn get_name(&self, m: &data::Metric) -> Cow<'static, str> {
         let name = utils::sanitize_name(&m.name);
-        let unit_suffixes = if self.without_units {
-            None
-        } else {
-            utils::get_unit_suffixes(&m.unit)
-        };
+        let unit_suffixes = self.get_unit(m);
         match (&self.namespace, unit_suffixes) {
             (Some(namespace), Some(suffix)) => Cow::Owned(format!("{namespace}{name}_{suffix}")),
             (Some(namespace), None) => Cow::Owned(format!("{namespace}{name}")),
@@ -264,6 +297,15 @@ impl Collector {
             (None, None) => name,
         }
     }
+
+    /// Returns the unit of a metric, which is also the suffix of its name.
+    fn get_unit(&self, m: &data::Metric) -> Option<Cow<'static, str>> {
+        if self.without_units {
+            None
+        } else {
+            utils::get_unit_suffixes(&m.unit)
+        }
+    }
 }
 
 impl prometheus::core::Collector for Collector {
@@ -295,6 +337,7 @@ impl prometheus::core::Collector for Collector {
             return vec![];
         }
         let mut res = Vec::with_capacity(metrics.scope_metrics.len() + 1);
+        let mut inf_bucket_exemplars = client_model::InfBucketExemplarsBuilder::default();
 
         let target_info = self.create_target_info_once.get_or_init(|| {
             // Resource should be immutable, we don't need to compute again
@@ -352,15 +395,62 @@ impl prometheus::core::Collector for Collector {
                     continue;
                 }
 
+                let unit = self.get_unit(&metrics);
+                let first_family = res.len();
                 let description = help.unwrap_or_else(|| metrics.description.into());
                 let data = metrics.data.as_any();
 
                 if let Some(hist) = data.downcast_ref::<data::Histogram<i64>>() {
-                    add_histogram_metric(&mut res, hist, description, &scope_labels, name);
+                    add_histogram_metric(
+                        &mut res,
+                        &mut inf_bucket_exemplars,
+                        hist,
+                        description,
+                        &scope_labels,
+                        name,
+                    );
                 } else if let Some(hist) = data.downcast_ref::<data::Histogram<u64>>() {
-                    add_histogram_metric(&mut res, hist, description, &scope_labels, name);
+                    add_histogram_metric(
+                        &mut res,
+                        &mut inf_bucket_exemplars,
+                        hist,
+                        description,
+                        &scope_labels,
+                        name,
+                    );
                 } else if let Some(hist) = data.downcast_ref::<data::Histogram<f64>>() {
-                    add_histogram_metric(&mut res, hist, description, &scope_labels, name);
+                    add_histogram_metric(
+                        &mut res,
+                        &mut inf_bucket_exemplars,
+                        hist,
+                        description,
+                        &scope_labels,
+                        name,
+                    );
+                } else if let Some(hist) = data.downcast_ref::<data::ExponentialHistogram<i64>>() {
+                    add_exponential_histogram_metric(
+                        &mut res,
+                        hist,
+                        description,
+                        &scope_labels,
+                        name,
+                    );
+                } else if let Some(hist) = data.downcast_ref::<data::ExponentialHistogram<u64>>() {
+                    add_exponential_histogram_metric(
+                        &mut res,
+                        hist,
+                        description,
+                        &scope_labels,
+                        name,
+                    );
+                } else if let Some(hist) = data.downcast_ref::<data::ExponentialHistogram<f64>>() {
+                    add_exponential_histogram_metric(
+                        &mut res,
+                        hist,
+                        description,
+                        &scope_labels,
+                        name,
+                    );
                 } else if let Some(sum) = data.downcast_ref::<data::Sum<u64>>() {
                     add_sum_metric(&mut res, sum, description, &scope_labels, name);
                 } else if let Some(sum) = data.downcast_ref::<data::Sum<i64>>() {
@@ -374,9 +464,16 @@ impl prometheus::core::Collector for Collector {
                 } else if let Some(g) = data.downcast_ref::<data::Gauge<f64>>() {
                     add_gauge_metric(&mut res, g, description, &scope_labels, name);
                 }
+
+                if let Some(unit) = unit {
+                    for mf in &mut res[first_family..] {
+                        client_model::set_string(mf, client_model::METRIC_FAMILY_UNIT, &unit);
+                    }
+                }
             }
         }
 
+        self.inf_bucket_exemplars.replace(inf_bucket_exemplars);
         res
     }
 }
@@ -450,21 +547,19 @@ fn validate_metrics(
 
 fn add_histogram_metric<T: Numeric>(
     res: &mut Vec<MetricFamily>,
+    inf_bucket_exemplars: &mut client_model::InfBucketExemplarsBuilder,
     histogram: &data::Histogram<T>,
     description: String,
     extra: &[LabelPair],
     name: Cow<'static, str>,
 ) {
-    // Consider supporting exemplars when `prometheus` crate has the feature
-    // See: https://github.com/tikv/rust-prometheus/issues/393
-
     for dp in &histogram.data_points {
         let kvs = get_attrs(
             &mut dp.attributes.iter().map(|kv| (&kv.key, &kv.value)),
             extra,
         );
         let bounds_len = dp.bounds.len();
-        let (bucket, _) = dp.bounds.iter().enumerate().fold(
+        let (mut bucket, _) = dp.bounds.iter().enumerate().fold(
             (Vec::with_capacity(bounds_len), 0),
             |(mut acc, mut count), (i, bound)| {
                 count += dp.bucket_counts[i];
@@ -477,9 +572,38 @@ fn add_histogram_metric<T: Numeric>(
             },
         );
 
+        // attach the latest exemplar of each bucket to it
+        let mut exemplars: Vec<Option<&data::Exemplar<T>>> = vec![None; bounds_len + 1];
+        for exemplar in &dp.exemplars {
+            let value = exemplar.value.as_f64();
+            let index = dp
+                .bounds
+                .iter()
+                .position(|bound| value <= *bound)
+                .unwrap_or(bounds_len);
+            if exemplars[index].map_or(true, |latest| latest.time <= exemplar.time) {
+                exemplars[index] = Some(exemplar);
+            }
+        }
+
         let mut h = prometheus::proto::Histogram::default();
+        for (b, exemplar) in bucket.iter_mut().zip(&exemplars) {
+            if let Some(exemplar) = exemplar {
+                let exemplar = convert_exemplar(exemplar);
+                client_model::set_exemplar(b, client_model::BUCKET_EXEMPLAR, &exemplar);
+            }
+        }
+        // the `+Inf` bucket stays implicit, so the text format is unchanged
+        if let Some(exemplar) = exemplars[bounds_len] {
+            inf_bucket_exemplars.insert(&name, &kvs, convert_exemplar(exemplar));
+        }
         h.set_sample_sum(dp.sum.as_f64());
         h.set_sample_count(dp.count);
+        client_model::set_timestamp(
+            &mut h,
+            client_model::HISTOGRAM_CREATED_TIMESTAMP,
+            dp.start_time,
+        );
         h.set_bucket(protobuf::RepeatedField::from_vec(bucket));
         let mut pm = prometheus::proto::Metric::default();
         pm.set_label(protobuf::RepeatedField::from_vec(kvs));
@@ -494,6 +618,101 @@ fn add_histogram_metric<T: Numeric>(
     }
 }
 
+/// Exports exponential histograms as Prometheus native histograms.
+///
+/// Native histograms are only encoded by the protobuf exposition format, the
+/// text format only exposes their count and sum.
+fn add_exponential_histogram_metric<T: Numeric>(
+    res: &mut Vec<MetricFamily>,
+    histogram: &data::ExponentialHistogram<T>,
+    description: String,
+    extra: &[LabelPair],
+    name: Cow<'static, str>,
+) {
+    for dp in &histogram.data_points {
+        let kvs = get_attrs(
+            &mut dp.attributes.iter().map(|kv| (&kv.key, &kv.value)),
+            extra,
+        );
+
+        let mut h = prometheus::proto::Histogram::default();
+        h.set_sample_sum(dp.sum.as_f64());
+        h.set_sample_count(dp.count as u64);
+        client_model::set_timestamp(
+            &mut h,
+            client_model::HISTOGRAM_CREATED_TIMESTAMP,
+            dp.start_time,
+        );
+        if let Some(native) = native_histogram::NativeHistogram::new(
+            dp.scale,
+            dp.zero_threshold,
+            dp.zero_count,
+            &dp.positive_bucket,
+            &dp.negative_bucket,
+        ) {
+            native.write_to(&mut h);
+        } else {
+            otel_warn!(
+                name: "ExponentialHistogramScaleUnsupported",
+                message = "Scale of exponential histogram is too low for a native histogram, only its count and sum are exported",
+                metric_name = name.as_ref(),
+                scale = dp.scale,
+                min_scale = native_histogram::MIN_SCHEMA,
+            );
+        }
+        let mut pm = prometheus::proto::Metric::default();
+        pm.set_label(protobuf::RepeatedField::from_vec(kvs));
+        pm.set_histogram(h);
+
+        let mut mf = prometheus::proto::MetricFamily::default();
+        mf.set_name(name.to_string());
+        mf.set_help(description.clone());
+        mf.set_field_type(prometheus::proto::MetricType::HISTOGRAM);
+        mf.set_metric(protobuf::RepeatedField::from_vec(vec![pm]));
+        res.push(mf);
+    }
+}
+
+/// The maximum length of the label names and values of an OpenMetrics exemplar.
+const EXEMPLAR_MAX_LABELS_LENGTH: usize = 128;
+
+/// Converts an exemplar, labelled with the ids of the trace and span it was
+/// recorded in, and with as many of its filtered attributes as its labels
+/// can hold.
+fn convert_exemplar<T: Numeric>(exemplar: &data::Exemplar<T>) -> client_model::Exemplar {
+    let mut labels = Vec::new();
+    let mut length = 0;
+    let mut push_label = |name: String, value: String| {
+        length += name.chars().count() + value.chars().count();
+        if length <= EXEMPLAR_MAX_LABELS_LENGTH {
+            let mut lp = LabelPair::new();
+            lp.set_name(name);
+            lp.set_value(value);
+            labels.push(lp);
+        }
+    };
+    if exemplar.trace_id != [0; 16] && exemplar.span_id != [0; 8] {
+        push_label("trace_id".to_string(), to_hex(&exemplar.trace_id));
+        push_label("span_id".to_string(), to_hex(&exemplar.span_id));
+    }
+    for kv in &exemplar.filtered_attributes {
+        push_label(
+            utils::sanitize_prom_kv(kv.key.as_str()),
+            kv.value.to_string(),
+        );
+    }
+
+    client_model::Exemplar {
+        labels,
+        value: exemplar.value.as_f64(),
+        timestamp: Some(exemplar.time),
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
 fn add_sum_metric<T: Numeric>(
     res: &mut Vec<MetricFamily>,
     sum: &data::Sum<T>,
@@ -519,6 +738,17 @@ fn add_sum_metric<T: Numeric>(
         if sum.is_monotonic {
             let mut c = prometheus::proto::Counter::default();
             c.set_value(dp.value.as_f64());
+            if let Some(start_time) = dp.start_time {
+                client_model::set_timestamp(
+                    &mut c,
+                    client_model::COUNTER_CREATED_TIMESTAMP,
+                    start_time,
+                );
+            }
+            if let Some(exemplar) = dp.exemplars.iter().max_by_key(|exemplar| exemplar.time) {
+                let exemplar = convert_exemplar(exemplar);
+                client_model::set_exemplar(&mut c, client_model::COUNTER_EXEMPLAR, &exemplar);
+            }
             pm.set_counter(c);
         } else {
             let mut g = prometheus::proto::Gauge::default();
@@ -636,3 +866,145 @@ impl Numeric for f64 {
         *self
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::KeyValue;
+    use prometheus::Encoder;
+    use protobuf::Message;
+    use std::time::{Duration, SystemTime};
+
+    fn new_exemplar(value: f64, time: SystemTime) -> data::Exemplar<f64> {
+        data::Exemplar {
+            filtered_attributes: vec![KeyValue::new("http.route", "/")],
+            time,
+            value,
+            span_id: [1; 8],
+            trace_id: [2; 16],
+        }
+    }
+
+    #[test]
+    fn convert_exemplar_labels() {
+        let exemplar = convert_exemplar(&new_exemplar(1.5, SystemTime::UNIX_EPOCH));
+        let labels: Vec<_> = exemplar
+            .labels
+            .iter()
+            .map(|lp| (lp.get_name(), lp.get_value()))
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                ("trace_id", "02020202020202020202020202020202"),
+                ("span_id", "0101010101010101"),
+                ("http_route", "/"),
+            ]
+        );
+        assert_eq!(exemplar.value, 1.5);
+        assert_eq!(exemplar.timestamp, Some(SystemTime::UNIX_EPOCH));
+
+        // labels are limited to 128 characters
+        let mut long = data::Exemplar {
+            filtered_attributes: vec![KeyValue::new("key", "v".repeat(100))],
+            ..new_exemplar(1.0, SystemTime::UNIX_EPOCH)
+        };
+        assert_eq!(convert_exemplar(&long).labels.len(), 2);
+        // exemplars recorded outside of a span have no trace and span ids
+        long.trace_id = [0; 16];
+        long.span_id = [0; 8];
+        assert_eq!(convert_exemplar(&long).labels.len(), 1);
+    }
+
+    #[test]
+    fn histogram_exemplars_and_created() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000);
+        let histogram = data::Histogram {
+            data_points: vec![data::HistogramDataPoint {
+                attributes: vec![],
+                start_time: start,
+                time: start + Duration::from_secs(10),
+                count: 4,
+                bounds: vec![1.0, 10.0],
+                bucket_counts: vec![2, 1, 1],
+                min: None,
+                max: None,
+                sum: 105.0,
+                exemplars: vec![
+                    new_exemplar(0.5, start + Duration::from_secs(1)),
+                    new_exemplar(0.7, start + Duration::from_secs(2)),
+                    new_exemplar(100.0, start + Duration::from_secs(3)),
+                ],
+            }],
+            temporality: Temporality::Cumulative,
+        };
+
+        let mut res = Vec::new();
+        let mut builder = client_model::InfBucketExemplarsBuilder::default();
+        add_histogram_metric(
+            &mut res,
+            &mut builder,
+            &histogram,
+            String::new(),
+            &[],
+            "h".into(),
+        );
+        let inf_bucket_exemplars = client_model::InfBucketExemplars::default();
+        inf_bucket_exemplars.replace(builder);
+        let h = res[0].get_metric()[0].get_histogram();
+
+        assert_eq!(
+            client_model::get_timestamp(h, client_model::HISTOGRAM_CREATED_TIMESTAMP),
+            Some(start)
+        );
+        let exemplars: Vec<_> = h
+            .get_bucket()
+            .iter()
+            .map(|b| {
+                (
+                    b.get_upper_bound(),
+                    client_model::get_exemplar(b, client_model::BUCKET_EXEMPLAR).map(|e| e.value),
+                )
+            })
+            .collect();
+        // the latest exemplar of each bucket, the `+Inf` bucket staying implicit
+        assert_eq!(exemplars, vec![(1.0, Some(0.7)), (10.0, None)]);
+        // the `+Inf` exemplar is kept out of the message
+        assert!(h
+            .get_unknown_fields()
+            .iter()
+            .all(|(field, _)| field == client_model::HISTOGRAM_CREATED_TIMESTAMP));
+        assert_eq!(
+            inf_bucket_exemplars
+                .get(&res[0], &res[0].get_metric()[0])
+                .map(|e| e.value),
+            Some(100.0)
+        );
+
+        // the protobuf format has an explicit `+Inf` bucket with the exemplar
+        let families = client_model::with_inf_buckets(&res, &inf_bucket_exemplars);
+        let h = families[0].get_metric()[0].get_histogram();
+        let inf_bucket = &h.get_bucket()[2];
+        assert_eq!(inf_bucket.get_upper_bound(), f64::INFINITY);
+        assert_eq!(inf_bucket.get_cumulative_count(), 4);
+        assert_eq!(
+            client_model::get_exemplar(inf_bucket, client_model::BUCKET_EXEMPLAR).map(|e| e.value),
+            Some(100.0)
+        );
+
+        // the text format is the same with or without the `+Inf` exemplar
+        let mut text = Vec::new();
+        prometheus::TextEncoder::new()
+            .encode(&res, &mut text)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(text).unwrap(),
+            "# TYPE h histogram\n\
+             h_bucket{le=\"1\"} 2\n\
+             h_bucket{le=\"10\"} 3\n\
+             h_bucket{le=\"+Inf\"} 4\n\
+             h_sum 105\n\
+             h_count 4\n"
+        );
+    }
+}
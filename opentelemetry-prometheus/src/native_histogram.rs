@@ -0,0 +1,296 @@
+//! Conversion of exponential histograms to Prometheus [native histograms].
+//!
+//! The `prometheus` crate does not know about native histograms yet, so their
+//! fields are added to the `Histogram` messages as unknown fields. They are
+//! encoded by the protobuf exposition format, and ignored by the text format,
+//! which only exposes the count and sum of such histograms.
+//!
+//! [native histograms]: https://prometheus.io/docs/specs/native_histograms/
+
+use opentelemetry_sdk::metrics::data::ExponentialBucket;
+use prometheus::proto::Histogram;
+use protobuf::Message;
+
+/// The lowest schema supported by Prometheus.
+pub(crate) const MIN_SCHEMA: i8 = -4;
+/// The highest schema supported by Prometheus, exponential histograms with a
+/// higher scale are downscaled.
+const MAX_SCHEMA: i8 = 8;
+
+// Field numbers of the native histogram fields of the `Histogram` message.
+// See https://github.com/prometheus/client_model/blob/master/io/prometheus/client/metrics.proto
+const SCHEMA_FIELD: u32 = 5;
+const ZERO_THRESHOLD_FIELD: u32 = 6;
+const ZERO_COUNT_FIELD: u32 = 7;
+const NEGATIVE_SPAN_FIELD: u32 = 9;
+const NEGATIVE_DELTA_FIELD: u32 = 10;
+const POSITIVE_SPAN_FIELD: u32 = 12;
+const POSITIVE_DELTA_FIELD: u32 = 13;
+
+/// A sequence of consecutive buckets of a native histogram.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct BucketSpan {
+    /// Gap to the previous span, or the index of the first bucket for the first span.
+    pub(crate) offset: i32,
+    /// Number of consecutive buckets.
+    pub(crate) length: u32,
+}
+
+/// The native histogram fields of a Prometheus `Histogram`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct NativeHistogram {
+    pub(crate) schema: i32,
+    pub(crate) zero_threshold: f64,
+    pub(crate) zero_count: u64,
+    pub(crate) negative_spans: Vec<BucketSpan>,
+    pub(crate) negative_deltas: Vec<i64>,
+    pub(crate) positive_spans: Vec<BucketSpan>,
+    pub(crate) positive_deltas: Vec<i64>,
+}
+
+impl NativeHistogram {
+    /// Converts the buckets of an exponential histogram data point.
+    ///
+    /// Returns `None` if `scale` is lower than [MIN_SCHEMA], as such histograms
+    /// cannot be represented. Scales higher than the highest schema are reduced
+    /// by merging neighbouring buckets.
+    pub(crate) fn new(
+        scale: i8,
+        zero_threshold: f64,
+        zero_count: u64,
+        positive: &ExponentialBucket,
+        negative: &ExponentialBucket,
+    ) -> Option<Self> {
+        if scale < MIN_SCHEMA {
+            return None;
+        }
+        let downscale = (scale - MAX_SCHEMA).max(0) as u32;
+        let (positive_spans, positive_deltas) = spans_and_deltas(positive, downscale);
+        let (negative_spans, negative_deltas) = spans_and_deltas(negative, downscale);
+
+        Some(NativeHistogram {
+            schema: scale.min(MAX_SCHEMA).into(),
+            zero_threshold,
+            zero_count,
+            negative_spans,
+            negative_deltas,
+            positive_spans,
+            positive_deltas,
+        })
+    }
+
+    /// Sets the native histogram fields of `histogram`.
+    pub(crate) fn write_to(&self, histogram: &mut Histogram) {
+        let fields = histogram.mut_unknown_fields();
+        fields.add_varint(SCHEMA_FIELD, zigzag(self.schema.into()));
+        fields.add_fixed64(ZERO_THRESHOLD_FIELD, self.zero_threshold.to_bits());
+        fields.add_varint(ZERO_COUNT_FIELD, self.zero_count);
+
+        let mut positive_spans = self.positive_spans.as_slice();
+        if positive_spans.is_empty()
+            && self.negative_spans.is_empty()
+            && self.zero_threshold == 0.0
+            && self.zero_count == 0
+        {
+            // Prometheus needs at least one span, or a zero bucket, to tell
+            // an empty native histogram apart from a classic one.
+            positive_spans = &[BucketSpan {
+                offset: 0,
+                length: 0,
+            }];
+        }
+
+        for span in &self.negative_spans {
+            fields.add_length_delimited(NEGATIVE_SPAN_FIELD, encode_span(span));
+        }
+        for delta in &self.negative_deltas {
+            fields.add_varint(NEGATIVE_DELTA_FIELD, zigzag(*delta));
+        }
+        for span in positive_spans {
+            fields.add_length_delimited(POSITIVE_SPAN_FIELD, encode_span(span));
+        }
+        for delta in &self.positive_deltas {
+            fields.add_varint(POSITIVE_DELTA_FIELD, zigzag(*delta));
+        }
+    }
+}
+
+/// Converts exponential histogram buckets to the spans and count deltas of
+/// native histogram buckets, after reducing their scale by `downscale`.
+///
+/// Exponential histogram bucket `i` holds the values in `(base^i, base^(i+1)]`,
+/// while native histogram bucket `i` holds the values in `(base^(i-1), base^i]`.
+/// Empty buckets are skipped, except for gaps of up to two buckets which are
+/// cheaper to encode as part of a span than as a new span.
+fn spans_and_deltas(bucket: &ExponentialBucket, downscale: u32) -> (Vec<BucketSpan>, Vec<i64>) {
+    let mut buckets: Vec<(i32, u64)> = Vec::new();
+    for (i, &count) in bucket.counts.iter().enumerate() {
+        if count == 0 {
+            continue;
+        }
+        let index = ((bucket.offset + i as i32) >> downscale) + 1;
+        match buckets.last_mut() {
+            Some((last, last_count)) if *last == index => *last_count += count,
+            _ => buckets.push((index, count)),
+        }
+    }
+
+    let mut spans: Vec<BucketSpan> = Vec::new();
+    let mut deltas = Vec::with_capacity(buckets.len());
+    let mut previous_count = 0;
+    let mut next_index = None;
+    for (index, count) in buckets {
+        match (next_index, spans.last_mut()) {
+            (Some(next), Some(span)) if index - next <= 2 => {
+                for _ in next..index {
+                    deltas.push(-previous_count);
+                    previous_count = 0;
+                }
+                span.length += (index - next) as u32;
+            }
+            (Some(next), _) => spans.push(BucketSpan {
+                offset: index - next,
+                length: 0,
+            }),
+            (None, _) => spans.push(BucketSpan {
+                offset: index,
+                length: 0,
+            }),
+        }
+        if let Some(span) = spans.last_mut() {
+            span.length += 1;
+        }
+        deltas.push(count as i64 - previous_count);
+        previous_count = count as i64;
+        next_index = Some(index + 1);
+    }
+
+    (spans, deltas)
+}
+
+/// Encodes a `BucketSpan` message.
+fn encode_span(span: &BucketSpan) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(8);
+    // field 1, sint32 offset
+    buf.push(0x08);
+    encode_varint(zigzag(span.offset.into()), &mut buf);
+    // field 2, uint32 length
+    buf.push(0x10);
+    encode_varint(span.length.into(), &mut buf);
+    buf
+}
+
+fn encode_varint(mut value: u64, buf: &mut Vec<u8>) {
+    while value >= 0x80 {
+        buf.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    buf.push(value as u8);
+}
+
+/// ZigZag encoding of signed integers, used by the `sint32` and `sint64` types.
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bucket(offset: i32, counts: Vec<u64>) -> ExponentialBucket {
+        ExponentialBucket { offset, counts }
+    }
+
+    fn span(offset: i32, length: u32) -> BucketSpan {
+        BucketSpan { offset, length }
+    }
+
+    #[test]
+    fn spans_and_deltas_of_buckets() {
+        // empty
+        assert_eq!(spans_and_deltas(&bucket(0, vec![]), 0), (vec![], vec![]));
+
+        // contiguous buckets, indexes are shifted by one
+        assert_eq!(
+            spans_and_deltas(&bucket(-2, vec![1, 3, 2]), 0),
+            (vec![span(-1, 3)], vec![1, 2, -1])
+        );
+
+        // gaps of up to two buckets are filled, longer gaps start a new span
+        assert_eq!(
+            spans_and_deltas(&bucket(0, vec![1, 0, 0, 2, 0, 0, 0, 4]), 0),
+            (vec![span(1, 4), span(3, 1)], vec![1, -1, 0, 2, 2])
+        );
+
+        // leading and trailing empty buckets are skipped
+        assert_eq!(
+            spans_and_deltas(&bucket(3, vec![0, 5, 0]), 0),
+            (vec![span(5, 1)], vec![5])
+        );
+    }
+
+    #[test]
+    fn spans_and_deltas_downscaled() {
+        // at half the resolution, buckets -2 and -1, 0 and 1, 2 and 3 are merged
+        assert_eq!(
+            spans_and_deltas(&bucket(-2, vec![1, 1, 2, 3, 4, 0]), 1),
+            (vec![span(0, 3)], vec![2, 3, -1])
+        );
+    }
+
+    #[test]
+    fn native_histogram_schema() {
+        let empty = bucket(0, vec![]);
+        assert_eq!(
+            NativeHistogram::new(3, 0.0, 0, &empty, &empty).map(|h| h.schema),
+            Some(3)
+        );
+        assert_eq!(
+            NativeHistogram::new(20, 0.0, 0, &empty, &empty).map(|h| h.schema),
+            Some(MAX_SCHEMA.into())
+        );
+        assert_eq!(NativeHistogram::new(-5, 0.0, 0, &empty, &empty), None);
+    }
+
+    #[test]
+    fn write_native_histogram() {
+        let native =
+            NativeHistogram::new(2, 0.0, 3, &bucket(0, vec![1, 2]), &bucket(-1, vec![4])).unwrap();
+        let mut histogram = Histogram::default();
+        native.write_to(&mut histogram);
+
+        let fields = histogram.get_unknown_fields();
+        assert_eq!(fields.get(SCHEMA_FIELD).unwrap().varint, vec![4]);
+        assert_eq!(
+            fields.get(ZERO_THRESHOLD_FIELD).unwrap().fixed64,
+            vec![0.0f64.to_bits()]
+        );
+        assert_eq!(fields.get(ZERO_COUNT_FIELD).unwrap().varint, vec![3]);
+        assert_eq!(
+            fields.get(POSITIVE_SPAN_FIELD).unwrap().length_delimited,
+            vec![vec![0x08, 0x02, 0x10, 0x02]]
+        );
+        assert_eq!(fields.get(POSITIVE_DELTA_FIELD).unwrap().varint, vec![2, 2]);
+        assert_eq!(
+            fields.get(NEGATIVE_SPAN_FIELD).unwrap().length_delimited,
+            vec![vec![0x08, 0x00, 0x10, 0x01]]
+        );
+        assert_eq!(fields.get(NEGATIVE_DELTA_FIELD).unwrap().varint, vec![8]);
+    }
+
+    #[test]
+    fn write_empty_native_histogram() {
+        let empty = bucket(0, vec![]);
+        let mut histogram = Histogram::default();
+        NativeHistogram::new(0, 0.0, 0, &empty, &empty)
+            .unwrap()
+            .write_to(&mut histogram);
+
+        let fields = histogram.get_unknown_fields();
+        assert_eq!(
+            fields.get(POSITIVE_SPAN_FIELD).unwrap().length_delimited,
+            vec![vec![0x08, 0x00, 0x10, 0x00]]
+        );
+        assert!(fields.get(POSITIVE_DELTA_FIELD).is_none());
+    }
+}
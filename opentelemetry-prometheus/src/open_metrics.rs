@@ -0,0 +1,487 @@
+//! Encoding of metric families in the [OpenMetrics] text format.
+//!
+//! [OpenMetrics]: https://github.com/OpenObservability/OpenMetrics/blob/main/specification/OpenMetrics.md
+
+use prometheus::proto::{LabelPair, Metric, MetricFamily, MetricType};
+use prometheus::Encoder;
+use std::io::{self, Write};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::client_model::{self, Exemplar, InfBucketExemplars};
+use crate::COUNTER_SUFFIX;
+
+/// The content type of the OpenMetrics text format.
+pub const OPENMETRICS_FORMAT: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// An [Encoder] for the [OpenMetrics] text format.
+///
+/// In addition to what the Prometheus text format exposes, it encodes the unit
+/// of metric families as `# UNIT` metadata, the start time of counters and
+/// histograms as `_created` samples, and their exemplars, labelled with the
+/// `trace_id` and `span_id` of the span they were recorded in.
+///
+/// It can be used in place of [prometheus::TextEncoder] when a scrape request
+/// accepts the [OPENMETRICS_FORMAT] content type.
+///
+/// The exemplars of the `+Inf` buckets of histograms are not part of the
+/// gathered metric families: they are only encoded by the encoder returned by
+/// [PrometheusExporter::open_metrics_encoder], for the histograms of that
+/// exporter.
+///
+/// [PrometheusExporter::open_metrics_encoder]: crate::PrometheusExporter::open_metrics_encoder
+///
+/// [OpenMetrics]: https://github.com/OpenObservability/OpenMetrics/blob/main/specification/OpenMetrics.md
+///
+/// # Examples
+///
+/// ```
+/// use prometheus::Encoder;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let registry = prometheus::Registry::new();
+/// let exporter = opentelemetry_prometheus::exporter()
+///     .with_registry(registry.clone())
+///     .build()?;
+///
+/// let encoder = exporter.open_metrics_encoder();
+/// let mut result = Vec::new();
+/// encoder.encode(&registry.gather(), &mut result)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Default)]
+pub struct OpenMetricsEncoder {
+    inf_bucket_exemplars: InfBucketExemplars,
+}
+
+impl OpenMetricsEncoder {
+    /// Creates a new OpenMetrics encoder, which does not encode the exemplars
+    /// of the `+Inf` buckets of histograms.
+    pub fn new() -> Self {
+        OpenMetricsEncoder::default()
+    }
+
+    /// Creates an OpenMetrics encoder also encoding the `+Inf` bucket
+    /// exemplars of `inf_bucket_exemplars`.
+    pub(crate) fn with_inf_bucket_exemplars(inf_bucket_exemplars: InfBucketExemplars) -> Self {
+        OpenMetricsEncoder {
+            inf_bucket_exemplars,
+        }
+    }
+}
+
+impl Encoder for OpenMetricsEncoder {
+    fn encode<W: Write>(&self, mfs: &[MetricFamily], writer: &mut W) -> prometheus::Result<()> {
+        encode(mfs, &self.inf_bucket_exemplars, writer)?;
+        Ok(())
+    }
+
+    fn format_type(&self) -> &str {
+        OPENMETRICS_FORMAT
+    }
+}
+
+/// Encodes `families` in the OpenMetrics text format, terminated by `# EOF`.
+fn encode(
+    families: &[MetricFamily],
+    inf_bucket_exemplars: &InfBucketExemplars,
+    writer: &mut dyn Write,
+) -> io::Result<()> {
+    for family in families {
+        if family.get_metric().is_empty() {
+            continue;
+        }
+
+        let name = family.get_name();
+        let metric_type = family.get_field_type();
+        // OpenMetrics counter families are named without their `_total` suffix,
+        // which is only part of the name of their samples.
+        let family_name = match metric_type {
+            MetricType::COUNTER => name.strip_suffix(COUNTER_SUFFIX).unwrap_or(name),
+            _ => name,
+        };
+
+        writeln!(writer, "# TYPE {family_name} {}", type_name(metric_type))?;
+        if let Some(unit) = client_model::get_string(family, client_model::METRIC_FAMILY_UNIT) {
+            // the name of a family must end with its unit
+            if family_name.ends_with(&format!("_{unit}")) {
+                writeln!(writer, "# UNIT {family_name} {unit}")?;
+            }
+        }
+        if !family.get_help().is_empty() {
+            writeln!(writer, "# HELP {family_name} {}", escape(family.get_help()))?;
+        }
+
+        for metric in family.get_metric() {
+            let mut samples = SampleWriter {
+                writer: &mut *writer,
+                name: family_name,
+                metric,
+            };
+            match metric_type {
+                MetricType::COUNTER => {
+                    let counter = metric.get_counter();
+                    let exemplar =
+                        client_model::get_exemplar(counter, client_model::COUNTER_EXEMPLAR);
+                    samples.write(COUNTER_SUFFIX, None, counter.get_value(), exemplar.as_ref())?;
+                    let created = client_model::get_timestamp(
+                        counter,
+                        client_model::COUNTER_CREATED_TIMESTAMP,
+                    );
+                    if let Some(created) = created {
+                        samples.write("_created", None, seconds(created), None)?;
+                    }
+                }
+                MetricType::GAUGE => {
+                    samples.write("", None, metric.get_gauge().get_value(), None)?;
+                }
+                MetricType::HISTOGRAM => {
+                    let histogram = metric.get_histogram();
+                    let mut has_inf_bucket = false;
+                    for bucket in histogram.get_bucket() {
+                        let upper_bound = bucket.get_upper_bound();
+                        has_inf_bucket |= upper_bound == f64::INFINITY;
+                        let exemplar =
+                            client_model::get_exemplar(bucket, client_model::BUCKET_EXEMPLAR);
+                        samples.write(
+                            "_bucket",
+                            Some(("le", upper_bound)),
+                            bucket.get_cumulative_count() as f64,
+                            exemplar.as_ref(),
+                        )?;
+                    }
+                    let count = histogram.get_sample_count() as f64;
+                    if !has_inf_bucket {
+                        let exemplar = inf_bucket_exemplars.get(family, metric);
+                        samples.write(
+                            "_bucket",
+                            Some(("le", f64::INFINITY)),
+                            count,
+                            exemplar.as_ref(),
+                        )?;
+                    }
+                    samples.write("_count", None, count, None)?;
+                    samples.write("_sum", None, histogram.get_sample_sum(), None)?;
+                    let created = client_model::get_timestamp(
+                        histogram,
+                        client_model::HISTOGRAM_CREATED_TIMESTAMP,
+                    );
+                    if let Some(created) = created {
+                        samples.write("_created", None, seconds(created), None)?;
+                    }
+                }
+                MetricType::SUMMARY => {
+                    let summary = metric.get_summary();
+                    for quantile in summary.get_quantile() {
+                        samples.write(
+                            "",
+                            Some(("quantile", quantile.get_quantile())),
+                            quantile.get_value(),
+                            None,
+                        )?;
+                    }
+                    samples.write("_count", None, summary.get_sample_count() as f64, None)?;
+                    samples.write("_sum", None, summary.get_sample_sum(), None)?;
+                }
+                MetricType::UNTYPED => {
+                    samples.write("", None, metric.get_untyped().get_value(), None)?;
+                }
+            }
+        }
+    }
+
+    writeln!(writer, "# EOF")
+}
+
+fn type_name(metric_type: MetricType) -> &'static str {
+    match metric_type {
+        MetricType::COUNTER => "counter",
+        MetricType::GAUGE => "gauge",
+        MetricType::HISTOGRAM => "histogram",
+        MetricType::SUMMARY => "summary",
+        MetricType::UNTYPED => "unknown",
+    }
+}
+
+/// Writes the samples of a metric.
+struct SampleWriter<'a> {
+    writer: &'a mut dyn Write,
+    name: &'a str,
+    metric: &'a Metric,
+}
+
+impl SampleWriter<'_> {
+    fn write(
+        &mut self,
+        suffix: &str,
+        extra_label: Option<(&str, f64)>,
+        value: f64,
+        exemplar: Option<&Exemplar>,
+    ) -> io::Result<()> {
+        write!(self.writer, "{}{suffix}", self.name)?;
+        write_labels(
+            self.writer,
+            self.metric.get_label(),
+            extra_label.map(|(name, value)| (name, format_value(value))),
+        )?;
+        write!(self.writer, " {}", format_value(value))?;
+        if self.metric.has_timestamp_ms() {
+            // OpenMetrics timestamps are in seconds
+            let timestamp = self.metric.get_timestamp_ms() as f64 / 1000.0;
+            write!(self.writer, " {}", format_value(timestamp))?;
+        }
+        if let Some(exemplar) = exemplar {
+            write!(self.writer, " #")?;
+            if exemplar.labels.is_empty() {
+                write!(self.writer, " {{}}")?;
+            } else {
+                write!(self.writer, " ")?;
+                write_labels(self.writer, &exemplar.labels, None)?;
+            }
+            write!(self.writer, " {}", format_value(exemplar.value))?;
+            if let Some(timestamp) = exemplar.timestamp {
+                write!(self.writer, " {}", format_value(seconds(timestamp)))?;
+            }
+        }
+        writeln!(self.writer)
+    }
+}
+
+fn write_labels(
+    writer: &mut dyn Write,
+    labels: &[LabelPair],
+    extra_label: Option<(&str, String)>,
+) -> io::Result<()> {
+    if labels.is_empty() && extra_label.is_none() {
+        return Ok(());
+    }
+    let mut separator = '{';
+    for label in labels {
+        write!(
+            writer,
+            "{separator}{}=\"{}\"",
+            label.get_name(),
+            escape(label.get_value())
+        )?;
+        separator = ',';
+    }
+    if let Some((name, value)) = extra_label {
+        write!(writer, "{separator}{name}=\"{value}\"")?;
+    }
+    write!(writer, "}}")
+}
+
+/// Returns the number of seconds since the Unix epoch, as OpenMetrics timestamps.
+fn seconds(time: SystemTime) -> f64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs_f64())
+        .unwrap_or_default()
+}
+
+fn format_value(value: f64) -> String {
+    if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else if value.is_nan() {
+        "NaN".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Escapes backslashes, double quotes and line feeds in label values and help texts.
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '\\' => escaped.push_str(r"\\"),
+            '"' => escaped.push_str(r#"\""#),
+            '\n' => escaped.push_str(r"\n"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client_model::InfBucketExemplarsBuilder;
+    use prometheus::proto::{Bucket, Counter, Gauge, Histogram};
+    use protobuf::RepeatedField;
+    use std::time::Duration;
+
+    fn label(name: &str, value: &str) -> LabelPair {
+        let mut label = LabelPair::default();
+        label.set_name(name.to_string());
+        label.set_value(value.to_string());
+        label
+    }
+
+    fn family(name: &str, help: &str, metric_type: MetricType, metric: Metric) -> MetricFamily {
+        let mut family = MetricFamily::default();
+        family.set_name(name.to_string());
+        family.set_help(help.to_string());
+        family.set_field_type(metric_type);
+        family.set_metric(RepeatedField::from_vec(vec![metric]));
+        family
+    }
+
+    fn encode_to_string(families: &[MetricFamily]) -> String {
+        encode_with_inf_bucket_exemplars(families, &InfBucketExemplars::default())
+    }
+
+    fn encode_with_inf_bucket_exemplars(
+        families: &[MetricFamily],
+        inf_bucket_exemplars: &InfBucketExemplars,
+    ) -> String {
+        let mut output = Vec::new();
+        encode(families, inf_bucket_exemplars, &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn encode_counter_and_gauge() {
+        let mut counter = Counter::default();
+        counter.set_value(100.0);
+        let mut counter_metric = Metric::default();
+        counter_metric.set_label(RepeatedField::from_vec(vec![label("type", "a\"b")]));
+        counter_metric.set_counter(counter);
+
+        let mut gauge = Gauge::default();
+        gauge.set_value(-1.5);
+        let mut gauge_metric = Metric::default();
+        gauge_metric.set_gauge(gauge);
+
+        let families = [
+            family(
+                "requests_total",
+                "Counts\nrequests",
+                MetricType::COUNTER,
+                counter_metric,
+            ),
+            family("temperature", "", MetricType::GAUGE, gauge_metric),
+        ];
+
+        assert_eq!(
+            encode_to_string(&families),
+            "# TYPE requests counter\n\
+             # HELP requests Counts\\nrequests\n\
+             requests_total{type=\"a\\\"b\"} 100\n\
+             # TYPE temperature gauge\n\
+             temperature -1.5\n\
+             # EOF\n"
+        );
+    }
+
+    #[test]
+    fn encode_histogram() {
+        let mut bucket = Bucket::default();
+        bucket.set_upper_bound(5.0);
+        bucket.set_cumulative_count(1);
+        let mut histogram = Histogram::default();
+        histogram.set_bucket(RepeatedField::from_vec(vec![bucket]));
+        histogram.set_sample_count(2);
+        histogram.set_sample_sum(12.5);
+        let mut metric = Metric::default();
+        metric.set_label(RepeatedField::from_vec(vec![label("key", "value")]));
+        metric.set_histogram(histogram);
+
+        assert_eq!(
+            encode_to_string(&[family("latency", "", MetricType::HISTOGRAM, metric)]),
+            "# TYPE latency histogram\n\
+             latency_bucket{key=\"value\",le=\"5\"} 1\n\
+             latency_bucket{key=\"value\",le=\"+Inf\"} 2\n\
+             latency_count{key=\"value\"} 2\n\
+             latency_sum{key=\"value\"} 12.5\n\
+             # EOF\n"
+        );
+    }
+
+    #[test]
+    fn encode_unit_created_and_exemplars() {
+        let created = UNIX_EPOCH + Duration::from_millis(1_700_000_000_250);
+        let exemplar = Exemplar {
+            labels: vec![
+                label("trace_id", "0af7651916cd43dd8448eb211c80319c"),
+                label("span_id", "b7ad6b7169203331"),
+            ],
+            value: 0.5,
+            timestamp: Some(UNIX_EPOCH + Duration::from_millis(1_700_000_001_500)),
+        };
+
+        let mut counter = Counter::default();
+        counter.set_value(3.0);
+        client_model::set_timestamp(
+            &mut counter,
+            client_model::COUNTER_CREATED_TIMESTAMP,
+            created,
+        );
+        client_model::set_exemplar(&mut counter, client_model::COUNTER_EXEMPLAR, &exemplar);
+        let mut counter_metric = Metric::default();
+        counter_metric.set_counter(counter);
+        let mut counter_family = family(
+            "duration_seconds_total",
+            "",
+            MetricType::COUNTER,
+            counter_metric,
+        );
+        client_model::set_string(
+            &mut counter_family,
+            client_model::METRIC_FAMILY_UNIT,
+            "seconds",
+        );
+
+        let mut bucket = Bucket::default();
+        bucket.set_upper_bound(1.0);
+        bucket.set_cumulative_count(1);
+        client_model::set_exemplar(&mut bucket, client_model::BUCKET_EXEMPLAR, &exemplar);
+        let mut histogram = Histogram::default();
+        histogram.set_bucket(RepeatedField::from_vec(vec![bucket]));
+        histogram.set_sample_count(2);
+        histogram.set_sample_sum(3.5);
+        let inf_exemplar = Exemplar {
+            value: 3.0,
+            ..exemplar.clone()
+        };
+        let mut inf_bucket_exemplars = InfBucketExemplarsBuilder::default();
+        inf_bucket_exemplars.insert("latency", &[], inf_exemplar);
+        let exemplars = InfBucketExemplars::default();
+        exemplars.replace(inf_bucket_exemplars);
+        client_model::set_timestamp(
+            &mut histogram,
+            client_model::HISTOGRAM_CREATED_TIMESTAMP,
+            created,
+        );
+        let mut histogram_metric = Metric::default();
+        histogram_metric.set_histogram(histogram);
+        let mut histogram_family = family("latency", "", MetricType::HISTOGRAM, histogram_metric);
+        // not a suffix of the name, so not exposed
+        client_model::set_string(
+            &mut histogram_family,
+            client_model::METRIC_FAMILY_UNIT,
+            "seconds",
+        );
+
+        assert_eq!(
+            encode_with_inf_bucket_exemplars(&[counter_family, histogram_family], &exemplars),
+            "# TYPE duration_seconds counter\n\
+             # UNIT duration_seconds seconds\n\
+             duration_seconds_total 3 # {trace_id=\"0af7651916cd43dd8448eb211c80319c\",span_id=\"b7ad6b7169203331\"} 0.5 1700000001.5\n\
+             duration_seconds_created 1700000000.25\n\
+             # TYPE latency histogram\n\
+             latency_bucket{le=\"1\"} 1 # {trace_id=\"0af7651916cd43dd8448eb211c80319c\",span_id=\"b7ad6b7169203331\"} 0.5 1700000001.5\n\
+             latency_bucket{le=\"+Inf\"} 2 # {trace_id=\"0af7651916cd43dd8448eb211c80319c\",span_id=\"b7ad6b7169203331\"} 3 1700000001.5\n\
+             latency_count 2\n\
+             latency_sum 3.5\n\
+             latency_created 1700000000.25\n\
+             # EOF\n"
+        );
+    }
+
+    #[test]
+    fn encode_empty() {
+        assert_eq!(encode_to_string(&[]), "# EOF\n");
+    }
+}
@@ -0,0 +1,513 @@
+//! Built-in HTTP server exposing the metrics of a [prometheus::Registry] for
+//! Prometheus to scrape.
+
+use flate2::{write::GzEncoder, Compression};
+use http_body_util::Full;
+use hyper::{
+    body::{Bytes, Incoming},
+    header::{HeaderValue, ACCEPT, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_TYPE},
+    server::conn::http1,
+    service::service_fn,
+    Method, Request, Response, StatusCode,
+};
+use hyper_util::rt::TokioIo;
+use opentelemetry::{otel_debug, otel_error};
+use prometheus::{proto::MetricFamily, Encoder, ProtobufEncoder, Registry, TextEncoder};
+use std::{
+    convert::Infallible,
+    fmt, io,
+    io::Write,
+    net::SocketAddr,
+    sync::Mutex,
+    thread::{self, JoinHandle},
+};
+use tokio::{net::TcpListener, sync::oneshot};
+
+use crate::{client_model::InfBucketExemplars, OpenMetricsEncoder};
+
+/// The path metrics are served on.
+const METRICS_PATH: &str = "/metrics";
+
+/// Exposition formats the server can respond with, in order of preference
+/// when the scraper accepts several of them with the same quality.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Format {
+    Protobuf,
+    OpenMetrics,
+    Text,
+}
+
+impl Format {
+    /// Returns the format matching a media range of an `Accept` header.
+    fn from_media_range(media_type: &str, params: &[(&str, &str)]) -> Option<Self> {
+        let param = |name: &str| {
+            params
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| *value)
+        };
+        match media_type.to_ascii_lowercase().as_str() {
+            "application/vnd.google.protobuf" => {
+                let proto = param("proto") == Some("io.prometheus.client.MetricFamily");
+                let delimited = param("encoding").map_or(true, |e| e == "delimited");
+                (proto && delimited).then_some(Format::Protobuf)
+            }
+            "application/openmetrics-text" => param("version")
+                .map_or(true, |v| v == "1.0.0")
+                .then_some(Format::OpenMetrics),
+            "text/plain" | "text/*" | "*/*" => Some(Format::Text),
+            _ => None,
+        }
+    }
+
+    /// Negotiates the format of the response from the `Accept` header of the request.
+    ///
+    /// The text format is used if the header is missing or if none of the
+    /// accepted formats are supported.
+    fn negotiate(accept: Option<&str>) -> Self {
+        let mut best: Option<(Format, f32)> = None;
+        for (media_type, params, quality) in accept.into_iter().flat_map(media_ranges) {
+            let Some(format) = Format::from_media_range(media_type, &params) else {
+                continue;
+            };
+            let better = match best {
+                None => quality > 0.0,
+                Some((best_format, best_quality)) => {
+                    quality > best_quality || (quality == best_quality && format < best_format)
+                }
+            };
+            if better {
+                best = Some((format, quality));
+            }
+        }
+        best.map_or(Format::Text, |(format, _)| format)
+    }
+
+    fn encode(
+        self,
+        registry: &Registry,
+        inf_bucket_exemplars: &InfBucketExemplars,
+    ) -> prometheus::Result<(Vec<u8>, String)> {
+        let families = registry.gather();
+        match self {
+            Format::Protobuf => encode_with(
+                ProtobufEncoder::new(),
+                &crate::client_model::with_inf_buckets(&families, inf_bucket_exemplars),
+            ),
+            Format::OpenMetrics => encode_with(
+                OpenMetricsEncoder::with_inf_bucket_exemplars(inf_bucket_exemplars.clone()),
+                &families,
+            ),
+            Format::Text => encode_with(TextEncoder::new(), &families),
+        }
+    }
+}
+
+fn encode_with(
+    encoder: impl Encoder,
+    families: &[MetricFamily],
+) -> prometheus::Result<(Vec<u8>, String)> {
+    let mut buffer = Vec::new();
+    encoder.encode(families, &mut buffer)?;
+    Ok((buffer, encoder.format_type().to_string()))
+}
+
+/// Splits the value of an `Accept` or `Accept-Encoding` header into its
+/// values, with their parameters and quality.
+fn media_ranges(header: &str) -> impl Iterator<Item = (&str, Vec<(&str, &str)>, f32)> {
+    header.split(',').filter_map(|range| {
+        let mut parts = range.split(';').map(str::trim);
+        let value = parts.next().filter(|value| !value.is_empty())?;
+        let mut quality = 1.0;
+        let mut params = Vec::new();
+        for param in parts {
+            let Some((key, param_value)) = param.split_once('=') else {
+                continue;
+            };
+            let (key, param_value) = (key.trim(), param_value.trim().trim_matches('"'));
+            if key.eq_ignore_ascii_case("q") {
+                quality = param_value.parse().unwrap_or(0.0);
+            } else {
+                params.push((key, param_value));
+            }
+        }
+        Some((value, params, quality))
+    })
+}
+
+/// Returns whether the `Accept-Encoding` header of a request accepts gzip.
+fn accepts_gzip(accept_encoding: Option<&str>) -> bool {
+    accept_encoding
+        .into_iter()
+        .flat_map(media_ranges)
+        .any(|(encoding, _, quality)| {
+            (encoding.eq_ignore_ascii_case("gzip") || encoding == "*") && quality > 0.0
+        })
+}
+
+fn gzip(body: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(body)?;
+    encoder.finish()
+}
+
+fn handle(
+    registry: &Registry,
+    inf_bucket_exemplars: &InfBucketExemplars,
+    request: &Request<Incoming>,
+) -> Response<Full<Bytes>> {
+    if request.uri().path() != METRICS_PATH {
+        return status_response(StatusCode::NOT_FOUND);
+    }
+    if request.method() != Method::GET && request.method() != Method::HEAD {
+        return status_response(StatusCode::METHOD_NOT_ALLOWED);
+    }
+
+    let header = |name| {
+        request
+            .headers()
+            .get(name)
+            .and_then(|value: &HeaderValue| value.to_str().ok())
+    };
+    let format = Format::negotiate(header(ACCEPT));
+    let (mut body, content_type) = match format.encode(registry, inf_bucket_exemplars) {
+        Ok(encoded) => encoded,
+        Err(err) => {
+            otel_error!(
+                name: "PrometheusServer.EncodeFailed",
+                message = err.to_string(),
+            );
+            return status_response(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    let mut response = Response::builder()
+        .status(StatusCode::OK)
+        .header(CONTENT_TYPE, content_type);
+    if accepts_gzip(header(ACCEPT_ENCODING)) {
+        match gzip(&body) {
+            Ok(compressed) => {
+                body = compressed;
+                response = response.header(CONTENT_ENCODING, "gzip");
+            }
+            Err(err) => {
+                otel_error!(
+                    name: "PrometheusServer.CompressFailed",
+                    message = err.to_string(),
+                );
+                return status_response(StatusCode::INTERNAL_SERVER_ERROR);
+            }
+        }
+    }
+
+    response
+        .body(Full::new(Bytes::from(body)))
+        .unwrap_or_else(|_| status_response(StatusCode::INTERNAL_SERVER_ERROR))
+}
+
+fn status_response(status: StatusCode) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::from(
+        status.canonical_reason().unwrap_or_default(),
+    )));
+    *response.status_mut() = status;
+    response
+}
+
+async fn serve(
+    listener: std::net::TcpListener,
+    registry: Registry,
+    inf_bucket_exemplars: InfBucketExemplars,
+    mut shutdown: oneshot::Receiver<()>,
+) {
+    let listener = match TcpListener::from_std(listener) {
+        Ok(listener) => listener,
+        Err(err) => {
+            otel_error!(
+                name: "PrometheusServer.StartFailed",
+                message = err.to_string(),
+            );
+            return;
+        }
+    };
+
+    loop {
+        tokio::select! {
+            _ = &mut shutdown => break,
+            accepted = listener.accept() => {
+                let stream = match accepted {
+                    Ok((stream, _)) => stream,
+                    Err(err) => {
+                        otel_debug!(
+                            name: "PrometheusServer.AcceptFailed",
+                            error = err.to_string(),
+                        );
+                        continue;
+                    }
+                };
+                let registry = registry.clone();
+                let inf_bucket_exemplars = inf_bucket_exemplars.clone();
+                tokio::spawn(async move {
+                    let service = service_fn(move |request| {
+                        let response = handle(&registry, &inf_bucket_exemplars, &request);
+                        async move { Ok::<_, Infallible>(response) }
+                    });
+                    if let Err(err) = http1::Builder::new()
+                        .serve_connection(TokioIo::new(stream), service)
+                        .await
+                    {
+                        otel_debug!(
+                            name: "PrometheusServer.ConnectionFailed",
+                            error = err.to_string(),
+                        );
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// A running HTTP server, serving the metrics of a registry on its own thread
+/// until it is shut down or dropped.
+pub(crate) struct HttpServer {
+    local_addr: SocketAddr,
+    shutdown: Mutex<Option<oneshot::Sender<()>>>,
+    handle: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl fmt::Debug for HttpServer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpServer")
+            .field("local_addr", &self.local_addr)
+            .finish()
+    }
+}
+
+impl HttpServer {
+    /// Binds `addr` and starts serving the metrics of `registry` on it, with
+    /// the `+Inf` bucket exemplars of the exporter.
+    pub(crate) fn start(
+        addr: SocketAddr,
+        registry: Registry,
+        inf_bucket_exemplars: InfBucketExemplars,
+    ) -> io::Result<Self> {
+        let listener = std::net::TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        let local_addr = listener.local_addr()?;
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_io()
+            .build()?;
+
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let handle = thread::Builder::new()
+            .name("OpenTelemetry.Metrics.PrometheusServer".to_string())
+            .spawn(move || {
+                runtime.block_on(serve(listener, registry, inf_bucket_exemplars, shutdown_rx))
+            })?;
+
+        Ok(HttpServer {
+            local_addr,
+            shutdown: Mutex::new(Some(shutdown_tx)),
+            handle: Mutex::new(Some(handle)),
+        })
+    }
+
+    /// The address the server is listening on.
+    pub(crate) fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Stops accepting connections and waits for the server thread to exit.
+    ///
+    /// Requests being served are aborted. Calling it more than once has no effect.
+    pub(crate) fn shutdown(&self) {
+        if let Some(shutdown) = self.shutdown.lock().ok().and_then(|mut s| s.take()) {
+            let _ = shutdown.send(());
+        }
+        if let Some(handle) = self.handle.lock().ok().and_then(|mut h| h.take()) {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for HttpServer {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+    use std::net::TcpStream;
+
+    /// Sends a `GET` request with the given headers and returns the response
+    /// head and body.
+    fn get(addr: SocketAddr, path: &str, headers: &[(&str, &str)]) -> (String, Vec<u8>) {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        let mut request = format!("GET {path} HTTP/1.1\r\nHost: {addr}\r\nConnection: close\r\n");
+        for (name, value) in headers {
+            request.push_str(&format!("{name}: {value}\r\n"));
+        }
+        request.push_str("\r\n");
+        stream.write_all(request.as_bytes()).unwrap();
+
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response).unwrap();
+        let end = response.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+        let head = String::from_utf8(response[..end].to_vec()).unwrap();
+        (head, response[end + 4..].to_vec())
+    }
+
+    fn registry() -> Registry {
+        let registry = Registry::new();
+        let counter = prometheus::IntCounter::new("requests_total", "Counts requests").unwrap();
+        counter.inc_by(3);
+        registry.register(Box::new(counter)).unwrap();
+        registry
+    }
+
+    #[test]
+    fn negotiate_format() {
+        assert_eq!(Format::negotiate(None), Format::Text);
+        assert_eq!(Format::negotiate(Some("application/json")), Format::Text);
+        assert_eq!(
+            Format::negotiate(Some("text/plain;version=0.0.4")),
+            Format::Text
+        );
+        assert_eq!(
+            Format::negotiate(Some(
+                "application/vnd.google.protobuf;proto=io.prometheus.client.MetricFamily;encoding=delimited;q=0.7,text/plain;version=0.0.4;q=0.3"
+            )),
+            Format::Protobuf
+        );
+        assert_eq!(
+            Format::negotiate(Some(
+                "text/plain,application/vnd.google.protobuf;proto=io.prometheus.client.MetricFamily;encoding=delimited"
+            )),
+            Format::Protobuf
+        );
+        // unsupported encodings, and `q=0` means not acceptable
+        assert_eq!(
+            Format::negotiate(Some(
+                "application/vnd.google.protobuf;proto=io.prometheus.client.MetricFamily;encoding=text"
+            )),
+            Format::Text
+        );
+        assert_eq!(
+            Format::negotiate(Some(
+                "application/vnd.google.protobuf;proto=io.prometheus.client.MetricFamily;q=0"
+            )),
+            Format::Text
+        );
+    }
+
+    #[test]
+    fn negotiate_open_metrics_format() {
+        assert_eq!(
+            Format::negotiate(Some(
+                "application/openmetrics-text;version=1.0.0,application/openmetrics-text;version=0.0.1;q=0.75,text/plain;version=0.0.4;q=0.5,*/*;q=0.1"
+            )),
+            Format::OpenMetrics
+        );
+        // unsupported versions
+        assert_eq!(
+            Format::negotiate(Some("application/openmetrics-text;version=2.0.0")),
+            Format::Text
+        );
+        // quality takes precedence over preference, `q=0` means not acceptable
+        assert_eq!(
+            Format::negotiate(Some("application/openmetrics-text;q=0.2,text/plain;q=0.5")),
+            Format::Text
+        );
+        assert_eq!(
+            Format::negotiate(Some("text/plain,application/openmetrics-text")),
+            Format::OpenMetrics
+        );
+        assert_eq!(
+            Format::negotiate(Some("application/openmetrics-text;q=0")),
+            Format::Text
+        );
+    }
+
+    #[test]
+    fn negotiate_gzip() {
+        assert!(!accepts_gzip(None));
+        assert!(accepts_gzip(Some("gzip")));
+        assert!(accepts_gzip(Some("deflate, gzip;q=0.5")));
+        assert!(accepts_gzip(Some("*")));
+        assert!(!accepts_gzip(Some("gzip;q=0")));
+        assert!(!accepts_gzip(Some("identity")));
+    }
+
+    #[test]
+    fn serve_metrics() {
+        let server = HttpServer::start(
+            ([127, 0, 0, 1], 0).into(),
+            registry(),
+            InfBucketExemplars::default(),
+        )
+        .unwrap();
+        let addr = server.local_addr();
+
+        let (head, body) = get(addr, METRICS_PATH, &[]);
+        assert!(head.starts_with("HTTP/1.1 200"), "{head}");
+        assert!(
+            head.contains("content-type: text/plain; version=0.0.4"),
+            "{head}"
+        );
+        let body = String::from_utf8(body).unwrap();
+        assert!(body.contains("requests_total 3"), "{body}");
+
+        let (head, _) = get(
+            addr,
+            METRICS_PATH,
+            &[(
+                "Accept",
+                "application/vnd.google.protobuf;proto=io.prometheus.client.MetricFamily;encoding=delimited",
+            )],
+        );
+        assert!(
+            head.contains("content-type: application/vnd.google.protobuf"),
+            "{head}"
+        );
+
+        let (head, body) = get(addr, METRICS_PATH, &[("Accept-Encoding", "gzip")]);
+        assert!(head.contains("content-encoding: gzip"), "{head}");
+        let mut decoded = String::new();
+        GzDecoder::new(body.as_slice())
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert!(decoded.contains("requests_total 3"), "{decoded}");
+
+        let (head, _) = get(addr, "/", &[]);
+        assert!(head.starts_with("HTTP/1.1 404"), "{head}");
+
+        server.shutdown();
+        assert!(TcpStream::connect(addr).is_err());
+    }
+
+    #[test]
+    fn serve_open_metrics() {
+        let server = HttpServer::start(
+            ([127, 0, 0, 1], 0).into(),
+            registry(),
+            InfBucketExemplars::default(),
+        )
+        .unwrap();
+
+        let (head, body) = get(
+            server.local_addr(),
+            METRICS_PATH,
+            &[("Accept", "application/openmetrics-text; version=1.0.0")],
+        );
+        assert!(
+            head.contains("content-type: application/openmetrics-text; version=1.0.0"),
+            "{head}"
+        );
+        let body = String::from_utf8(body).unwrap();
+        assert!(body.contains("# TYPE requests counter"), "{body}");
+        assert!(body.ends_with("# EOF\n"), "{body}");
+    }
+}
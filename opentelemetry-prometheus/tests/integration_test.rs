@@ -6,14 +6,16 @@ use std::time::Duration;
 use opentelemetry::metrics::{Meter, MeterProvider as _};
 use opentelemetry::KeyValue;
 use opentelemetry::{InstrumentationScope, Key};
-use opentelemetry_prometheus::{ExporterBuilder, ResourceSelector};
-use opentelemetry_sdk::metrics::SdkMeterProvider;
+use opentelemetry_prometheus::{ExporterBuilder, OpenMetricsEncoder, ResourceSelector};
+use opentelemetry_sdk::metrics::{new_view, Aggregation, Instrument, SdkMeterProvider, Stream};
 use opentelemetry_sdk::resource::{
     EnvResourceDetector, SdkProvidedResourceDetector, TelemetryResourceDetector,
 };
 use opentelemetry_sdk::Resource;
 use opentelemetry_semantic_conventions::resource::{SERVICE_NAME, TELEMETRY_SDK_VERSION};
-use prometheus::{Encoder, TextEncoder};
+use prometheus::proto::{MetricFamily, MetricType};
+use prometheus::{Encoder, ProtobufEncoder, TextEncoder};
+use protobuf::Message;
 
 const BOUNDARIES: &[f64] = &[
     0.0, 5.0, 10.0, 25.0, 50.0, 75.0, 100.0, 250.0, 500.0, 1000.0,
@@ -849,3 +851,142 @@ fn gather_and_compare_multi(
         "mismatched output in {name}"
     )
 }
+
+#[test]
+fn native_histograms() {
+    let registry = prometheus::Registry::new();
+    let exporter = ExporterBuilder::default()
+        .with_registry(registry.clone())
+        .without_scope_info()
+        .without_target_info()
+        .build()
+        .unwrap();
+
+    let view = new_view(
+        Instrument::new().name("exponential"),
+        Stream::new().aggregation(Aggregation::Base2ExponentialHistogram {
+            max_size: 160,
+            max_scale: 0,
+            record_min_max: false,
+        }),
+    )
+    .unwrap();
+    let provider = SdkMeterProvider::builder()
+        .with_reader(exporter)
+        .with_view(view)
+        .build();
+
+    let histogram = provider
+        .meter("testmeter")
+        .f64_histogram("exponential")
+        .with_description("an exponential histogram")
+        .build();
+    for v in [1.0, 2.0, 4.0] {
+        histogram.record(v, &[]);
+    }
+
+    // native histograms are only encoded by the protobuf exposition format
+    let mut output = Vec::new();
+    ProtobufEncoder::new()
+        .encode(&registry.gather(), &mut output)
+        .unwrap();
+    let mut input = protobuf::CodedInputStream::from_bytes(&output);
+    let family: MetricFamily = input.read_message().unwrap();
+
+    assert_eq!(family.get_name(), "exponential");
+    assert_eq!(family.get_field_type(), MetricType::HISTOGRAM);
+    let histogram = family.get_metric()[0].get_histogram();
+    assert_eq!(histogram.get_sample_count(), 3);
+    assert_eq!(histogram.get_sample_sum(), 7.0);
+
+    let fields = histogram.get_unknown_fields();
+    // schema 0
+    assert_eq!(fields.get(5).unwrap().varint, vec![0]);
+    // buckets 0, 1 and 2, holding 1, 2 and 4, with a count of 1 each
+    assert_eq!(
+        fields.get(12).unwrap().length_delimited,
+        vec![vec![0x08, 0x00, 0x10, 0x03]]
+    );
+    assert_eq!(fields.get(13).unwrap().varint, vec![2, 0, 0]);
+}
+
+#[cfg(feature = "http-server")]
+#[test]
+fn http_server() {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+
+    let exporter = ExporterBuilder::default()
+        .with_registry(prometheus::Registry::new())
+        .without_scope_info()
+        .without_target_info()
+        .with_http_server(([127, 0, 0, 1], 0).into())
+        .build()
+        .unwrap();
+    let addr = exporter.server_addr().unwrap();
+    let provider = SdkMeterProvider::builder().with_reader(exporter).build();
+
+    let counter = provider.meter("testmeter").u64_counter("foo").build();
+    counter.add(5, &[KeyValue::new("A", "B")]);
+
+    let mut stream = TcpStream::connect(addr).unwrap();
+    stream
+        .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+        .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    assert!(response.starts_with("HTTP/1.1 200"), "{response}");
+    assert!(response.contains("foo_total{A=\"B\"} 5"), "{response}");
+
+    // the server stops with the meter provider
+    provider.shutdown().unwrap();
+    assert!(TcpStream::connect(addr).is_err());
+}
+
+#[test]
+fn open_metrics_encoder() {
+    let registry = prometheus::Registry::new();
+    let exporter = ExporterBuilder::default()
+        .with_registry(registry.clone())
+        .without_scope_info()
+        .without_target_info()
+        .build()
+        .unwrap();
+    let provider = SdkMeterProvider::builder().with_reader(exporter).build();
+    let meter = provider.meter("testmeter");
+
+    let counter = meter.f64_counter("foo").with_unit("s").build();
+    counter.add(5.0, &[KeyValue::new("A", "B")]);
+    let histogram = meter
+        .u64_histogram("bar")
+        .with_unit("By")
+        .with_boundaries(vec![100.0])
+        .build();
+    histogram.record(10, &[]);
+
+    let mut output = Vec::new();
+    OpenMetricsEncoder::new()
+        .encode(&registry.gather(), &mut output)
+        .unwrap();
+    let output = String::from_utf8(output).unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+
+    let expected_prefixes = [
+        "# TYPE bar_bytes histogram",
+        "# UNIT bar_bytes bytes",
+        "bar_bytes_bucket{le=\"100\"} 1",
+        "bar_bytes_bucket{le=\"+Inf\"} 1",
+        "bar_bytes_count 1",
+        "bar_bytes_sum 10",
+        "bar_bytes_created ",
+        "# TYPE foo_seconds counter",
+        "# UNIT foo_seconds seconds",
+        "foo_seconds_total{A=\"B\"} 5",
+        "foo_seconds_created{A=\"B\"} ",
+        "# EOF",
+    ];
+    assert_eq!(lines.len(), expected_prefixes.len(), "{output}");
+    for (line, prefix) in lines.iter().zip(expected_prefixes) {
+        assert!(line.starts_with(prefix), "{line} in {output}");
+    }
+}
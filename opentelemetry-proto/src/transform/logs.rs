@@ -108,7 +108,7 @@ pub mod tonic {
                     .map(Into::into)
                     .unwrap_or_default(),
                 body: log_record.body().cloned().map(Into::into),
-                dropped_attributes_count: 0,
+                dropped_attributes_count: log_record.dropped_attributes_count(),
                 flags: trace_context
                     .map(|ctx| {
                         ctx.trace_flags
@@ -312,4 +312,32 @@ mod tests {
         assert_eq!(scope_logs_1.log_records.len(), 1);
         assert_eq!(scope_logs_2.log_records.len(), 1);
     }
+    #[test]
+    fn test_dropped_attributes_count() {
+        use opentelemetry_sdk::logs::LogRecordLimits;
+
+        let logger = SdkLoggerProvider::builder()
+            .with_log_processor(MockProcessor {})
+            .with_log_record_limits(LogRecordLimits {
+                max_attributes_per_log_record: 1,
+                max_attribute_value_length: None,
+            })
+            .build()
+            .logger("test");
+        let mut log_record = logger.create_log_record();
+        log_record.set_observed_timestamp(now());
+        log_record.add_attributes([("k1", "v1"), ("k2", "v2"), ("k3", "v3")]);
+        let instrumentation = InstrumentationScope::builder("test-lib").build();
+
+        let logs = [(&log_record, &instrumentation)];
+        let resource: ResourceAttributesWithSchema = (&Resource::builder().build()).into();
+        let grouped_logs = crate::transform::logs::tonic::group_logs_by_resource_and_scope(
+            LogBatch::new(&logs),
+            &resource,
+        );
+
+        let log_record = &grouped_logs[0].scope_logs[0].log_records[0];
+        assert_eq!(log_record.attributes.len(), 1);
+        assert_eq!(log_record.dropped_attributes_count, 2);
+    }
 }
@@ -0,0 +1,23 @@
+use std::env;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+
+    // Expose the compiler version to the `ProcessResourceDetector`, which reports
+    // it as the runtime of the process.
+    if env::var_os("CARGO_FEATURE_RESOURCE_DETECTORS").is_none() {
+        return;
+    }
+    println!("cargo:rerun-if-env-changed=RUSTC");
+    let rustc = env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    if let Some(version) = Command::new(rustc)
+        .arg("--version")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+    {
+        println!("cargo:rustc-env=OTEL_SDK_RUSTC_VERSION={}", version.trim());
+    }
+}
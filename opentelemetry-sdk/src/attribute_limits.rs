@@ -0,0 +1,103 @@
+//! Limits shared by the attributes of spans and log records.
+//!
+//! See <https://github.com/open-telemetry/opentelemetry-specification/blob/main/specification/common/README.md#attribute-limits>.
+
+use std::env;
+use std::str::FromStr;
+
+use opentelemetry::StringValue;
+
+/// The environment variable limiting the number of attributes, used when the
+/// signal specific variable is not set.
+pub(crate) const OTEL_ATTRIBUTE_COUNT_LIMIT: &str = "OTEL_ATTRIBUTE_COUNT_LIMIT";
+/// The environment variable limiting the length of attribute values, used when
+/// the signal specific variable is not set.
+pub(crate) const OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT: &str = "OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT";
+
+/// Reads a limit from the `name` environment variable, or from the general
+/// `fallback` one if `name` is not set.
+pub(crate) fn limit_from_env(name: &str, fallback: &str) -> Option<u32> {
+    env::var(name)
+        .or_else(|_| env::var(fallback))
+        .ok()
+        .and_then(|limit| u32::from_str(&limit).ok())
+}
+
+/// Truncates the string values of `value`, including the elements of string
+/// arrays, to at most `max_length` characters.
+#[cfg(feature = "trace")]
+pub(crate) fn truncate_value(value: &mut opentelemetry::Value, max_length: usize) {
+    use opentelemetry::{Array, Value};
+
+    match value {
+        Value::String(s) => truncate_string(s, max_length),
+        Value::Array(Array::String(values)) => {
+            for s in values {
+                truncate_string(s, max_length);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Truncates the strings of `value`, including the ones nested in lists and
+/// maps, to at most `max_length` characters.
+#[cfg(feature = "logs")]
+pub(crate) fn truncate_any_value(value: &mut opentelemetry::logs::AnyValue, max_length: usize) {
+    use opentelemetry::logs::AnyValue;
+
+    match value {
+        AnyValue::String(s) => truncate_string(s, max_length),
+        AnyValue::ListAny(values) => values
+            .iter_mut()
+            .for_each(|value| truncate_any_value(value, max_length)),
+        AnyValue::Map(map) => map
+            .values_mut()
+            .for_each(|value| truncate_any_value(value, max_length)),
+        _ => {}
+    }
+}
+
+fn truncate_string(s: &mut StringValue, max_length: usize) {
+    if let Some((end, _)) = s.as_str().char_indices().nth(max_length) {
+        *s = s.as_str()[..end].to_owned().into();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limit_from_env_falls_back_to_general_variable() {
+        for (specific, general, expected) in [
+            (Some("10"), Some("20"), Some(10)),
+            (None, Some("20"), Some(20)),
+            (None, None, None),
+            (Some("invalid"), Some("20"), None),
+        ] {
+            temp_env::with_vars(
+                [
+                    ("OTEL_TEST_ATTRIBUTE_LIMIT", specific),
+                    (OTEL_ATTRIBUTE_COUNT_LIMIT, general),
+                ],
+                || {
+                    assert_eq!(
+                        limit_from_env("OTEL_TEST_ATTRIBUTE_LIMIT", OTEL_ATTRIBUTE_COUNT_LIMIT),
+                        expected,
+                        "{specific:?} {general:?}"
+                    );
+                },
+            );
+        }
+    }
+
+    #[test]
+    fn truncate_string_counts_characters() {
+        for (value, expected) in [("abc", "abc"), ("abcdef", "abc"), ("héllo", "hél")] {
+            let mut s = StringValue::from(value);
+            truncate_string(&mut s, 3);
+            assert_eq!(s.as_str(), expected);
+        }
+    }
+}
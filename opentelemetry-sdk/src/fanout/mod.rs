@@ -0,0 +1,374 @@
+//! # Fan-out exporters
+//!
+//! Registering one batch processor, or one periodic reader, per destination
+//! duplicates the telemetry and the background threads for each of them. The
+//! fan-out exporters instead share a single pipeline, and export each batch to
+//! all their destinations concurrently:
+//!
+//! - [`FanOutSpanExporter`] for traces,
+//! - [`FanOutLogExporter`] for logs,
+//! - [`FanOutMetricExporter`] for metrics.
+//!
+//! Each destination has its own export timeout, so that a slow or unreachable
+//! destination neither delays nor fails the exports to the others. The exports
+//! of each destination run on a thread of their own, so that this holds even
+//! for exporters which block the thread they are polled on, like the ones
+//! using a blocking HTTP client. An export which is still running when the
+//! next batch is exported fails that next export right away. The outcomes of
+//! the exports to each destination are counted by [`FanOutStats`], and the
+//! failures are reported through internal logs.
+//!
+//! ```ascii
+//!                                                  +--> otlp   (timeout 10s)
+//!   +-----+    +----------------+    +--------+    |
+//!   | SDK +--->+ BatchProcessor +--->+ FanOut +----+
+//!   +-----+    +----------------+    +--------+    |
+//!                                                  +--> zipkin (timeout 5s)
+//! ```
+//!
+//! [`FanOutSpanExporter`]: crate::trace::FanOutSpanExporter
+//! [`FanOutLogExporter`]: crate::logs::FanOutLogExporter
+//! [`FanOutMetricExporter`]: crate::metrics::FanOutMetricExporter
+
+use crate::util::timer;
+use futures_channel::oneshot;
+use futures_util::future::join_all;
+use opentelemetry::otel_warn;
+use std::borrow::Cow;
+use std::fmt::{self, Display};
+use std::future::Future;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// The outcomes of the exports to one destination of a fan-out exporter.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DestinationStats {
+    name: Cow<'static, str>,
+    succeeded: u64,
+    failed: u64,
+    timed_out: u64,
+    last_error: Option<String>,
+}
+
+impl DestinationStats {
+    /// The name of the destination.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The number of batches exported successfully.
+    pub fn succeeded(&self) -> u64 {
+        self.succeeded
+    }
+
+    /// The number of batches whose export returned an error.
+    pub fn failed(&self) -> u64 {
+        self.failed
+    }
+
+    /// The number of batches whose export did not complete within the timeout
+    /// of the destination.
+    pub fn timed_out(&self) -> u64 {
+        self.timed_out
+    }
+
+    /// The error of the last failed or timed out export, if any.
+    pub fn last_error(&self) -> Option<&str> {
+        self.last_error.as_deref()
+    }
+}
+
+/// The outcomes of the exports of a fan-out exporter, per destination.
+///
+/// The stats are shared by all the clones, and keep being updated by the
+/// exporter once it is registered in a pipeline.
+#[derive(Clone, Debug, Default)]
+pub struct FanOutStats {
+    destinations: Arc<Mutex<Vec<DestinationStats>>>,
+}
+
+impl FanOutStats {
+    /// The stats of all the destinations, in the order they were added.
+    pub fn destinations(&self) -> Vec<DestinationStats> {
+        self.destinations
+            .lock()
+            .map(|destinations| destinations.clone())
+            .unwrap_or_default()
+    }
+
+    /// The stats of the destination with the given name.
+    pub fn destination(&self, name: &str) -> Option<DestinationStats> {
+        self.destinations
+            .lock()
+            .ok()?
+            .iter()
+            .find(|destination| destination.name == name)
+            .cloned()
+    }
+
+    pub(crate) fn add_destination(&self, name: Cow<'static, str>) {
+        if let Ok(mut destinations) = self.destinations.lock() {
+            destinations.push(DestinationStats {
+                name,
+                ..Default::default()
+            });
+        }
+    }
+
+    /// Record the outcome of an export to the destination at `index`,
+    /// returning the error message of a failed export.
+    fn record<E: Display>(
+        &self,
+        index: usize,
+        outcome: Result<Result<(), E>, (timer::Elapsed, Duration)>,
+    ) -> Option<String> {
+        let mut destinations = self.destinations.lock().ok()?;
+        let destination = destinations.get_mut(index)?;
+        let error = match outcome {
+            Ok(Ok(())) => {
+                destination.succeeded += 1;
+                return None;
+            }
+            Ok(Err(err)) => {
+                destination.failed += 1;
+                format!("export to {} failed: {}", destination.name, err)
+            }
+            Err((_, timeout)) => {
+                destination.timed_out += 1;
+                format!(
+                    "export to {} timed out after {:?}",
+                    destination.name, timeout
+                )
+            }
+        };
+        otel_warn!(
+            name: "FanOutExporter.ExportFailed",
+            destination = destination.name.as_ref(),
+            error = error.as_str()
+        );
+        destination.last_error = Some(error.clone());
+        Some(error)
+    }
+}
+
+/// A destination of a fan-out exporter.
+#[derive(Debug)]
+pub(crate) struct Destination<E> {
+    pub(crate) name: Cow<'static, str>,
+    pub(crate) timeout: Duration,
+    pub(crate) exporter: E,
+    pub(crate) worker: Worker,
+}
+
+impl<E> Destination<E> {
+    pub(crate) fn new(name: Cow<'static, str>, timeout: Duration, exporter: E) -> Self {
+        let worker = Worker::spawn(&name);
+        Destination {
+            name,
+            timeout,
+            exporter,
+            worker,
+        }
+    }
+}
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// The thread running the exports of a destination.
+///
+/// Running them on their own thread keeps an exporter blocking the thread it
+/// is polled on from stalling the exports to the other destinations, and lets
+/// the timeout of its own exports fire. The thread exits once the destination
+/// is dropped and its last export returned.
+pub(crate) struct Worker {
+    // `None` if the thread could not be started, the jobs then run on the
+    // calling thread.
+    jobs: Option<SyncSender<Job>>,
+}
+
+impl fmt::Debug for Worker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Worker")
+            .field("started", &self.jobs.is_some())
+            .finish()
+    }
+}
+
+impl Worker {
+    fn spawn(name: &str) -> Self {
+        // Keep room for one pending job, so that an export issued while the
+        // previous one returns its result is not rejected.
+        let (sender, receiver) = mpsc::sync_channel::<Job>(1);
+        let jobs = thread::Builder::new()
+            .name(format!("OpenTelemetry.FanOut.{name}"))
+            .spawn(move || {
+                while let Ok(job) = receiver.recv() {
+                    job();
+                }
+            })
+            .map_err(|err| {
+                otel_warn!(
+                    name: "FanOutExporter.WorkerStartFailed",
+                    destination = name,
+                    error = err.to_string()
+                );
+            })
+            .ok()
+            .map(|_| sender);
+        Worker { jobs }
+    }
+
+    /// Run `export` on the thread of the worker, returning a future
+    /// completing with its result.
+    ///
+    /// When called from a Tokio runtime, `export` runs in the context of that
+    /// runtime, so that exporters relying on its reactor, like the gRPC ones,
+    /// keep working. The returned future fails right away if the worker is
+    /// still busy with a previous export.
+    pub(crate) fn run<F, E>(&self, export: F) -> impl Future<Output = Result<(), String>>
+    where
+        F: FnOnce() -> Result<(), E> + Send + 'static,
+        E: Display,
+    {
+        #[cfg(any(feature = "rt-tokio", feature = "rt-tokio-current-thread"))]
+        let runtime = tokio::runtime::Handle::try_current().ok();
+        let (sender, receiver) = oneshot::channel();
+        let job: Job = Box::new(move || {
+            #[cfg(any(feature = "rt-tokio", feature = "rt-tokio-current-thread"))]
+            let _guard = runtime.as_ref().map(|runtime| runtime.enter());
+            // keep the thread alive for the next exports if this one panics
+            let result = match panic::catch_unwind(AssertUnwindSafe(export)) {
+                Ok(result) => result.map_err(|err| err.to_string()),
+                Err(_) => Err("the export panicked".to_string()),
+            };
+            let _ = sender.send(result);
+        });
+
+        let submitted = match &self.jobs {
+            Some(jobs) => jobs.try_send(job).map_err(|err| match err {
+                TrySendError::Full(_) => "a previous export is still running".to_string(),
+                TrySendError::Disconnected(_) => "the export thread has stopped".to_string(),
+            }),
+            None => {
+                job();
+                Ok(())
+            }
+        };
+        async move {
+            submitted?;
+            receiver
+                .await
+                .unwrap_or_else(|_| Err("the export thread has stopped".to_string()))
+        }
+    }
+}
+
+/// Run the exports to all the destinations concurrently, each bounded by the
+/// timeout of its destination, and record their outcomes in `stats`.
+///
+/// `exports` holds the export timeout and future of each destination, in the
+/// order of the destinations. All the exports run to completion or time out,
+/// and the errors of the failed exports are joined in the returned error.
+pub(crate) async fn export_all<F, E>(
+    stats: FanOutStats,
+    exports: Vec<(Duration, F)>,
+) -> Result<(), String>
+where
+    F: Future<Output = Result<(), E>>,
+    E: Display,
+{
+    let outcomes = join_all(exports.into_iter().map(|(timeout, export)| async move {
+        timer::timeout(timeout, export)
+            .await
+            .map_err(|elapsed| (elapsed, timeout))
+    }))
+    .await;
+
+    join_errors(
+        outcomes
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, outcome)| stats.record(index, outcome)),
+    )
+}
+
+/// Run an operation other than an export, like a flush, on all the
+/// destinations concurrently, each bounded by the timeout of its destination.
+///
+/// `runs` holds the name, timeout and future of each destination.
+#[cfg(feature = "metrics")]
+pub(crate) async fn run_all<F, E>(
+    operation: &str,
+    runs: Vec<(&str, Duration, F)>,
+) -> Result<(), String>
+where
+    F: Future<Output = Result<(), E>>,
+    E: Display,
+{
+    let outcomes = join_all(runs.into_iter().map(|(name, timeout, run)| async move {
+        match timer::timeout(timeout, run).await {
+            Ok(Ok(())) => None,
+            Ok(Err(err)) => Some(format!("{operation} of {name} failed: {err}")),
+            Err(_) => Some(format!("{operation} of {name} timed out after {timeout:?}")),
+        }
+    }))
+    .await;
+    join_errors(outcomes.into_iter().flatten())
+}
+
+/// Join the error messages of the destinations, if any.
+pub(crate) fn join_errors(errors: impl Iterator<Item = String>) -> Result<(), String> {
+    let errors = errors.collect::<Vec<_>>();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::future::BoxFuture;
+    use futures_util::FutureExt;
+
+    #[test]
+    fn export_all_isolates_destinations() {
+        let stats = FanOutStats::default();
+        for name in ["ok", "failing", "slow"] {
+            stats.add_destination(name.into());
+        }
+
+        let exports: Vec<(Duration, BoxFuture<'static, Result<(), String>>)> = vec![
+            (Duration::from_secs(5), async { Ok(()) }.boxed()),
+            (
+                Duration::from_secs(5),
+                async { Err("unavailable".to_string()) }.boxed(),
+            ),
+            (
+                Duration::from_millis(10),
+                futures_util::future::pending().boxed(),
+            ),
+        ];
+        let err = futures_executor::block_on(export_all(stats.clone(), exports)).unwrap_err();
+        assert_eq!(
+            err,
+            "export to failing failed: unavailable, export to slow timed out after 10ms"
+        );
+
+        let destinations = stats.destinations();
+        assert_eq!(destinations.len(), 3);
+        assert_eq!(destinations[0].succeeded(), 1);
+        assert_eq!(destinations[0].last_error(), None);
+        assert_eq!(destinations[1].failed(), 1);
+        assert_eq!(
+            destinations[1].last_error(),
+            Some("export to failing failed: unavailable")
+        );
+        assert_eq!(stats.destination("slow").unwrap().timed_out(), 1);
+        assert!(stats.destination("unknown").is_none());
+    }
+}
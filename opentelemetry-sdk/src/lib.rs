@@ -83,11 +83,19 @@
 //! For `trace` the following feature flags are available:
 //!
 //! * `jaeger_remote_sampler`: Enables the [Jaeger remote sampler](https://www.jaegertracing.io/docs/1.53/sampling/).
+//! * `jaeger_remote_sampler_grpc`: Enables fetching the sampling strategies of the Jaeger
+//!   remote sampler with the gRPC sampling API.
+//! * `rule_based_sampler`: Enables the `RuleBasedSampler`, routing spans to
+//!   samplers depending on their name, kind and attributes.
 //!
 //! For `logs` the following feature flags are available:
 //!
 //! * `spec_unstable_logs_enabled`: control the log level
 //!
+//! The `redaction` feature flag enables the `RedactingSpanProcessor` and
+//! `RedactingLogProcessor`, deleting, hashing, masking and renaming attributes
+//! before export.
+//!
 //! Support for recording and exporting telemetry asynchronously and perform
 //! metrics aggregation can be added via the following flags:
 //!
@@ -118,6 +126,14 @@
 )]
 #![cfg_attr(test, deny(warnings))]
 
+#[cfg(any(feature = "trace", feature = "logs"))]
+pub(crate) mod attribute_limits;
+#[cfg(any(feature = "trace", feature = "logs", feature = "metrics"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "trace", feature = "logs", feature = "metrics")))
+)]
+pub mod fanout;
 pub(crate) mod growable_array;
 
 #[cfg(feature = "logs")]
@@ -129,6 +145,9 @@ pub mod metrics;
 #[cfg(feature = "trace")]
 #[cfg_attr(docsrs, doc(cfg(feature = "trace")))]
 pub mod propagation;
+#[cfg(all(feature = "redaction", any(feature = "trace", feature = "logs")))]
+#[cfg_attr(docsrs, doc(cfg(feature = "redaction")))]
+pub mod redaction;
 pub mod resource;
 #[cfg(feature = "experimental_async_runtime")]
 pub mod runtime;
@@ -0,0 +1,242 @@
+use crate::fanout::{export_all, Destination, FanOutStats};
+use crate::logs::{LogBatch, LogError, LogExporter, LogResult};
+use crate::Resource;
+use futures_util::future::BoxFuture;
+#[cfg(feature = "spec_unstable_logs_enabled")]
+use opentelemetry::logs::Severity;
+use opentelemetry::otel_warn;
+use std::borrow::Cow;
+use std::fmt::Debug;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// An object safe [`LogExporter`], so that exporters of different types can
+/// be destinations of the same [`FanOutLogExporter`].
+trait DynLogExporter: Send + Sync + Debug {
+    fn export_batch<'a>(&'a self, batch: LogBatch<'a>) -> BoxFuture<'a, LogResult<()>>;
+    fn shutdown(&mut self);
+    #[cfg(feature = "spec_unstable_logs_enabled")]
+    fn event_enabled(&self, level: Severity, target: &str, name: &str) -> bool;
+    fn set_resource(&mut self, resource: &Resource);
+}
+
+impl<E: LogExporter> DynLogExporter for E {
+    fn export_batch<'a>(&'a self, batch: LogBatch<'a>) -> BoxFuture<'a, LogResult<()>> {
+        Box::pin(self.export(batch))
+    }
+
+    fn shutdown(&mut self) {
+        LogExporter::shutdown(self)
+    }
+
+    #[cfg(feature = "spec_unstable_logs_enabled")]
+    fn event_enabled(&self, level: Severity, target: &str, name: &str) -> bool {
+        LogExporter::event_enabled(self, level, target, name)
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        LogExporter::set_resource(self, resource)
+    }
+}
+
+/// A [`LogExporter`] exporting each batch of log records to several log
+/// exporters concurrently.
+///
+/// Each destination has its own export timeout: a destination failing or
+/// timing out does not prevent the export to the other destinations. The
+/// export fails if any of the destinations failed, with the errors of the
+/// failed destinations, and the outcomes of the exports are counted per
+/// destination by the [`FanOutStats`] of the exporter.
+///
+/// The log records of the batch are copied once, and the copy is shared by all
+/// the destinations.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry_sdk::logs::{FanOutLogExporter, InMemoryLogExporter, SdkLoggerProvider};
+/// use std::time::Duration;
+///
+/// let exporter = FanOutLogExporter::builder()
+///     .with_exporter("otlp", InMemoryLogExporter::default(), Duration::from_secs(10))
+///     .with_exporter("vendor", InMemoryLogExporter::default(), Duration::from_secs(5))
+///     .build();
+/// let stats = exporter.stats();
+///
+/// let provider = SdkLoggerProvider::builder()
+///     .with_batch_exporter(exporter)
+///     .build();
+///
+/// // ...
+///
+/// provider.shutdown().unwrap();
+/// for destination in stats.destinations() {
+///     println!("{}: {} failed exports", destination.name(), destination.failed());
+/// }
+/// ```
+#[derive(Debug)]
+pub struct FanOutLogExporter {
+    destinations: Vec<Destination<Arc<dyn DynLogExporter>>>,
+    stats: FanOutStats,
+}
+
+impl FanOutLogExporter {
+    /// Create a builder to configure the destinations of the exporter.
+    pub fn builder() -> FanOutLogExporterBuilder {
+        FanOutLogExporterBuilder::default()
+    }
+
+    /// The outcomes of the exports, per destination.
+    pub fn stats(&self) -> FanOutStats {
+        self.stats.clone()
+    }
+}
+
+impl LogExporter for FanOutLogExporter {
+    #[allow(clippy::manual_async_fn)]
+    fn export(
+        &self,
+        batch: LogBatch<'_>,
+    ) -> impl std::future::Future<Output = LogResult<()>> + Send {
+        async move {
+            // the exports outlive the batch if they time out
+            let records: Arc<Vec<_>> = Arc::new(
+                batch
+                    .iter()
+                    .map(|(record, scope)| Box::new((record.clone(), scope.clone())))
+                    .collect(),
+            );
+            let exports = self
+                .destinations
+                .iter()
+                .map(|destination| {
+                    let exporter = Arc::clone(&destination.exporter);
+                    let records = Arc::clone(&records);
+                    let export = destination.worker.run(move || {
+                        futures_executor::block_on(
+                            exporter.export_batch(LogBatch::new_with_owned_data(&records)),
+                        )
+                    });
+                    (destination.timeout, export)
+                })
+                .collect::<Vec<_>>();
+            export_all(self.stats.clone(), exports)
+                .await
+                .map_err(LogError::from)
+        }
+    }
+
+    fn shutdown(&mut self) {
+        for destination in self.destinations.iter_mut() {
+            match Arc::get_mut(&mut destination.exporter) {
+                Some(exporter) => exporter.shutdown(),
+                None => {
+                    otel_warn!(
+                        name: "FanOutExporter.ShutdownSkipped",
+                        destination = destination.name.as_ref(),
+                        error = "an export is still running"
+                    );
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "spec_unstable_logs_enabled")]
+    fn event_enabled(&self, level: Severity, target: &str, name: &str) -> bool {
+        self.destinations
+            .iter()
+            .any(|destination| destination.exporter.event_enabled(level, target, name))
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        for destination in self.destinations.iter_mut() {
+            if let Some(exporter) = Arc::get_mut(&mut destination.exporter) {
+                exporter.set_resource(resource);
+            }
+        }
+    }
+}
+
+/// Builder for [`FanOutLogExporter`].
+#[derive(Debug, Default)]
+pub struct FanOutLogExporterBuilder {
+    destinations: Vec<Destination<Arc<dyn DynLogExporter>>>,
+}
+
+impl FanOutLogExporterBuilder {
+    /// Add a destination named `name`, exporting the log records with
+    /// `exporter`, and giving up on each export after `timeout`.
+    pub fn with_exporter<E>(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        exporter: E,
+        timeout: Duration,
+    ) -> Self
+    where
+        E: LogExporter + 'static,
+    {
+        self.destinations
+            .push(Destination::new(name.into(), timeout, Arc::new(exporter)));
+        self
+    }
+
+    /// Build a [`FanOutLogExporter`] exporting to the provided destinations.
+    pub fn build(self) -> FanOutLogExporter {
+        let stats = FanOutStats::default();
+        for destination in &self.destinations {
+            stats.add_destination(destination.name.clone());
+        }
+        FanOutLogExporter {
+            destinations: self.destinations,
+            stats,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::logs::{InMemoryLogExporter, SdkLogRecord};
+    use opentelemetry::InstrumentationScope;
+
+    #[derive(Debug)]
+    struct HangingExporter;
+
+    impl LogExporter for HangingExporter {
+        #[allow(clippy::manual_async_fn)]
+        fn export(
+            &self,
+            _batch: LogBatch<'_>,
+        ) -> impl std::future::Future<Output = LogResult<()>> + Send {
+            futures_util::future::pending()
+        }
+    }
+
+    #[test]
+    fn exports_to_all_destinations() {
+        let first = InMemoryLogExporter::default();
+        let second = InMemoryLogExporter::default();
+        let mut exporter = FanOutLogExporter::builder()
+            .with_exporter("first", first.clone(), Duration::from_secs(5))
+            .with_exporter("hanging", HangingExporter, Duration::from_millis(50))
+            .with_exporter("second", second.clone(), Duration::from_secs(5))
+            .build();
+
+        let record = SdkLogRecord::new();
+        let scope = InstrumentationScope::default();
+        let data = [(&record, &scope)];
+        let err = futures_executor::block_on(exporter.export(LogBatch::new(&data))).unwrap_err();
+        assert_eq!(err.to_string(), "export to hanging timed out after 50ms");
+        assert_eq!(first.get_emitted_logs().unwrap().len(), 1);
+        assert_eq!(second.get_emitted_logs().unwrap().len(), 1);
+
+        let stats = exporter.stats();
+        assert_eq!(stats.destination("first").unwrap().succeeded(), 1);
+        assert_eq!(stats.destination("hanging").unwrap().timed_out(), 1);
+        assert_eq!(stats.destination("second").unwrap().succeeded(), 1);
+
+        LogExporter::shutdown(&mut exporter);
+        assert!(first.get_emitted_logs().unwrap().is_empty());
+        assert!(second.get_emitted_logs().unwrap().is_empty());
+    }
+}
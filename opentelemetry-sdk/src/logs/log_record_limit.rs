@@ -0,0 +1,111 @@
+//! # Log record limit
+//! Erroneous code can add unintended attributes, or attributes with very large
+//! values, to a log record. If these are unbounded, they can quickly exhaust
+//! available memory and exceed the limits of the backend receiving the logs.
+//!
+//! To protect against those errors, users can use log record limits to configure
+//!  - Maximum allowed attribute count per log record
+//!  - Maximum allowed attribute value length
+//!
+//! If the count limit has been breached, attributes are dropped in the order
+//! they are added: the ones added later are dropped first. The number of
+//! dropped attributes is reported with the log record. Attribute values longer
+//! than the length limit are truncated.
+
+use crate::attribute_limits::{
+    limit_from_env, OTEL_ATTRIBUTE_COUNT_LIMIT, OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT,
+};
+
+pub(crate) const DEFAULT_MAX_ATTRIBUTES_PER_LOG_RECORD: u32 = 128;
+
+/// Log record limit configuration to keep attributes of log records in a
+/// reasonable size.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LogRecordLimits {
+    /// The max attributes that can be added to a `LogRecord`.
+    pub max_attributes_per_log_record: u32,
+    /// The max length of attribute values, in characters. String values, and
+    /// strings nested in array and map values, longer than this are truncated.
+    /// `None` means there is no limit.
+    pub max_attribute_value_length: Option<u32>,
+}
+
+impl Default for LogRecordLimits {
+    fn default() -> Self {
+        LogRecordLimits {
+            max_attributes_per_log_record: DEFAULT_MAX_ATTRIBUTES_PER_LOG_RECORD,
+            max_attribute_value_length: None,
+        }
+    }
+}
+
+impl LogRecordLimits {
+    /// Creates the default limits, overridden by the
+    /// `OTEL_LOGRECORD_ATTRIBUTE_COUNT_LIMIT` and
+    /// `OTEL_LOGRECORD_ATTRIBUTE_VALUE_LENGTH_LIMIT` environment variables, or
+    /// else by the general `OTEL_ATTRIBUTE_COUNT_LIMIT` and
+    /// `OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT` ones.
+    pub(crate) fn from_env() -> Self {
+        let mut limits = LogRecordLimits::default();
+
+        if let Some(max_attributes) = limit_from_env(
+            "OTEL_LOGRECORD_ATTRIBUTE_COUNT_LIMIT",
+            OTEL_ATTRIBUTE_COUNT_LIMIT,
+        ) {
+            limits.max_attributes_per_log_record = max_attributes;
+        }
+
+        if let Some(max_length) = limit_from_env(
+            "OTEL_LOGRECORD_ATTRIBUTE_VALUE_LENGTH_LIMIT",
+            OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT,
+        ) {
+            limits.max_attribute_value_length = Some(max_length);
+        }
+
+        limits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_record_limits_from_env() {
+        temp_env::with_vars(
+            [
+                ("OTEL_LOGRECORD_ATTRIBUTE_COUNT_LIMIT", Some("10")),
+                ("OTEL_LOGRECORD_ATTRIBUTE_VALUE_LENGTH_LIMIT", Some("32")),
+            ],
+            || {
+                let limits = LogRecordLimits::from_env();
+                assert_eq!(limits.max_attributes_per_log_record, 10);
+                assert_eq!(limits.max_attribute_value_length, Some(32));
+            },
+        );
+
+        temp_env::with_vars(
+            [
+                ("OTEL_LOGRECORD_ATTRIBUTE_COUNT_LIMIT", Some("invalid")),
+                ("OTEL_LOGRECORD_ATTRIBUTE_VALUE_LENGTH_LIMIT", None),
+            ],
+            || {
+                assert_eq!(LogRecordLimits::from_env(), LogRecordLimits::default());
+            },
+        );
+
+        temp_env::with_vars(
+            [
+                ("OTEL_LOGRECORD_ATTRIBUTE_COUNT_LIMIT", None),
+                ("OTEL_LOGRECORD_ATTRIBUTE_VALUE_LENGTH_LIMIT", Some("32")),
+                ("OTEL_ATTRIBUTE_COUNT_LIMIT", Some("20")),
+                ("OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT", Some("64")),
+            ],
+            || {
+                let limits = LogRecordLimits::from_env();
+                assert_eq!(limits.max_attributes_per_log_record, 20);
+                assert_eq!(limits.max_attribute_value_length, Some(32));
+            },
+        );
+    }
+}
@@ -1,4 +1,7 @@
-use super::{BatchLogProcessor, LogProcessor, SdkLogRecord, SimpleLogProcessor, TraceContext};
+use super::{
+    BatchLogProcessor, LogProcessor, LogRecordLimits, SdkLogRecord, SimpleLogProcessor,
+    TraceContext,
+};
 use crate::logs::{LogError, LogExporter, LogResult};
 use crate::Resource;
 use opentelemetry::{otel_debug, otel_info, trace::TraceContextExt, Context, InstrumentationScope};
@@ -25,6 +28,7 @@ fn noop_logger_provider() -> &'static SdkLoggerProvider {
         inner: Arc::new(LoggerProviderInner {
             processors: Vec::new(),
             resource: Resource::empty(),
+            log_record_limits: LogRecordLimits::default(),
             is_shutdown: AtomicBool::new(true),
         }),
     })
@@ -90,6 +94,10 @@ impl SdkLoggerProvider {
         &self.inner.resource
     }
 
+    pub(crate) fn log_record_limits(&self) -> LogRecordLimits {
+        self.inner.log_record_limits
+    }
+
     /// Force flush all remaining logs in log processors and return results.
     pub fn force_flush(&self) -> Vec<LogResult<()>> {
         self.log_processors()
@@ -126,6 +134,7 @@ impl SdkLoggerProvider {
 struct LoggerProviderInner {
     processors: Vec<Box<dyn LogProcessor>>,
     resource: Resource,
+    log_record_limits: LogRecordLimits,
     is_shutdown: AtomicBool,
 }
 
@@ -182,6 +191,7 @@ impl Drop for LoggerProviderInner {
 pub struct LoggerProviderBuilder {
     processors: Vec<Box<dyn LogProcessor>>,
     resource: Option<Resource>,
+    log_record_limits: Option<LogRecordLimits>,
 }
 
 impl LoggerProviderBuilder {
@@ -245,6 +255,20 @@ impl LoggerProviderBuilder {
         }
     }
 
+    /// The limits applied to the attributes of the log records created by
+    /// the loggers of this provider.
+    ///
+    /// If not set, the limits are read from the
+    /// `OTEL_LOGRECORD_ATTRIBUTE_COUNT_LIMIT` and
+    /// `OTEL_LOGRECORD_ATTRIBUTE_VALUE_LENGTH_LIMIT` environment variables,
+    /// falling back to [LogRecordLimits::default].
+    pub fn with_log_record_limits(self, log_record_limits: LogRecordLimits) -> Self {
+        LoggerProviderBuilder {
+            log_record_limits: Some(log_record_limits),
+            ..self
+        }
+    }
+
     /// Create a new provider from this configuration.
     pub fn build(self) -> SdkLoggerProvider {
         let resource = self.resource.unwrap_or(Resource::builder().build());
@@ -253,6 +277,9 @@ impl LoggerProviderBuilder {
             inner: Arc::new(LoggerProviderInner {
                 processors: self.processors,
                 resource,
+                log_record_limits: self
+                    .log_record_limits
+                    .unwrap_or_else(LogRecordLimits::from_env),
                 is_shutdown: AtomicBool::new(false),
             }),
         };
@@ -293,7 +320,7 @@ impl opentelemetry::logs::Logger for SdkLogger {
     type LogRecord = SdkLogRecord;
 
     fn create_log_record(&self) -> Self::LogRecord {
-        SdkLogRecord::new()
+        SdkLogRecord::with_limits(self.provider.log_record_limits())
     }
 
     /// Emit a `LogRecord`.
@@ -665,6 +692,7 @@ mod tests {
                     flush_called.clone(),
                 ))],
                 resource: Resource::empty(),
+                log_record_limits: LogRecordLimits::default(),
                 is_shutdown: AtomicBool::new(false),
             });
 
@@ -706,6 +734,7 @@ mod tests {
                 flush_called.clone(),
             ))],
             resource: Resource::empty(),
+            log_record_limits: LogRecordLimits::default(),
             is_shutdown: AtomicBool::new(false),
         });
 
@@ -736,6 +765,45 @@ mod tests {
         assert_eq!(*shutdown_called.lock().unwrap(), 1);
     }
 
+    #[test]
+    fn test_log_record_limits() {
+        let exporter = InMemoryLogExporter::default();
+        let logger_provider = SdkLoggerProvider::builder()
+            .with_log_record_limits(LogRecordLimits {
+                max_attributes_per_log_record: 1,
+                max_attribute_value_length: Some(2),
+            })
+            .with_simple_exporter(exporter.clone())
+            .build();
+
+        let logger = logger_provider.logger("test-logger");
+        let mut record = logger.create_log_record();
+        record.add_attribute("k1", "value");
+        record.add_attribute("k2", "value");
+        logger.emit(record);
+
+        let logs = exporter.get_emitted_logs().unwrap();
+        let record = &logs[0].record;
+        assert_eq!(record.dropped_attributes_count(), 1);
+        assert_eq!(
+            record.attributes_iter().collect::<Vec<_>>(),
+            vec![&(Key::new("k1"), AnyValue::from("va"))]
+        );
+    }
+
+    #[test]
+    fn test_log_record_limits_from_env() {
+        temp_env::with_var("OTEL_LOGRECORD_ATTRIBUTE_COUNT_LIMIT", Some("3"), || {
+            let logger_provider = SdkLoggerProvider::builder().build();
+            assert_eq!(
+                logger_provider
+                    .log_record_limits()
+                    .max_attributes_per_log_record,
+                3
+            );
+        });
+    }
+
     #[test]
     fn test_empty_logger_name() {
         let exporter = InMemoryLogExporter::default();
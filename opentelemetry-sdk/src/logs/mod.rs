@@ -1,9 +1,13 @@
 //! # OpenTelemetry Log SDK
 mod error;
 mod export;
+mod fanout;
 mod log_processor;
+mod log_record_limit;
 mod logger_provider;
 pub(crate) mod record;
+#[cfg(feature = "redaction")]
+mod redaction;
 
 /// In-Memory log exporter for testing purpose.
 #[cfg(any(feature = "testing", test))]
@@ -15,12 +19,16 @@ pub use in_memory_exporter::{InMemoryLogExporter, InMemoryLogExporterBuilder};
 
 pub use error::{LogError, LogResult};
 pub use export::{ExportResult, LogBatch, LogExporter};
+pub use fanout::{FanOutLogExporter, FanOutLogExporterBuilder};
 pub use log_processor::{
     BatchConfig, BatchConfigBuilder, BatchLogProcessor, BatchLogProcessorBuilder, LogProcessor,
     SimpleLogProcessor,
 };
+pub use log_record_limit::LogRecordLimits;
 pub use logger_provider::{LoggerProviderBuilder, SdkLogger, SdkLoggerProvider};
 pub use record::{SdkLogRecord, TraceContext};
+#[cfg(feature = "redaction")]
+pub use redaction::{RedactingLogProcessor, RedactingLogProcessorBuilder};
 
 #[cfg(feature = "experimental_logs_batch_log_processor_with_async_runtime")]
 /// Module for BatchLogProcessor with async runtime.
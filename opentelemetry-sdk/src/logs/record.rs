@@ -1,3 +1,5 @@
+use super::LogRecordLimits;
+use crate::attribute_limits::truncate_any_value;
 use crate::growable_array::GrowableArray;
 use opentelemetry::{
     logs::{AnyValue, Severity},
@@ -49,6 +51,12 @@ pub struct SdkLogRecord {
 
     /// Additional attributes associated with this record
     pub(crate) attributes: LogRecordAttributes,
+
+    /// Number of attributes dropped due to the attribute count limit
+    pub(crate) dropped_attributes_count: u32,
+
+    /// Limits applied to the attributes of this record
+    pub(crate) limits: LogRecordLimits,
 }
 
 impl opentelemetry::logs::LogRecord for SdkLogRecord {
@@ -100,7 +108,16 @@ impl opentelemetry::logs::LogRecord for SdkLogRecord {
         K: Into<Key>,
         V: Into<AnyValue>,
     {
-        self.attributes.push(Some((key.into(), value.into())));
+        if self.attributes.len() >= self.limits.max_attributes_per_log_record as usize {
+            self.dropped_attributes_count = self.dropped_attributes_count.saturating_add(1);
+            return;
+        }
+
+        let mut value = value.into();
+        if let Some(max_length) = self.limits.max_attribute_value_length {
+            truncate_any_value(&mut value, max_length as usize);
+        }
+        self.attributes.push(Some((key.into(), value)));
     }
 
     fn set_trace_context(
@@ -119,7 +136,13 @@ impl opentelemetry::logs::LogRecord for SdkLogRecord {
 
 impl SdkLogRecord {
     /// Crate only default constructor
+    #[cfg(test)]
     pub(crate) fn new() -> Self {
+        Self::with_limits(LogRecordLimits::default())
+    }
+
+    /// Crate only constructor of records whose attributes are bounded by `limits`
+    pub(crate) fn with_limits(limits: LogRecordLimits) -> Self {
         SdkLogRecord {
             event_name: None,
             target: None,
@@ -130,6 +153,8 @@ impl SdkLogRecord {
             severity_number: None,
             body: None,
             attributes: LogRecordAttributes::default(),
+            dropped_attributes_count: 0,
+            limits,
         }
     }
 
@@ -187,6 +212,13 @@ impl SdkLogRecord {
         self.attributes.iter().filter_map(|opt| opt.as_ref())
     }
 
+    /// Returns the number of attributes dropped because the attribute count
+    /// limit was exceeded.
+    #[inline]
+    pub fn dropped_attributes_count(&self) -> u32 {
+        self.dropped_attributes_count
+    }
+
     #[allow(dead_code)]
     /// Returns the number of attributes in the `LogRecord`.
     pub(crate) fn attributes_len(&self) -> usize {
@@ -305,6 +337,47 @@ mod tests {
         assert!(log_record.attributes_contains(&key, &value));
     }
 
+    #[test]
+    fn test_attribute_count_limit() {
+        let mut log_record = SdkLogRecord::with_limits(LogRecordLimits {
+            max_attributes_per_log_record: 2,
+            max_attribute_value_length: None,
+        });
+        log_record.add_attributes([("k1", "v1"), ("k2", "v2"), ("k3", "v3"), ("k4", "v4")]);
+
+        assert_eq!(log_record.attributes_len(), 2);
+        assert_eq!(log_record.dropped_attributes_count(), 2);
+        // attributes added last are dropped first
+        assert!(log_record.attributes_contains(&Key::new("k1"), &AnyValue::from("v1")));
+        assert!(log_record.attributes_contains(&Key::new("k2"), &AnyValue::from("v2")));
+    }
+
+    #[test]
+    fn test_attribute_value_length_limit() {
+        let mut log_record = SdkLogRecord::with_limits(LogRecordLimits {
+            max_attributes_per_log_record: 128,
+            max_attribute_value_length: Some(3),
+        });
+        log_record.add_attribute("short", "abc");
+        log_record.add_attribute("long", "abcdef");
+        log_record.add_attribute("multibyte", "héllo");
+        log_record.add_attribute("int", 123456);
+        log_record.add_attribute(
+            "list",
+            AnyValue::ListAny(Box::new(vec!["abcdef".into(), 42.into()])),
+        );
+
+        assert_eq!(log_record.dropped_attributes_count(), 0);
+        assert!(log_record.attributes_contains(&Key::new("short"), &AnyValue::from("abc")));
+        assert!(log_record.attributes_contains(&Key::new("long"), &AnyValue::from("abc")));
+        assert!(log_record.attributes_contains(&Key::new("multibyte"), &AnyValue::from("hél")));
+        assert!(log_record.attributes_contains(&Key::new("int"), &AnyValue::Int(123456)));
+        assert!(log_record.attributes_contains(
+            &Key::new("list"),
+            &AnyValue::ListAny(Box::new(vec!["abc".into(), 42.into()]))
+        ));
+    }
+
     #[test]
     fn compare_trace_context() {
         let trace_context = TraceContext {
@@ -337,6 +410,8 @@ mod tests {
             severity_number: Some(Severity::Error),
             body: Some(AnyValue::String("Test body".into())),
             attributes: LogRecordAttributes::new(),
+            dropped_attributes_count: 0,
+            limits: LogRecordLimits::default(),
             trace_context: Some(TraceContext {
                 trace_id: TraceId::from_u128(1),
                 span_id: SpanId::from_u64(1),
@@ -0,0 +1,185 @@
+use crate::logs::{LogError, LogProcessor, LogResult, SdkLogRecord};
+use crate::redaction::{RedactionRule, Redactor};
+use crate::Resource;
+#[cfg(feature = "spec_unstable_logs_enabled")]
+use opentelemetry::logs::Severity;
+use opentelemetry::InstrumentationScope;
+
+/// A [`LogProcessor`] applying [`RedactionRule`]s to the log records, before
+/// forwarding them to another log processor.
+///
+/// The rules apply, in order, to the attributes of the log records, and to
+/// their body when it is a map. Other bodies are only masked by the
+/// [`RedactionRule::Mask`] rules.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry_sdk::logs::{
+///     BatchLogProcessor, InMemoryLogExporter, RedactingLogProcessor, SdkLoggerProvider,
+/// };
+/// use opentelemetry_sdk::redaction::RedactionRule;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let processor = RedactingLogProcessor::builder(
+///     BatchLogProcessor::builder(InMemoryLogExporter::default()).build(),
+/// )
+/// .with_rule(RedactionRule::Mask {
+///     pattern: r"(?i)bearer [\w.~+/-]+=*".into(),
+///     replacement: "Bearer ****".into(),
+/// })
+/// .with_rule(RedactionRule::AllowKeys(vec!["http.request.method".into(), "url.path".into()]))
+/// .build()?;
+///
+/// let provider = SdkLoggerProvider::builder()
+///     .with_log_processor(processor)
+///     .build();
+/// # drop(provider);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct RedactingLogProcessor {
+    redactor: Redactor,
+    processor: Box<dyn LogProcessor>,
+}
+
+impl RedactingLogProcessor {
+    /// Create a builder of a processor forwarding the redacted log records to
+    /// the given log processor.
+    pub fn builder<P>(processor: P) -> RedactingLogProcessorBuilder
+    where
+        P: LogProcessor + 'static,
+    {
+        RedactingLogProcessorBuilder {
+            processor: Box::new(processor),
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl LogProcessor for RedactingLogProcessor {
+    fn emit(&self, record: &mut SdkLogRecord, instrumentation: &InstrumentationScope) {
+        if record.attributes.len() > 0 {
+            let mut attributes = std::mem::take(&mut record.attributes)
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+            self.redactor.redact(&mut attributes);
+            for attribute in attributes {
+                record.attributes.push(Some(attribute));
+            }
+        }
+        if let Some(body) = record.body.as_mut() {
+            self.redactor.redact_body(body);
+        }
+        self.processor.emit(record, instrumentation);
+    }
+
+    fn force_flush(&self) -> LogResult<()> {
+        self.processor.force_flush()
+    }
+
+    fn shutdown(&self) -> LogResult<()> {
+        self.processor.shutdown()
+    }
+
+    #[cfg(feature = "spec_unstable_logs_enabled")]
+    fn event_enabled(&self, level: Severity, target: &str, name: &str) -> bool {
+        self.processor.event_enabled(level, target, name)
+    }
+
+    fn set_resource(&self, resource: &Resource) {
+        self.processor.set_resource(resource);
+    }
+}
+
+/// Builder for [`RedactingLogProcessor`].
+#[derive(Debug)]
+pub struct RedactingLogProcessorBuilder {
+    processor: Box<dyn LogProcessor>,
+    rules: Vec<RedactionRule>,
+}
+
+impl RedactingLogProcessorBuilder {
+    /// Add a rule, applied after the rules already added.
+    pub fn with_rule(mut self, rule: RedactionRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Build a [`RedactingLogProcessor`] applying the provided rules.
+    ///
+    /// Return errors if any of the regular expressions is invalid.
+    pub fn build(self) -> Result<RedactingLogProcessor, LogError> {
+        Ok(RedactingLogProcessor {
+            redactor: Redactor::new(self.rules)?,
+            processor: self.processor,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::logs::{InMemoryLogExporter, SimpleLogProcessor};
+    use opentelemetry::logs::{AnyValue, LogRecord};
+    use opentelemetry::Key;
+    use std::collections::HashMap;
+
+    #[test]
+    fn redacts_attributes_and_body() {
+        let exporter = InMemoryLogExporter::default();
+        let processor = RedactingLogProcessor::builder(SimpleLogProcessor::new(exporter.clone()))
+            .with_rule(RedactionRule::AllowKeys(vec![
+                "user".into(),
+                "message".into(),
+            ]))
+            .with_rule(RedactionRule::Mask {
+                pattern: "secret".into(),
+                replacement: "***".into(),
+            })
+            .build()
+            .unwrap();
+
+        let mut record = SdkLogRecord::new();
+        record.add_attribute("user", "jane");
+        record.add_attribute("password", "secret");
+        record.add_attribute("message", "the secret is secret");
+        record.set_body(AnyValue::Map(Box::new(HashMap::from([
+            (Key::new("token"), AnyValue::from("abc")),
+            (Key::new("message"), AnyValue::from("no secret")),
+        ]))));
+        processor.emit(&mut record, &InstrumentationScope::default());
+
+        let logs = exporter.get_emitted_logs().unwrap();
+        let record = &logs[0].record;
+        assert_eq!(
+            record.attributes_iter().cloned().collect::<Vec<_>>(),
+            vec![
+                (Key::new("user"), AnyValue::from("jane")),
+                (Key::new("message"), AnyValue::from("the *** is ***")),
+            ]
+        );
+        assert_eq!(
+            record.body(),
+            Some(&AnyValue::Map(Box::new(HashMap::from([(
+                Key::new("message"),
+                AnyValue::from("no ***"),
+            )]))))
+        );
+    }
+
+    #[test]
+    fn invalid_rule() {
+        let err =
+            RedactingLogProcessor::builder(SimpleLogProcessor::new(InMemoryLogExporter::default()))
+                .with_rule(RedactionRule::Mask {
+                    pattern: "[".into(),
+                    replacement: "*".into(),
+                })
+                .build()
+                .unwrap_err();
+        assert!(err.to_string().contains("invalid regex"));
+    }
+}
@@ -2,7 +2,7 @@
 
 use std::{any, borrow::Cow, fmt, time::SystemTime};
 
-use opentelemetry::{InstrumentationScope, KeyValue};
+use opentelemetry::{otel_warn, InstrumentationScope, KeyValue};
 
 use crate::Resource;
 
@@ -26,6 +26,86 @@ pub struct ScopeMetrics {
     pub metrics: Vec<Metric>,
 }
 
+impl ResourceMetrics {
+    /// Returns a deep copy of the metrics, leaving out, with a warning, the
+    /// metrics whose aggregation is not one of the aggregations of the SDK.
+    pub(crate) fn clone_known(&self) -> ResourceMetrics {
+        self.clone_with(|metric| {
+            otel_warn!(
+                name: "ResourceMetrics.UnknownAggregation",
+                message = "The metric is not copied, as its aggregation is not one of the aggregations of the SDK.",
+                metric_name = metric.name.as_ref()
+            );
+        })
+    }
+
+    /// Returns a deep copy of the metrics.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the aggregation of a metric is not one of the aggregations of
+    /// the SDK.
+    #[cfg(any(feature = "testing", test))]
+    pub(crate) fn clone_all(&self) -> ResourceMetrics {
+        self.clone_with(|metric| {
+            panic!(
+                "the aggregation of the metric {} is not one of the aggregations of the SDK",
+                metric.name
+            )
+        })
+    }
+
+    fn clone_with(&self, mut on_unknown: impl FnMut(&Metric)) -> ResourceMetrics {
+        ResourceMetrics {
+            resource: self.resource.clone(),
+            scope_metrics: self
+                .scope_metrics
+                .iter()
+                .map(|scope_metrics| ScopeMetrics {
+                    scope: scope_metrics.scope.clone(),
+                    metrics: scope_metrics
+                        .metrics
+                        .iter()
+                        .filter_map(|metric| {
+                            let Some(data) = clone_aggregation(metric.data.as_ref()) else {
+                                on_unknown(metric);
+                                return None;
+                            };
+                            Some(Metric {
+                                name: metric.name.clone(),
+                                description: metric.description.clone(),
+                                unit: metric.unit.clone(),
+                                data,
+                            })
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Returns a copy of `data` if it is one of the aggregations of the SDK.
+pub(crate) fn clone_aggregation(data: &dyn Aggregation) -> Option<Box<dyn Aggregation>> {
+    macro_rules! try_clone {
+        ($($aggregation:ident),*) => {
+            $(
+                if let Some(data) = data.as_any().downcast_ref::<$aggregation<i64>>() {
+                    return Some(Box::new(data.clone()));
+                }
+                if let Some(data) = data.as_any().downcast_ref::<$aggregation<u64>>() {
+                    return Some(Box::new(data.clone()));
+                }
+                if let Some(data) = data.as_any().downcast_ref::<$aggregation<f64>>() {
+                    return Some(Box::new(data.clone()));
+                }
+            )*
+        };
+    }
+    try_clone!(Gauge, Sum, Histogram, ExponentialHistogram);
+    None
+}
+
 /// A collection of one or more aggregated time series from an [Instrument].
 ///
 /// [Instrument]: crate::metrics::Instrument
@@ -86,6 +166,16 @@ pub struct Gauge<T> {
     pub time: SystemTime,
 }
 
+impl<T: Copy> Clone for Gauge<T> {
+    fn clone(&self) -> Self {
+        Self {
+            data_points: self.data_points.clone(),
+            start_time: self.start_time,
+            time: self.time,
+        }
+    }
+}
+
 impl<T: fmt::Debug + Send + Sync + 'static> Aggregation for Gauge<T> {
     fn as_any(&self) -> &dyn any::Any {
         self
@@ -133,6 +223,18 @@ pub struct Sum<T> {
     pub is_monotonic: bool,
 }
 
+impl<T: Copy> Clone for Sum<T> {
+    fn clone(&self) -> Self {
+        Self {
+            data_points: self.data_points.clone(),
+            start_time: self.start_time,
+            time: self.time,
+            temporality: self.temporality,
+            is_monotonic: self.is_monotonic,
+        }
+    }
+}
+
 impl<T: fmt::Debug + Send + Sync + 'static> Aggregation for Sum<T> {
     fn as_any(&self) -> &dyn any::Any {
         self
@@ -156,6 +258,17 @@ pub struct Histogram<T> {
     pub temporality: Temporality,
 }
 
+impl<T: Copy> Clone for Histogram<T> {
+    fn clone(&self) -> Self {
+        Self {
+            data_points: self.data_points.clone(),
+            start_time: self.start_time,
+            time: self.time,
+            temporality: self.temporality,
+        }
+    }
+}
+
 impl<T: fmt::Debug + Send + Sync + 'static> Aggregation for Histogram<T> {
     fn as_any(&self) -> &dyn any::Any {
         self
@@ -219,6 +332,17 @@ pub struct ExponentialHistogram<T> {
     pub temporality: Temporality,
 }
 
+impl<T: Copy> Clone for ExponentialHistogram<T> {
+    fn clone(&self) -> Self {
+        Self {
+            data_points: self.data_points.clone(),
+            start_time: self.start_time,
+            time: self.time,
+            temporality: self.temporality,
+        }
+    }
+}
+
 impl<T: fmt::Debug + Send + Sync + 'static> Aggregation for ExponentialHistogram<T> {
     fn as_any(&self) -> &dyn any::Any {
         self
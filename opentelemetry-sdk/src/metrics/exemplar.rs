@@ -0,0 +1,101 @@
+//! Exemplar sampling configuration.
+//!
+//! See <https://github.com/open-telemetry/opentelemetry-specification/blob/main/specification/metrics/sdk.md#exemplar>.
+
+use std::env;
+
+use opentelemetry::otel_warn;
+
+/// The environment variable used to select the default [ExemplarFilter].
+const METRICS_EXEMPLAR_FILTER: &str = "OTEL_METRICS_EXEMPLAR_FILTER";
+
+/// Determines which measurements are eligible to become [exemplars].
+///
+/// Measurements that pass the filter are offered to the exemplar reservoir of
+/// the metric stream they are recorded in, which decides which of them are
+/// kept and reported with the next collection.
+///
+/// Only measurements of synchronous instruments are considered, measurements
+/// reported by observable instruments never become exemplars.
+///
+/// [exemplars]: crate::metrics::data::Exemplar
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ExemplarFilter {
+    /// All measurements are eligible.
+    AlwaysOn,
+    /// No measurements are eligible, effectively disabling exemplars.
+    AlwaysOff,
+    /// Measurements recorded in the context of a sampled span are eligible.
+    #[default]
+    TraceBased,
+}
+
+/// The reservoir a metric stream keeps its [exemplars] in.
+///
+/// Each attribute set of the stream has its own reservoir, which is emptied on
+/// every collection. Use [Stream::exemplar_reservoir] to select the reservoir of
+/// the streams matched by a view. By default, explicit bucket histograms use an
+/// [ExemplarReservoir::AlignedHistogramBucket] reservoir, exponential histograms
+/// an [ExemplarReservoir::SimpleFixedSize] reservoir as large as the histogram
+/// (capped to twenty measurements), and other aggregations a
+/// [ExemplarReservoir::SimpleFixedSize] reservoir of size one.
+///
+/// [exemplars]: crate::metrics::data::Exemplar
+/// [Stream::exemplar_reservoir]: crate::metrics::Stream::exemplar_reservoir
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ExemplarReservoir {
+    /// Keeps a uniformly weighted random sample of at most `size` measurements.
+    SimpleFixedSize(usize),
+    /// Keeps one measurement per bucket of the histogram, chosen uniformly at
+    /// random among the measurements that fell in that bucket.
+    ///
+    /// Aggregations other than explicit bucket histograms have a single bucket.
+    AlignedHistogramBucket,
+}
+
+impl ExemplarFilter {
+    /// Reads the filter from the `OTEL_METRICS_EXEMPLAR_FILTER` environment
+    /// variable, falling back to [ExemplarFilter::TraceBased].
+    pub(crate) fn from_env() -> Self {
+        match env::var(METRICS_EXEMPLAR_FILTER) {
+            Ok(filter) => match filter.as_str() {
+                "always_on" => ExemplarFilter::AlwaysOn,
+                "always_off" => ExemplarFilter::AlwaysOff,
+                "trace_based" => ExemplarFilter::TraceBased,
+                s => {
+                    otel_warn!(
+                        name: "MeterProvider.InvalidExemplarFilter",
+                        message = format!(
+                            "Unrecognized exemplar filter '{}' in OTEL_METRICS_EXEMPLAR_FILTER environment variable. Valid values are: always_on, always_off, trace_based. Using fallback filter: trace_based",
+                            s
+                        ),
+                    );
+                    ExemplarFilter::TraceBased
+                }
+            },
+            Err(_) => ExemplarFilter::TraceBased,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exemplar_filter_from_env() {
+        for (value, expected) in [
+            (Some("always_on"), ExemplarFilter::AlwaysOn),
+            (Some("always_off"), ExemplarFilter::AlwaysOff),
+            (Some("trace_based"), ExemplarFilter::TraceBased),
+            (Some("unknown"), ExemplarFilter::TraceBased),
+            (None, ExemplarFilter::TraceBased),
+        ] {
+            temp_env::with_var(METRICS_EXEMPLAR_FILTER, value, || {
+                assert_eq!(ExemplarFilter::from_env(), expected, "{value:?}");
+            });
+        }
+    }
+}
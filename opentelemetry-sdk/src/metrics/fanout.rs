@@ -0,0 +1,321 @@
+use crate::error::{OTelSdkError, OTelSdkResult};
+use crate::fanout::{export_all, join_errors, run_all, Destination, FanOutStats};
+use crate::metrics::data::ResourceMetrics;
+use crate::metrics::exporter::PushMetricExporter;
+use crate::metrics::{MetricError, Temporality};
+use async_trait::async_trait;
+use std::borrow::Cow;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// A [`PushMetricExporter`] exporting the metrics to several metric exporters
+/// concurrently.
+///
+/// Each destination has its own export timeout: a destination failing or
+/// timing out does not prevent the export to the other destinations. The
+/// export fails if any of the destinations failed, with the errors of the
+/// failed destinations, and the outcomes of the exports are counted per
+/// destination by the [`FanOutStats`] of the exporter.
+///
+/// The metrics are copied for each destination, as the exports outlive the
+/// metrics if they time out. Only the aggregations of the SDK can be copied: a
+/// metric with another aggregation is not exported, and a warning is logged.
+/// All the destinations must use the same [`Temporality`].
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry_sdk::metrics::{
+///     FanOutMetricExporter, InMemoryMetricExporter, PeriodicReader, SdkMeterProvider,
+/// };
+/// use std::time::Duration;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let exporter = FanOutMetricExporter::builder()
+///     .with_exporter("otlp", InMemoryMetricExporter::default(), Duration::from_secs(10))
+///     .with_exporter("vendor", InMemoryMetricExporter::default(), Duration::from_secs(5))
+///     .build()?;
+/// let stats = exporter.stats();
+///
+/// let provider = SdkMeterProvider::builder()
+///     .with_reader(PeriodicReader::builder(exporter).build())
+///     .build();
+///
+/// // ...
+///
+/// provider.shutdown()?;
+/// for destination in stats.destinations() {
+///     println!("{}: {} failed exports", destination.name(), destination.failed());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct FanOutMetricExporter {
+    destinations: Vec<Destination<Arc<dyn PushMetricExporter>>>,
+    temporality: Temporality,
+    stats: FanOutStats,
+}
+
+impl fmt::Debug for FanOutMetricExporter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FanOutMetricExporter")
+            .field(
+                "destinations",
+                &self
+                    .destinations
+                    .iter()
+                    .map(|destination| (&destination.name, destination.timeout))
+                    .collect::<Vec<_>>(),
+            )
+            .field("temporality", &self.temporality)
+            .finish()
+    }
+}
+
+impl FanOutMetricExporter {
+    /// Create a builder to configure the destinations of the exporter.
+    pub fn builder() -> FanOutMetricExporterBuilder {
+        FanOutMetricExporterBuilder::default()
+    }
+
+    /// The outcomes of the exports, per destination.
+    pub fn stats(&self) -> FanOutStats {
+        self.stats.clone()
+    }
+}
+
+#[async_trait]
+impl PushMetricExporter for FanOutMetricExporter {
+    async fn export(&self, metrics: &mut ResourceMetrics) -> OTelSdkResult {
+        let exports = self
+            .destinations
+            .iter()
+            .map(|destination| {
+                let exporter = Arc::clone(&destination.exporter);
+                let mut metrics = metrics.clone_known();
+                let export = destination
+                    .worker
+                    .run(move || futures_executor::block_on(exporter.export(&mut metrics)));
+                (destination.timeout, export)
+            })
+            .collect::<Vec<_>>();
+
+        export_all(self.stats.clone(), exports)
+            .await
+            .map_err(OTelSdkError::InternalFailure)
+    }
+
+    async fn force_flush(&self) -> OTelSdkResult {
+        let flushes = self
+            .destinations
+            .iter()
+            .map(|destination| {
+                let exporter = Arc::clone(&destination.exporter);
+                let flush = destination
+                    .worker
+                    .run(move || futures_executor::block_on(exporter.force_flush()));
+                (destination.name.as_ref(), destination.timeout, flush)
+            })
+            .collect::<Vec<_>>();
+        run_all("flush", flushes)
+            .await
+            .map_err(OTelSdkError::InternalFailure)
+    }
+
+    fn shutdown(&self) -> OTelSdkResult {
+        join_errors(self.destinations.iter().filter_map(|destination| {
+            let err = destination.exporter.shutdown().err()?;
+            Some(format!("shutdown of {} failed: {}", destination.name, err))
+        }))
+        .map_err(OTelSdkError::InternalFailure)
+    }
+
+    fn temporality(&self) -> Temporality {
+        self.temporality
+    }
+}
+
+/// Builder for [`FanOutMetricExporter`].
+#[derive(Default)]
+pub struct FanOutMetricExporterBuilder {
+    destinations: Vec<Destination<Arc<dyn PushMetricExporter>>>,
+}
+
+impl fmt::Debug for FanOutMetricExporterBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FanOutMetricExporterBuilder")
+            .field(
+                "destinations",
+                &self
+                    .destinations
+                    .iter()
+                    .map(|destination| (&destination.name, destination.timeout))
+                    .collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl FanOutMetricExporterBuilder {
+    /// Add a destination named `name`, exporting the metrics with `exporter`,
+    /// and giving up on each export after `timeout`.
+    pub fn with_exporter<E>(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        exporter: E,
+        timeout: Duration,
+    ) -> Self
+    where
+        E: PushMetricExporter,
+    {
+        self.destinations
+            .push(Destination::new(name.into(), timeout, Arc::new(exporter)));
+        self
+    }
+
+    /// Build a [`FanOutMetricExporter`] exporting to the provided
+    /// destinations.
+    ///
+    /// Return errors if the destinations do not all use the same temporality.
+    pub fn build(self) -> Result<FanOutMetricExporter, MetricError> {
+        let temporality = self
+            .destinations
+            .first()
+            .map(|destination| destination.exporter.temporality())
+            .unwrap_or_default();
+        if let Some(destination) = self
+            .destinations
+            .iter()
+            .find(|destination| destination.exporter.temporality() != temporality)
+        {
+            return Err(MetricError::Config(format!(
+                "the temporality of {} is {:?}, but the temporality of the other destinations is {:?}",
+                destination.name,
+                destination.exporter.temporality(),
+                temporality
+            )));
+        }
+
+        let stats = FanOutStats::default();
+        for destination in &self.destinations {
+            stats.add_destination(destination.name.clone());
+        }
+        Ok(FanOutMetricExporter {
+            destinations: self.destinations,
+            temporality,
+            stats,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::metrics::data::{Gauge, GaugeDataPoint, Metric, ScopeMetrics};
+    use crate::metrics::InMemoryMetricExporterBuilder;
+    use crate::Resource;
+    use opentelemetry::{InstrumentationScope, KeyValue};
+    use std::time::SystemTime;
+
+    struct FailingExporter;
+
+    #[async_trait]
+    impl PushMetricExporter for FailingExporter {
+        async fn export(&self, _metrics: &mut ResourceMetrics) -> OTelSdkResult {
+            Err(OTelSdkError::InternalFailure("unavailable".into()))
+        }
+
+        async fn force_flush(&self) -> OTelSdkResult {
+            Ok(())
+        }
+
+        fn shutdown(&self) -> OTelSdkResult {
+            Ok(())
+        }
+
+        fn temporality(&self) -> Temporality {
+            Temporality::Cumulative
+        }
+    }
+
+    fn resource_metrics() -> ResourceMetrics {
+        ResourceMetrics {
+            resource: Resource::builder_empty().build(),
+            scope_metrics: vec![ScopeMetrics {
+                scope: InstrumentationScope::builder("test").build(),
+                metrics: vec![Metric {
+                    name: "queue.size".into(),
+                    description: "".into(),
+                    unit: "".into(),
+                    data: Box::new(Gauge {
+                        data_points: vec![GaugeDataPoint {
+                            attributes: vec![KeyValue::new("queue", "jobs")],
+                            value: 42i64,
+                            exemplars: vec![],
+                        }],
+                        start_time: None,
+                        time: SystemTime::now(),
+                    }),
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn exports_to_all_destinations() {
+        let first = InMemoryMetricExporterBuilder::new().build();
+        let second = InMemoryMetricExporterBuilder::new().build();
+        let exporter = FanOutMetricExporter::builder()
+            .with_exporter("first", first.clone(), Duration::from_secs(5))
+            .with_exporter("failing", FailingExporter, Duration::from_secs(5))
+            .with_exporter("second", second.clone(), Duration::from_secs(5))
+            .build()
+            .unwrap();
+
+        let err = futures_executor::block_on(exporter.export(&mut resource_metrics())).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Operation failed: export to failing failed: Operation failed: unavailable"
+        );
+        for destination in [first, second] {
+            let metrics = destination.get_finished_metrics().unwrap();
+            let gauge = metrics[0].scope_metrics[0].metrics[0]
+                .data
+                .as_any()
+                .downcast_ref::<Gauge<i64>>()
+                .unwrap();
+            assert_eq!(gauge.data_points[0].value, 42);
+        }
+
+        let stats = exporter.stats();
+        assert_eq!(stats.destination("first").unwrap().succeeded(), 1);
+        assert_eq!(stats.destination("failing").unwrap().failed(), 1);
+        assert_eq!(stats.destination("second").unwrap().succeeded(), 1);
+
+        futures_executor::block_on(exporter.force_flush()).unwrap();
+        exporter.shutdown().unwrap();
+    }
+
+    #[test]
+    fn rejects_mixed_temporalities() {
+        let err = FanOutMetricExporter::builder()
+            .with_exporter(
+                "cumulative",
+                InMemoryMetricExporterBuilder::new().build(),
+                Duration::from_secs(5),
+            )
+            .with_exporter(
+                "delta",
+                InMemoryMetricExporterBuilder::new()
+                    .with_temporality(Temporality::Delta)
+                    .build(),
+                Duration::from_secs(5),
+            )
+            .build()
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("the temporality of delta is Delta"));
+    }
+}
@@ -7,7 +7,7 @@ use opentelemetry::{
 
 use crate::metrics::{aggregation::Aggregation, internal::Measure};
 
-use super::Temporality;
+use super::{ExemplarReservoir, Temporality};
 
 /// The identifier of a group of instruments that all perform the same function.
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
@@ -203,6 +203,19 @@ pub struct Stream {
     /// dropped. If the set is empty, all attributes will be dropped, if `None` all
     /// attributes will be kept.
     pub allowed_attribute_keys: Option<Arc<HashSet<Key>>>,
+    /// The maximum number of distinct attribute sets the stream keeps track of.
+    ///
+    /// Measurements with attribute sets beyond this limit are aggregated into a
+    /// single data point with the `otel.metric.overflow=true` attribute. If `None`,
+    /// the limit of the [MetricReader], or else of the [MeterProvider], is used.
+    ///
+    /// [MetricReader]: crate::metrics::reader::MetricReader
+    /// [MeterProvider]: crate::metrics::SdkMeterProvider
+    pub cardinality_limit: Option<usize>,
+    /// The reservoir the exemplars of the stream are kept in.
+    ///
+    /// If `None`, the default reservoir of the aggregation is used.
+    pub exemplar_reservoir: Option<ExemplarReservoir>,
 }
 
 #[cfg(feature = "spec_unstable_metrics_views")]
@@ -245,6 +258,27 @@ impl Stream {
 
         self
     }
+
+    /// Set the stream cardinality limit.
+    ///
+    /// Measurements with attribute sets beyond the first `limit` distinct ones are
+    /// aggregated into a single overflow data point.
+    pub fn cardinality_limit(mut self, limit: usize) -> Self {
+        self.cardinality_limit = Some(limit);
+        self
+    }
+
+    /// Set the stream exemplar reservoir.
+    ///
+    /// The reservoir only decides which measurements are kept, the
+    /// [ExemplarFilter] of the [MeterProvider] decides which are offered.
+    ///
+    /// [ExemplarFilter]: crate::metrics::ExemplarFilter
+    /// [MeterProvider]: crate::metrics::SdkMeterProvider
+    pub fn exemplar_reservoir(mut self, reservoir: ExemplarReservoir) -> Self {
+        self.exemplar_reservoir = Some(reservoir);
+        self
+    }
 }
 
 /// The identifying properties of an instrument.
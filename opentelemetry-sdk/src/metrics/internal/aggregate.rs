@@ -11,20 +11,13 @@ use opentelemetry::time::now;
 use opentelemetry::KeyValue;
 
 use super::{
-    exponential_histogram::ExpoHistogram, histogram::Histogram, last_value::LastValue,
-    precomputed_sum::PrecomputedSum, sum::Sum, Number,
+    exemplar::ExemplarConfig, exponential_histogram::ExpoHistogram, histogram::Histogram,
+    last_value::LastValue, precomputed_sum::PrecomputedSum, sum::Sum, Number,
 };
 
+/// The default maximum number of attribute sets of a metric stream.
 pub(crate) const STREAM_CARDINALITY_LIMIT: usize = 2000;
 
-/// Checks whether aggregator has hit cardinality limit for metric streams
-pub(crate) fn is_under_cardinality_limit(_size: usize) -> bool {
-    true
-
-    // TODO: Implement this feature, after allowing the ability to customize the cardinality limit.
-    // size < STREAM_CARDINALITY_LIMIT
-}
-
 /// Receives measurements to be aggregated.
 pub(crate) trait Measure<T>: Send + Sync + 'static {
     fn call(&self, measurement: T, attrs: &[KeyValue]);
@@ -133,14 +126,27 @@ pub(crate) struct AggregateBuilder<T> {
     /// measurements.
     filter: AttributeSetFilter,
 
+    /// How the aggregate functions sample exemplars.
+    exemplars: ExemplarConfig,
+
+    /// The maximum number of attribute sets the aggregate functions track.
+    cardinality_limit: usize,
+
     _marker: marker::PhantomData<T>,
 }
 
 impl<T: Number> AggregateBuilder<T> {
-    pub(crate) fn new(temporality: Temporality, filter: Option<Filter>) -> Self {
+    pub(crate) fn new(
+        temporality: Temporality,
+        filter: Option<Filter>,
+        exemplars: ExemplarConfig,
+        cardinality_limit: usize,
+    ) -> Self {
         AggregateBuilder {
             temporality,
             filter: AttributeSetFilter::new(filter),
+            exemplars,
+            cardinality_limit,
             _marker: marker::PhantomData,
         }
     }
@@ -150,18 +156,33 @@ impl<T: Number> AggregateBuilder<T> {
         LastValue::new(
             overwrite_temporality.unwrap_or(self.temporality),
             self.filter.clone(),
+            self.exemplars,
+            self.cardinality_limit,
         )
         .into()
     }
 
     /// Builds a precomputed sum aggregate function input and output.
     pub(crate) fn precomputed_sum(&self, monotonic: bool) -> AggregateFns<T> {
-        PrecomputedSum::new(self.temporality, self.filter.clone(), monotonic).into()
+        PrecomputedSum::new(
+            self.temporality,
+            self.filter.clone(),
+            monotonic,
+            self.cardinality_limit,
+        )
+        .into()
     }
 
     /// Builds a sum aggregate function input and output.
     pub(crate) fn sum(&self, monotonic: bool) -> AggregateFns<T> {
-        Sum::new(self.temporality, self.filter.clone(), monotonic).into()
+        Sum::new(
+            self.temporality,
+            self.filter.clone(),
+            self.exemplars,
+            monotonic,
+            self.cardinality_limit,
+        )
+        .into()
     }
 
     /// Builds a histogram aggregate function input and output.
@@ -174,9 +195,11 @@ impl<T: Number> AggregateBuilder<T> {
         Histogram::new(
             self.temporality,
             self.filter.clone(),
+            self.exemplars,
             boundaries,
             record_min_max,
             record_sum,
+            self.cardinality_limit,
         )
         .into()
     }
@@ -192,10 +215,12 @@ impl<T: Number> AggregateBuilder<T> {
         ExpoHistogram::new(
             self.temporality,
             self.filter.clone(),
+            self.exemplars,
             max_size,
             max_scale,
             record_min_max,
             record_sum,
+            self.cardinality_limit,
         )
         .into()
     }
@@ -207,14 +232,20 @@ mod tests {
         ExponentialBucket, ExponentialHistogram, ExponentialHistogramDataPoint, Gauge,
         GaugeDataPoint, Histogram, HistogramDataPoint, Sum, SumDataPoint,
     };
+    use crate::metrics::ExemplarFilter;
     use std::vec;
 
     use super::*;
 
     #[test]
     fn last_value_aggregation() {
-        let AggregateFns { measure, collect } =
-            AggregateBuilder::<u64>::new(Temporality::Cumulative, None).last_value(None);
+        let AggregateFns { measure, collect } = AggregateBuilder::<u64>::new(
+            Temporality::Cumulative,
+            None,
+            ExemplarFilter::AlwaysOff.into(),
+            STREAM_CARDINALITY_LIMIT,
+        )
+        .last_value(None);
         let mut a = Gauge {
             data_points: vec![GaugeDataPoint {
                 attributes: vec![KeyValue::new("a", 1)],
@@ -239,8 +270,13 @@ mod tests {
     #[test]
     fn precomputed_sum_aggregation() {
         for temporality in [Temporality::Delta, Temporality::Cumulative] {
-            let AggregateFns { measure, collect } =
-                AggregateBuilder::<u64>::new(temporality, None).precomputed_sum(true);
+            let AggregateFns { measure, collect } = AggregateBuilder::<u64>::new(
+                temporality,
+                None,
+                ExemplarFilter::AlwaysOff.into(),
+                STREAM_CARDINALITY_LIMIT,
+            )
+            .precomputed_sum(true);
             let mut a = Sum {
                 data_points: vec![
                     SumDataPoint {
@@ -281,8 +317,13 @@ mod tests {
     #[test]
     fn sum_aggregation() {
         for temporality in [Temporality::Delta, Temporality::Cumulative] {
-            let AggregateFns { measure, collect } =
-                AggregateBuilder::<u64>::new(temporality, None).sum(true);
+            let AggregateFns { measure, collect } = AggregateBuilder::<u64>::new(
+                temporality,
+                None,
+                ExemplarFilter::AlwaysOff.into(),
+                STREAM_CARDINALITY_LIMIT,
+            )
+            .sum(true);
             let mut a = Sum {
                 data_points: vec![
                     SumDataPoint {
@@ -323,8 +364,13 @@ mod tests {
     #[test]
     fn explicit_bucket_histogram_aggregation() {
         for temporality in [Temporality::Delta, Temporality::Cumulative] {
-            let AggregateFns { measure, collect } = AggregateBuilder::<u64>::new(temporality, None)
-                .explicit_bucket_histogram(vec![1.0], true, true);
+            let AggregateFns { measure, collect } = AggregateBuilder::<u64>::new(
+                temporality,
+                None,
+                ExemplarFilter::AlwaysOff.into(),
+                STREAM_CARDINALITY_LIMIT,
+            )
+            .explicit_bucket_histogram(vec![1.0], true, true);
             let mut a = Histogram {
                 data_points: vec![HistogramDataPoint {
                     attributes: vec![KeyValue::new("a1", 1)],
@@ -366,8 +412,13 @@ mod tests {
     #[test]
     fn exponential_histogram_aggregation() {
         for temporality in [Temporality::Delta, Temporality::Cumulative] {
-            let AggregateFns { measure, collect } = AggregateBuilder::<u64>::new(temporality, None)
-                .exponential_bucket_histogram(4, 20, true, true);
+            let AggregateFns { measure, collect } = AggregateBuilder::<u64>::new(
+                temporality,
+                None,
+                ExemplarFilter::AlwaysOff.into(),
+                STREAM_CARDINALITY_LIMIT,
+            )
+            .exponential_bucket_histogram(4, 20, true, true);
             let mut a = ExponentialHistogram {
                 data_points: vec![ExponentialHistogramDataPoint {
                     attributes: vec![KeyValue::new("a1", 1)],
@@ -0,0 +1,519 @@
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use opentelemetry::time::now;
+use opentelemetry::KeyValue;
+use rand::{rngs, Rng, SeedableRng};
+
+use crate::metrics::data::Exemplar;
+use crate::metrics::{ExemplarFilter, ExemplarReservoir};
+
+use super::{Aggregator, Number};
+
+/// The largest reservoir used by exponential histograms.
+const EXPO_HISTOGRAM_MAX_RESERVOIR_SIZE: usize = 20;
+
+thread_local! {
+    /// Store random number generator for each thread
+    static CURRENT_RNG: RefCell<rngs::SmallRng> = RefCell::new(rngs::SmallRng::from_entropy());
+}
+
+/// Returns a uniformly distributed index in `[0, bound)`.
+fn random_index(bound: usize) -> usize {
+    CURRENT_RNG.with(|rng| rng.borrow_mut().gen_range(0..bound))
+}
+
+/// How the aggregations of a metric stream sample exemplars.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ExemplarConfig {
+    filter: ExemplarFilter,
+    /// The reservoir selected by a view, if any.
+    reservoir: Option<ExemplarReservoir>,
+}
+
+impl ExemplarConfig {
+    pub(crate) fn new(filter: ExemplarFilter, reservoir: Option<ExemplarReservoir>) -> Self {
+        ExemplarConfig { filter, reservoir }
+    }
+
+    /// Returns the reservoir created for each attribute set, `default` unless a
+    /// view selected another one, or `None` if the filter disables exemplars.
+    pub(crate) fn reservoir(&self, default: ReservoirConfig) -> Option<ReservoirConfig> {
+        if self.filter == ExemplarFilter::AlwaysOff {
+            return None;
+        }
+        Some(match self.reservoir {
+            None => default,
+            Some(ExemplarReservoir::SimpleFixedSize(size)) => {
+                ReservoirConfig::SimpleFixedSize(size.max(1))
+            }
+            Some(ExemplarReservoir::AlignedHistogramBucket) => match default {
+                ReservoirConfig::AlignedHistogramBucket(_) => default,
+                ReservoirConfig::SimpleFixedSize(_) => ReservoirConfig::AlignedHistogramBucket(1),
+            },
+        })
+    }
+
+    /// Builds a candidate out of a measurement, if the filter lets it through.
+    ///
+    /// `attrs` are the attributes the measurement was recorded with, and
+    /// `filtered` the ones kept by the attribute filter of the stream. The
+    /// difference is stored with the exemplar.
+    ///
+    /// Only the trace based filter has to look at the current context here,
+    /// everything else is deferred until a reservoir keeps the candidate.
+    pub(crate) fn sample<'a, T: Number>(
+        &self,
+        value: T,
+        attrs: &'a [KeyValue],
+        filtered: &'a [KeyValue],
+        bucket: usize,
+    ) -> Option<ExemplarCandidate<'a, T>> {
+        let span = match self.filter {
+            ExemplarFilter::AlwaysOff => return None,
+            ExemplarFilter::AlwaysOn => None,
+            ExemplarFilter::TraceBased => Some(sampled_span()?),
+        };
+
+        Some(ExemplarCandidate {
+            attrs,
+            filtered,
+            value,
+            span,
+            bucket,
+        })
+    }
+}
+
+impl From<ExemplarFilter> for ExemplarConfig {
+    fn from(filter: ExemplarFilter) -> Self {
+        ExemplarConfig::new(filter, None)
+    }
+}
+
+/// Returns the attributes of `attrs` that are not in `filtered`.
+///
+/// The attribute filter of a stream keeps the attributes in the order they
+/// were recorded, so `filtered` is a subsequence of `attrs` and a single pass
+/// over both is enough.
+fn dropped_attributes(attrs: &[KeyValue], filtered: &[KeyValue]) -> Vec<KeyValue> {
+    if attrs.len() == filtered.len() {
+        return vec![];
+    }
+    let mut kept = filtered.iter().peekable();
+    attrs
+        .iter()
+        .filter(|kv| {
+            if kept.peek() == Some(kv) {
+                kept.next();
+                false
+            } else {
+                true
+            }
+        })
+        .cloned()
+        .collect()
+}
+
+/// A measurement that passed the [ExemplarFilter] and is offered to a reservoir.
+///
+/// The time, the dropped attributes and, unless the filter needed them, the
+/// span of the measurement are only read once a reservoir decides to keep it.
+/// Reservoirs are offered candidates while the measurement is recorded, so the
+/// current context is still the one of the measurement.
+pub(crate) struct ExemplarCandidate<'a, T> {
+    attrs: &'a [KeyValue],
+    filtered: &'a [KeyValue],
+    value: T,
+    /// The trace and span ids, if already looked up by the filter.
+    span: Option<([u8; 16], [u8; 8])>,
+    /// The histogram bucket the measurement falls in, `0` for other aggregations.
+    bucket: usize,
+}
+
+impl<T> ExemplarCandidate<'_, T> {
+    fn into_exemplar(self) -> Exemplar<T> {
+        let (trace_id, span_id) = self.span.or_else(sampled_span).unwrap_or_default();
+        Exemplar {
+            filtered_attributes: dropped_attributes(self.attrs, self.filtered),
+            time: now(),
+            value: self.value,
+            span_id,
+            trace_id,
+        }
+    }
+}
+
+/// The trace and span ids of the sampled span of the current context, if any.
+#[cfg(feature = "trace")]
+fn sampled_span() -> Option<([u8; 16], [u8; 8])> {
+    use opentelemetry::trace::TraceContextExt;
+
+    opentelemetry::Context::map_current(|cx| {
+        let span = cx.span();
+        let span_context = span.span_context();
+        (span_context.is_valid() && span_context.is_sampled()).then(|| {
+            (
+                span_context.trace_id().to_bytes(),
+                span_context.span_id().to_bytes(),
+            )
+        })
+    })
+}
+
+/// Without tracing support there is never a sampled span.
+#[cfg(not(feature = "trace"))]
+fn sampled_span() -> Option<([u8; 16], [u8; 8])> {
+    None
+}
+
+/// Selects the reservoir created for each attribute set of an aggregation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ReservoirConfig {
+    /// Keeps a uniformly weighted random sample of at most the given number of
+    /// measurements.
+    SimpleFixedSize(usize),
+    /// Keeps one measurement per histogram bucket, chosen uniformly at random
+    /// among the measurements that fell in that bucket.
+    AlignedHistogramBucket(usize),
+}
+
+impl ReservoirConfig {
+    /// The default reservoir of exponential histograms, as large as the
+    /// histogram but capped to twenty measurements.
+    pub(crate) fn exponential(max_size: u32) -> Self {
+        let size = (max_size as usize).clamp(1, EXPO_HISTOGRAM_MAX_RESERVOIR_SIZE);
+        ReservoirConfig::SimpleFixedSize(size)
+    }
+
+    fn create<T>(&self) -> Reservoir<T> {
+        let (slots, counters) = match *self {
+            ReservoirConfig::SimpleFixedSize(size) => (size, 1),
+            ReservoirConfig::AlignedHistogramBucket(buckets) => (buckets, buckets),
+        };
+        Reservoir {
+            config: *self,
+            slots: (0..slots).map(|_| Mutex::new(None)).collect(),
+            seen: (0..counters).map(|_| AtomicUsize::new(0)).collect(),
+        }
+    }
+}
+
+/// The exemplars of one attribute set.
+///
+/// Which slot a measurement goes to is decided with atomic counters, and the
+/// slot is only written if its lock is free. Measurements offered while
+/// another thread writes the same slot are dropped, the sample stays random
+/// and recording never waits on the reservoir.
+struct Reservoir<T> {
+    config: ReservoirConfig,
+    slots: Box<[Mutex<Option<Exemplar<T>>>]>,
+    /// Number of measurements offered since the last collection, in total for
+    /// simple reservoirs and per bucket for aligned ones.
+    seen: Box<[AtomicUsize]>,
+}
+
+impl<T: Number> Reservoir<T> {
+    fn offer(&self, candidate: ExemplarCandidate<'_, T>) {
+        let slot = match self.config {
+            ReservoirConfig::SimpleFixedSize(size) => {
+                let seen = self.seen[0].fetch_add(1, Ordering::Relaxed);
+                // Reservoir sampling, each of the `seen + 1` measurements has
+                // the same chance to be part of the sample.
+                let index = if seen < size {
+                    seen
+                } else {
+                    random_index(seen + 1)
+                };
+                self.slots.get(index)
+            }
+            ReservoirConfig::AlignedHistogramBucket(_) => {
+                let Some(seen) = self.seen.get(candidate.bucket) else {
+                    return;
+                };
+                let seen = seen.fetch_add(1, Ordering::Relaxed) + 1;
+                (random_index(seen) == 0).then(|| &self.slots[candidate.bucket])
+            }
+        };
+
+        if let Some(Ok(mut slot)) = slot.map(Mutex::try_lock) {
+            *slot = Some(candidate.into_exemplar());
+        }
+    }
+
+    /// Moves the exemplars out of the reservoir, emptying it.
+    fn collect(&self, dest: &mut Vec<Exemplar<T>>) {
+        for slot in self.slots.iter() {
+            dest.extend(slot.lock().unwrap_or_else(|err| err.into_inner()).take());
+        }
+        self.seen
+            .iter()
+            .for_each(|seen| seen.store(0, Ordering::Relaxed));
+    }
+
+    /// Returns a reservoir with the current exemplars, emptying this one.
+    fn take(&self) -> Self {
+        Reservoir {
+            config: self.config,
+            slots: self
+                .slots
+                .iter()
+                .map(|slot| Mutex::new(slot.lock().unwrap_or_else(|err| err.into_inner()).take()))
+                .collect(),
+            seen: self
+                .seen
+                .iter()
+                .map(|seen| AtomicUsize::new(seen.swap(0, Ordering::Relaxed)))
+                .collect(),
+        }
+    }
+}
+
+/// Pairs an [Aggregator] with the reservoir of the exemplars of its attribute set.
+pub(crate) struct WithExemplars<A, T> {
+    pub(crate) aggregator: A,
+    reservoir: Option<Reservoir<T>>,
+}
+
+impl<A, T> WithExemplars<A, T>
+where
+    T: Number,
+{
+    /// Returns the exemplars sampled since the last collection.
+    pub(crate) fn collect_exemplars(&self) -> Vec<Exemplar<T>> {
+        let mut exemplars = vec![];
+        if let Some(reservoir) = &self.reservoir {
+            reservoir.collect(&mut exemplars);
+        }
+        exemplars
+    }
+}
+
+impl<A, T> Aggregator for WithExemplars<A, T>
+where
+    A: Aggregator,
+    T: Number,
+{
+    type InitConfig = (A::InitConfig, Option<ReservoirConfig>);
+
+    type PreComputedValue<'a> = (A::PreComputedValue<'a>, Option<ExemplarCandidate<'a, T>>);
+
+    fn create((init, reservoir): &Self::InitConfig) -> Self {
+        WithExemplars {
+            aggregator: A::create(init),
+            reservoir: reservoir.as_ref().map(ReservoirConfig::create),
+        }
+    }
+
+    fn update(&self, (value, candidate): Self::PreComputedValue<'_>) {
+        self.aggregator.update(value);
+        if let (Some(reservoir), Some(candidate)) = (&self.reservoir, candidate) {
+            reservoir.offer(candidate);
+        }
+    }
+
+    fn clone_and_reset(&self, (init, _): &Self::InitConfig) -> Self {
+        WithExemplars {
+            aggregator: self.aggregator.clone_and_reset(init),
+            reservoir: self.reservoir.as_ref().map(Reservoir::take),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(filter: ExemplarFilter, attrs: &[KeyValue]) -> Option<ExemplarCandidate<'_, u64>> {
+        ExemplarConfig::from(filter).sample(1, attrs, attrs, 0)
+    }
+
+    fn candidate(value: u64, bucket: usize) -> ExemplarCandidate<'static, u64> {
+        ExemplarConfig::from(ExemplarFilter::AlwaysOn)
+            .sample(value, &[], &[], bucket)
+            .unwrap()
+    }
+
+    fn collect(reservoir: &Reservoir<u64>) -> Vec<u64> {
+        let mut exemplars = vec![];
+        reservoir.collect(&mut exemplars);
+        exemplars.into_iter().map(|e| e.value).collect()
+    }
+
+    #[test]
+    fn simple_fixed_size_reservoir_keeps_at_most_size_exemplars() {
+        let reservoir = ReservoirConfig::SimpleFixedSize(3).create::<u64>();
+        for v in 0..2 {
+            reservoir.offer(candidate(v, 0));
+        }
+        assert_eq!(collect(&reservoir), vec![0, 1]);
+
+        for v in 10..100 {
+            reservoir.offer(candidate(v, 0));
+        }
+        let values = collect(&reservoir);
+        assert_eq!(values.len(), 3);
+        assert!(values.iter().all(|v| (10..100).contains(v)), "{values:?}");
+    }
+
+    #[test]
+    fn simple_fixed_size_reservoir_favours_current_cycle() {
+        let reservoir = ReservoirConfig::SimpleFixedSize(2).create::<u64>();
+        for v in 0..10 {
+            reservoir.offer(candidate(v, 0));
+        }
+        collect(&reservoir);
+
+        reservoir.offer(candidate(42, 0));
+        assert_eq!(collect(&reservoir), vec![42]);
+    }
+
+    #[test]
+    fn aligned_histogram_bucket_reservoir_keeps_one_exemplar_per_bucket() {
+        let reservoir = ReservoirConfig::AlignedHistogramBucket(3).create::<u64>();
+        for v in 0..10 {
+            reservoir.offer(candidate(v, 0));
+        }
+        reservoir.offer(candidate(100, 2));
+        // out of range buckets are ignored
+        reservoir.offer(candidate(1000, 3));
+
+        let values = collect(&reservoir);
+        assert_eq!(values.len(), 2);
+        assert!(values[0] < 10);
+        assert_eq!(values[1], 100);
+    }
+
+    #[test]
+    fn reservoirs_are_emptied_on_collection() {
+        for config in [
+            ReservoirConfig::SimpleFixedSize(2),
+            ReservoirConfig::AlignedHistogramBucket(2),
+        ] {
+            let reservoir = config.create::<u64>();
+            reservoir.offer(candidate(1, 0));
+            reservoir.offer(candidate(2, 1));
+            assert_eq!(collect(&reservoir).len(), 2, "{config:?}");
+            assert!(collect(&reservoir).is_empty(), "{config:?}");
+
+            reservoir.offer(candidate(3, 1));
+            let taken = reservoir.take();
+            assert!(collect(&reservoir).is_empty(), "{config:?}");
+            assert_eq!(collect(&taken), vec![3], "{config:?}");
+        }
+    }
+
+    #[test]
+    fn candidate_records_filtered_attributes() {
+        let attrs = [
+            KeyValue::new("a", 1),
+            KeyValue::new("b", 2),
+            KeyValue::new("c", 3),
+        ];
+        let filtered = [attrs[0].clone(), attrs[2].clone()];
+        let exemplar = ExemplarConfig::from(ExemplarFilter::AlwaysOn)
+            .sample(1u64, &attrs, &filtered, 0)
+            .unwrap()
+            .into_exemplar();
+        assert_eq!(exemplar.filtered_attributes, vec![KeyValue::new("b", 2)]);
+        assert_eq!(exemplar.trace_id, [0; 16]);
+        assert_eq!(exemplar.span_id, [0; 8]);
+        assert_eq!(
+            dropped_attributes(&attrs, &[]),
+            attrs.to_vec(),
+            "all attributes dropped"
+        );
+    }
+
+    #[test]
+    fn candidate_respects_filter() {
+        assert!(sample(ExemplarFilter::AlwaysOff, &[]).is_none());
+        // no span is active
+        assert!(sample(ExemplarFilter::TraceBased, &[]).is_none());
+        assert!(sample(ExemplarFilter::AlwaysOn, &[]).is_some());
+    }
+
+    #[test]
+    fn view_selects_reservoir() {
+        let default = ReservoirConfig::AlignedHistogramBucket(5);
+        let config = |filter, reservoir| ExemplarConfig::new(filter, reservoir).reservoir(default);
+
+        assert_eq!(config(ExemplarFilter::AlwaysOff, None), None);
+        assert_eq!(
+            config(
+                ExemplarFilter::AlwaysOff,
+                Some(ExemplarReservoir::SimpleFixedSize(3))
+            ),
+            None
+        );
+        assert_eq!(config(ExemplarFilter::TraceBased, None), Some(default));
+        assert_eq!(
+            config(
+                ExemplarFilter::TraceBased,
+                Some(ExemplarReservoir::SimpleFixedSize(3))
+            ),
+            Some(ReservoirConfig::SimpleFixedSize(3))
+        );
+        assert_eq!(
+            config(
+                ExemplarFilter::AlwaysOn,
+                Some(ExemplarReservoir::AlignedHistogramBucket)
+            ),
+            Some(default)
+        );
+        assert_eq!(
+            ExemplarConfig::new(
+                ExemplarFilter::AlwaysOn,
+                Some(ExemplarReservoir::AlignedHistogramBucket)
+            )
+            .reservoir(ReservoirConfig::SimpleFixedSize(1)),
+            Some(ReservoirConfig::AlignedHistogramBucket(1))
+        );
+        assert_eq!(
+            ReservoirConfig::exponential(160),
+            ReservoirConfig::SimpleFixedSize(20)
+        );
+    }
+
+    #[cfg(feature = "trace")]
+    #[test]
+    fn candidate_captures_sampled_span() {
+        use opentelemetry::trace::{
+            SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState,
+        };
+        use opentelemetry::Context;
+
+        let span_context = |flags| {
+            SpanContext::new(
+                TraceId::from(0x0102),
+                SpanId::from(0x0304),
+                flags,
+                false,
+                TraceState::default(),
+            )
+        };
+
+        let _guard = Context::new()
+            .with_remote_span_context(span_context(TraceFlags::SAMPLED))
+            .attach();
+        let exemplar = sample(ExemplarFilter::TraceBased, &[])
+            .unwrap()
+            .into_exemplar();
+        assert_eq!(exemplar.trace_id, TraceId::from(0x0102).to_bytes());
+        assert_eq!(exemplar.span_id, SpanId::from(0x0304).to_bytes());
+        let exemplar = sample(ExemplarFilter::AlwaysOn, &[])
+            .unwrap()
+            .into_exemplar();
+        assert_eq!(exemplar.trace_id, TraceId::from(0x0102).to_bytes());
+
+        let _guard = Context::new()
+            .with_remote_span_context(span_context(TraceFlags::default()))
+            .attach();
+        assert!(sample(ExemplarFilter::TraceBased, &[]).is_none());
+        let exemplar = sample(ExemplarFilter::AlwaysOn, &[])
+            .unwrap()
+            .into_exemplar();
+        assert_eq!(exemplar.trace_id, [0; 16]);
+    }
+}
@@ -10,6 +10,7 @@ use crate::metrics::{
 
 use super::{
     aggregate::{AggregateTimeInitiator, AttributeSetFilter},
+    exemplar::{ExemplarConfig, ReservoirConfig, WithExemplars},
     Aggregator, ComputeAggregation, Measure, Number, ValueMap,
 };
 
@@ -319,7 +320,7 @@ where
 {
     type InitConfig = BucketConfig;
 
-    type PreComputedValue = T;
+    type PreComputedValue<'a> = T;
 
     fn create(init: &BucketConfig) -> Self {
         Mutex::new(ExpoHistogramDataPoint::new(init))
@@ -352,32 +353,43 @@ struct BucketConfig {
 /// Each histogram is scoped by attributes and the aggregation cycle the
 /// measurements were made in.
 pub(crate) struct ExpoHistogram<T: Number> {
-    value_map: ValueMap<Mutex<ExpoHistogramDataPoint<T>>>,
+    value_map: ValueMap<WithExemplars<Mutex<ExpoHistogramDataPoint<T>>, T>>,
     init_time: AggregateTimeInitiator,
     temporality: Temporality,
     filter: AttributeSetFilter,
+    exemplars: ExemplarConfig,
     record_sum: bool,
     record_min_max: bool,
 }
 
 impl<T: Number> ExpoHistogram<T> {
     /// Create a new exponential histogram.
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn new(
         temporality: Temporality,
         filter: AttributeSetFilter,
+        exemplars: ExemplarConfig,
         max_size: u32,
         max_scale: i8,
         record_min_max: bool,
         record_sum: bool,
+        cardinality_limit: usize,
     ) -> Self {
         ExpoHistogram {
-            value_map: ValueMap::new(BucketConfig {
-                max_size: max_size as i32,
-                max_scale,
-            }),
+            value_map: ValueMap::new(
+                (
+                    BucketConfig {
+                        max_size: max_size as i32,
+                        max_scale,
+                    },
+                    exemplars.reservoir(ReservoirConfig::exponential(max_size)),
+                ),
+                cardinality_limit,
+            ),
             init_time: AggregateTimeInitiator::default(),
             temporality,
             filter,
+            exemplars,
             record_sum,
             record_min_max,
         }
@@ -404,7 +416,11 @@ impl<T: Number> ExpoHistogram<T> {
 
         self.value_map
             .collect_and_reset(&mut h.data_points, |attributes, attr| {
-                let b = attr.into_inner().unwrap_or_else(|err| err.into_inner());
+                let exemplars = attr.collect_exemplars();
+                let b = attr
+                    .aggregator
+                    .into_inner()
+                    .unwrap_or_else(|err| err.into_inner());
                 data::ExponentialHistogramDataPoint {
                     attributes,
                     count: b.count,
@@ -430,7 +446,7 @@ impl<T: Number> ExpoHistogram<T> {
                         counts: b.neg_buckets.counts,
                     },
                     zero_threshold: 0.0,
-                    exemplars: vec![],
+                    exemplars,
                 }
             });
 
@@ -461,7 +477,10 @@ impl<T: Number> ExpoHistogram<T> {
 
         self.value_map
             .collect_readonly(&mut h.data_points, |attributes, attr| {
-                let b = attr.lock().unwrap_or_else(|err| err.into_inner());
+                let b = attr
+                    .aggregator
+                    .lock()
+                    .unwrap_or_else(|err| err.into_inner());
                 data::ExponentialHistogramDataPoint {
                     attributes,
                     count: b.count,
@@ -487,7 +506,7 @@ impl<T: Number> ExpoHistogram<T> {
                         counts: b.neg_buckets.counts.clone(),
                     },
                     zero_threshold: 0.0,
-                    exemplars: vec![],
+                    exemplars: attr.collect_exemplars(),
                 }
             });
 
@@ -508,7 +527,8 @@ where
         }
 
         self.filter.apply(attrs, |filtered| {
-            self.value_map.measure(measurement, filtered);
+            let exemplar = self.exemplars.sample(measurement, attrs, filtered, 0);
+            self.value_map.measure((measurement, exemplar), filtered);
         })
     }
 }
@@ -531,7 +551,8 @@ mod tests {
     use std::ops::Neg;
     use tests::internal::AggregateFns;
 
-    use crate::metrics::internal::{self, AggregateBuilder};
+    use crate::metrics::internal::{self, AggregateBuilder, STREAM_CARDINALITY_LIMIT};
+    use crate::metrics::ExemplarFilter;
 
     use super::*;
 
@@ -695,15 +716,17 @@ mod tests {
             let h = ExpoHistogram::new(
                 Temporality::Cumulative,
                 AttributeSetFilter::new(None),
+                ExemplarFilter::AlwaysOff.into(),
                 4,
                 20,
                 true,
                 true,
+                STREAM_CARDINALITY_LIMIT,
             );
             for v in test.values {
                 Measure::call(&h, v, &[]);
             }
-            let dp = h.value_map.no_attribute_tracker.lock().unwrap();
+            let dp = h.value_map.no_attribute_tracker.aggregator.lock().unwrap();
 
             assert_eq!(test.expected.max, dp.max);
             assert_eq!(test.expected.min, dp.min);
@@ -751,15 +774,17 @@ mod tests {
             let h = ExpoHistogram::new(
                 Temporality::Cumulative,
                 AttributeSetFilter::new(None),
+                ExemplarFilter::AlwaysOff.into(),
                 4,
                 20,
                 true,
                 true,
+                STREAM_CARDINALITY_LIMIT,
             );
             for v in test.values {
                 Measure::call(&h, v, &[]);
             }
-            let dp = h.value_map.no_attribute_tracker.lock().unwrap();
+            let dp = h.value_map.no_attribute_tracker.aggregator.lock().unwrap();
 
             assert_eq!(test.expected.max, dp.max);
             assert_eq!(test.expected.min, dp.min);
@@ -1267,7 +1292,13 @@ mod tests {
             TestCase {
                 name: "Delta Single",
                 build: Box::new(move || {
-                    AggregateBuilder::new(Temporality::Delta, None).exponential_bucket_histogram(
+                    AggregateBuilder::new(
+                        Temporality::Delta,
+                        None,
+                        ExemplarFilter::AlwaysOff.into(),
+                        STREAM_CARDINALITY_LIMIT,
+                    )
+                    .exponential_bucket_histogram(
                         max_size,
                         max_scale,
                         record_min_max,
@@ -1307,13 +1338,18 @@ mod tests {
             TestCase {
                 name: "Cumulative Single",
                 build: Box::new(move || {
-                    internal::AggregateBuilder::new(Temporality::Cumulative, None)
-                        .exponential_bucket_histogram(
-                            max_size,
-                            max_scale,
-                            record_min_max,
-                            record_sum,
-                        )
+                    internal::AggregateBuilder::new(
+                        Temporality::Cumulative,
+                        None,
+                        ExemplarFilter::AlwaysOff.into(),
+                        STREAM_CARDINALITY_LIMIT,
+                    )
+                    .exponential_bucket_histogram(
+                        max_size,
+                        max_scale,
+                        record_min_max,
+                        record_sum,
+                    )
                 }),
                 input: vec![vec![4, 4, 4, 2, 16, 1]
                     .into_iter()
@@ -1348,13 +1384,18 @@ mod tests {
             TestCase {
                 name: "Delta Multiple",
                 build: Box::new(move || {
-                    internal::AggregateBuilder::new(Temporality::Delta, None)
-                        .exponential_bucket_histogram(
-                            max_size,
-                            max_scale,
-                            record_min_max,
-                            record_sum,
-                        )
+                    internal::AggregateBuilder::new(
+                        Temporality::Delta,
+                        None,
+                        ExemplarFilter::AlwaysOff.into(),
+                        STREAM_CARDINALITY_LIMIT,
+                    )
+                    .exponential_bucket_histogram(
+                        max_size,
+                        max_scale,
+                        record_min_max,
+                        record_sum,
+                    )
                 }),
                 input: vec![
                     vec![2, 3, 8].into_iter().map(Into::into).collect(),
@@ -1392,13 +1433,18 @@ mod tests {
             TestCase {
                 name: "Cumulative Multiple ",
                 build: Box::new(move || {
-                    internal::AggregateBuilder::new(Temporality::Cumulative, None)
-                        .exponential_bucket_histogram(
-                            max_size,
-                            max_scale,
-                            record_min_max,
-                            record_sum,
-                        )
+                    internal::AggregateBuilder::new(
+                        Temporality::Cumulative,
+                        None,
+                        ExemplarFilter::AlwaysOff.into(),
+                        STREAM_CARDINALITY_LIMIT,
+                    )
+                    .exponential_bucket_histogram(
+                        max_size,
+                        max_scale,
+                        record_min_max,
+                        record_sum,
+                    )
                 }),
                 input: vec![
                     vec![2, 3, 8].into_iter().map(Into::into).collect(),
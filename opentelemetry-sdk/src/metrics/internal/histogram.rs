@@ -9,6 +9,7 @@ use opentelemetry::KeyValue;
 
 use super::aggregate::AggregateTimeInitiator;
 use super::aggregate::AttributeSetFilter;
+use super::exemplar::{ExemplarConfig, ReservoirConfig, WithExemplars};
 use super::ComputeAggregation;
 use super::Measure;
 use super::ValueMap;
@@ -20,7 +21,7 @@ where
 {
     type InitConfig = usize;
     /// Value and bucket index
-    type PreComputedValue = (T, usize);
+    type PreComputedValue<'a> = (T, usize);
 
     fn update(&self, (value, index): (T, usize)) {
         let mut buckets = self.lock().unwrap_or_else(|err| err.into_inner());
@@ -70,10 +71,11 @@ impl<T: Number> Buckets<T> {
 /// Summarizes a set of measurements as a histogram with explicitly defined
 /// buckets.
 pub(crate) struct Histogram<T: Number> {
-    value_map: ValueMap<Mutex<Buckets<T>>>,
+    value_map: ValueMap<WithExemplars<Mutex<Buckets<T>>, T>>,
     init_time: AggregateTimeInitiator,
     temporality: Temporality,
     filter: AttributeSetFilter,
+    exemplars: ExemplarConfig,
     bounds: Vec<f64>,
     record_min_max: bool,
     record_sum: bool,
@@ -84,9 +86,11 @@ impl<T: Number> Histogram<T> {
     pub(crate) fn new(
         temporality: Temporality,
         filter: AttributeSetFilter,
+        exemplars: ExemplarConfig,
         mut bounds: Vec<f64>,
         record_min_max: bool,
         record_sum: bool,
+        cardinality_limit: usize,
     ) -> Self {
         #[cfg(feature = "spec_unstable_metrics_views")]
         {
@@ -97,10 +101,17 @@ impl<T: Number> Histogram<T> {
 
         let buckets_count = bounds.len() + 1;
         Histogram {
-            value_map: ValueMap::new(buckets_count),
+            value_map: ValueMap::new(
+                (
+                    buckets_count,
+                    exemplars.reservoir(ReservoirConfig::AlignedHistogramBucket(buckets_count)),
+                ),
+                cardinality_limit,
+            ),
             init_time: AggregateTimeInitiator::default(),
             temporality,
             filter,
+            exemplars,
             bounds,
             record_min_max,
             record_sum,
@@ -128,7 +139,11 @@ impl<T: Number> Histogram<T> {
 
         self.value_map
             .collect_and_reset(&mut h.data_points, |attributes, aggr| {
-                let b = aggr.into_inner().unwrap_or_else(|err| err.into_inner());
+                let exemplars = aggr.collect_exemplars();
+                let b = aggr
+                    .aggregator
+                    .into_inner()
+                    .unwrap_or_else(|err| err.into_inner());
                 HistogramDataPoint {
                     attributes,
                     count: b.count,
@@ -149,7 +164,7 @@ impl<T: Number> Histogram<T> {
                     } else {
                         None
                     },
-                    exemplars: vec![],
+                    exemplars,
                 }
             });
 
@@ -179,7 +194,10 @@ impl<T: Number> Histogram<T> {
 
         self.value_map
             .collect_readonly(&mut h.data_points, |attributes, aggr| {
-                let b = aggr.lock().unwrap_or_else(|err| err.into_inner());
+                let b = aggr
+                    .aggregator
+                    .lock()
+                    .unwrap_or_else(|err| err.into_inner());
                 HistogramDataPoint {
                     attributes,
                     count: b.count,
@@ -200,7 +218,7 @@ impl<T: Number> Histogram<T> {
                     } else {
                         None
                     },
-                    exemplars: vec![],
+                    exemplars: aggr.collect_exemplars(),
                 }
             });
 
@@ -222,7 +240,9 @@ where
         let index = self.bounds.partition_point(|&x| x < f);
 
         self.filter.apply(attrs, |filtered| {
-            self.value_map.measure((measurement, index), filtered);
+            let exemplar = self.exemplars.sample(measurement, attrs, filtered, index);
+            self.value_map
+                .measure(((measurement, index), exemplar), filtered);
         })
     }
 }
@@ -242,15 +262,19 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::metrics::internal::STREAM_CARDINALITY_LIMIT;
+    use crate::metrics::ExemplarFilter;
 
     #[test]
     fn check_buckets_are_selected_correctly() {
         let hist = Histogram::<i64>::new(
             Temporality::Cumulative,
             AttributeSetFilter::new(None),
+            ExemplarFilter::AlwaysOff.into(),
             vec![1.0, 3.0, 6.0],
             false,
             false,
+            STREAM_CARDINALITY_LIMIT,
         );
         for v in 1..11 {
             Measure::call(&hist, v, &[]);
@@ -6,6 +6,7 @@ use opentelemetry::KeyValue;
 
 use super::{
     aggregate::{AggregateTimeInitiator, AttributeSetFilter},
+    exemplar::{ExemplarConfig, ReservoirConfig, WithExemplars},
     Aggregator, AtomicTracker, AtomicallyUpdate, ComputeAggregation, Measure, Number, ValueMap,
 };
 
@@ -22,7 +23,7 @@ where
     T: Number,
 {
     type InitConfig = ();
-    type PreComputedValue = T;
+    type PreComputedValue<'a> = T;
 
     fn create(_init: &()) -> Self {
         Self {
@@ -43,19 +44,29 @@ where
 
 /// Summarizes a set of measurements as the last one made.
 pub(crate) struct LastValue<T: Number> {
-    value_map: ValueMap<Assign<T>>,
+    value_map: ValueMap<WithExemplars<Assign<T>, T>>,
     init_time: AggregateTimeInitiator,
     temporality: Temporality,
     filter: AttributeSetFilter,
+    exemplars: ExemplarConfig,
 }
 
 impl<T: Number> LastValue<T> {
-    pub(crate) fn new(temporality: Temporality, filter: AttributeSetFilter) -> Self {
+    pub(crate) fn new(
+        temporality: Temporality,
+        filter: AttributeSetFilter,
+        exemplars: ExemplarConfig,
+        cardinality_limit: usize,
+    ) -> Self {
         LastValue {
-            value_map: ValueMap::new(()),
+            value_map: ValueMap::new(
+                ((), exemplars.reservoir(ReservoirConfig::SimpleFixedSize(1))),
+                cardinality_limit,
+            ),
             init_time: AggregateTimeInitiator::default(),
             temporality,
             filter,
+            exemplars,
         }
     }
 
@@ -82,8 +93,8 @@ impl<T: Number> LastValue<T> {
         self.value_map
             .collect_and_reset(&mut s_data.data_points, |attributes, aggr| GaugeDataPoint {
                 attributes,
-                value: aggr.value.get_value(),
-                exemplars: vec![],
+                value: aggr.aggregator.value.get_value(),
+                exemplars: aggr.collect_exemplars(),
             });
 
         (
@@ -115,8 +126,8 @@ impl<T: Number> LastValue<T> {
         self.value_map
             .collect_readonly(&mut s_data.data_points, |attributes, aggr| GaugeDataPoint {
                 attributes,
-                value: aggr.value.get_value(),
-                exemplars: vec![],
+                value: aggr.aggregator.value.get_value(),
+                exemplars: aggr.collect_exemplars(),
             });
 
         (
@@ -132,7 +143,8 @@ where
 {
     fn call(&self, measurement: T, attrs: &[KeyValue]) {
         self.filter.apply(attrs, |filtered| {
-            self.value_map.measure(measurement, filtered);
+            let exemplar = self.exemplars.sample(measurement, attrs, filtered, 0);
+            self.value_map.measure((measurement, exemplar), filtered);
         })
     }
 }
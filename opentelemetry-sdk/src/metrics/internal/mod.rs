@@ -1,4 +1,5 @@
 mod aggregate;
+mod exemplar;
 mod exponential_histogram;
 mod histogram;
 mod last_value;
@@ -19,8 +20,10 @@ use portable_atomic::{AtomicBool, AtomicI64, AtomicU64, AtomicUsize};
 
 use std::sync::{Arc, OnceLock, RwLock};
 
-use aggregate::{is_under_cardinality_limit, STREAM_CARDINALITY_LIMIT};
-pub(crate) use aggregate::{AggregateBuilder, AggregateFns, ComputeAggregation, Measure};
+pub(crate) use aggregate::{
+    AggregateBuilder, AggregateFns, ComputeAggregation, Measure, STREAM_CARDINALITY_LIMIT,
+};
+pub(crate) use exemplar::ExemplarConfig;
 pub(crate) use exponential_histogram::{EXPO_MAX_SCALE, EXPO_MIN_SCALE};
 use opentelemetry::{otel_warn, KeyValue};
 
@@ -40,13 +43,13 @@ pub(crate) trait Aggregator {
     /// Some aggregators can do some computations before updating aggregator.
     /// This helps to reduce contention for aggregators because it makes
     /// [`Aggregator::update`] as short as possible.
-    type PreComputedValue;
+    type PreComputedValue<'a>;
 
     /// Called everytime a new attribute-set is stored.
     fn create(init: &Self::InitConfig) -> Self;
 
     /// Called for each measurement.
-    fn update(&self, value: Self::PreComputedValue);
+    fn update(&self, value: Self::PreComputedValue<'_>);
 
     /// Return current value and reset this instance
     fn clone_and_reset(&self, init: &Self::InitConfig) -> Self;
@@ -76,30 +79,37 @@ where
     no_attribute_tracker: A,
     /// Configuration for an Aggregator
     config: A::InitConfig,
+    /// Maximum number of attribute sets tracked, beyond which measurements go
+    /// to the overflow attribute set.
+    cardinality_limit: usize,
 }
 
 impl<A> ValueMap<A>
 where
     A: Aggregator,
 {
-    fn new(config: A::InitConfig) -> Self {
+    fn new(config: A::InitConfig, cardinality_limit: usize) -> Self {
         ValueMap {
-            trackers: RwLock::new(HashMap::with_capacity(1 + STREAM_CARDINALITY_LIMIT)),
+            trackers: RwLock::new(HashMap::with_capacity(initial_capacity(cardinality_limit))),
             trackers_for_collect: OnceLock::new(),
             has_no_attribute_value: AtomicBool::new(false),
             no_attribute_tracker: A::create(&config),
             count: AtomicUsize::new(0),
             config,
+            cardinality_limit,
         }
     }
 
     #[inline]
     fn trackers_for_collect(&self) -> &RwLock<HashMap<Vec<KeyValue>, Arc<A>>> {
-        self.trackers_for_collect
-            .get_or_init(|| RwLock::new(HashMap::with_capacity(1 + STREAM_CARDINALITY_LIMIT)))
+        self.trackers_for_collect.get_or_init(|| {
+            RwLock::new(HashMap::with_capacity(initial_capacity(
+                self.cardinality_limit,
+            )))
+        })
     }
 
-    fn measure(&self, value: A::PreComputedValue, attributes: &[KeyValue]) {
+    fn measure(&self, value: A::PreComputedValue<'_>, attributes: &[KeyValue]) {
         if attributes.is_empty() {
             self.no_attribute_tracker.update(value);
             self.has_no_attribute_value.store(true, Ordering::Release);
@@ -136,7 +146,7 @@ where
             tracker.update(value);
         } else if let Some(tracker) = trackers.get(sorted_attrs.as_slice()) {
             tracker.update(value);
-        } else if is_under_cardinality_limit(self.count.load(Ordering::SeqCst)) {
+        } else if self.count.load(Ordering::SeqCst) < self.cardinality_limit {
             let new_tracker = Arc::new(A::create(&self.config));
             new_tracker.update(value);
 
@@ -215,6 +225,12 @@ where
     }
 }
 
+/// The number of entries the trackers map is allocated with. Streams with a
+/// large limit grow as needed instead of reserving all the memory upfront.
+fn initial_capacity(cardinality_limit: usize) -> usize {
+    1 + cardinality_limit.min(STREAM_CARDINALITY_LIMIT)
+}
+
 /// Clear and allocate exactly required amount of space for all attribute-sets
 fn prepare_data<T>(data: &mut Vec<T>, list_len: usize) {
     data.clear();
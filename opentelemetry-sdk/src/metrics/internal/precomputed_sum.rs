@@ -23,9 +23,10 @@ impl<T: Number> PrecomputedSum<T> {
         temporality: Temporality,
         filter: AttributeSetFilter,
         monotonic: bool,
+        cardinality_limit: usize,
     ) -> Self {
         PrecomputedSum {
-            value_map: ValueMap::new(()),
+            value_map: ValueMap::new((), cardinality_limit),
             init_time: AggregateTimeInitiator::default(),
             temporality,
             filter,
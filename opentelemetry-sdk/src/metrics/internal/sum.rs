@@ -3,6 +3,7 @@ use crate::metrics::Temporality;
 use opentelemetry::KeyValue;
 
 use super::aggregate::{AggregateTimeInitiator, AttributeSetFilter};
+use super::exemplar::{ExemplarConfig, ReservoirConfig, WithExemplars};
 use super::{Aggregator, AtomicTracker, ComputeAggregation, Measure, Number};
 use super::{AtomicallyUpdate, ValueMap};
 
@@ -18,7 +19,7 @@ where
     T: Number,
 {
     type InitConfig = ();
-    type PreComputedValue = T;
+    type PreComputedValue<'a> = T;
 
     fn create(_init: &()) -> Self {
         Self {
@@ -39,10 +40,11 @@ where
 
 /// Summarizes a set of measurements made as their arithmetic sum.
 pub(crate) struct Sum<T: Number> {
-    value_map: ValueMap<Increment<T>>,
+    value_map: ValueMap<WithExemplars<Increment<T>, T>>,
     init_time: AggregateTimeInitiator,
     temporality: Temporality,
     filter: AttributeSetFilter,
+    exemplars: ExemplarConfig,
     monotonic: bool,
 }
 
@@ -55,13 +57,19 @@ impl<T: Number> Sum<T> {
     pub(crate) fn new(
         temporality: Temporality,
         filter: AttributeSetFilter,
+        exemplars: ExemplarConfig,
         monotonic: bool,
+        cardinality_limit: usize,
     ) -> Self {
         Sum {
-            value_map: ValueMap::new(()),
+            value_map: ValueMap::new(
+                ((), exemplars.reservoir(ReservoirConfig::SimpleFixedSize(1))),
+                cardinality_limit,
+            ),
             init_time: AggregateTimeInitiator::default(),
             temporality,
             filter,
+            exemplars,
             monotonic,
         }
     }
@@ -92,8 +100,8 @@ impl<T: Number> Sum<T> {
         self.value_map
             .collect_and_reset(&mut s_data.data_points, |attributes, aggr| SumDataPoint {
                 attributes,
-                value: aggr.value.get_value(),
-                exemplars: vec![],
+                value: aggr.aggregator.value.get_value(),
+                exemplars: aggr.collect_exemplars(),
             });
 
         (
@@ -129,8 +137,8 @@ impl<T: Number> Sum<T> {
         self.value_map
             .collect_readonly(&mut s_data.data_points, |attributes, aggr| SumDataPoint {
                 attributes,
-                value: aggr.value.get_value(),
-                exemplars: vec![],
+                value: aggr.aggregator.value.get_value(),
+                exemplars: aggr.collect_exemplars(),
             });
 
         (
@@ -146,7 +154,8 @@ where
 {
     fn call(&self, measurement: T, attrs: &[KeyValue]) {
         self.filter.apply(attrs, |filtered| {
-            self.value_map.measure(measurement, filtered);
+            let exemplar = self.exemplars.sample(measurement, attrs, filtered, 0);
+            self.value_map.measure((measurement, exemplar), filtered);
         })
     }
 }
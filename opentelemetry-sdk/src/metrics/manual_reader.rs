@@ -32,6 +32,7 @@ use super::{
 pub struct ManualReader {
     inner: Mutex<ManualReaderInner>,
     temporality: Temporality,
+    cardinality_limit: Option<usize>,
 }
 
 impl Default for ManualReader {
@@ -59,13 +60,14 @@ impl ManualReader {
     }
 
     /// A [MetricReader] which is directly called to collect metrics.
-    pub(crate) fn new(temporality: Temporality) -> Self {
+    pub(crate) fn new(temporality: Temporality, cardinality_limit: Option<usize>) -> Self {
         ManualReader {
             inner: Mutex::new(ManualReaderInner {
                 sdk_producer: None,
                 is_shutdown: false,
             }),
             temporality,
+            cardinality_limit,
         }
     }
 }
@@ -126,12 +128,17 @@ impl MetricReader for ManualReader {
     fn temporality(&self, kind: super::InstrumentKind) -> Temporality {
         kind.temporality_preference(self.temporality)
     }
+
+    fn cardinality_limit(&self, _kind: super::InstrumentKind) -> Option<usize> {
+        self.cardinality_limit
+    }
 }
 
 /// Configuration for a [ManualReader]
 #[derive(Default)]
 pub struct ManualReaderBuilder {
     temporality: Temporality,
+    cardinality_limit: Option<usize>,
 }
 
 impl fmt::Debug for ManualReaderBuilder {
@@ -152,8 +159,17 @@ impl ManualReaderBuilder {
         self
     }
 
+    /// Set the cardinality limit of the metric streams read by the [ManualReader].
+    ///
+    /// This overrides the default limit of the meter provider, but not the limits
+    /// configured by views.
+    pub fn with_cardinality_limit(mut self, limit: usize) -> Self {
+        self.cardinality_limit = Some(limit);
+        self
+    }
+
     /// Create a new [ManualReader] from this configuration.
     pub fn build(self) -> ManualReader {
-        ManualReader::new(self.temporality)
+        ManualReader::new(self.temporality, self.cardinality_limit)
     }
 }
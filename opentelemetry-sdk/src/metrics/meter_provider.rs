@@ -16,8 +16,8 @@ use crate::error::OTelSdkResult;
 use crate::Resource;
 
 use super::{
-    exporter::PushMetricExporter, meter::SdkMeter, noop::NoopMeter, pipeline::Pipelines,
-    reader::MetricReader, view::View, PeriodicReader,
+    exemplar::ExemplarFilter, exporter::PushMetricExporter, internal, meter::SdkMeter,
+    noop::NoopMeter, pipeline::Pipelines, reader::MetricReader, view::View, PeriodicReader,
 };
 
 /// Handles the creation and coordination of [Meter]s.
@@ -223,6 +223,8 @@ pub struct MeterProviderBuilder {
     resource: Option<Resource>,
     readers: Vec<Box<dyn MetricReader>>,
     views: Vec<Arc<dyn View>>,
+    exemplar_filter: Option<ExemplarFilter>,
+    cardinality_limit: Option<usize>,
 }
 
 impl MeterProviderBuilder {
@@ -284,6 +286,31 @@ impl MeterProviderBuilder {
         self
     }
 
+    /// Sets the [ExemplarFilter] deciding which measurements of the [MeterProvider]'s
+    /// synchronous instruments may be sampled as exemplars.
+    ///
+    /// By default, if this option is not used, the filter is read from the
+    /// `OTEL_METRICS_EXEMPLAR_FILTER` environment variable (`always_on`,
+    /// `always_off` or `trace_based`), and falls back to
+    /// [ExemplarFilter::TraceBased].
+    pub fn with_exemplar_filter(mut self, filter: ExemplarFilter) -> Self {
+        self.exemplar_filter = Some(filter);
+        self
+    }
+
+    /// Sets the default cardinality limit of the metric streams of the [MeterProvider].
+    ///
+    /// Once a metric stream has seen `limit` distinct attribute sets, measurements
+    /// with new attribute sets are aggregated into a single data point with the
+    /// `otel.metric.overflow=true` attribute. Limits configured on a
+    /// [MetricReader] or a [View] take precedence over this one.
+    ///
+    /// By default, if this option is not used, the limit is 2000.
+    pub fn with_cardinality_limit(mut self, limit: usize) -> Self {
+        self.cardinality_limit = Some(limit);
+        self
+    }
+
     /// Construct a new [MeterProvider] with this configuration.
     pub fn build(self) -> SdkMeterProvider {
         otel_debug!(
@@ -297,6 +324,10 @@ impl MeterProviderBuilder {
                     self.resource.unwrap_or(Resource::builder().build()),
                     self.readers,
                     self.views,
+                    self.exemplar_filter
+                        .unwrap_or_else(ExemplarFilter::from_env),
+                    self.cardinality_limit
+                        .unwrap_or(internal::STREAM_CARDINALITY_LIMIT),
                 )),
                 meters: Default::default(),
                 shutdown_invoked: AtomicBool::new(false),
@@ -316,6 +347,8 @@ impl fmt::Debug for MeterProviderBuilder {
             .field("resource", &self.resource)
             .field("readers", &self.readers)
             .field("views", &self.views.len())
+            .field("exemplar_filter", &self.exemplar_filter)
+            .field("cardinality_limit", &self.cardinality_limit)
             .finish()
     }
 }
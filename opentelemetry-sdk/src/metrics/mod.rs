@@ -42,7 +42,9 @@
 pub(crate) mod aggregation;
 pub mod data;
 mod error;
+mod exemplar;
 pub mod exporter;
+mod fanout;
 pub(crate) mod instrument;
 pub(crate) mod internal;
 pub(crate) mod manual_reader;
@@ -67,6 +69,8 @@ pub use in_memory_exporter::{InMemoryMetricExporter, InMemoryMetricExporterBuild
 
 pub use aggregation::*;
 pub use error::{MetricError, MetricResult};
+pub use exemplar::{ExemplarFilter, ExemplarReservoir};
+pub use fanout::{FanOutMetricExporter, FanOutMetricExporterBuilder};
 pub use manual_reader::*;
 pub use meter_provider::*;
 pub use periodic_reader::*;
@@ -122,6 +126,9 @@ mod tests {
     use data::Histogram;
     use data::Sum;
     use opentelemetry::metrics::{Counter, Meter, UpDownCounter};
+    use opentelemetry::trace::{
+        SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState,
+    };
     use opentelemetry::InstrumentationScope;
     use opentelemetry::{metrics::MeterProvider as _, KeyValue};
     use rand::{rngs, Rng, SeedableRng};
@@ -363,18 +370,67 @@ mod tests {
         assert_eq!(data_point.value, 50, "Unexpected data point value");
     }
 
-    #[ignore = "https://github.com/open-telemetry/opentelemetry-rust/issues/1065"]
     #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
     async fn counter_aggregation_overflow_delta() {
         counter_aggregation_overflow_helper(Temporality::Delta);
     }
 
-    #[ignore = "https://github.com/open-telemetry/opentelemetry-rust/issues/1065"]
     #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
     async fn counter_aggregation_overflow_cumulative() {
         counter_aggregation_overflow_helper(Temporality::Cumulative);
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn cardinality_limit_from_meter_provider() {
+        // cargo test cardinality_limit_from_meter_provider --features=testing
+        let exporter = InMemoryMetricExporter::default();
+        let meter_provider = SdkMeterProvider::builder()
+            .with_reader(PeriodicReader::builder(exporter.clone()).build())
+            .with_cardinality_limit(5)
+            .build();
+
+        cardinality_limit_helper(&meter_provider, &exporter, 5);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn cardinality_limit_from_reader() {
+        // cargo test cardinality_limit_from_reader --features=testing
+        let exporter = InMemoryMetricExporter::default();
+        let meter_provider = SdkMeterProvider::builder()
+            .with_reader(
+                PeriodicReader::builder(exporter.clone())
+                    .with_cardinality_limit(3)
+                    .build(),
+            )
+            .with_cardinality_limit(5)
+            .build();
+
+        // The reader limit takes precedence over the provider default.
+        cardinality_limit_helper(&meter_provider, &exporter, 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn cardinality_limit_from_view() {
+        // cargo test cardinality_limit_from_view --features=testing
+        let exporter = InMemoryMetricExporter::default();
+        let view = new_view(
+            Instrument::new().name("my_counter"),
+            Stream::new().cardinality_limit(4),
+        )
+        .expect("Expected to create a new view");
+        let meter_provider = SdkMeterProvider::builder()
+            .with_reader(
+                PeriodicReader::builder(exporter.clone())
+                    .with_cardinality_limit(3)
+                    .build(),
+            )
+            .with_view(view)
+            .build();
+
+        // The view limit takes precedence over the reader limit.
+        cardinality_limit_helper(&meter_provider, &exporter, 4);
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
     async fn counter_aggregation_attribute_order_sorted_first_delta() {
         // Run this test with stdout enabled to see output.
@@ -1539,6 +1595,186 @@ mod tests {
         }
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn counter_exemplars_trace_based() {
+        // cargo test counter_exemplars_trace_based --features=testing
+        for temporality in [Temporality::Delta, Temporality::Cumulative] {
+            let mut test_context =
+                TestContext::new_with_exemplar_filter(temporality, ExemplarFilter::TraceBased);
+            let counter = test_context.u64_counter("test", "my_counter", None);
+
+            // Not sampled, as no span is active.
+            counter.add(1, &[KeyValue::new("key1", "value1")]);
+            {
+                let _guard = sampled_span_context().attach();
+                counter.add(5, &[KeyValue::new("key1", "value1")]);
+            }
+            counter.add(1, &[KeyValue::new("key1", "value1")]);
+
+            test_context.flush_metrics();
+
+            let sum = test_context.get_aggregation::<Sum<u64>>("my_counter", None);
+            let data_point = find_sum_datapoint_with_key_value(&sum.data_points, "key1", "value1")
+                .expect("datapoint with key1=value1 expected");
+            assert_eq!(data_point.value, 7);
+            assert_eq!(data_point.exemplars.len(), 1);
+            let exemplar = &data_point.exemplars[0];
+            assert_eq!(exemplar.value, 5);
+            assert_eq!(exemplar.trace_id, TraceId::from(42).to_bytes());
+            assert_eq!(exemplar.span_id, SpanId::from(7).to_bytes());
+            assert!(exemplar.filtered_attributes.is_empty());
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn counter_exemplars_always_off() {
+        // cargo test counter_exemplars_always_off --features=testing
+        let mut test_context = TestContext::new_with_exemplar_filter(
+            Temporality::Cumulative,
+            ExemplarFilter::AlwaysOff,
+        );
+        let counter = test_context.u64_counter("test", "my_counter", None);
+
+        {
+            let _guard = sampled_span_context().attach();
+            counter.add(5, &[]);
+        }
+
+        test_context.flush_metrics();
+
+        let sum = test_context.get_aggregation::<Sum<u64>>("my_counter", None);
+        assert_eq!(sum.data_points[0].value, 5);
+        assert!(sum.data_points[0].exemplars.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn histogram_exemplars_aligned_with_buckets() {
+        // cargo test histogram_exemplars_aligned_with_buckets --features=testing
+        let mut test_context =
+            TestContext::new_with_exemplar_filter(Temporality::Delta, ExemplarFilter::AlwaysOn);
+        let histogram = test_context
+            .meter()
+            .u64_histogram("my_histogram")
+            .with_boundaries(vec![10.0, 100.0])
+            .build();
+
+        for value in [1, 2, 3, 50, 500, 600] {
+            histogram.record(value, &[]);
+        }
+
+        test_context.flush_metrics();
+
+        let histogram_data = test_context.get_aggregation::<Histogram<u64>>("my_histogram", None);
+        let data_point = &histogram_data.data_points[0];
+        assert_eq!(data_point.count, 6);
+        // One exemplar per bucket that received a measurement.
+        let mut values = data_point
+            .exemplars
+            .iter()
+            .map(|exemplar| exemplar.value)
+            .collect::<Vec<_>>();
+        values.sort();
+        assert_eq!(values.len(), 3);
+        assert!(values[0] <= 3);
+        assert_eq!(values[1], 50);
+        assert!(values[2] >= 500);
+        // No span was active.
+        assert!(data_point
+            .exemplars
+            .iter()
+            .all(|exemplar| exemplar.trace_id == [0; 16]));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn observable_counter_has_no_exemplars() {
+        // cargo test observable_counter_has_no_exemplars --features=testing
+        let mut test_context = TestContext::new_with_exemplar_filter(
+            Temporality::Cumulative,
+            ExemplarFilter::AlwaysOn,
+        );
+        let _observable_counter = test_context
+            .meter()
+            .u64_observable_counter("my_observable_counter")
+            .with_callback(|observer| observer.observe(10, &[]))
+            .build();
+
+        test_context.flush_metrics();
+
+        let sum = test_context.get_aggregation::<Sum<u64>>("my_observable_counter", None);
+        assert_eq!(sum.data_points[0].value, 10);
+        assert!(sum.data_points[0].exemplars.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn counter_exemplars_not_reexported() {
+        // cargo test counter_exemplars_not_reexported --features=testing
+        let mut test_context = TestContext::new_with_exemplar_filter(
+            Temporality::Cumulative,
+            ExemplarFilter::AlwaysOn,
+        );
+        let counter = test_context.u64_counter("test", "my_counter", None);
+
+        counter.add(5, &[]);
+        test_context.flush_metrics();
+        let sum = test_context.get_aggregation::<Sum<u64>>("my_counter", None);
+        assert_eq!(sum.data_points[0].exemplars.len(), 1);
+
+        // The cumulative sum is exported again, but its exemplar is not.
+        test_context.reset_metrics();
+        test_context.flush_metrics();
+        let sum = test_context.get_aggregation::<Sum<u64>>("my_counter", None);
+        assert_eq!(sum.data_points[0].value, 5);
+        assert!(sum.data_points[0].exemplars.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn exemplar_reservoir_from_view() {
+        // cargo test exemplar_reservoir_from_view --features=testing
+        let exporter = InMemoryMetricExporter::default();
+        let view = new_view(
+            Instrument::new().name("my_histogram"),
+            Stream::new().exemplar_reservoir(ExemplarReservoir::SimpleFixedSize(2)),
+        )
+        .expect("Expected to create a new view");
+        let meter_provider = SdkMeterProvider::builder()
+            .with_reader(PeriodicReader::builder(exporter.clone()).build())
+            .with_exemplar_filter(ExemplarFilter::AlwaysOn)
+            .with_view(view)
+            .build();
+        let histogram = meter_provider
+            .meter("test")
+            .u64_histogram("my_histogram")
+            .with_boundaries(vec![10.0, 100.0])
+            .build();
+
+        for value in [1, 50, 500, 600] {
+            histogram.record(value, &[]);
+        }
+        meter_provider.force_flush().unwrap();
+
+        let resource_metrics = exporter
+            .get_finished_metrics()
+            .expect("metrics are expected to be exported.");
+        let metric = &resource_metrics[0].scope_metrics[0].metrics[0];
+        let histogram_data = metric
+            .data
+            .as_any()
+            .downcast_ref::<Histogram<u64>>()
+            .expect("Histogram aggregation expected for Histogram instruments by default");
+        // The view replaced the reservoir aligned with the three buckets.
+        assert_eq!(histogram_data.data_points[0].exemplars.len(), 2);
+    }
+
+    fn sampled_span_context() -> opentelemetry::Context {
+        opentelemetry::Context::new().with_remote_span_context(SpanContext::new(
+            TraceId::from(42),
+            SpanId::from(7),
+            TraceFlags::SAMPLED,
+            false,
+            TraceState::default(),
+        ))
+    }
+
     fn counter_multithreaded_aggregation_helper(temporality: Temporality) {
         // Arrange
         let mut test_context = TestContext::new(temporality);
@@ -2313,6 +2549,42 @@ mod tests {
         );
     }
 
+    fn cardinality_limit_helper(
+        meter_provider: &SdkMeterProvider,
+        exporter: &InMemoryMetricExporter,
+        limit: usize,
+    ) {
+        let counter = meter_provider
+            .meter("test")
+            .u64_counter("my_counter")
+            .build();
+
+        // Record one more attribute set than the limit allows, plus empty
+        // attributes which do not count towards the limit.
+        for v in 0..=limit {
+            counter.add(1, &[KeyValue::new("A", v.to_string())]);
+        }
+        counter.add(1, &[]);
+        meter_provider.force_flush().unwrap();
+
+        let resource_metrics = exporter
+            .get_finished_metrics()
+            .expect("metrics are expected to be exported.");
+        let metric = &resource_metrics[0].scope_metrics[0].metrics[0];
+        let sum = metric
+            .data
+            .as_any()
+            .downcast_ref::<Sum<u64>>()
+            .expect("Sum aggregation expected for Counter instruments by default");
+
+        // limit attribute sets + 1 overflow + empty attributes
+        assert_eq!(sum.data_points.len(), limit + 2);
+        let data_point =
+            find_sum_datapoint_with_key_value(&sum.data_points, "otel.metric.overflow", "true")
+                .expect("overflow point expected");
+        assert_eq!(data_point.value, 1);
+    }
+
     fn counter_aggregation_attribute_order_helper(temporality: Temporality, start_sorted: bool) {
         // Arrange
         let mut test_context = TestContext::new(temporality);
@@ -2542,11 +2814,21 @@ mod tests {
 
     impl TestContext {
         fn new(temporality: Temporality) -> Self {
+            Self::new_with_exemplar_filter(temporality, ExemplarFilter::default())
+        }
+
+        fn new_with_exemplar_filter(
+            temporality: Temporality,
+            exemplar_filter: ExemplarFilter,
+        ) -> Self {
             let exporter = InMemoryMetricExporterBuilder::new().with_temporality(temporality);
 
             let exporter = exporter.build();
             let reader = PeriodicReader::builder(exporter.clone()).build();
-            let meter_provider = SdkMeterProvider::builder().with_reader(reader).build();
+            let meter_provider = SdkMeterProvider::builder()
+                .with_reader(reader)
+                .with_exemplar_filter(exemplar_filter)
+                .build();
 
             TestContext {
                 exporter,
@@ -28,6 +28,7 @@ const METRIC_EXPORT_INTERVAL_NAME: &str = "OTEL_METRIC_EXPORT_INTERVAL";
 #[derive(Debug)]
 pub struct PeriodicReaderBuilder<E> {
     interval: Duration,
+    cardinality_limit: Option<usize>,
     exporter: E,
 }
 
@@ -41,7 +42,11 @@ where
             .and_then(|v| v.parse().map(Duration::from_millis).ok())
             .unwrap_or(DEFAULT_INTERVAL);
 
-        PeriodicReaderBuilder { interval, exporter }
+        PeriodicReaderBuilder {
+            interval,
+            cardinality_limit: None,
+            exporter,
+        }
     }
 
     /// Configures the intervening time between exports for a [PeriodicReader].
@@ -58,9 +63,19 @@ where
         self
     }
 
+    /// Configures the cardinality limit of the metric streams read by the
+    /// [PeriodicReader].
+    ///
+    /// This overrides the default limit of the meter provider, but not the limits
+    /// configured by views.
+    pub fn with_cardinality_limit(mut self, limit: usize) -> Self {
+        self.cardinality_limit = Some(limit);
+        self
+    }
+
     /// Create a [PeriodicReader] with the given config.
     pub fn build(self) -> PeriodicReader {
-        PeriodicReader::new(self.exporter, self.interval)
+        PeriodicReader::new(self.exporter, self.interval, self.cardinality_limit)
     }
 }
 
@@ -138,7 +153,7 @@ impl PeriodicReader {
         PeriodicReaderBuilder::new(exporter)
     }
 
-    fn new<E>(exporter: E, interval: Duration) -> Self
+    fn new<E>(exporter: E, interval: Duration, cardinality_limit: Option<usize>) -> Self
     where
         E: PushMetricExporter,
     {
@@ -150,6 +165,7 @@ impl PeriodicReader {
                 message_sender,
                 producer: Mutex::new(None),
                 exporter: exporter_arc.clone(),
+                cardinality_limit,
             }),
         };
         let cloned_reader = reader.clone();
@@ -343,6 +359,7 @@ struct PeriodicReaderInner {
     exporter: Arc<dyn PushMetricExporter>,
     message_sender: mpsc::Sender<Message>,
     producer: Mutex<Option<Weak<dyn SdkProducer>>>,
+    cardinality_limit: Option<usize>,
 }
 
 impl PeriodicReaderInner {
@@ -503,6 +520,10 @@ impl MetricReader for PeriodicReader {
     fn temporality(&self, kind: InstrumentKind) -> Temporality {
         kind.temporality_preference(self.inner.temporality(kind))
     }
+
+    fn cardinality_limit(&self, _kind: InstrumentKind) -> Option<usize> {
+        self.inner.cardinality_limit
+    }
 }
 
 #[cfg(all(test, feature = "testing"))]
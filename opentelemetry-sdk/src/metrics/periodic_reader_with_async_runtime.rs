@@ -48,6 +48,7 @@ pub struct PeriodicReaderBuilder<E, RT> {
     timeout: Duration,
     exporter: E,
     runtime: RT,
+    cardinality_limit: Option<usize>,
 }
 
 impl<E, RT> PeriodicReaderBuilder<E, RT>
@@ -70,6 +71,7 @@ where
             timeout,
             exporter,
             runtime,
+            cardinality_limit: None,
         }
     }
 
@@ -102,6 +104,16 @@ where
         self
     }
 
+    /// Configures the cardinality limit of the metric streams read by the
+    /// [PeriodicReader].
+    ///
+    /// This overrides the default limit of the meter provider, but not the limits
+    /// configured by views.
+    pub fn with_cardinality_limit(mut self, limit: usize) -> Self {
+        self.cardinality_limit = Some(limit);
+        self
+    }
+
     /// Create a [PeriodicReader] with the given config.
     pub fn build(self) -> PeriodicReader {
         let (message_sender, message_receiver) = mpsc::channel(256);
@@ -138,6 +150,7 @@ where
 
         PeriodicReader {
             exporter: Arc::new(self.exporter),
+            cardinality_limit: self.cardinality_limit,
             inner: Arc::new(Mutex::new(PeriodicReaderInner {
                 message_sender,
                 is_shutdown: false,
@@ -187,6 +200,7 @@ where
 #[derive(Clone)]
 pub struct PeriodicReader {
     exporter: Arc<dyn PushMetricExporter>,
+    cardinality_limit: Option<usize>,
     inner: Arc<Mutex<PeriodicReaderInner>>,
 }
 
@@ -422,13 +436,17 @@ impl MetricReader for PeriodicReader {
     fn temporality(&self, kind: InstrumentKind) -> super::Temporality {
         kind.temporality_preference(self.exporter.temporality())
     }
+
+    fn cardinality_limit(&self, _kind: InstrumentKind) -> Option<usize> {
+        self.cardinality_limit
+    }
 }
 
 #[cfg(all(test, feature = "testing"))]
 mod tests {
     use super::PeriodicReader;
     use crate::metrics::reader::MetricReader;
-    use crate::metrics::MetricError;
+    use crate::metrics::{InstrumentKind, MetricError};
     use crate::{
         metrics::data::ResourceMetrics, metrics::InMemoryMetricExporter, metrics::SdkMeterProvider,
         runtime, Resource,
@@ -474,6 +492,18 @@ mod tests {
         collection_triggered_by_interval_helper(runtime::TokioCurrentThread);
     }
 
+    #[test]
+    fn cardinality_limit_from_builder() {
+        let exporter = InMemoryMetricExporter::default();
+        let reader = PeriodicReader::builder(exporter.clone(), runtime::Tokio).build();
+        assert_eq!(reader.cardinality_limit(InstrumentKind::Counter), None);
+
+        let reader = PeriodicReader::builder(exporter, runtime::Tokio)
+            .with_cardinality_limit(3)
+            .build();
+        assert_eq!(reader.cardinality_limit(InstrumentKind::Counter), Some(3));
+    }
+
     #[test]
     fn unregistered_collect() {
         // Arrange
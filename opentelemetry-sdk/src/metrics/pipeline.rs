@@ -12,8 +12,9 @@ use crate::{
     metrics::{
         aggregation,
         data::{Metric, ResourceMetrics, ScopeMetrics},
+        exemplar::ExemplarFilter,
         instrument::{Instrument, InstrumentId, InstrumentKind, Stream},
-        internal::{self, AggregateBuilder, Number},
+        internal::{self, AggregateBuilder, ExemplarConfig, Number},
         reader::{MetricReader, SdkProducer},
         view::View,
         MetricError, MetricResult,
@@ -38,6 +39,8 @@ pub struct Pipeline {
     pub(crate) resource: Resource,
     reader: Box<dyn MetricReader>,
     views: Vec<Arc<dyn View>>,
+    exemplar_filter: ExemplarFilter,
+    cardinality_limit: usize,
     inner: Mutex<PipelineInner>,
 }
 
@@ -303,6 +306,8 @@ where
             unit: inst.unit,
             aggregation: None,
             allowed_attribute_keys: None,
+            cardinality_limit: None,
+            exemplar_reservoir: None,
         };
 
         // Override default histogram boundaries if provided.
@@ -385,7 +390,27 @@ where
                 .clone()
                 .map(|allowed| Arc::new(move |kv: &KeyValue| allowed.contains(&kv.key)) as Arc<_>);
 
-            let b = AggregateBuilder::new(self.pipeline.reader.temporality(kind), filter);
+            // Only measurements of synchronous instruments can become exemplars.
+            let exemplar_filter = match kind {
+                InstrumentKind::ObservableCounter
+                | InstrumentKind::ObservableUpDownCounter
+                | InstrumentKind::ObservableGauge => ExemplarFilter::AlwaysOff,
+                _ => self.pipeline.exemplar_filter,
+            };
+
+            // The limit of the view takes precedence over the one of the reader,
+            // which takes precedence over the default of the meter provider.
+            let cardinality_limit = stream
+                .cardinality_limit
+                .or_else(|| self.pipeline.reader.cardinality_limit(kind))
+                .unwrap_or(self.pipeline.cardinality_limit);
+
+            let b = AggregateBuilder::new(
+                self.pipeline.reader.temporality(kind),
+                filter,
+                ExemplarConfig::new(exemplar_filter, stream.exemplar_reservoir),
+                cardinality_limit,
+            );
             let AggregateFns { measure, collect } = match aggregate_fn(b, &agg, kind) {
                 Ok(Some(inst)) => inst,
                 other => return other.map(|fs| fs.map(|inst| inst.measure)), // Drop aggregator or error
@@ -621,6 +646,8 @@ impl Pipelines {
         res: Resource,
         readers: Vec<Box<dyn MetricReader>>,
         views: Vec<Arc<dyn View>>,
+        exemplar_filter: ExemplarFilter,
+        cardinality_limit: usize,
     ) -> Self {
         let mut pipes = Vec::with_capacity(readers.len());
         for r in readers {
@@ -628,6 +655,8 @@ impl Pipelines {
                 resource: res.clone(),
                 reader: r,
                 views: views.clone(),
+                exemplar_filter,
+                cardinality_limit,
                 inner: Default::default(),
             });
             p.reader.register_pipeline(Arc::downgrade(&p));
@@ -53,6 +53,15 @@ pub trait MetricReader: fmt::Debug + Send + Sync + 'static {
     ///
     /// If not configured, the Cumulative temporality SHOULD be used.
     fn temporality(&self, kind: InstrumentKind) -> Temporality;
+
+    /// The maximum number of distinct attribute sets of each metric stream, a
+    /// function of instrument kind.
+    ///
+    /// Views configuring a cardinality limit take precedence over this limit. If
+    /// `None`, the default limit of the meter provider is used.
+    fn cardinality_limit(&self, _kind: InstrumentKind) -> Option<usize> {
+        None
+    }
 }
 
 /// Produces metrics for a [MetricReader].
@@ -161,6 +161,8 @@ pub fn new_view(criteria: Instrument, mask: Stream) -> MetricResult<Box<dyn View
                 },
                 aggregation: agg.clone(),
                 allowed_attribute_keys: mask.allowed_attribute_keys.clone(),
+                cardinality_limit: mask.cardinality_limit,
+                exemplar_reservoir: mask.exemplar_reservoir,
             })
         } else {
             None
@@ -0,0 +1,522 @@
+//! # Redaction of attributes
+//!
+//! Spans and log records can carry sensitive data, like emails or
+//! authorization tokens, in their attributes and bodies. The
+//! [`RedactingSpanProcessor`] and [`RedactingLogProcessor`] apply a list of
+//! [`RedactionRule`]s to the telemetry before forwarding it to another
+//! processor, so it never reaches the exporters.
+//!
+//! [`RedactingSpanProcessor`]: crate::trace::RedactingSpanProcessor
+//! [`RedactingLogProcessor`]: crate::logs::RedactingLogProcessor
+
+use hmac::{Hmac, Mac};
+#[cfg(feature = "logs")]
+use opentelemetry::logs::AnyValue;
+use opentelemetry::{Array, Key, KeyValue, StringValue, Value};
+use regex::Regex;
+use sha2::Sha256;
+use std::borrow::Cow;
+use std::collections::HashSet;
+use std::fmt::{self, Write};
+
+/// A rule applied to the attributes of the telemetry by the redacting
+/// processors.
+///
+/// The rules apply to the attributes of spans, span events and span links, to
+/// the attributes of log records, and to the entries of log record bodies
+/// which are maps. Other log record bodies are only masked.
+#[derive(Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum RedactionRule {
+    /// Delete the attributes with the given key.
+    Delete(Key),
+    /// Replace the values of the attributes with key `key` by the hexadecimal
+    /// HMAC-SHA256 of their string representation, keyed with `secret`, so that
+    /// they can still be correlated without being disclosed.
+    ///
+    /// Without the secret, the original values cannot be recovered by hashing
+    /// guesses, but the same value always hashes to the same digest: this is
+    /// pseudonymisation, not anonymisation. Keep the secret out of the
+    /// telemetry, and rotate it to break the correlation with older digests.
+    ///
+    /// The strings nested in the arrays and maps of log records are hashed
+    /// individually. The secret must not be empty.
+    Hash {
+        /// The key of the attributes to hash.
+        key: Key,
+        /// The secret key of the HMAC.
+        secret: Vec<u8>,
+    },
+    /// Replace the matches of the regular expression `pattern` in all the
+    /// string values, including the strings nested in arrays and maps, by
+    /// `replacement`.
+    ///
+    /// `replacement` may refer to the capture groups of `pattern`, like
+    /// [`Regex::replace_all`] does: use `$$` for a literal `$`.
+    ///
+    /// [`Regex::replace_all`]: https://docs.rs/regex/latest/regex/struct.Regex.html#method.replace_all
+    Mask {
+        /// The regular expression matching the data to mask.
+        pattern: String,
+        /// The replacement of the matches.
+        replacement: String,
+    },
+    /// Delete all the attributes whose key is not in the list.
+    AllowKeys(Vec<Key>),
+    /// Rename the attributes with key `from` to `to`. The attributes already
+    /// named `to` are deleted when an attribute is renamed.
+    Rename {
+        /// The key of the attributes to rename.
+        from: Key,
+        /// The new key of the attributes.
+        to: Key,
+    },
+}
+
+impl fmt::Debug for RedactionRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RedactionRule::Delete(key) => f.debug_tuple("Delete").field(key).finish(),
+            // The secret is left out, so that it does not leak into logs.
+            RedactionRule::Hash { key, .. } => f
+                .debug_struct("Hash")
+                .field("key", key)
+                .finish_non_exhaustive(),
+            RedactionRule::Mask {
+                pattern,
+                replacement,
+            } => f
+                .debug_struct("Mask")
+                .field("pattern", pattern)
+                .field("replacement", replacement)
+                .finish(),
+            RedactionRule::AllowKeys(keys) => f.debug_tuple("AllowKeys").field(keys).finish(),
+            RedactionRule::Rename { from, to } => f
+                .debug_struct("Rename")
+                .field("from", from)
+                .field("to", to)
+                .finish(),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum CompiledRule {
+    Delete(Key),
+    Hash(Key, Hmac<Sha256>),
+    Mask(Regex, String),
+    AllowKeys(HashSet<Key>),
+    Rename(Key, Key),
+}
+
+/// The compiled rules of a redacting processor.
+#[derive(Debug)]
+pub(crate) struct Redactor {
+    rules: Vec<CompiledRule>,
+}
+
+impl Redactor {
+    /// Compile `rules`, returning an error if any of the regular expressions
+    /// is invalid or if the secret of a hash rule is empty.
+    pub(crate) fn new(rules: Vec<RedactionRule>) -> Result<Self, String> {
+        let rules = rules
+            .into_iter()
+            .map(|rule| {
+                Ok(match rule {
+                    RedactionRule::Delete(key) => CompiledRule::Delete(key),
+                    RedactionRule::Hash { secret, .. } if secret.is_empty() => {
+                        return Err("the secret of a hash rule must not be empty".into());
+                    }
+                    RedactionRule::Hash { key, secret } => Hmac::new_from_slice(&secret)
+                        .map(|mac| CompiledRule::Hash(key, mac))
+                        .map_err(|err| format!("invalid HMAC secret: {err}"))?,
+                    RedactionRule::Mask {
+                        pattern,
+                        replacement,
+                    } => Regex::new(&pattern)
+                        .map(|regex| CompiledRule::Mask(regex, replacement))
+                        .map_err(|err| format!("invalid regex {pattern:?}: {err}"))?,
+                    RedactionRule::AllowKeys(keys) => {
+                        CompiledRule::AllowKeys(keys.into_iter().collect())
+                    }
+                    RedactionRule::Rename { from, to } => CompiledRule::Rename(from, to),
+                })
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Redactor { rules })
+    }
+
+    /// Apply the rules, in order, to `attributes`.
+    pub(crate) fn redact<A: Attribute>(&self, attributes: &mut Vec<A>) {
+        if attributes.is_empty() {
+            return;
+        }
+        for rule in &self.rules {
+            match rule {
+                CompiledRule::Delete(key) => attributes.retain(|attr| attr.key() != key),
+                CompiledRule::Hash(key, mac) => attributes
+                    .iter_mut()
+                    .filter(|attr| attr.key() == key)
+                    .for_each(|attr| attr.value_mut().hash(mac)),
+                CompiledRule::Mask(regex, replacement) => attributes
+                    .iter_mut()
+                    .for_each(|attr| attr.value_mut().mask(regex, replacement)),
+                CompiledRule::AllowKeys(keys) => {
+                    attributes.retain(|attr| keys.contains(attr.key()))
+                }
+                CompiledRule::Rename(from, to) => {
+                    if from == to || !attributes.iter().any(|attr| attr.key() == from) {
+                        continue;
+                    }
+                    attributes.retain(|attr| attr.key() != to);
+                    attributes
+                        .iter_mut()
+                        .filter(|attr| attr.key() == from)
+                        .for_each(|attr| attr.set_key(to.clone()));
+                }
+            }
+        }
+    }
+
+    /// Apply the rules to a log record body: the rules apply to the entries of
+    /// maps, and other bodies are only masked.
+    #[cfg(feature = "logs")]
+    pub(crate) fn redact_body(&self, body: &mut AnyValue) {
+        if let AnyValue::Map(map) = body {
+            let mut entries = std::mem::take(map.as_mut()).into_iter().collect::<Vec<_>>();
+            self.redact(&mut entries);
+            *map.as_mut() = entries.into_iter().collect();
+        } else {
+            for rule in &self.rules {
+                if let CompiledRule::Mask(regex, replacement) = rule {
+                    body.mask(regex, replacement);
+                }
+            }
+        }
+    }
+}
+
+/// An attribute, as stored by spans or log records.
+pub(crate) trait Attribute {
+    type Value: RedactValue;
+
+    fn key(&self) -> &Key;
+    fn set_key(&mut self, key: Key);
+    fn value_mut(&mut self) -> &mut Self::Value;
+}
+
+impl Attribute for KeyValue {
+    type Value = Value;
+
+    fn key(&self) -> &Key {
+        &self.key
+    }
+
+    fn set_key(&mut self, key: Key) {
+        self.key = key;
+    }
+
+    fn value_mut(&mut self) -> &mut Value {
+        &mut self.value
+    }
+}
+
+#[cfg(feature = "logs")]
+impl Attribute for (Key, AnyValue) {
+    type Value = AnyValue;
+
+    fn key(&self) -> &Key {
+        &self.0
+    }
+
+    fn set_key(&mut self, key: Key) {
+        self.0 = key;
+    }
+
+    fn value_mut(&mut self) -> &mut AnyValue {
+        &mut self.1
+    }
+}
+
+/// A value which can be hashed or masked in place.
+pub(crate) trait RedactValue {
+    fn hash(&mut self, mac: &Hmac<Sha256>);
+    fn mask(&mut self, regex: &Regex, replacement: &str);
+}
+
+impl RedactValue for Value {
+    fn hash(&mut self, mac: &Hmac<Sha256>) {
+        *self = Value::String(hmac_hex(mac, self.as_str().as_bytes()).into());
+    }
+
+    fn mask(&mut self, regex: &Regex, replacement: &str) {
+        match self {
+            Value::String(s) => mask_string(s, regex, replacement),
+            Value::Array(Array::String(values)) => values
+                .iter_mut()
+                .for_each(|s| mask_string(s, regex, replacement)),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(feature = "logs")]
+impl RedactValue for AnyValue {
+    fn hash(&mut self, mac: &Hmac<Sha256>) {
+        match self {
+            AnyValue::Int(v) => *self = AnyValue::String(hmac_hex(mac, v.to_string()).into()),
+            AnyValue::Double(v) => *self = AnyValue::String(hmac_hex(mac, v.to_string()).into()),
+            AnyValue::String(s) => *s = hmac_hex(mac, s.as_str()).into(),
+            AnyValue::Boolean(v) => *self = AnyValue::String(hmac_hex(mac, v.to_string()).into()),
+            AnyValue::Bytes(bytes) => {
+                *self = AnyValue::String(hmac_hex(mac, bytes.as_slice()).into())
+            }
+            AnyValue::ListAny(values) => values.iter_mut().for_each(|value| value.hash(mac)),
+            AnyValue::Map(map) => map.values_mut().for_each(|value| value.hash(mac)),
+            _ => {}
+        }
+    }
+
+    fn mask(&mut self, regex: &Regex, replacement: &str) {
+        match self {
+            AnyValue::String(s) => mask_string(s, regex, replacement),
+            AnyValue::ListAny(values) => values
+                .iter_mut()
+                .for_each(|value| value.mask(regex, replacement)),
+            AnyValue::Map(map) => map
+                .values_mut()
+                .for_each(|value| value.mask(regex, replacement)),
+            _ => {}
+        }
+    }
+}
+
+/// Replace the matches of `regex` in `s`, leaving it untouched without match.
+fn mask_string(s: &mut StringValue, regex: &Regex, replacement: &str) {
+    if let Cow::Owned(masked) = regex.replace_all(s.as_str(), replacement) {
+        *s = masked.into();
+    }
+}
+
+/// The hexadecimal HMAC of `data`, computed with a copy of the keyed `mac`.
+fn hmac_hex(mac: &Hmac<Sha256>, data: impl AsRef<[u8]>) -> String {
+    mac.clone()
+        .chain_update(data)
+        .finalize()
+        .into_bytes()
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn redact(rules: Vec<RedactionRule>, mut attributes: Vec<KeyValue>) -> Vec<KeyValue> {
+        Redactor::new(rules).unwrap().redact(&mut attributes);
+        attributes
+    }
+
+    #[test]
+    fn delete_and_allow_keys() {
+        let attributes = vec![
+            KeyValue::new("user.email", "jane@example.com"),
+            KeyValue::new("http.request.method", "GET"),
+            KeyValue::new("http.request.header.authorization", "Bearer token"),
+        ];
+        assert_eq!(
+            redact(
+                vec![RedactionRule::Delete(
+                    "http.request.header.authorization".into()
+                )],
+                attributes.clone()
+            ),
+            attributes[..2]
+        );
+        assert_eq!(
+            redact(
+                vec![RedactionRule::AllowKeys(vec![
+                    "http.request.method".into(),
+                    "url.path".into()
+                ])],
+                attributes
+            ),
+            vec![KeyValue::new("http.request.method", "GET")]
+        );
+    }
+
+    #[test]
+    fn hash() {
+        assert_eq!(
+            redact(
+                vec![RedactionRule::Hash {
+                    key: "user.id".into(),
+                    secret: b"secret".to_vec()
+                }],
+                vec![
+                    KeyValue::new("user.id", 42),
+                    KeyValue::new("user.name", "jane")
+                ]
+            ),
+            vec![
+                KeyValue::new(
+                    "user.id",
+                    "93c121e7aa437a1e01e3c512c6f0ce3c821a839025dca4408f85616de4aaee70"
+                ),
+                KeyValue::new("user.name", "jane")
+            ]
+        );
+
+        let rule = RedactionRule::Hash {
+            key: "user.id".into(),
+            secret: b"secret".to_vec(),
+        };
+        assert!(!format!("{rule:?}").contains("secret: "));
+        let err = Redactor::new(vec![RedactionRule::Hash {
+            key: "user.id".into(),
+            secret: Vec::new(),
+        }])
+        .unwrap_err();
+        assert_eq!(err, "the secret of a hash rule must not be empty");
+    }
+
+    #[test]
+    fn mask() {
+        let rules = vec![RedactionRule::Mask {
+            pattern: r"[\w.+-]+@([\w-]+\.[\w.-]+)".into(),
+            replacement: "****@$1".into(),
+        }];
+        assert_eq!(
+            redact(
+                rules.clone(),
+                vec![
+                    KeyValue::new("message", "sent to jane@example.com and joe@example.org"),
+                    KeyValue::new(
+                        "recipients",
+                        Value::Array(vec![StringValue::from("jane@example.com")].into())
+                    ),
+                    KeyValue::new("count", 2),
+                ]
+            ),
+            vec![
+                KeyValue::new("message", "sent to ****@example.com and ****@example.org"),
+                KeyValue::new(
+                    "recipients",
+                    Value::Array(vec![StringValue::from("****@example.com")].into())
+                ),
+                KeyValue::new("count", 2),
+            ]
+        );
+
+        let err = Redactor::new(vec![RedactionRule::Mask {
+            pattern: "(".into(),
+            replacement: String::new(),
+        }])
+        .unwrap_err();
+        assert!(err.starts_with("invalid regex \"(\""));
+    }
+
+    #[test]
+    fn rename() {
+        assert_eq!(
+            redact(
+                vec![
+                    RedactionRule::Rename {
+                        from: "http.method".into(),
+                        to: "http.request.method".into()
+                    },
+                    RedactionRule::Rename {
+                        from: "absent".into(),
+                        to: "user.name".into()
+                    },
+                ],
+                vec![
+                    KeyValue::new("http.request.method", "POST"),
+                    KeyValue::new("http.method", "GET"),
+                    KeyValue::new("user.name", "jane"),
+                ]
+            ),
+            vec![
+                KeyValue::new("http.request.method", "GET"),
+                KeyValue::new("user.name", "jane"),
+            ]
+        );
+    }
+
+    #[cfg(feature = "logs")]
+    #[test]
+    fn log_values() {
+        use std::collections::HashMap;
+
+        let redactor = Redactor::new(vec![
+            RedactionRule::Hash {
+                key: "token".into(),
+                secret: b"secret".to_vec(),
+            },
+            RedactionRule::Mask {
+                pattern: r"\d{4}-\d{4}".into(),
+                replacement: "####-####".into(),
+            },
+            RedactionRule::Delete("password".into()),
+        ])
+        .unwrap();
+
+        let mut attributes = vec![
+            (
+                Key::new("token"),
+                AnyValue::ListAny(Box::new(vec![AnyValue::Int(42), "abc".into()])),
+            ),
+            (
+                Key::new("card"),
+                AnyValue::Map(Box::new(HashMap::from([(
+                    Key::new("number"),
+                    AnyValue::from("1234-5678"),
+                )]))),
+            ),
+        ];
+        redactor.redact(&mut attributes);
+        assert_eq!(
+            attributes,
+            vec![
+                (
+                    Key::new("token"),
+                    AnyValue::ListAny(Box::new(vec![
+                        AnyValue::from(
+                            "93c121e7aa437a1e01e3c512c6f0ce3c821a839025dca4408f85616de4aaee70"
+                        ),
+                        AnyValue::from(
+                            "9946dad4e00e913fc8be8e5d3f7e110a4a9e832f83fb09c345285d78638d8a0e"
+                        ),
+                    ]))
+                ),
+                (
+                    Key::new("card"),
+                    AnyValue::Map(Box::new(HashMap::from([(
+                        Key::new("number"),
+                        AnyValue::from("####-####"),
+                    )])))
+                ),
+            ]
+        );
+
+        let mut body = AnyValue::from("card 1234-5678");
+        redactor.redact_body(&mut body);
+        assert_eq!(body, AnyValue::from("card ####-####"));
+
+        let mut body = AnyValue::Map(Box::new(HashMap::from([
+            (Key::new("password"), AnyValue::from("secret")),
+            (Key::new("user"), AnyValue::from("jane")),
+        ])));
+        redactor.redact_body(&mut body);
+        assert_eq!(
+            body,
+            AnyValue::Map(Box::new(HashMap::from([(
+                Key::new("user"),
+                AnyValue::from("jane")
+            )])))
+        );
+    }
+}
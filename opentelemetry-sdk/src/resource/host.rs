@@ -0,0 +1,97 @@
+use crate::resource::ResourceDetector;
+use crate::Resource;
+use opentelemetry::KeyValue;
+use opentelemetry_semantic_conventions::resource::{HOST_ARCH, HOST_ID, HOST_NAME};
+use std::env::consts::ARCH;
+use std::{env, fs};
+
+/// Files holding the machine id, in order of preference.
+const MACHINE_ID_PATHS: [&str; 3] = ["/etc/machine-id", "/var/lib/dbus/machine-id", "/etc/hostid"];
+
+/// Detect the information of the host the process is running on.
+///
+/// It provides:
+/// - The name of the host(`host.name`). It is read from the kernel on Linux, and from the
+///   `HOSTNAME` or `COMPUTERNAME` environment variables on other systems.
+/// - The unique id of the host(`host.id`). It is the `machine-id` on Linux and the `hostid` on BSD
+///   systems, and is not detected on other systems.
+/// - The CPU architecture of the host(`host.arch`).
+///
+/// Attributes which cannot be detected are omitted.
+///
+/// See [semantic conventions](https://github.com/open-telemetry/semantic-conventions/blob/main/docs/resource/host.md) for details.
+#[derive(Debug)]
+pub struct HostResourceDetector;
+
+impl ResourceDetector for HostResourceDetector {
+    fn detect(&self) -> Resource {
+        Resource::builder_empty()
+            .with_attributes(
+                [
+                    host_name().map(|name| KeyValue::new(HOST_NAME, name)),
+                    host_id().map(|id| KeyValue::new(HOST_ID, id)),
+                    Some(KeyValue::new(HOST_ARCH, host_arch(ARCH))),
+                ]
+                .into_iter()
+                .flatten(),
+            )
+            .build()
+    }
+}
+
+fn host_name() -> Option<String> {
+    fs::read_to_string("/proc/sys/kernel/hostname")
+        .ok()
+        .or_else(|| env::var("HOSTNAME").ok())
+        .or_else(|| env::var("COMPUTERNAME").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+fn host_id() -> Option<String> {
+    MACHINE_ID_PATHS
+        .iter()
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .map(|id| id.trim().to_string())
+        .find(|id| !id.is_empty())
+}
+
+/// Maps the architecture names of the Rust standard library to the well-known
+/// values of `host.arch`.
+fn host_arch(arch: &str) -> &str {
+    match arch {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        "arm" => "arm32",
+        "powerpc" => "ppc32",
+        "powerpc64" => "ppc64",
+        arch => arch,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::Key;
+
+    #[test]
+    fn test_host_resource_detector() {
+        let resource = HostResourceDetector.detect();
+        assert_eq!(
+            resource.get(&Key::from_static_str(HOST_ARCH)),
+            Some(host_arch(ARCH).to_string().into())
+        );
+        #[cfg(target_os = "linux")]
+        assert!(resource.get(&Key::from_static_str(HOST_NAME)).is_some());
+    }
+
+    #[test]
+    fn test_host_arch() {
+        assert_eq!(host_arch("x86_64"), "amd64");
+        assert_eq!(host_arch("aarch64"), "arm64");
+        assert_eq!(host_arch("arm"), "arm32");
+        assert_eq!(host_arch("powerpc64"), "ppc64");
+        assert_eq!(host_arch("x86"), "x86");
+        assert_eq!(host_arch("s390x"), "s390x");
+    }
+}
@@ -18,9 +18,28 @@
 //! - [`EnvResourceDetector`] - detect resource from environmental variables.
 //! - [`TelemetryResourceDetector`] - detect telemetry SDK's information.
 //!
-//! The OS and Process resource detectors are packaged separately in the
-//! [`opentelemetry-resource-detector` crate](https://github.com/open-telemetry/opentelemetry-rust-contrib/tree/main/opentelemetry-resource-detectors).
+//! With the `resource_detectors` feature, the following detectors are provided
+//! as well, but are not used by default. They can be added with
+//! [`ResourceBuilder::with_detector`].
+//!
+//! - [`HostResourceDetector`] - detect the host's name, id and architecture.
+//! - [`OsResourceDetector`] - detect the operating system's type and version.
+//! - [`ProcessResourceDetector`] - detect the process' id, executable,
+//!   command, owner and runtime.
+//!
+//! These detectors used to live in the SDK, and were moved to the
+//! [`opentelemetry-resource-detector` crate](https://github.com/open-telemetry/opentelemetry-rust-contrib/tree/main/opentelemetry-resource-detectors)
+//! along with the cloud specific ones. The host, OS and process attributes
+//! being needed by almost every service, the SDK provides them again, behind
+//! an opt-in feature so that the default build is unchanged. The cloud and
+//! platform specific detectors remain in that crate.
 mod env;
+#[cfg(feature = "resource_detectors")]
+mod host;
+#[cfg(feature = "resource_detectors")]
+mod os;
+#[cfg(feature = "resource_detectors")]
+mod process;
 mod telemetry;
 
 mod attributes;
@@ -28,6 +47,12 @@ pub(crate) use attributes::*;
 
 pub use env::EnvResourceDetector;
 pub use env::SdkProvidedResourceDetector;
+#[cfg(feature = "resource_detectors")]
+pub use host::HostResourceDetector;
+#[cfg(feature = "resource_detectors")]
+pub use os::OsResourceDetector;
+#[cfg(feature = "resource_detectors")]
+pub use process::ProcessResourceDetector;
 pub use telemetry::TelemetryResourceDetector;
 
 use opentelemetry::{Key, KeyValue, Value};
@@ -544,4 +569,22 @@ mod tests {
             },
         )
     }
+
+    #[cfg(feature = "resource_detectors")]
+    #[test]
+    fn with_host_os_and_process_detectors() {
+        use opentelemetry_semantic_conventions::resource::{
+            HOST_ARCH, OS_TYPE, PROCESS_PID, PROCESS_RUNTIME_NAME,
+        };
+
+        let resource = Resource::builder_empty()
+            .with_detector(Box::new(HostResourceDetector))
+            .with_detector(Box::new(OsResourceDetector))
+            .with_detector(Box::new(ProcessResourceDetector::new()))
+            .build();
+
+        for key in [HOST_ARCH, OS_TYPE, PROCESS_PID, PROCESS_RUNTIME_NAME] {
+            assert!(resource.get(&Key::from_static_str(key)).is_some(), "{key}");
+        }
+    }
 }
@@ -0,0 +1,105 @@
+use crate::resource::ResourceDetector;
+use crate::Resource;
+use opentelemetry::KeyValue;
+use opentelemetry_semantic_conventions::resource::{OS_DESCRIPTION, OS_NAME, OS_TYPE, OS_VERSION};
+use std::env::consts::OS;
+use std::fs;
+
+/// Detect the information of the operating system the process is running on.
+///
+/// It provides:
+/// - The type of the operating system(`os.type`), for example `linux`, `windows` or `darwin`.
+/// - The name(`os.name`), version(`os.version`) and description(`os.description`) of the
+///   distribution, read from `/etc/os-release` on Linux. They are not detected on other systems.
+///
+/// See [semantic conventions](https://github.com/open-telemetry/semantic-conventions/blob/main/docs/resource/os.md) for details.
+#[derive(Debug)]
+pub struct OsResourceDetector;
+
+impl ResourceDetector for OsResourceDetector {
+    fn detect(&self) -> Resource {
+        let mut attributes = vec![KeyValue::new(OS_TYPE, os_type(OS))];
+        if let Ok(os_release) = fs::read_to_string("/etc/os-release")
+            .or_else(|_| fs::read_to_string("/usr/lib/os-release"))
+        {
+            attributes.extend(os_release_attributes(&os_release));
+        }
+        Resource::builder_empty()
+            .with_attributes(attributes)
+            .build()
+    }
+}
+
+/// Maps the operating system names of the Rust standard library to the
+/// well-known values of `os.type`.
+fn os_type(os: &str) -> &str {
+    match os {
+        "macos" | "ios" => "darwin",
+        "dragonfly" => "dragonflybsd",
+        "illumos" => "solaris",
+        os => os,
+    }
+}
+
+/// Extracts the `os.name`, `os.version` and `os.description` attributes from
+/// the content of an [os-release] file.
+///
+/// [os-release]: https://www.freedesktop.org/software/systemd/man/latest/os-release.html
+fn os_release_attributes(os_release: &str) -> Vec<KeyValue> {
+    let mut attributes = Vec::new();
+    for line in os_release.lines() {
+        let Some((key, value)) = line.trim().split_once('=') else {
+            continue;
+        };
+        let value = value.trim_matches(|c| c == '"' || c == '\'').to_string();
+        match key {
+            "NAME" => attributes.push(KeyValue::new(OS_NAME, value)),
+            "VERSION_ID" => attributes.push(KeyValue::new(OS_VERSION, value)),
+            "PRETTY_NAME" => attributes.push(KeyValue::new(OS_DESCRIPTION, value)),
+            _ => {}
+        }
+    }
+    attributes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::Key;
+
+    #[test]
+    fn test_os_resource_detector() {
+        let resource = OsResourceDetector.detect();
+        assert_eq!(
+            resource.get(&Key::from_static_str(OS_TYPE)),
+            Some(os_type(OS).to_string().into())
+        );
+    }
+
+    #[test]
+    fn test_os_type() {
+        assert_eq!(os_type("linux"), "linux");
+        assert_eq!(os_type("windows"), "windows");
+        assert_eq!(os_type("macos"), "darwin");
+        assert_eq!(os_type("dragonfly"), "dragonflybsd");
+    }
+
+    #[test]
+    fn test_os_release_attributes() {
+        let os_release = r#"PRETTY_NAME="Ubuntu 22.04.4 LTS"
+NAME="Ubuntu"
+VERSION_ID="22.04"
+VERSION="22.04.4 LTS (Jammy Jellyfish)"
+ID=ubuntu
+"#;
+        assert_eq!(
+            os_release_attributes(os_release),
+            vec![
+                KeyValue::new(OS_DESCRIPTION, "Ubuntu 22.04.4 LTS"),
+                KeyValue::new(OS_NAME, "Ubuntu"),
+                KeyValue::new(OS_VERSION, "22.04"),
+            ]
+        );
+        assert!(os_release_attributes("").is_empty());
+    }
+}
@@ -0,0 +1,200 @@
+use crate::resource::ResourceDetector;
+use crate::Resource;
+use opentelemetry::{Array, KeyValue, StringValue, Value};
+use opentelemetry_semantic_conventions::resource::{
+    PROCESS_COMMAND, PROCESS_COMMAND_ARGS, PROCESS_EXECUTABLE_NAME, PROCESS_EXECUTABLE_PATH,
+    PROCESS_PID, PROCESS_RUNTIME_DESCRIPTION, PROCESS_RUNTIME_NAME, PROCESS_RUNTIME_VERSION,
+};
+#[cfg(unix)]
+use opentelemetry_semantic_conventions::resource::{PROCESS_OWNER, PROCESS_PARENT_PID};
+use std::env;
+
+/// Detect the information of the running process.
+///
+/// It provides:
+/// - The process id(`process.pid`), and the parent process id(`process.parent_pid`) on Unix systems.
+/// - The name(`process.executable.name`) and full path(`process.executable.path`) of the executable.
+/// - The command(`process.command`) the process was launched with.
+/// - All the arguments(`process.command_args`) the process was launched with, only if enabled
+///   with [`with_command_args`], as they may contain secrets.
+/// - The name of the user owning the process(`process.owner`), looked up from its effective user
+///   id on Unix systems. It is not detected on other systems.
+/// - The name(`process.runtime.name`), version(`process.runtime.version`) and
+///   description(`process.runtime.description`) of the compiler the SDK was built with.
+///
+/// Attributes which cannot be detected are omitted.
+///
+/// See [semantic conventions](https://github.com/open-telemetry/semantic-conventions/blob/main/docs/resource/process.md) for details.
+///
+/// [`with_command_args`]: ProcessResourceDetector::with_command_args
+#[derive(Debug, Default)]
+pub struct ProcessResourceDetector {
+    with_command_args: bool,
+}
+
+impl ProcessResourceDetector {
+    /// Create a `ProcessResourceDetector`, which does not detect the arguments
+    /// of the command.
+    pub fn new() -> Self {
+        ProcessResourceDetector::default()
+    }
+
+    /// Also detect all the arguments the process was launched with
+    /// (`process.command_args`).
+    ///
+    /// Only enable it if the command line of the process never contains
+    /// secrets, like passwords or tokens.
+    pub fn with_command_args(mut self) -> Self {
+        self.with_command_args = true;
+        self
+    }
+}
+
+impl ResourceDetector for ProcessResourceDetector {
+    fn detect(&self) -> Resource {
+        let mut attributes = vec![KeyValue::new(PROCESS_PID, i64::from(std::process::id()))];
+        #[cfg(unix)]
+        attributes.push(KeyValue::new(
+            PROCESS_PARENT_PID,
+            i64::from(std::os::unix::process::parent_id()),
+        ));
+
+        if let Ok(path) = env::current_exe() {
+            if let Some(name) = path.file_name() {
+                attributes.push(KeyValue::new(
+                    PROCESS_EXECUTABLE_NAME,
+                    name.to_string_lossy().into_owned(),
+                ));
+            }
+            attributes.push(KeyValue::new(
+                PROCESS_EXECUTABLE_PATH,
+                path.to_string_lossy().into_owned(),
+            ));
+        }
+
+        if let Some(command) = env::args_os().next() {
+            attributes.push(KeyValue::new(
+                PROCESS_COMMAND,
+                command.to_string_lossy().into_owned(),
+            ));
+        }
+        if self.with_command_args {
+            let args: Vec<StringValue> = env::args_os()
+                .map(|arg| arg.to_string_lossy().into_owned().into())
+                .collect();
+            attributes.push(KeyValue::new(
+                PROCESS_COMMAND_ARGS,
+                Value::Array(Array::String(args)),
+            ));
+        }
+
+        #[cfg(unix)]
+        if let Some(owner) = process_owner() {
+            attributes.push(KeyValue::new(PROCESS_OWNER, owner));
+        }
+
+        attributes.push(KeyValue::new(PROCESS_RUNTIME_NAME, "rustc"));
+        // Set by the build script, from the output of `rustc --version`.
+        if let Some(description) = option_env!("OTEL_SDK_RUSTC_VERSION") {
+            if let Some(version) = description.split_whitespace().nth(1) {
+                attributes.push(KeyValue::new(PROCESS_RUNTIME_VERSION, version));
+            }
+            attributes.push(KeyValue::new(PROCESS_RUNTIME_DESCRIPTION, description));
+        }
+
+        Resource::builder_empty()
+            .with_attributes(attributes)
+            .build()
+    }
+}
+
+/// The name of the user matching the effective user id of the process, from
+/// the user database.
+#[cfg(unix)]
+fn process_owner() -> Option<String> {
+    use std::ffi::CStr;
+
+    let mut buffer = vec![0 as libc::c_char; 1024];
+    loop {
+        // SAFETY: `passwd` is plain data, for which all zeroes is valid.
+        let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+        let mut result = std::ptr::null_mut();
+        // SAFETY: `getpwuid_r` fills in `passwd` with pointers into `buffer`,
+        // of the given length, which both outlive the uses of `result`.
+        let code = unsafe {
+            libc::getpwuid_r(
+                libc::geteuid(),
+                &mut passwd,
+                buffer.as_mut_ptr(),
+                buffer.len(),
+                &mut result,
+            )
+        };
+        if code == libc::ERANGE && buffer.len() < 1 << 20 {
+            buffer.resize(buffer.len() * 2, 0);
+            continue;
+        }
+        if code != 0 || result.is_null() || passwd.pw_name.is_null() {
+            return None;
+        }
+        // SAFETY: `pw_name` points to a nul terminated string in `buffer`.
+        let name = unsafe { CStr::from_ptr(passwd.pw_name) };
+        return Some(name.to_string_lossy().into_owned()).filter(|name| !name.is_empty());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::Key;
+
+    #[test]
+    fn test_process_resource_detector() {
+        let resource = ProcessResourceDetector::new().detect();
+        let get = |key: &'static str| resource.get(&Key::from_static_str(key));
+
+        assert_eq!(
+            get(PROCESS_PID),
+            Some(Value::I64(std::process::id().into()))
+        );
+        #[cfg(unix)]
+        assert!(get(PROCESS_PARENT_PID).is_some());
+
+        let path = env::current_exe().unwrap();
+        assert_eq!(
+            get(PROCESS_EXECUTABLE_PATH),
+            Some(path.to_string_lossy().into_owned().into())
+        );
+        assert_eq!(
+            get(PROCESS_EXECUTABLE_NAME),
+            Some(
+                path.file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .into_owned()
+                    .into()
+            )
+        );
+
+        let command = env::args().next().unwrap();
+        assert_eq!(get(PROCESS_COMMAND), Some(command.into()));
+        assert_eq!(get(PROCESS_COMMAND_ARGS), None);
+
+        #[cfg(unix)]
+        assert!(get(PROCESS_OWNER).is_some());
+
+        assert_eq!(get(PROCESS_RUNTIME_NAME), Some("rustc".into()));
+        assert!(get(PROCESS_RUNTIME_VERSION).is_some());
+        assert!(get(PROCESS_RUNTIME_DESCRIPTION).is_some());
+    }
+
+    #[test]
+    fn test_process_resource_detector_with_command_args() {
+        let resource = ProcessResourceDetector::new().with_command_args().detect();
+
+        match resource.get(&Key::from_static_str(PROCESS_COMMAND_ARGS)) {
+            Some(Value::Array(Array::String(args))) => assert_eq!(args.len(), env::args().count()),
+            other => panic!("unexpected command args {other:?}"),
+        }
+    }
+}
@@ -2,7 +2,15 @@
 //!
 //! Configuration represents the global tracing configuration, overrides
 //! can be set for the default OpenTelemetry limits and Sampler.
-use crate::trace::{span_limit::SpanLimits, IdGenerator, RandomIdGenerator, Sampler, ShouldSample};
+use crate::attribute_limits::{
+    limit_from_env, OTEL_ATTRIBUTE_COUNT_LIMIT, OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT,
+};
+#[cfg(feature = "jaeger_remote_sampler")]
+use crate::trace::sampler::JaegerRemoteEnvConfig;
+use crate::trace::{
+    span_limit::SpanLimits, IdGenerator, RandomIdGenerator, RateLimitingSampler, Sampler,
+    ShouldSample,
+};
 use crate::Resource;
 use opentelemetry::otel_warn;
 use std::borrow::Cow;
@@ -30,6 +38,11 @@ pub struct Config {
 
     /// Contains attributes representing an entity that produces telemetry.
     pub resource: Cow<'static, Resource>,
+
+    /// The Jaeger remote sampler selected by `OTEL_TRACES_SAMPLER`, which is
+    /// built with the provider.
+    #[cfg(feature = "jaeger_remote_sampler")]
+    pub(crate) jaeger_remote_env: Option<JaegerRemoteEnvConfig>,
 }
 
 impl Config {
@@ -40,6 +53,10 @@ impl Config {
     )]
     pub fn with_sampler<T: crate::trace::ShouldSample + 'static>(mut self, sampler: T) -> Self {
         self.sampler = Box::new(sampler);
+        #[cfg(feature = "jaeger_remote_sampler")]
+        {
+            self.jaeger_remote_env = None;
+        }
         self
     }
 
@@ -132,12 +149,14 @@ impl Default for Config {
             id_generator: Box::<RandomIdGenerator>::default(),
             span_limits: SpanLimits::default(),
             resource: Cow::Owned(Resource::builder().build()),
+            #[cfg(feature = "jaeger_remote_sampler")]
+            jaeger_remote_env: None,
         };
 
-        if let Some(max_attributes_per_span) = env::var("OTEL_SPAN_ATTRIBUTE_COUNT_LIMIT")
-            .ok()
-            .and_then(|count_limit| u32::from_str(&count_limit).ok())
-        {
+        if let Some(max_attributes_per_span) = limit_from_env(
+            "OTEL_SPAN_ATTRIBUTE_COUNT_LIMIT",
+            OTEL_ATTRIBUTE_COUNT_LIMIT,
+        ) {
             config.span_limits.max_attributes_per_span = max_attributes_per_span;
         }
 
@@ -155,6 +174,13 @@ impl Default for Config {
             config.span_limits.max_links_per_span = max_links_per_span;
         }
 
+        if let Some(max_attribute_value_length) = limit_from_env(
+            "OTEL_SPAN_ATTRIBUTE_VALUE_LENGTH_LIMIT",
+            OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT,
+        ) {
+            config.span_limits.max_attribute_value_length = Some(max_attribute_value_length);
+        }
+
         let sampler_arg = env::var("OTEL_TRACES_SAMPLER_ARG").ok();
         if let Ok(sampler) = env::var("OTEL_TRACES_SAMPLER") {
             config.sampler = match sampler.as_str() {
@@ -196,17 +222,51 @@ impl Default for Config {
                         ))))
                     }
                 }
-                "parentbased_jaeger_remote" => {
-                    otel_warn!(
-                        name: "TracerProvider.Config.UnsupportedSampler",
-                        message = "OTEL_TRACES_SAMPLER is set to 'parentbased_jaeger_remote' which is not implemented in this SDK version. Using fallback sampler: ParentBased(AlwaysOn). Configure an alternative sampler using OTEL_TRACES_SAMPLER"
-                    );
+                "ratelimiting" => {
+                    let rate = sampler_arg.as_ref().and_then(|r| r.parse::<f64>().ok());
+                    if let Some(r) = rate {
+                        Box::new(Sampler::RateLimited(RateLimitingSampler::new(r)))
+                    } else {
+                        otel_warn!(
+                            name: "TracerProvider.Config.InvalidSamplerArgument",
+                            message = "OTEL_TRACES_SAMPLER is set to 'ratelimiting' but OTEL_TRACES_SAMPLER_ARG environment variable is missing or invalid. OTEL_TRACES_SAMPLER_ARG must be a valid float representing the maximum number of traces sampled per second. Falling back to AlwaysOn (no rate limit)",
+                            otel_traces_sampler_arg = format!("{:?}", sampler_arg)
+                        );
+                        Box::new(Sampler::AlwaysOn)
+                    }
+                }
+                "parentbased_ratelimiting" => {
+                    let rate = sampler_arg.as_ref().and_then(|r| r.parse::<f64>().ok());
+                    if let Some(r) = rate {
+                        Box::new(Sampler::ParentBased(Box::new(Sampler::RateLimited(
+                            RateLimitingSampler::new(r),
+                        ))))
+                    } else {
+                        otel_warn!(
+                            name: "TracerProvider.Config.InvalidSamplerArgument",
+                            message = "OTEL_TRACES_SAMPLER is set to 'parentbased_ratelimiting' but OTEL_TRACES_SAMPLER_ARG environment variable is missing or invalid. OTEL_TRACES_SAMPLER_ARG must be a valid float representing the maximum number of traces sampled per second. Falling back to ParentBased(AlwaysOn) (no rate limit)",
+                            otel_traces_sampler_arg = format!("{:?}", sampler_arg)
+                        );
+                        Box::new(Sampler::ParentBased(Box::new(Sampler::AlwaysOn)))
+                    }
+                }
+                // The Jaeger remote sampler needs a runtime and an HTTP client, so
+                // it is built with the provider, and ParentBased(AlwaysOn) is
+                // used until then.
+                #[cfg(feature = "jaeger_remote_sampler")]
+                "parentbased_jaeger_remote" | "jaeger_remote" => {
+                    config.jaeger_remote_env = Some(JaegerRemoteEnvConfig::parse(
+                        sampler == "parentbased_jaeger_remote",
+                        sampler_arg.as_deref(),
+                    ));
                     Box::new(Sampler::ParentBased(Box::new(Sampler::AlwaysOn)))
                 }
-                "jaeger_remote" => {
+                #[cfg(not(feature = "jaeger_remote_sampler"))]
+                "parentbased_jaeger_remote" | "jaeger_remote" => {
                     otel_warn!(
                         name: "TracerProvider.Config.UnsupportedSampler",
-                        message = "OTEL_TRACES_SAMPLER is set to 'jaeger_remote' which is implemented in this SDK version. Using fallback sampler: ParentBased(AlwaysOn). Configure an alternative sampler using OTEL_TRACES_SAMPLER"
+                        message = "OTEL_TRACES_SAMPLER is set to a Jaeger remote sampler, which requires the jaeger_remote_sampler feature of opentelemetry_sdk. Using fallback sampler: ParentBased(AlwaysOn). Configure an alternative sampler using OTEL_TRACES_SAMPLER",
+                        otel_traces_sampler = sampler.clone()
                     );
                     Box::new(Sampler::ParentBased(Box::new(Sampler::AlwaysOn)))
                 }
@@ -221,7 +281,7 @@ impl Default for Config {
                     otel_warn!(
                         name: "TracerProvider.Config.InvalidSamplerType",
                         message = format!(
-                            "Unrecognized sampler type '{}' in OTEL_TRACES_SAMPLER environment variable. Valid values are: always_on, always_off, traceidratio, parentbased_always_on, parentbased_always_off, parentbased_traceidratio. Using fallback sampler: ParentBased(AlwaysOn)",
+                            "Unrecognized sampler type '{}' in OTEL_TRACES_SAMPLER environment variable. Valid values are: always_on, always_off, traceidratio, parentbased_always_on, parentbased_always_off, parentbased_traceidratio, ratelimiting, parentbased_ratelimiting, jaeger_remote, parentbased_jaeger_remote. Using fallback sampler: ParentBased(AlwaysOn)",
                             s
                         ),
                     );
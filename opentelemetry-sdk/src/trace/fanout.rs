@@ -0,0 +1,315 @@
+use crate::error::{OTelSdkError, OTelSdkResult};
+use crate::fanout::{export_all, join_errors, Destination, FanOutStats};
+use crate::resource::Resource;
+use crate::trace::{SpanData, SpanExporter};
+use futures_util::future::BoxFuture;
+use std::borrow::Cow;
+use std::time::Duration;
+
+/// A [`SpanExporter`] exporting each batch of spans to several span
+/// exporters concurrently.
+///
+/// Each destination has its own export timeout: a destination failing or
+/// timing out does not prevent the export to the other destinations. The
+/// export fails if any of the destinations failed, with the errors of the
+/// failed destinations, and the outcomes of the exports are counted per
+/// destination by the [`FanOutStats`] of the exporter.
+///
+/// The batch is cloned for all the destinations but the last one.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry_sdk::testing::trace::NoopSpanExporter;
+/// use opentelemetry_sdk::trace::{FanOutSpanExporter, SdkTracerProvider};
+/// use std::time::Duration;
+///
+/// // e.g. an OTLP and a Zipkin exporter
+/// let exporter = FanOutSpanExporter::builder()
+///     .with_exporter("otlp", NoopSpanExporter::new(), Duration::from_secs(10))
+///     .with_exporter("zipkin", NoopSpanExporter::new(), Duration::from_secs(5))
+///     .build();
+/// let stats = exporter.stats();
+///
+/// let provider = SdkTracerProvider::builder()
+///     .with_batch_exporter(exporter)
+///     .build();
+///
+/// // ...
+///
+/// provider.shutdown().unwrap();
+/// for destination in stats.destinations() {
+///     println!("{}: {} failed exports", destination.name(), destination.failed());
+/// }
+/// ```
+#[derive(Debug)]
+pub struct FanOutSpanExporter {
+    destinations: Vec<Destination<Box<dyn SpanExporter>>>,
+    stats: FanOutStats,
+}
+
+impl FanOutSpanExporter {
+    /// Create a builder to configure the destinations of the exporter.
+    pub fn builder() -> FanOutSpanExporterBuilder {
+        FanOutSpanExporterBuilder::default()
+    }
+
+    /// The outcomes of the exports, per destination.
+    pub fn stats(&self) -> FanOutStats {
+        self.stats.clone()
+    }
+}
+
+impl SpanExporter for FanOutSpanExporter {
+    fn export(&mut self, batch: Vec<SpanData>) -> BoxFuture<'static, OTelSdkResult> {
+        let mut batch = Some(batch);
+        let last = self.destinations.len().saturating_sub(1);
+        let exports = self
+            .destinations
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, destination)| {
+                let batch = if index == last {
+                    batch.take()?
+                } else {
+                    batch.clone()?
+                };
+                let export = destination.exporter.export(batch);
+                let export = destination
+                    .worker
+                    .run(move || futures_executor::block_on(export));
+                Some((destination.timeout, export))
+            })
+            .collect::<Vec<_>>();
+
+        let stats = self.stats.clone();
+        Box::pin(async move {
+            export_all(stats, exports)
+                .await
+                .map_err(OTelSdkError::InternalFailure)
+        })
+    }
+
+    fn shutdown(&mut self) -> OTelSdkResult {
+        join_errors(self.destinations.iter_mut().filter_map(|destination| {
+            let err = destination.exporter.shutdown().err()?;
+            Some(format!("shutdown of {} failed: {}", destination.name, err))
+        }))
+        .map_err(OTelSdkError::InternalFailure)
+    }
+
+    fn force_flush(&mut self) -> OTelSdkResult {
+        join_errors(self.destinations.iter_mut().filter_map(|destination| {
+            let err = destination.exporter.force_flush().err()?;
+            Some(format!("flush of {} failed: {}", destination.name, err))
+        }))
+        .map_err(OTelSdkError::InternalFailure)
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        for destination in self.destinations.iter_mut() {
+            destination.exporter.set_resource(resource);
+        }
+    }
+}
+
+/// Builder for [`FanOutSpanExporter`].
+#[derive(Debug, Default)]
+pub struct FanOutSpanExporterBuilder {
+    destinations: Vec<Destination<Box<dyn SpanExporter>>>,
+}
+
+impl FanOutSpanExporterBuilder {
+    /// Add a destination named `name`, exporting the spans with `exporter`,
+    /// and giving up on each export after `timeout`.
+    pub fn with_exporter<E>(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        exporter: E,
+        timeout: Duration,
+    ) -> Self
+    where
+        E: SpanExporter + 'static,
+    {
+        self.destinations
+            .push(Destination::new(name.into(), timeout, Box::new(exporter)));
+        self
+    }
+
+    /// Build a [`FanOutSpanExporter`] exporting to the provided destinations.
+    pub fn build(self) -> FanOutSpanExporter {
+        let stats = FanOutStats::default();
+        for destination in &self.destinations {
+            stats.add_destination(destination.name.clone());
+        }
+        FanOutSpanExporter {
+            destinations: self.destinations,
+            stats,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::testing::trace::new_test_export_span_data;
+    use crate::trace::InMemorySpanExporterBuilder;
+    use std::time::Instant;
+
+    #[derive(Debug)]
+    struct FailingExporter;
+
+    impl SpanExporter for FailingExporter {
+        fn export(&mut self, _batch: Vec<SpanData>) -> BoxFuture<'static, OTelSdkResult> {
+            Box::pin(async { Err(OTelSdkError::InternalFailure("unavailable".into())) })
+        }
+    }
+
+    #[derive(Debug)]
+    struct HangingExporter;
+
+    impl SpanExporter for HangingExporter {
+        fn export(&mut self, _batch: Vec<SpanData>) -> BoxFuture<'static, OTelSdkResult> {
+            Box::pin(futures_util::future::pending())
+        }
+    }
+
+    /// An exporter blocking the thread polling its export, like the ones
+    /// using a blocking HTTP client.
+    #[derive(Debug)]
+    struct BlockingExporter;
+
+    impl SpanExporter for BlockingExporter {
+        fn export(&mut self, _batch: Vec<SpanData>) -> BoxFuture<'static, OTelSdkResult> {
+            Box::pin(async {
+                std::thread::sleep(Duration::from_secs(5));
+                Ok(())
+            })
+        }
+    }
+
+    /// An exporter relying on the reactor of the Tokio runtime, like the gRPC
+    /// ones.
+    #[derive(Debug)]
+    struct TokioExporter;
+
+    impl SpanExporter for TokioExporter {
+        fn export(&mut self, _batch: Vec<SpanData>) -> BoxFuture<'static, OTelSdkResult> {
+            Box::pin(async {
+                tokio::time::sleep(Duration::from_millis(1)).await;
+                Ok(())
+            })
+        }
+    }
+
+    #[test]
+    fn exports_to_all_destinations() {
+        let first = InMemorySpanExporterBuilder::new().build();
+        let second = InMemorySpanExporterBuilder::new().build();
+        let mut exporter = FanOutSpanExporter::builder()
+            .with_exporter("first", first.clone(), Duration::from_secs(5))
+            .with_exporter("second", second.clone(), Duration::from_secs(5))
+            .build();
+
+        let batch = vec![new_test_export_span_data(), new_test_export_span_data()];
+        futures_executor::block_on(exporter.export(batch)).unwrap();
+        assert_eq!(first.get_finished_spans().unwrap().len(), 2);
+        assert_eq!(second.get_finished_spans().unwrap().len(), 2);
+
+        let stats = exporter.stats();
+        assert_eq!(stats.destination("first").unwrap().succeeded(), 1);
+        assert_eq!(stats.destination("second").unwrap().succeeded(), 1);
+
+        exporter.shutdown().unwrap();
+        assert!(first.get_finished_spans().unwrap().is_empty());
+        assert!(second.get_finished_spans().unwrap().is_empty());
+    }
+
+    #[test]
+    fn isolates_failing_destinations() {
+        let healthy = InMemorySpanExporterBuilder::new().build();
+        let mut exporter = FanOutSpanExporter::builder()
+            .with_exporter("hanging", HangingExporter, Duration::from_millis(50))
+            .with_exporter("failing", FailingExporter, Duration::from_secs(5))
+            .with_exporter("healthy", healthy.clone(), Duration::from_secs(5))
+            .build();
+
+        let start = Instant::now();
+        let err = futures_executor::block_on(exporter.export(vec![new_test_export_span_data()]))
+            .unwrap_err();
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(
+            err.to_string(),
+            "Operation failed: export to hanging timed out after 50ms, export to failing failed: Operation failed: unavailable"
+        );
+        assert_eq!(healthy.get_finished_spans().unwrap().len(), 1);
+
+        let stats = exporter.stats().destinations();
+        assert_eq!(
+            stats
+                .iter()
+                .map(|destination| (
+                    destination.name(),
+                    destination.succeeded(),
+                    destination.failed(),
+                    destination.timed_out()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("hanging", 0, 0, 1),
+                ("failing", 0, 1, 0),
+                ("healthy", 1, 0, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn isolates_blocking_destinations() {
+        let healthy = InMemorySpanExporterBuilder::new().build();
+        let mut exporter = FanOutSpanExporter::builder()
+            .with_exporter("blocking", BlockingExporter, Duration::from_millis(50))
+            .with_exporter("healthy", healthy.clone(), Duration::from_secs(5))
+            .build();
+
+        let start = Instant::now();
+        let err = futures_executor::block_on(exporter.export(vec![new_test_export_span_data()]))
+            .unwrap_err();
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(
+            err.to_string(),
+            "Operation failed: export to blocking timed out after 50ms"
+        );
+        assert_eq!(healthy.get_finished_spans().unwrap().len(), 1);
+
+        // the next exports don't wait for the blocked one either
+        for _ in 0..2 {
+            futures_executor::block_on(exporter.export(vec![new_test_export_span_data()]))
+                .unwrap_err();
+        }
+        assert!(start.elapsed() < Duration::from_secs(5));
+        assert_eq!(healthy.get_finished_spans().unwrap().len(), 3);
+        let blocking = exporter.stats().destination("blocking").unwrap();
+        assert_eq!(blocking.timed_out(), 2);
+        assert_eq!(blocking.failed(), 1);
+        assert_eq!(
+            blocking.last_error(),
+            Some("export to blocking failed: a previous export is still running")
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn exports_in_the_calling_tokio_runtime() {
+        let mut exporter = FanOutSpanExporter::builder()
+            .with_exporter("tokio", TokioExporter, Duration::from_secs(5))
+            .build();
+
+        exporter
+            .export(vec![new_test_export_span_data()])
+            .await
+            .unwrap();
+        assert_eq!(
+            exporter.stats().destination("tokio").unwrap().succeeded(),
+            1
+        );
+    }
+}
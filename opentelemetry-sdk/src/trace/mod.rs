@@ -9,21 +9,27 @@
 mod config;
 mod events;
 mod export;
+mod fanout;
 mod id_generator;
 mod links;
 mod provider;
+#[cfg(feature = "redaction")]
+mod redaction;
 mod sampler;
 mod span;
+mod span_filter;
 mod span_limit;
 mod span_processor;
 #[cfg(feature = "experimental_trace_batch_span_processor_with_async_runtime")]
 /// Experimental feature to use async runtime with batch span processor.
 pub mod span_processor_with_async_runtime;
+mod tail_sampling;
 mod tracer;
 
 pub use config::{config, Config};
 pub use events::SpanEvents;
 pub use export::{SpanData, SpanExporter};
+pub use fanout::{FanOutSpanExporter, FanOutSpanExporterBuilder};
 
 /// In-Memory span exporter for testing purpose.
 #[cfg(any(feature = "testing", test))]
@@ -36,19 +42,37 @@ pub use in_memory_exporter::{InMemorySpanExporter, InMemorySpanExporterBuilder};
 pub use id_generator::{IdGenerator, RandomIdGenerator};
 pub use links::SpanLinks;
 pub use provider::{SdkTracerProvider, TracerProviderBuilder};
-pub use sampler::{Sampler, ShouldSample};
+#[cfg(feature = "redaction")]
+pub use redaction::{RedactingSpanProcessor, RedactingSpanProcessorBuilder};
+pub use sampler::{
+    ConsistentFixedThreshold, ConsistentParentBased, RateLimitingSampler, Sampler, ShouldSample,
+};
 pub use span::Span;
+pub use span_filter::{
+    FilteringSpanProcessor, FilteringSpanProcessorBuilder, SpanFilter, SpanPredicate,
+};
 pub use span_limit::SpanLimits;
 pub use span_processor::{
     BatchConfig, BatchConfigBuilder, BatchSpanProcessor, BatchSpanProcessorBuilder,
     SimpleSpanProcessor, SpanProcessor,
 };
 
+pub use tail_sampling::{
+    ShouldKeepTrace, TailSamplingPolicy, TailSamplingSpanProcessor,
+    TailSamplingSpanProcessorBuilder, TailSamplingStats,
+};
 pub use tracer::SdkTracer;
 pub use tracer::SdkTracer as Tracer; // for back-compat else tracing-opentelemetry won't build
 
+#[cfg(feature = "jaeger_remote_sampler_grpc")]
+pub use sampler::GrpcStrategySource;
+#[cfg(feature = "rule_based_sampler")]
+pub use sampler::{
+    AttributeCondition, MatchPattern, RuleBasedSampler, RuleBasedSamplerBuilder,
+    RuleBasedSamplerConfig, SamplerConfig, SamplingRule, SamplingRuleConfig,
+};
 #[cfg(feature = "jaeger_remote_sampler")]
-pub use sampler::{JaegerRemoteSampler, JaegerRemoteSamplerBuilder};
+pub use sampler::{FileStrategySource, JaegerRemoteSampler, JaegerRemoteSamplerBuilder};
 
 #[cfg(feature = "experimental_trace_batch_span_processor_with_async_runtime")]
 #[cfg(test)]
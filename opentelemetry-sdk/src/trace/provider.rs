@@ -1,4 +1,8 @@
 use crate::error::{OTelSdkError, OTelSdkResult};
+#[cfg(feature = "jaeger_remote_sampler")]
+use crate::resource::SERVICE_NAME;
+#[cfg(feature = "jaeger_remote_sampler")]
+use crate::trace::sampler::JaegerRemoteEnvHook;
 /// # Trace Provider SDK
 ///
 /// The `TracerProvider` handles the creation and management of [`Tracer`] instances and coordinates
@@ -70,6 +74,8 @@ use crate::trace::{
 use crate::Resource;
 use crate::{trace::SpanExporter, trace::SpanProcessor};
 use opentelemetry::otel_debug;
+#[cfg(feature = "jaeger_remote_sampler")]
+use opentelemetry::otel_warn;
 use opentelemetry::{otel_info, InstrumentationScope};
 use std::borrow::Cow;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -94,6 +100,8 @@ fn noop_tracer_provider() -> &'static SdkTracerProvider {
                     id_generator: Box::<RandomIdGenerator>::default(),
                     span_limits: SpanLimits::default(),
                     resource: Cow::Owned(Resource::empty()),
+                    #[cfg(feature = "jaeger_remote_sampler")]
+                    jaeger_remote_env: None,
                 },
                 is_shutdown: AtomicBool::new(true),
             }),
@@ -290,6 +298,8 @@ impl opentelemetry::trace::TracerProvider for SdkTracerProvider {
 pub struct TracerProviderBuilder {
     processors: Vec<Box<dyn SpanProcessor>>,
     config: crate::trace::Config,
+    #[cfg(feature = "jaeger_remote_sampler")]
+    jaeger_remote_env_hook: Option<JaegerRemoteEnvHook>,
 }
 
 impl TracerProviderBuilder {
@@ -355,11 +365,48 @@ impl TracerProviderBuilder {
     }
 
     /// Specify the sampler to be used.
+    ///
+    /// It takes precedence over the sampler selected by the `OTEL_TRACES_SAMPLER`
+    /// environment variable.
     pub fn with_sampler<T: crate::trace::ShouldSample + 'static>(mut self, sampler: T) -> Self {
         self.config.sampler = Box::new(sampler);
+        #[cfg(feature = "jaeger_remote_sampler")]
+        {
+            self.config.jaeger_remote_env = None;
+        }
         self
     }
 
+    /// Provide the runtime and HTTP client of the Jaeger remote sampler
+    /// selected by the `OTEL_TRACES_SAMPLER` environment variable, when it is
+    /// set to `jaeger_remote` or `parentbased_jaeger_remote`.
+    ///
+    /// The sampler is built with the provider, for the `service.name` of its
+    /// resource, and configured by the `OTEL_TRACES_SAMPLER_ARG` environment
+    /// variable, a comma separated list of:
+    /// - `endpoint`: the sampling endpoint, by default `http://localhost:5778/sampling`.
+    /// - `pollingIntervalMs`: how often to fetch the sampling strategy, by default every 5 minutes.
+    /// - `initialSamplingRate`: the sampling ratio used until the first strategy is
+    ///   fetched, by default `0.001`.
+    ///
+    /// For example `endpoint=http://localhost:5778/sampling,pollingIntervalMs=5000,initialSamplingRate=0.25`.
+    ///
+    /// It has no effect if the environment variable selects another sampler, or
+    /// if a sampler is set with [`TracerProviderBuilder::with_sampler`]. Without
+    /// it, the Jaeger remote samplers selected by the environment variable fall
+    /// back to `ParentBased(AlwaysOn)`.
+    #[cfg(feature = "jaeger_remote_sampler")]
+    pub fn with_jaeger_remote_sampler_from_env<R, C>(self, runtime: R, http_client: C) -> Self
+    where
+        R: crate::runtime::RuntimeChannel,
+        C: opentelemetry_http::HttpClient + 'static,
+    {
+        TracerProviderBuilder {
+            jaeger_remote_env_hook: Some(JaegerRemoteEnvHook::new(runtime, http_client)),
+            ..self
+        }
+    }
+
     /// Specify the id generator to be used.
     pub fn with_id_generator<T: IdGenerator + 'static>(mut self, id_generator: T) -> Self {
         self.config.id_generator = Box::new(id_generator);
@@ -396,6 +443,13 @@ impl TracerProviderBuilder {
         self
     }
 
+    /// Specify the max length, in characters, of string attribute values of
+    /// spans, events and links. Longer values are truncated.
+    pub fn with_max_attribute_value_length(mut self, max_length: u32) -> Self {
+        self.config.span_limits.max_attribute_value_length = Some(max_length);
+        self
+    }
+
     /// Specify all limit via the span_limits
     pub fn with_span_limits(mut self, span_limits: SpanLimits) -> Self {
         self.config.span_limits = span_limits;
@@ -437,6 +491,35 @@ impl TracerProviderBuilder {
                 };
         }
 
+        #[cfg(feature = "jaeger_remote_sampler")]
+        if let Some(env_config) = config.jaeger_remote_env.take() {
+            match self.jaeger_remote_env_hook {
+                Some(hook) => {
+                    let service_name = config
+                        .resource
+                        .get(&SERVICE_NAME.into())
+                        .map(|name| name.to_string())
+                        .unwrap_or_default();
+                    match hook.build(&env_config, &service_name) {
+                        Ok(sampler) => config.sampler = sampler,
+                        Err(err) => {
+                            otel_warn!(
+                                name: "TracerProvider.Config.InvalidSamplerArgument",
+                                message = "Failed to build the Jaeger remote sampler selected by OTEL_TRACES_SAMPLER. Using fallback sampler: ParentBased(AlwaysOn)",
+                                reason = format!("{}", err)
+                            );
+                        }
+                    }
+                }
+                None => {
+                    otel_warn!(
+                        name: "TracerProvider.Config.UnsupportedSampler",
+                        message = "OTEL_TRACES_SAMPLER is set to a Jaeger remote sampler, but no runtime and HTTP client were provided with TracerProviderBuilder::with_jaeger_remote_sampler_from_env. Using fallback sampler: ParentBased(AlwaysOn)"
+                    );
+                }
+            }
+        }
+
         // Create a new vector to hold the modified processors
         let mut processors = self.processors;
 
@@ -686,6 +769,153 @@ mod tests {
         assert_eq!(no_service_name.config().resource.len(), 0)
     }
 
+    #[test]
+    fn test_attribute_value_length_limit_from_env() {
+        let max_length = || {
+            super::SdkTracerProvider::builder()
+                .build()
+                .config()
+                .span_limits
+                .max_attribute_value_length
+        };
+
+        temp_env::with_vars_unset(
+            [
+                "OTEL_SPAN_ATTRIBUTE_VALUE_LENGTH_LIMIT",
+                "OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT",
+            ],
+            || assert_eq!(max_length(), None),
+        );
+        temp_env::with_vars(
+            [
+                ("OTEL_SPAN_ATTRIBUTE_VALUE_LENGTH_LIMIT", None),
+                ("OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT", Some("20")),
+            ],
+            || assert_eq!(max_length(), Some(20)),
+        );
+        // the span specific limit takes precedence over the general one
+        temp_env::with_vars(
+            [
+                ("OTEL_SPAN_ATTRIBUTE_VALUE_LENGTH_LIMIT", Some("10")),
+                ("OTEL_ATTRIBUTE_VALUE_LENGTH_LIMIT", Some("20")),
+            ],
+            || assert_eq!(max_length(), Some(10)),
+        );
+    }
+
+    #[test]
+    fn test_attribute_count_limit_from_env() {
+        let max_attributes = |span, general| {
+            temp_env::with_vars(
+                [
+                    ("OTEL_SPAN_ATTRIBUTE_COUNT_LIMIT", span),
+                    ("OTEL_ATTRIBUTE_COUNT_LIMIT", general),
+                ],
+                || {
+                    super::SdkTracerProvider::builder()
+                        .build()
+                        .config()
+                        .span_limits
+                        .max_attributes_per_span
+                },
+            )
+        };
+
+        assert_eq!(max_attributes(None, None), 128);
+        assert_eq!(max_attributes(None, Some("20")), 20);
+        // the span specific limit takes precedence over the general one
+        assert_eq!(max_attributes(Some("10"), Some("20")), 10);
+    }
+
+    #[test]
+    fn test_rate_limiting_sampler_from_env() {
+        let sampler = |sampler: &str, arg: Option<&str>| {
+            temp_env::with_vars(
+                [
+                    ("OTEL_TRACES_SAMPLER", Some(sampler)),
+                    ("OTEL_TRACES_SAMPLER_ARG", arg),
+                ],
+                || {
+                    format!(
+                        "{:?}",
+                        super::SdkTracerProvider::builder().build().config().sampler
+                    )
+                },
+            )
+        };
+
+        assert_eq!(
+            sampler("ratelimiting", Some("5")),
+            "RateLimited(RateLimitingSampler { traces_per_second: 5.0 })"
+        );
+        assert_eq!(
+            sampler("parentbased_ratelimiting", Some("0.5")),
+            "ParentBased(RateLimited(RateLimitingSampler { traces_per_second: 0.5 }))"
+        );
+        assert_eq!(sampler("ratelimiting", None), "AlwaysOn");
+        assert_eq!(
+            sampler("parentbased_ratelimiting", Some("many")),
+            "ParentBased(AlwaysOn)"
+        );
+    }
+
+    #[cfg(feature = "jaeger_remote_sampler")]
+    #[tokio::test]
+    async fn test_jaeger_remote_sampler_from_env() {
+        #[derive(Debug)]
+        struct NoopHttpClient;
+
+        #[async_trait::async_trait]
+        impl opentelemetry_http::HttpClient for NoopHttpClient {
+            async fn send_bytes(
+                &self,
+                _request: opentelemetry_http::Request<opentelemetry_http::Bytes>,
+            ) -> Result<
+                opentelemetry_http::Response<opentelemetry_http::Bytes>,
+                opentelemetry_http::HttpError,
+            > {
+                Err("unavailable".into())
+            }
+        }
+
+        let sampler = |sampler: &str, with_hook: bool, with_sampler: bool| {
+            temp_env::with_vars(
+                [
+                    ("OTEL_TRACES_SAMPLER", Some(sampler)),
+                    (
+                        "OTEL_TRACES_SAMPLER_ARG",
+                        Some("endpoint=http://localhost:5778/sampling,initialSamplingRate=0.5"),
+                    ),
+                ],
+                || {
+                    let mut builder = super::SdkTracerProvider::builder();
+                    if with_hook {
+                        builder = builder.with_jaeger_remote_sampler_from_env(
+                            crate::runtime::Tokio,
+                            NoopHttpClient,
+                        );
+                    }
+                    if with_sampler {
+                        builder = builder.with_sampler(crate::trace::Sampler::AlwaysOff);
+                    }
+                    format!("{:?}", builder.build().config().sampler)
+                },
+            )
+        };
+
+        assert!(sampler("jaeger_remote", true, false).starts_with("JaegerRemote("));
+        assert!(sampler("parentbased_jaeger_remote", true, false)
+            .starts_with("ParentBased(JaegerRemote("));
+        // falls back to the default sampler without a runtime and HTTP client
+        assert_eq!(
+            sampler("jaeger_remote", false, false),
+            "ParentBased(AlwaysOn)"
+        );
+        // the sampler of the builder takes precedence
+        assert_eq!(sampler("jaeger_remote", true, true), "AlwaysOff");
+        assert_eq!(sampler("always_off", true, false), "AlwaysOff");
+    }
+
     #[test]
     fn test_shutdown_noops() {
         let processor = TestSpanProcessor::new(false);
@@ -0,0 +1,184 @@
+use crate::error::OTelSdkResult;
+use crate::redaction::{RedactionRule, Redactor};
+use crate::resource::Resource;
+use crate::trace::{Span, SpanData, SpanProcessor};
+use opentelemetry::trace::TraceError;
+use opentelemetry::Context;
+
+/// A [`SpanProcessor`] applying [`RedactionRule`]s to the finished spans,
+/// before forwarding them to another span processor.
+///
+/// The rules apply, in order, to the attributes of the spans, and to the
+/// attributes of their events and links. The spans given to the `on_start`
+/// callback of the downstream processor are not redacted.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry_sdk::redaction::RedactionRule;
+/// use opentelemetry_sdk::testing::trace::NoopSpanExporter;
+/// use opentelemetry_sdk::trace::{BatchSpanProcessor, RedactingSpanProcessor, SdkTracerProvider};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// # std::env::set_var("REDACTION_SECRET", "secret");
+/// let processor = RedactingSpanProcessor::builder(
+///     BatchSpanProcessor::builder(NoopSpanExporter::new()).build(),
+/// )
+/// .with_rule(RedactionRule::Delete("http.request.header.authorization".into()))
+/// .with_rule(RedactionRule::Hash {
+///     key: "user.email".into(),
+///     secret: std::env::var("REDACTION_SECRET")?.into_bytes(),
+/// })
+/// .with_rule(RedactionRule::Mask {
+///     pattern: r"\b\d{4}-\d{4}-\d{4}-\d{4}\b".into(),
+///     replacement: "****-****-****-****".into(),
+/// })
+/// .build()?;
+///
+/// let provider = SdkTracerProvider::builder()
+///     .with_span_processor(processor)
+///     .build();
+/// # drop(provider);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct RedactingSpanProcessor {
+    redactor: Redactor,
+    processor: Box<dyn SpanProcessor>,
+}
+
+impl RedactingSpanProcessor {
+    /// Create a builder of a processor forwarding the redacted spans to the
+    /// given span processor.
+    pub fn builder<P>(processor: P) -> RedactingSpanProcessorBuilder
+    where
+        P: SpanProcessor + 'static,
+    {
+        RedactingSpanProcessorBuilder {
+            processor: Box::new(processor),
+            rules: Vec::new(),
+        }
+    }
+}
+
+impl SpanProcessor for RedactingSpanProcessor {
+    fn on_start(&self, span: &mut Span, cx: &Context) {
+        self.processor.on_start(span, cx);
+    }
+
+    fn on_end(&self, mut span: SpanData) {
+        self.redactor.redact(&mut span.attributes);
+        for event in span.events.events.iter_mut() {
+            self.redactor.redact(&mut event.attributes);
+        }
+        for link in span.links.links.iter_mut() {
+            self.redactor.redact(&mut link.attributes);
+        }
+        self.processor.on_end(span);
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.processor.force_flush()
+    }
+
+    fn shutdown(&self) -> OTelSdkResult {
+        self.processor.shutdown()
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.processor.set_resource(resource);
+    }
+}
+
+/// Builder for [`RedactingSpanProcessor`].
+#[derive(Debug)]
+pub struct RedactingSpanProcessorBuilder {
+    processor: Box<dyn SpanProcessor>,
+    rules: Vec<RedactionRule>,
+}
+
+impl RedactingSpanProcessorBuilder {
+    /// Add a rule, applied after the rules already added.
+    pub fn with_rule(mut self, rule: RedactionRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Build a [`RedactingSpanProcessor`] applying the provided rules.
+    ///
+    /// Return errors if any of the regular expressions is invalid.
+    pub fn build(self) -> Result<RedactingSpanProcessor, TraceError> {
+        Ok(RedactingSpanProcessor {
+            redactor: Redactor::new(self.rules)?,
+            processor: self.processor,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::testing::trace::new_test_export_span_data;
+    use crate::trace::{InMemorySpanExporterBuilder, SimpleSpanProcessor};
+    use opentelemetry::trace::{Event, Link, SpanContext};
+    use opentelemetry::KeyValue;
+    use std::time::SystemTime;
+
+    #[test]
+    fn redacts_span_event_and_link_attributes() {
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor =
+            RedactingSpanProcessor::builder(SimpleSpanProcessor::new(Box::new(exporter.clone())))
+                .with_rule(RedactionRule::Delete("password".into()))
+                .with_rule(RedactionRule::Rename {
+                    from: "email".into(),
+                    to: "user.email".into(),
+                })
+                .build()
+                .unwrap();
+
+        let mut span = new_test_export_span_data();
+        span.attributes = vec![
+            KeyValue::new("password", "secret"),
+            KeyValue::new("email", "jane@example.com"),
+        ];
+        span.events.events.push(Event::new(
+            "login",
+            SystemTime::now(),
+            vec![KeyValue::new("password", "secret")],
+            0,
+        ));
+        span.links.links.push(Link::new(
+            SpanContext::empty_context(),
+            vec![KeyValue::new("email", "joe@example.com")],
+            0,
+        ));
+        processor.on_end(span);
+
+        let spans = exporter.get_finished_spans().unwrap();
+        assert_eq!(
+            spans[0].attributes,
+            vec![KeyValue::new("user.email", "jane@example.com")]
+        );
+        assert!(spans[0].events[0].attributes.is_empty());
+        assert_eq!(
+            spans[0].links.links[0].attributes,
+            vec![KeyValue::new("user.email", "joe@example.com")]
+        );
+    }
+
+    #[test]
+    fn invalid_rule() {
+        let err = RedactingSpanProcessor::builder(SimpleSpanProcessor::new(Box::new(
+            InMemorySpanExporterBuilder::new().build(),
+        )))
+        .with_rule(RedactionRule::Mask {
+            pattern: "[".into(),
+            replacement: "*".into(),
+        })
+        .build()
+        .unwrap_err();
+        assert!(err.to_string().contains("invalid regex"));
+    }
+}
@@ -5,13 +5,28 @@ use opentelemetry::{
     Context, KeyValue,
 };
 
+mod consistent;
 #[cfg(feature = "jaeger_remote_sampler")]
 mod jaeger_remote;
+mod rate_limit;
+#[cfg(feature = "rule_based_sampler")]
+mod rule_based;
 
+pub use consistent::{ConsistentFixedThreshold, ConsistentParentBased};
+#[cfg(feature = "jaeger_remote_sampler_grpc")]
+pub use jaeger_remote::GrpcStrategySource;
 #[cfg(feature = "jaeger_remote_sampler")]
-pub use jaeger_remote::{JaegerRemoteSampler, JaegerRemoteSamplerBuilder};
+pub use jaeger_remote::{FileStrategySource, JaegerRemoteSampler, JaegerRemoteSamplerBuilder};
+#[cfg(feature = "jaeger_remote_sampler")]
+pub(crate) use jaeger_remote::{JaegerRemoteEnvConfig, JaegerRemoteEnvHook};
 #[cfg(feature = "jaeger_remote_sampler")]
 use opentelemetry_http::HttpClient;
+pub use rate_limit::RateLimitingSampler;
+#[cfg(feature = "rule_based_sampler")]
+pub use rule_based::{
+    AttributeCondition, MatchPattern, RuleBasedSampler, RuleBasedSamplerBuilder,
+    RuleBasedSamplerConfig, SamplerConfig, SamplingRule, SamplingRuleConfig,
+};
 
 /// The [`ShouldSample`] interface allows implementations to provide samplers
 /// which will return a sampling [`SamplingResult`] based on information that
@@ -123,6 +138,10 @@ pub enum Sampler {
     /// *Note:* If this is used then all Spans in a trace will become sampled assuming that the
     /// first span is sampled as it is based on the `trace_id` not the `span_id`
     TraceIdRatioBased(f64),
+    /// Sample at most a given number of traces per second, see [`RateLimitingSampler`].
+    /// Like [`Sampler::TraceIdRatioBased`], it ignores the decision of the parent span,
+    /// wrap it in [`Sampler::ParentBased`] to only rate limit root spans.
+    RateLimited(RateLimitingSampler),
     /// Jaeger remote sampler supports any remote service that implemented the jaeger remote sampler protocol.
     /// The proto definition can be found [here](https://github.com/jaegertracing/jaeger-idl/blob/main/proto/api_v2/sampling.proto)
     ///
@@ -154,12 +173,60 @@ impl Sampler {
     ) -> JaegerRemoteSamplerBuilder<C, Sampler, R>
     where
         C: HttpClient + 'static,
-        Sampler: ShouldSample,
+        Sampler: ShouldSample + 'static,
         R: crate::runtime::RuntimeChannel,
         Svc: Into<String>,
     {
         JaegerRemoteSamplerBuilder::new(runtime, http_client, default_sampler, service_name)
     }
+
+    /// Create a jaeger remote sampler builder reading the sampling strategy from a local file,
+    /// instead of fetching it from remote servers.
+    ///
+    /// The file holds a sampling strategy in the JSON format of the responses of the remote
+    /// sampling endpoint. It's read again whenever it's modified, which is checked at every
+    /// update interval, so the sampling can be changed without a redeploy.
+    ///
+    /// ### Arguments
+    /// * `runtime` - A runtime to check the file for changes.
+    /// * `default_sampler` - A default sampler to make a sampling decision until the file is read successfully.
+    /// * `path` - The path of the strategy file.
+    #[cfg(feature = "jaeger_remote_sampler")]
+    pub fn jaeger_remote_file<Sampler, R, P>(
+        runtime: R,
+        default_sampler: Sampler,
+        path: P,
+    ) -> JaegerRemoteSamplerBuilder<FileStrategySource, Sampler, R>
+    where
+        Sampler: ShouldSample + 'static,
+        R: crate::runtime::RuntimeChannel,
+        P: Into<std::path::PathBuf>,
+    {
+        JaegerRemoteSamplerBuilder::new_file(runtime, default_sampler, path)
+    }
+
+    /// Create a jaeger remote sampler builder fetching the sampling strategy with the
+    /// `SamplingManager.GetSamplingStrategy` gRPC API of Jaeger, usually served on port 14250.
+    ///
+    /// ### Arguments
+    /// * `runtime` - A runtime to run the gRPC client.
+    /// * `channel` - A gRPC channel to the Jaeger agent or collector.
+    /// * `default_sampler` - A default sampler to make a sampling decision when the remote is unavailable or before the SDK receives the first response from remote.
+    /// * `service_name` - The name of the service. This is a required parameter to query the sampling strategy.
+    #[cfg(feature = "jaeger_remote_sampler_grpc")]
+    pub fn jaeger_remote_grpc<Sampler, R, Svc>(
+        runtime: R,
+        channel: tonic::transport::Channel,
+        default_sampler: Sampler,
+        service_name: Svc,
+    ) -> JaegerRemoteSamplerBuilder<GrpcStrategySource, Sampler, R>
+    where
+        Sampler: ShouldSample + 'static,
+        R: crate::runtime::RuntimeChannel,
+        Svc: Into<String>,
+    {
+        JaegerRemoteSamplerBuilder::new_grpc(runtime, channel, default_sampler, service_name)
+    }
 }
 
 impl ShouldSample for Sampler {
@@ -205,6 +272,11 @@ impl ShouldSample for Sampler {
                 ),
             // Probabilistically sample the trace.
             Sampler::TraceIdRatioBased(prob) => sample_based_on_probability(prob, trace_id),
+            Sampler::RateLimited(rate_limiting_sampler) => {
+                rate_limiting_sampler
+                    .should_sample(parent_context, trace_id, name, span_kind, attributes, links)
+                    .decision
+            }
             #[cfg(feature = "jaeger_remote_sampler")]
             Sampler::JaegerRemote(remote_sampler) => {
                 remote_sampler
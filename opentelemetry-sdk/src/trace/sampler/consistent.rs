@@ -0,0 +1,509 @@
+//! Consistent probability samplers.
+//!
+//! These samplers make their decisions by comparing 56 bits of randomness of
+//! the trace with a rejection threshold, and record the threshold in the `th`
+//! sub-key of the `ot` entry of the [`TraceState`], so the adjusted count of
+//! sampled spans can be computed downstream. The randomness is read from the
+//! `rv` sub-key of the `ot` entry when present, or else from the 56 least
+//! significant bits of the trace id.
+//!
+//! See the [specification] for more details.
+//!
+//! [specification]: https://opentelemetry.io/docs/specs/otel/trace/tracestate-probability-sampling/
+
+use crate::trace::ShouldSample;
+use opentelemetry::{
+    trace::{
+        Link, SamplingDecision, SamplingResult, SpanKind, TraceContextExt, TraceId, TraceState,
+    },
+    Context, KeyValue,
+};
+
+/// The key of the OpenTelemetry entry of the trace state.
+const OT_KEY: &str = "ot";
+/// The number of hexadecimal digits of thresholds and randomness values.
+const HEX_DIGITS: usize = 14;
+/// The number of possible randomness values, `2^56`. As a threshold, it
+/// rejects all spans.
+const MAX_THRESHOLD: u64 = 1 << 56;
+const RANDOMNESS_MASK: u64 = MAX_THRESHOLD - 1;
+
+/// A consistent probability sampler, sampling spans with a fixed probability.
+///
+/// Spans are sampled if the randomness of their trace is greater than or equal
+/// to the rejection threshold of the sampler, so that all spans of a trace
+/// sampled with the same probability are either sampled or dropped together,
+/// and spans sampled with a higher probability are a superset of the ones
+/// sampled with a lower probability.
+///
+/// The threshold of sampled spans is recorded in the `th` sub-key of the `ot`
+/// entry of their trace state, and removed from the trace state of dropped
+/// spans.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry_sdk::trace::{ConsistentFixedThreshold, ConsistentParentBased, SdkTracerProvider};
+///
+/// let provider = SdkTracerProvider::builder()
+///     .with_sampler(ConsistentParentBased::new(ConsistentFixedThreshold::new(0.25)))
+///     .build();
+/// # drop(provider);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConsistentFixedThreshold {
+    threshold: u64,
+}
+
+impl ConsistentFixedThreshold {
+    /// Create a sampler sampling spans with the given probability.
+    ///
+    /// Probabilities greater than or equal to 1 sample all spans. Probabilities
+    /// lower than `2^-57`, which round to a zero probability in 56 bits, as well
+    /// as NaN, sample no spans.
+    pub fn new(probability: f64) -> Self {
+        ConsistentFixedThreshold {
+            threshold: probability_to_threshold(probability),
+        }
+    }
+
+    /// The rejection threshold of the sampler, between 0 (sample all spans)
+    /// and `2^56` (sample no spans).
+    pub fn threshold(&self) -> u64 {
+        self.threshold
+    }
+
+    /// The sampling probability of the sampler.
+    pub fn probability(&self) -> f64 {
+        (MAX_THRESHOLD - self.threshold) as f64 / MAX_THRESHOLD as f64
+    }
+}
+
+impl ShouldSample for ConsistentFixedThreshold {
+    fn should_sample(
+        &self,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+        _name: &str,
+        _span_kind: &SpanKind,
+        _attributes: &[KeyValue],
+        _links: &[Link],
+    ) -> SamplingResult {
+        let trace_state = parent_trace_state(parent_context);
+        let mut ot = OtTraceState::parse(&trace_state);
+
+        let sampled = self.threshold < MAX_THRESHOLD
+            && ot
+                .randomness
+                .unwrap_or_else(|| trace_id_randomness(trace_id))
+                >= self.threshold;
+        ot.threshold = sampled.then_some(self.threshold);
+
+        SamplingResult {
+            decision: if sampled {
+                SamplingDecision::RecordAndSample
+            } else {
+                SamplingDecision::Drop
+            },
+            attributes: Vec::new(),
+            trace_state: ot.write_to(trace_state),
+        }
+    }
+}
+
+/// A consistent probability sampler respecting the decision of the parent
+/// span, and delegating the decision of root spans to another sampler,
+/// usually a [`ConsistentFixedThreshold`].
+///
+/// The threshold of a sampled parent is kept in the trace state of its
+/// children if it is consistent with the randomness of the trace, and removed
+/// otherwise, since the adjusted count of the span would be wrong.
+#[derive(Clone, Debug)]
+pub struct ConsistentParentBased {
+    root: Box<dyn ShouldSample>,
+}
+
+impl ConsistentParentBased {
+    /// Create a sampler delegating the decision of root spans to `root`.
+    pub fn new<S: ShouldSample + 'static>(root: S) -> Self {
+        ConsistentParentBased {
+            root: Box::new(root),
+        }
+    }
+}
+
+impl ShouldSample for ConsistentParentBased {
+    fn should_sample(
+        &self,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+        links: &[Link],
+    ) -> SamplingResult {
+        let Some(parent) = parent_context.filter(|cx| cx.has_active_span()) else {
+            return self.root.should_sample(
+                parent_context,
+                trace_id,
+                name,
+                span_kind,
+                attributes,
+                links,
+            );
+        };
+
+        let span = parent.span();
+        let parent_span_context = span.span_context();
+        let trace_state = parent_span_context.trace_state().clone();
+        let mut ot = OtTraceState::parse(&trace_state);
+
+        let sampled = parent_span_context.is_sampled();
+        if !sampled {
+            ot.threshold = None;
+        } else if let Some(threshold) = ot.threshold {
+            let randomness = ot
+                .randomness
+                .unwrap_or_else(|| trace_id_randomness(trace_id));
+            if randomness < threshold {
+                ot.threshold = None;
+            }
+        }
+
+        SamplingResult {
+            decision: if sampled {
+                SamplingDecision::RecordAndSample
+            } else {
+                SamplingDecision::Drop
+            },
+            attributes: Vec::new(),
+            trace_state: ot.write_to(trace_state),
+        }
+    }
+}
+
+/// Convert a sampling probability to a rejection threshold, rounded to the
+/// nearest multiple of `2^-56`.
+fn probability_to_threshold(probability: f64) -> u64 {
+    if probability >= 1.0 {
+        0
+    } else if probability > 0.0 {
+        // Multiplying by a power of two is exact, so the threshold is exact
+        // for the given probability, up to the rounding of probabilities
+        // lower than 2^-3 to 56 bits.
+        let scaled = (probability * MAX_THRESHOLD as f64).round() as u64;
+        MAX_THRESHOLD - scaled.min(MAX_THRESHOLD)
+    } else {
+        MAX_THRESHOLD
+    }
+}
+
+/// The 56 least significant bits of the trace id.
+fn trace_id_randomness(trace_id: TraceId) -> u64 {
+    let bytes = trace_id.to_bytes();
+    let (_, low) = bytes.split_at(8);
+    u64::from_be_bytes(low.try_into().unwrap()) & RANDOMNESS_MASK
+}
+
+fn parent_trace_state(parent_context: Option<&Context>) -> TraceState {
+    match parent_context {
+        Some(cx) => cx.span().span_context().trace_state().clone(),
+        None => TraceState::default(),
+    }
+}
+
+/// Encode a threshold with up to 14 hexadecimal digits, without trailing zeros.
+fn encode_threshold(threshold: u64) -> String {
+    if threshold == 0 {
+        return "0".to_string();
+    }
+    let encoded = format!("{threshold:0HEX_DIGITS$x}");
+    encoded.trim_end_matches('0').to_string()
+}
+
+/// Decode a threshold of 1 to 14 hexadecimal digits, with implied trailing zeros.
+fn decode_threshold(value: &str) -> Option<u64> {
+    if value.is_empty() || value.len() > HEX_DIGITS {
+        return None;
+    }
+    parse_hex(value).map(|threshold| threshold << (4 * (HEX_DIGITS - value.len())))
+}
+
+/// Decode a randomness value of exactly 14 hexadecimal digits.
+fn decode_randomness(value: &str) -> Option<u64> {
+    if value.len() != HEX_DIGITS {
+        return None;
+    }
+    parse_hex(value)
+}
+
+fn parse_hex(value: &str) -> Option<u64> {
+    if !value
+        .bytes()
+        .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+    {
+        return None;
+    }
+    u64::from_str_radix(value, 16).ok()
+}
+
+/// The sub-keys of the `ot` entry of the trace state.
+#[derive(Debug, Default, PartialEq)]
+struct OtTraceState {
+    threshold: Option<u64>,
+    randomness: Option<u64>,
+    /// The other sub-keys, kept as is.
+    other: Vec<String>,
+}
+
+impl OtTraceState {
+    /// Parse the `ot` entry of `trace_state`. Invalid thresholds and
+    /// randomness values are ignored, and removed when the entry is written.
+    fn parse(trace_state: &TraceState) -> Self {
+        let mut ot = OtTraceState::default();
+        let Some(value) = trace_state.get(OT_KEY) else {
+            return ot;
+        };
+
+        for field in value.split(';').filter(|field| !field.is_empty()) {
+            match field.split_once(':') {
+                Some(("th", threshold)) => ot.threshold = decode_threshold(threshold),
+                Some(("rv", randomness)) => ot.randomness = decode_randomness(randomness),
+                _ => ot.other.push(field.to_string()),
+            }
+        }
+        ot
+    }
+
+    fn encode(&self) -> String {
+        let mut fields = Vec::with_capacity(self.other.len() + 2);
+        if let Some(threshold) = self.threshold {
+            fields.push(format!("th:{}", encode_threshold(threshold)));
+        }
+        if let Some(randomness) = self.randomness {
+            fields.push(format!("rv:{randomness:0HEX_DIGITS$x}"));
+        }
+        fields.extend(self.other.iter().cloned());
+        fields.join(";")
+    }
+
+    /// Write the `ot` entry to `trace_state`, removing it if it is empty.
+    ///
+    /// The trace state is returned unchanged if the entry cannot be written.
+    fn write_to(&self, trace_state: TraceState) -> TraceState {
+        let encoded = self.encode();
+        if trace_state.get(OT_KEY).unwrap_or_default() == encoded {
+            return trace_state;
+        }
+        let updated = if encoded.is_empty() {
+            trace_state.delete(OT_KEY)
+        } else {
+            trace_state.insert(OT_KEY, encoded)
+        };
+        updated.unwrap_or(trace_state)
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::testing::trace::TestSpan;
+    use opentelemetry::trace::{SpanContext, SpanId, TraceFlags};
+    use std::str::FromStr;
+
+    fn sample(
+        sampler: &dyn ShouldSample,
+        parent: Option<(bool, &str)>,
+        trace_id: u128,
+    ) -> (SamplingDecision, String) {
+        let cx = parent.map(|(sampled, trace_state)| {
+            Context::current_with_span(TestSpan(SpanContext::new(
+                TraceId::from_u128(trace_id),
+                SpanId::from_u64(1),
+                if sampled {
+                    TraceFlags::SAMPLED
+                } else {
+                    TraceFlags::default()
+                },
+                true,
+                TraceState::from_str(trace_state).unwrap(),
+            )))
+        });
+        let result = sampler.should_sample(
+            cx.as_ref(),
+            TraceId::from_u128(trace_id),
+            "span",
+            &SpanKind::Internal,
+            &[],
+            &[],
+        );
+        (result.decision, result.trace_state.header())
+    }
+
+    #[test]
+    fn thresholds_of_probabilities() {
+        for (probability, threshold) in [
+            (1.0, 0),
+            (2.0, 0),
+            (0.5, 0x80000000000000),
+            (0.25, 0xc0000000000000),
+            (1.0 / 3.0, 0xaaaaaaaaaaaaac),
+            (2f64.powi(-56), MAX_THRESHOLD - 1),
+            (2f64.powi(-58), MAX_THRESHOLD),
+            (0.0, MAX_THRESHOLD),
+            (-1.0, MAX_THRESHOLD),
+            (f64::NAN, MAX_THRESHOLD),
+        ] {
+            let sampler = ConsistentFixedThreshold::new(probability);
+            assert_eq!(sampler.threshold(), threshold, "{probability}");
+        }
+        assert_eq!(ConsistentFixedThreshold::new(0.25).probability(), 0.25);
+    }
+
+    #[test]
+    fn threshold_encoding() {
+        for (threshold, encoded) in [
+            (0, "0"),
+            (0x80000000000000, "8"),
+            (0xc0000000000000, "c"),
+            (0xaaaaaaaaaaaaac, "aaaaaaaaaaaaac"),
+            (0x00100000000000, "001"),
+        ] {
+            assert_eq!(encode_threshold(threshold), encoded);
+            assert_eq!(decode_threshold(encoded), Some(threshold));
+        }
+        for invalid in ["", "0123456789abcde", "C", "x", "-1"] {
+            assert_eq!(decode_threshold(invalid), None, "{invalid}");
+        }
+        assert_eq!(decode_randomness("ffffffffffffff"), Some(RANDOMNESS_MASK));
+        assert_eq!(decode_randomness("fff"), None);
+    }
+
+    #[test]
+    fn parse_and_write_ot_trace_state() {
+        let trace_state = TraceState::from_str("ot=th:8;rv:00112233445566;xy:1,foo=bar").unwrap();
+        let mut ot = OtTraceState::parse(&trace_state);
+        assert_eq!(
+            ot,
+            OtTraceState {
+                threshold: Some(0x80000000000000),
+                randomness: Some(0x00112233445566),
+                other: vec!["xy:1".to_string()],
+            }
+        );
+
+        ot.threshold = Some(0);
+        assert_eq!(
+            ot.write_to(trace_state.clone()).header(),
+            "ot=th:0;rv:00112233445566;xy:1,foo=bar"
+        );
+
+        let ot = OtTraceState::default();
+        assert_eq!(ot.write_to(trace_state).header(), "foo=bar");
+
+        // invalid values are ignored
+        let trace_state = TraceState::from_str("ot=th:xyz;rv:1").unwrap();
+        assert_eq!(OtTraceState::parse(&trace_state), OtTraceState::default());
+    }
+
+    #[test]
+    fn fixed_threshold() {
+        let sampler = ConsistentFixedThreshold::new(0.5);
+        let half = 0x80000000000000u128;
+
+        // randomness from the trace id
+        assert_eq!(
+            sample(&sampler, None, half),
+            (SamplingDecision::RecordAndSample, "ot=th:8".to_string())
+        );
+        assert_eq!(
+            sample(&sampler, None, half - 1),
+            (SamplingDecision::Drop, String::new())
+        );
+        // only the 56 least significant bits of the trace id are used
+        assert_eq!(
+            sample(&sampler, None, (1 << 100) | (half - 1)).0,
+            SamplingDecision::Drop
+        );
+
+        // explicit randomness takes precedence, the threshold of the parent is replaced
+        assert_eq!(
+            sample(
+                &sampler,
+                Some((true, "ot=rv:c0000000000000;th:0,foo=bar")),
+                0
+            ),
+            (
+                SamplingDecision::RecordAndSample,
+                "ot=th:8;rv:c0000000000000,foo=bar".to_string()
+            )
+        );
+        assert_eq!(
+            sample(
+                &sampler,
+                Some((true, "foo=bar,ot=th:0;rv:10000000000000")),
+                0
+            ),
+            (
+                SamplingDecision::Drop,
+                "ot=rv:10000000000000,foo=bar".to_string()
+            )
+        );
+
+        let never = ConsistentFixedThreshold::new(0.0);
+        assert_eq!(
+            sample(&never, None, RANDOMNESS_MASK as u128).0,
+            SamplingDecision::Drop
+        );
+        let always = ConsistentFixedThreshold::new(1.0);
+        assert_eq!(
+            sample(&always, None, 0),
+            (SamplingDecision::RecordAndSample, "ot=th:0".to_string())
+        );
+    }
+
+    #[test]
+    fn parent_based() {
+        let sampler = ConsistentParentBased::new(ConsistentFixedThreshold::new(0.25));
+
+        // root spans are delegated to the root sampler
+        assert_eq!(
+            sample(&sampler, None, 0xc0000000000000),
+            (SamplingDecision::RecordAndSample, "ot=th:c".to_string())
+        );
+        assert_eq!(
+            sample(&sampler, None, 0xbfffffffffffff).0,
+            SamplingDecision::Drop
+        );
+
+        // the decision of the parent is respected, and its consistent threshold kept
+        assert_eq!(
+            sample(&sampler, Some((true, "ot=th:8,foo=bar")), 0x80000000000000),
+            (
+                SamplingDecision::RecordAndSample,
+                "ot=th:8,foo=bar".to_string()
+            )
+        );
+        // an inconsistent threshold is removed
+        assert_eq!(
+            sample(
+                &sampler,
+                Some((true, "ot=th:8;xy:1,foo=bar")),
+                0x7fffffffffffff
+            ),
+            (
+                SamplingDecision::RecordAndSample,
+                "ot=xy:1,foo=bar".to_string()
+            )
+        );
+        // the threshold of an unsampled parent is removed
+        assert_eq!(
+            sample(&sampler, Some((false, "ot=th:8")), 0xffffffffffffff),
+            (SamplingDecision::Drop, String::new())
+        );
+        assert_eq!(
+            sample(&sampler, Some((false, "foo=bar")), 0xffffffffffffff),
+            (SamplingDecision::Drop, "foo=bar".to_string())
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use crate::runtime::RuntimeChannel;
+use crate::trace::{Sampler, ShouldSample};
+use opentelemetry::otel_warn;
+use opentelemetry::trace::TraceError;
+use opentelemetry_http::HttpClient;
+use std::fmt::{Debug, Formatter};
+use std::time::Duration;
+
+/// The sampling rate of the default sampler, used until the first strategy is
+/// fetched, when `initialSamplingRate` is not set.
+const DEFAULT_INITIAL_SAMPLING_RATE: f64 = 0.001;
+
+/// The Jaeger remote sampler selected by the `OTEL_TRACES_SAMPLER` environment
+/// variable, configured by `OTEL_TRACES_SAMPLER_ARG`.
+///
+/// It cannot be built when the environment variables are read, as it needs a
+/// runtime and an HTTP client, which are provided with
+/// [`TracerProviderBuilder::with_jaeger_remote_sampler_from_env`].
+///
+/// [`TracerProviderBuilder::with_jaeger_remote_sampler_from_env`]: crate::trace::TracerProviderBuilder::with_jaeger_remote_sampler_from_env
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct JaegerRemoteEnvConfig {
+    pub(crate) parent_based: bool,
+    pub(crate) endpoint: Option<String>,
+    pub(crate) polling_interval: Option<Duration>,
+    pub(crate) initial_sampling_rate: f64,
+}
+
+impl JaegerRemoteEnvConfig {
+    /// Parse `OTEL_TRACES_SAMPLER_ARG`, a comma separated list of `key=value`
+    /// pairs with the `endpoint`, `pollingIntervalMs` and `initialSamplingRate`
+    /// keys. Invalid pairs are ignored.
+    pub(crate) fn parse(parent_based: bool, sampler_arg: Option<&str>) -> Self {
+        let mut config = JaegerRemoteEnvConfig {
+            parent_based,
+            endpoint: None,
+            polling_interval: None,
+            initial_sampling_rate: DEFAULT_INITIAL_SAMPLING_RATE,
+        };
+
+        let pairs = sampler_arg
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|pair| !pair.is_empty());
+        for pair in pairs {
+            let parsed = match pair.split_once('=').map(|(k, v)| (k.trim(), v.trim())) {
+                Some(("endpoint", endpoint)) if !endpoint.is_empty() => {
+                    config.endpoint = Some(endpoint.to_string());
+                    true
+                }
+                Some(("pollingIntervalMs", interval)) => interval
+                    .parse::<u64>()
+                    .map(|millis| config.polling_interval = Some(Duration::from_millis(millis)))
+                    .is_ok(),
+                Some(("initialSamplingRate", rate)) => rate
+                    .parse::<f64>()
+                    .map(|rate| config.initial_sampling_rate = rate)
+                    .is_ok(),
+                _ => false,
+            };
+            if !parsed {
+                otel_warn!(
+                    name: "TracerProvider.Config.InvalidSamplerArgument",
+                    message = "Ignoring invalid entry of OTEL_TRACES_SAMPLER_ARG for the Jaeger remote sampler. Expected a comma separated list of endpoint=<url>, pollingIntervalMs=<integer> and initialSamplingRate=<float>",
+                    entry = pair.to_string(),
+                );
+            }
+        }
+        config
+    }
+}
+
+type BuildSampler =
+    dyn FnOnce(&JaegerRemoteEnvConfig, &str) -> Result<Sampler, TraceError> + Send + Sync;
+
+/// Builds the Jaeger remote sampler selected by the environment variables,
+/// with the runtime and HTTP client provided to the tracer provider builder.
+pub(crate) struct JaegerRemoteEnvHook(Box<BuildSampler>);
+
+impl JaegerRemoteEnvHook {
+    pub(crate) fn new<R, C>(runtime: R, http_client: C) -> Self
+    where
+        R: RuntimeChannel,
+        C: HttpClient + 'static,
+    {
+        JaegerRemoteEnvHook(Box::new(move |config, service_name| {
+            let mut builder = Sampler::jaeger_remote(
+                runtime,
+                http_client,
+                Sampler::TraceIdRatioBased(config.initial_sampling_rate),
+                service_name,
+            );
+            if let Some(endpoint) = &config.endpoint {
+                builder = builder.with_endpoint(endpoint.clone());
+            }
+            if let Some(interval) = config.polling_interval {
+                builder = builder.with_update_interval(interval);
+            }
+            builder.build()
+        }))
+    }
+
+    /// Build the sampler for `service_name`.
+    pub(crate) fn build(
+        self,
+        config: &JaegerRemoteEnvConfig,
+        service_name: &str,
+    ) -> Result<Box<dyn ShouldSample>, TraceError> {
+        let sampler = (self.0)(config, service_name)?;
+        Ok(if config.parent_based {
+            Box::new(Sampler::ParentBased(Box::new(sampler)))
+        } else {
+            Box::new(sampler)
+        })
+    }
+}
+
+impl Debug for JaegerRemoteEnvHook {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("JaegerRemoteEnvHook")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_sampler_arg() {
+        assert_eq!(
+            JaegerRemoteEnvConfig::parse(false, None),
+            JaegerRemoteEnvConfig {
+                parent_based: false,
+                endpoint: None,
+                polling_interval: None,
+                initial_sampling_rate: DEFAULT_INITIAL_SAMPLING_RATE,
+            }
+        );
+
+        assert_eq!(
+            JaegerRemoteEnvConfig::parse(
+                true,
+                Some("endpoint=http://localhost:5778/sampling, pollingIntervalMs=5000,initialSamplingRate=0.25")
+            ),
+            JaegerRemoteEnvConfig {
+                parent_based: true,
+                endpoint: Some("http://localhost:5778/sampling".to_string()),
+                polling_interval: Some(Duration::from_millis(5000)),
+                initial_sampling_rate: 0.25,
+            }
+        );
+
+        // invalid entries are ignored
+        assert_eq!(
+            JaegerRemoteEnvConfig::parse(
+                false,
+                Some("pollingIntervalMs=soon,initialSamplingRate=0.5,unknown=1,endpoint=")
+            ),
+            JaegerRemoteEnvConfig {
+                parent_based: false,
+                endpoint: None,
+                polling_interval: None,
+                initial_sampling_rate: 0.5,
+            }
+        );
+    }
+}
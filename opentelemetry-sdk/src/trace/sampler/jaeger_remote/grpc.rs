@@ -0,0 +1,248 @@
+//! Client of the `SamplingManager` gRPC service of Jaeger.
+//!
+//! The messages are defined in [sampling.proto].
+//!
+//! [sampling.proto]: https://github.com/jaegertracing/jaeger-idl/blob/main/proto/api_v2/sampling.proto
+
+use crate::trace::sampler::jaeger_remote::remote;
+use crate::trace::sampler::jaeger_remote::source::StrategySource;
+use crate::util::timer::timeout;
+use futures_util::future::BoxFuture;
+use std::time::Duration;
+use tonic::transport::Channel;
+
+const GET_SAMPLING_STRATEGY_PATH: &str = "/jaeger.api_v2.SamplingManager/GetSamplingStrategy";
+
+/// How long a fetch of the sampling strategy may take by default.
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A gRPC channel to the `SamplingManager` service of a Jaeger agent or
+/// collector.
+///
+/// See [`Sampler::jaeger_remote_grpc`] for details.
+///
+/// [`Sampler::jaeger_remote_grpc`]: crate::trace::Sampler::jaeger_remote_grpc
+#[derive(Debug)]
+pub struct GrpcStrategySource {
+    client: tonic::client::Grpc<Channel>,
+    service_name: String,
+    pub(crate) request_timeout: Duration,
+}
+
+impl GrpcStrategySource {
+    pub(crate) fn new(channel: Channel, service_name: String) -> Self {
+        GrpcStrategySource {
+            client: tonic::client::Grpc::new(channel),
+            service_name,
+            request_timeout: DEFAULT_REQUEST_TIMEOUT,
+        }
+    }
+
+    async fn get_sampling_strategy(&mut self) -> Result<remote::SamplingStrategyResponse, String> {
+        self.client
+            .ready()
+            .await
+            .map_err(|err| format!("the gRPC channel is not ready, {}", err))?;
+
+        let mut request = tonic::Request::new(SamplingStrategyParameters {
+            service_name: self.service_name.clone(),
+        });
+        // let the server know when the client gives up
+        request.set_timeout(self.request_timeout);
+        let response: tonic::Response<SamplingStrategyResponse> = self
+            .client
+            .unary(
+                request,
+                http::uri::PathAndQuery::from_static(GET_SAMPLING_STRATEGY_PATH),
+                tonic::codec::ProstCodec::default(),
+            )
+            .await
+            .map_err(|status| format!("the gRPC request failed, {}", status))?;
+
+        Ok(response.into_inner().into())
+    }
+}
+
+impl StrategySource for GrpcStrategySource {
+    fn fetch(&mut self) -> BoxFuture<'_, Result<Option<remote::SamplingStrategyResponse>, String>> {
+        Box::pin(async move {
+            let request_timeout = self.request_timeout;
+            match timeout(request_timeout, self.get_sampling_strategy()).await {
+                Ok(response) => response.map(Some),
+                Err(_) => Err(format!(
+                    "the gRPC request timed out after {:?}",
+                    request_timeout
+                )),
+            }
+        })
+    }
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct SamplingStrategyParameters {
+    #[prost(string, tag = "1")]
+    service_name: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, prost::Enumeration)]
+#[repr(i32)]
+enum SamplingStrategyType {
+    Probabilistic = 0,
+    RateLimiting = 1,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct ProbabilisticSamplingStrategy {
+    #[prost(double, tag = "1")]
+    sampling_rate: f64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct RateLimitingSamplingStrategy {
+    #[prost(int32, tag = "1")]
+    max_traces_per_second: i32,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct OperationSamplingStrategy {
+    #[prost(string, tag = "1")]
+    operation: String,
+    #[prost(message, optional, tag = "2")]
+    probabilistic_sampling: Option<ProbabilisticSamplingStrategy>,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct PerOperationSamplingStrategies {
+    #[prost(double, tag = "1")]
+    default_sampling_probability: f64,
+    #[prost(double, tag = "2")]
+    default_lower_bound_traces_per_second: f64,
+    #[prost(message, repeated, tag = "3")]
+    per_operation_strategies: Vec<OperationSamplingStrategy>,
+    #[prost(double, tag = "4")]
+    default_upper_bound_traces_per_second: f64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+struct SamplingStrategyResponse {
+    #[prost(enumeration = "SamplingStrategyType", tag = "1")]
+    strategy_type: i32,
+    #[prost(message, optional, tag = "2")]
+    probabilistic_sampling: Option<ProbabilisticSamplingStrategy>,
+    #[prost(message, optional, tag = "3")]
+    rate_limiting_sampling: Option<RateLimitingSamplingStrategy>,
+    #[prost(message, optional, tag = "4")]
+    operation_sampling: Option<PerOperationSamplingStrategies>,
+}
+
+impl From<ProbabilisticSamplingStrategy> for remote::ProbabilisticSamplingStrategy {
+    fn from(strategy: ProbabilisticSamplingStrategy) -> Self {
+        remote::ProbabilisticSamplingStrategy {
+            sampling_rate: strategy.sampling_rate,
+        }
+    }
+}
+
+impl From<SamplingStrategyResponse> for remote::SamplingStrategyResponse {
+    fn from(response: SamplingStrategyResponse) -> Self {
+        remote::SamplingStrategyResponse {
+            strategy_type: match SamplingStrategyType::try_from(response.strategy_type) {
+                Ok(SamplingStrategyType::RateLimiting) => {
+                    remote::SamplingStrategyType::RateLimiting
+                }
+                _ => remote::SamplingStrategyType::Probabilistic,
+            },
+            probabilistic_sampling: response.probabilistic_sampling.map(Into::into),
+            rate_limiting_sampling: response.rate_limiting_sampling.map(|strategy| {
+                remote::RateLimitingSamplingStrategy {
+                    max_traces_per_second: strategy.max_traces_per_second,
+                }
+            }),
+            operation_sampling: response.operation_sampling.map(|strategies| {
+                remote::PerOperationSamplingStrategies {
+                    default_sampling_probability: strategies.default_sampling_probability,
+                    default_lower_bound_traces_per_second: strategies
+                        .default_lower_bound_traces_per_second,
+                    // only probabilistic sampling is supported per operation
+                    per_operation_strategies: strategies
+                        .per_operation_strategies
+                        .into_iter()
+                        .filter_map(|strategy| {
+                            Some(remote::OperationSamplingStrategy {
+                                operation: strategy.operation,
+                                probabilistic_sampling: strategy.probabilistic_sampling?.into(),
+                            })
+                        })
+                        .collect(),
+                    default_upper_bound_traces_per_second: strategies
+                        .default_upper_bound_traces_per_second,
+                }
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prost::Message;
+
+    #[test]
+    fn decode_sampling_strategy_response() {
+        let response = SamplingStrategyResponse {
+            strategy_type: SamplingStrategyType::Probabilistic as i32,
+            probabilistic_sampling: None,
+            rate_limiting_sampling: None,
+            operation_sampling: Some(PerOperationSamplingStrategies {
+                default_sampling_probability: 0.1,
+                default_lower_bound_traces_per_second: 1.0,
+                per_operation_strategies: vec![
+                    OperationSamplingStrategy {
+                        operation: "GET /users".to_string(),
+                        probabilistic_sampling: Some(ProbabilisticSamplingStrategy {
+                            sampling_rate: 0.5,
+                        }),
+                    },
+                    OperationSamplingStrategy {
+                        operation: "GET /health".to_string(),
+                        probabilistic_sampling: None,
+                    },
+                ],
+                default_upper_bound_traces_per_second: 0.0,
+            }),
+        };
+        let decoded = SamplingStrategyResponse::decode(&response.encode_to_vec()[..]).unwrap();
+        assert_eq!(decoded, response);
+
+        let converted = remote::SamplingStrategyResponse::from(decoded);
+        assert!(converted.strategy_type == remote::SamplingStrategyType::Probabilistic);
+        assert!(converted.probabilistic_sampling.is_none());
+        let operation_sampling = converted.operation_sampling.unwrap();
+        assert_eq!(operation_sampling.default_sampling_probability, 0.1);
+        assert!(
+            operation_sampling.per_operation_strategies
+                == vec![remote::OperationSamplingStrategy {
+                    operation: "GET /users".to_string(),
+                    probabilistic_sampling: remote::ProbabilisticSamplingStrategy {
+                        sampling_rate: 0.5
+                    },
+                }]
+        );
+
+        let converted = remote::SamplingStrategyResponse::from(SamplingStrategyResponse {
+            strategy_type: SamplingStrategyType::RateLimiting as i32,
+            rate_limiting_sampling: Some(RateLimitingSamplingStrategy {
+                max_traces_per_second: 10,
+            }),
+            ..Default::default()
+        });
+        assert!(converted.strategy_type == remote::SamplingStrategyType::RateLimiting);
+        assert_eq!(
+            converted
+                .rate_limiting_sampling
+                .unwrap()
+                .max_traces_per_second,
+            10
+        );
+    }
+}
@@ -1,10 +1,17 @@
-mod rate_limit;
+mod env;
+#[cfg(feature = "jaeger_remote_sampler_grpc")]
+mod grpc;
 #[allow(dead_code)]
 mod remote;
 mod sampler;
 mod sampling_strategy;
+mod source;
 
+pub(crate) use env::{JaegerRemoteEnvConfig, JaegerRemoteEnvHook};
+#[cfg(feature = "jaeger_remote_sampler_grpc")]
+pub use grpc::GrpcStrategySource;
 pub use sampler::{JaegerRemoteSampler, JaegerRemoteSamplerBuilder};
+pub use source::FileStrategySource;
 
 #[cfg(test)]
 mod tests {}
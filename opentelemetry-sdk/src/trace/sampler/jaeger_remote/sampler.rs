@@ -1,12 +1,17 @@
 use crate::runtime::RuntimeChannel;
-use crate::trace::sampler::jaeger_remote::remote::SamplingStrategyResponse;
+#[cfg(feature = "jaeger_remote_sampler_grpc")]
+use crate::trace::sampler::jaeger_remote::grpc::GrpcStrategySource;
 use crate::trace::sampler::jaeger_remote::sampling_strategy::Inner;
+use crate::trace::sampler::jaeger_remote::source::{
+    FileStrategySource, HttpStrategySource, StrategySource,
+};
 use crate::trace::{Sampler, ShouldSample};
 use futures_util::{stream, StreamExt as _};
 use http::Uri;
 use opentelemetry::trace::{Link, SamplingResult, SpanKind, TraceError, TraceId};
 use opentelemetry::{otel_warn, Context, KeyValue};
 use opentelemetry_http::HttpClient;
+use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::Arc;
 use std::time::Duration;
@@ -14,14 +19,13 @@ use std::time::Duration;
 const DEFAULT_REMOTE_SAMPLER_ENDPOINT: &str = "http://localhost:5778/sampling";
 
 /// Builder for [`JaegerRemoteSampler`].
-/// See [Sampler::jaeger_remote] for details.
+///
+/// `C` is the source of the sampling strategies: an [`HttpClient`] querying the
+/// HTTP sampling endpoint (see [Sampler::jaeger_remote]), a [`FileStrategySource`]
+/// (see [Sampler::jaeger_remote_file]) or a `GrpcStrategySource` (see
+/// `Sampler::jaeger_remote_grpc`, with the `jaeger_remote_sampler_grpc` feature).
 #[derive(Debug)]
-pub struct JaegerRemoteSamplerBuilder<C, S, R>
-where
-    R: RuntimeChannel,
-    C: HttpClient + 'static,
-    S: ShouldSample + 'static,
-{
+pub struct JaegerRemoteSamplerBuilder<C, S, R> {
     pub(crate) update_interval: Duration,
     pub(crate) client: C,
     pub(crate) endpoint: String,
@@ -33,23 +37,17 @@ where
 
 impl<C, S, R> JaegerRemoteSamplerBuilder<C, S, R>
 where
-    C: HttpClient + 'static,
     S: ShouldSample + 'static,
     R: RuntimeChannel,
 {
-    pub(crate) fn new<Svc>(
-        runtime: R,
-        http_client: C,
-        default_sampler: S,
-        service_name: Svc,
-    ) -> Self
+    fn with_source<Svc>(runtime: R, source: C, default_sampler: S, service_name: Svc) -> Self
     where
         Svc: Into<String>,
     {
         JaegerRemoteSamplerBuilder {
             runtime,
             update_interval: Duration::from_secs(60 * 5),
-            client: http_client,
+            client: source,
             endpoint: DEFAULT_REMOTE_SAMPLER_ENDPOINT.to_string(),
             default_sampler,
             leaky_bucket_size: 100.0,
@@ -62,6 +60,8 @@ where
     /// By default it fetches every 5 minutes.
     ///
     /// A shorter interval have a performance overhead and should be avoid.
+    ///
+    /// With a [`FileStrategySource`], it's how often the file is checked for changes.
     pub fn with_update_interval(self, interval: Duration) -> Self {
         Self {
             update_interval: interval,
@@ -69,26 +69,45 @@ where
         }
     }
 
-    /// The endpoint of remote servers.
+    /// The size of the leaky bucket.
     ///
-    /// By default it's `http://localhost:5778/sampling`.
+    /// By default the size is 100.
     ///
-    /// If service name is provided as part of the endpoint, it will be ignored.
-    pub fn with_endpoint<Str: Into<String>>(self, endpoint: Str) -> Self {
+    /// It's used when sampling strategy is rate limiting.
+    pub fn with_leaky_bucket_size(self, size: f64) -> Self {
         Self {
-            endpoint: endpoint.into(),
+            leaky_bucket_size: size,
             ..self
         }
     }
+}
 
-    /// The size of the leaky bucket.
+impl<C, S, R> JaegerRemoteSamplerBuilder<C, S, R>
+where
+    C: HttpClient + 'static,
+    S: ShouldSample + 'static,
+    R: RuntimeChannel,
+{
+    pub(crate) fn new<Svc>(
+        runtime: R,
+        http_client: C,
+        default_sampler: S,
+        service_name: Svc,
+    ) -> Self
+    where
+        Svc: Into<String>,
+    {
+        Self::with_source(runtime, http_client, default_sampler, service_name)
+    }
+
+    /// The endpoint of remote servers.
     ///
-    /// By default the size is 100.
+    /// By default it's `http://localhost:5778/sampling`.
     ///
-    /// It's used when sampling strategy is rate limiting.
-    pub fn with_leaky_bucket_size(self, size: f64) -> Self {
+    /// If service name is provided as part of the endpoint, it will be ignored.
+    pub fn with_endpoint<Str: Into<String>>(self, endpoint: Str) -> Self {
         Self {
-            leaky_bucket_size: size,
+            endpoint: endpoint.into(),
             ..self
         }
     }
@@ -106,8 +125,10 @@ where
         Ok(Sampler::JaegerRemote(JaegerRemoteSampler::new(
             self.runtime,
             self.update_interval,
-            self.client,
-            endpoint,
+            HttpStrategySource {
+                client: self.client,
+                endpoint,
+            },
             self.default_sampler,
             self.leaky_bucket_size,
         )))
@@ -128,6 +149,80 @@ where
     }
 }
 
+impl<S, R> JaegerRemoteSamplerBuilder<FileStrategySource, S, R>
+where
+    S: ShouldSample + 'static,
+    R: RuntimeChannel,
+{
+    pub(crate) fn new_file<P: Into<PathBuf>>(runtime: R, default_sampler: S, path: P) -> Self {
+        Self::with_source(
+            runtime,
+            FileStrategySource::new(path.into()),
+            default_sampler,
+            String::new(),
+        )
+    }
+
+    /// Build a [JaegerRemoteSampler] reading its sampling strategy from the file.
+    pub fn build(self) -> Result<Sampler, TraceError> {
+        Ok(Sampler::JaegerRemote(JaegerRemoteSampler::new(
+            self.runtime,
+            self.update_interval,
+            self.client,
+            self.default_sampler,
+            self.leaky_bucket_size,
+        )))
+    }
+}
+
+#[cfg(feature = "jaeger_remote_sampler_grpc")]
+impl<S, R> JaegerRemoteSamplerBuilder<GrpcStrategySource, S, R>
+where
+    S: ShouldSample + 'static,
+    R: RuntimeChannel,
+{
+    pub(crate) fn new_grpc<Svc: Into<String>>(
+        runtime: R,
+        channel: tonic::transport::Channel,
+        default_sampler: S,
+        service_name: Svc,
+    ) -> Self {
+        let service_name = service_name.into();
+        Self::with_source(
+            runtime,
+            GrpcStrategySource::new(channel, service_name.clone()),
+            default_sampler,
+            service_name,
+        )
+    }
+
+    /// How long a fetch of the sampling strategy may take, including waiting
+    /// for the connection, before it is given up until the next update.
+    ///
+    /// By default it's 10 seconds.
+    pub fn with_request_timeout(mut self, timeout: Duration) -> Self {
+        self.client.request_timeout = timeout;
+        self
+    }
+
+    /// Build a [JaegerRemoteSampler] fetching its sampling strategy with gRPC.
+    ///
+    /// Return errors if the service name provided is empty.
+    pub fn build(self) -> Result<Sampler, TraceError> {
+        if self.service_name.is_empty() {
+            return Err(TraceError::Other("service name cannot be empty".into()));
+        }
+
+        Ok(Sampler::JaegerRemote(JaegerRemoteSampler::new(
+            self.runtime,
+            self.update_interval,
+            self.client,
+            self.default_sampler,
+            self.leaky_bucket_size,
+        )))
+    }
+}
+
 /// Sampler that fetches the sampling configuration from remotes.
 ///
 /// It offers the following sampling strategies:
@@ -136,6 +231,9 @@ where
 /// - **Per Operations**, instead of sampling all traces, it samples traces based on the span name. Only probabilistic sampling is supported at the moment.
 ///
 /// User can build a [`JaegerRemoteSampler`] by getting a [`JaegerRemoteSamplerBuilder`] from [`Sampler::jaeger_remote`].
+/// The sampling configuration can also be read from a local file with [`Sampler::jaeger_remote_file`],
+/// or fetched with the gRPC sampling API with `Sampler::jaeger_remote_grpc` and the
+/// `jaeger_remote_sampler_grpc` feature.
 ///
 /// Note that the backend doesn't need to be Jaeger so long as it supports jaeger remote sampling
 /// protocol.
@@ -146,17 +244,16 @@ pub struct JaegerRemoteSampler {
 }
 
 impl JaegerRemoteSampler {
-    fn new<C, R, S>(
+    fn new<Src, R, S>(
         runtime: R,
         update_timeout: Duration,
-        client: C,
-        endpoint: Uri,
+        source: Src,
         default_sampler: S,
         leaky_bucket_size: f64,
     ) -> Self
     where
         R: RuntimeChannel,
-        C: HttpClient + 'static,
+        Src: StrategySource,
         S: ShouldSample + 'static,
     {
         let (shutdown_tx, shutdown_rx) = futures_channel::mpsc::channel(1);
@@ -169,24 +266,22 @@ impl JaegerRemoteSampler {
             runtime,
             sampler.inner.clone(),
             update_timeout,
-            client,
+            source,
             shutdown_rx,
-            endpoint,
         );
         sampler
     }
 
     // start a updating thread/task
-    fn run_update_task<C, R>(
+    fn run_update_task<Src, R>(
         runtime: R,
         strategy: Arc<Inner>,
         update_timeout: Duration,
-        client: C,
+        mut source: Src,
         shutdown: futures_channel::mpsc::Receiver<()>,
-        endpoint: Uri,
     ) where
         R: RuntimeChannel,
-        C: HttpClient + 'static,
+        Src: StrategySource,
     {
         // todo: review if we need 'static here
         let interval = runtime.interval(update_timeout);
@@ -201,8 +296,10 @@ impl JaegerRemoteSampler {
                 if should_update {
                     // poll next available configuration or shutdown
                     // send request
-                    match Self::request_new_strategy(&client, endpoint.clone()).await {
-                        Ok(remote_strategy_resp) => strategy.update(remote_strategy_resp),
+                    match source.fetch().await {
+                        Ok(Some(remote_strategy_resp)) => strategy.update(remote_strategy_resp),
+                        // unchanged
+                        Ok(None) => {}
                         Err(err_msg) => {
                             otel_warn!(
                                 name: "JaegerRemoteSampler.FailedToFetchStrategy",
@@ -218,36 +315,6 @@ impl JaegerRemoteSampler {
             }
         }));
     }
-
-    async fn request_new_strategy<C>(
-        client: &C,
-        endpoint: Uri,
-    ) -> Result<SamplingStrategyResponse, String>
-    where
-        C: HttpClient,
-    {
-        let request = http::Request::get(endpoint)
-            .header("Content-Type", "application/json")
-            .body(Default::default())
-            .unwrap();
-
-        let resp = client
-            .send(request)
-            .await
-            .map_err(|err| format!("the request is failed to send {}", err))?;
-
-        // process failures
-        if resp.status() != http::StatusCode::OK {
-            return Err(format!(
-                "the http response code is not 200 but {}",
-                resp.status()
-            ));
-        }
-
-        // deserialize the response
-        serde_json::from_slice(&resp.body()[..])
-            .map_err(|err| format!("cannot deserialize the response, {}", err))
-    }
 }
 
 impl ShouldSample for JaegerRemoteSampler {
@@ -297,8 +364,67 @@ mod tests {
                 "samplingRate": 0.5
             }
         }"#;
-        let resp: super::SamplingStrategyResponse = serde_json::from_str(json).unwrap();
+        let resp: crate::trace::sampler::jaeger_remote::remote::SamplingStrategyResponse =
+            serde_json::from_str(json).unwrap();
         assert_eq!(resp.strategy_type, SamplingStrategyType::Probabilistic);
         assert_eq!(resp.probabilistic_sampling.unwrap().sampling_rate, 0.5);
     }
+
+    #[cfg(feature = "rt-tokio")]
+    #[tokio::test]
+    async fn reload_strategy_file() {
+        use crate::trace::{Sampler, ShouldSample};
+        use opentelemetry::trace::{SamplingDecision, SpanKind, TraceId};
+        use std::time::{Duration, SystemTime};
+
+        let path =
+            std::env::temp_dir().join(format!("jaeger-remote-sampler-{}.json", std::process::id()));
+        let write_strategy = |sampling_rate: f64, modified: SystemTime| {
+            let content = format!(
+                r#"{{"strategyType":"PROBABILISTIC","probabilisticSampling":{{"samplingRate":{sampling_rate}}}}}"#
+            );
+            std::fs::write(&path, content).unwrap();
+            std::fs::File::options()
+                .write(true)
+                .open(&path)
+                .unwrap()
+                .set_modified(modified)
+                .unwrap();
+        };
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        write_strategy(0.0, modified);
+
+        let sampler = Sampler::jaeger_remote_file(crate::runtime::Tokio, Sampler::AlwaysOn, &path)
+            .with_update_interval(Duration::from_millis(10))
+            .build()
+            .unwrap();
+        let wait_for = |expected: SamplingDecision| {
+            let sampler = sampler.clone();
+            async move {
+                for _ in 0..500 {
+                    let decision = sampler
+                        .should_sample(
+                            None,
+                            TraceId::from_u128(1),
+                            "op",
+                            &SpanKind::Internal,
+                            &[],
+                            &[],
+                        )
+                        .decision;
+                    if decision == expected {
+                        return;
+                    }
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+                panic!("the sampling decision never became {expected:?}");
+            }
+        };
+
+        wait_for(SamplingDecision::Drop).await;
+        write_strategy(1.0, modified + Duration::from_secs(1));
+        wait_for(SamplingDecision::RecordAndSample).await;
+
+        std::fs::remove_file(&path).unwrap();
+    }
 }
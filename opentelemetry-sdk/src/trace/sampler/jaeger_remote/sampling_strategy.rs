@@ -2,6 +2,7 @@ use crate::trace::sampler::jaeger_remote::remote::{
     PerOperationSamplingStrategies, ProbabilisticSamplingStrategy, RateLimitingSamplingStrategy,
     SamplingStrategyResponse,
 };
+use crate::trace::sampler::rate_limit::LeakyBucket;
 use crate::trace::sampler::sample_based_on_probability;
 use opentelemetry::trace::{
     SamplingDecision, SamplingResult, TraceContextExt, TraceId, TraceState,
@@ -11,8 +12,6 @@ use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
 use std::sync::Mutex;
 
-use super::rate_limit::LeakyBucket;
-
 // todo: remove the mutex as probabilistic doesn't require mutable ref
 // sampling strategy that sent by remote agents or collectors.
 enum Strategy {
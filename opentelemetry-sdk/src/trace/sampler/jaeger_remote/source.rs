@@ -0,0 +1,192 @@
+use crate::trace::sampler::jaeger_remote::remote::SamplingStrategyResponse;
+use futures_util::future::BoxFuture;
+use http::Uri;
+use opentelemetry_http::HttpClient;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+/// Where the Jaeger remote sampler fetches its sampling strategy from.
+pub(crate) trait StrategySource: Send + 'static {
+    /// Fetch the sampling strategy.
+    ///
+    /// Returns `None` if the strategy did not change since the last fetch.
+    fn fetch(&mut self) -> BoxFuture<'_, Result<Option<SamplingStrategyResponse>, String>>;
+}
+
+/// Fetches the sampling strategy from the HTTP sampling endpoint.
+pub(crate) struct HttpStrategySource<C> {
+    pub(crate) client: C,
+    pub(crate) endpoint: Uri,
+}
+
+impl<C: HttpClient + 'static> StrategySource for HttpStrategySource<C> {
+    fn fetch(&mut self) -> BoxFuture<'_, Result<Option<SamplingStrategyResponse>, String>> {
+        Box::pin(async move {
+            let request = http::Request::get(self.endpoint.clone())
+                .header("Content-Type", "application/json")
+                .body(Default::default())
+                .unwrap();
+
+            let resp = self
+                .client
+                .send_bytes(request)
+                .await
+                .map_err(|err| format!("the request is failed to send {}", err))?;
+
+            // process failures
+            if resp.status() != http::StatusCode::OK {
+                return Err(format!(
+                    "the http response code is not 200 but {}",
+                    resp.status()
+                ));
+            }
+
+            // deserialize the response
+            serde_json::from_slice(&resp.body()[..])
+                .map(Some)
+                .map_err(|err| format!("cannot deserialize the response, {}", err))
+        })
+    }
+}
+
+/// A local JSON file holding a sampling strategy, in the format of the
+/// responses of the HTTP sampling endpoint.
+///
+/// The file is read again whenever its modification time or its length
+/// changes, and on every fetch while it does not hold a valid strategy.
+/// See [`Sampler::jaeger_remote_file`] for details.
+///
+/// [`Sampler::jaeger_remote_file`]: crate::trace::Sampler::jaeger_remote_file
+#[derive(Debug)]
+pub struct FileStrategySource {
+    path: PathBuf,
+    /// The modification time and length of the last valid strategy read.
+    last_loaded: Option<(SystemTime, u64)>,
+}
+
+impl FileStrategySource {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        FileStrategySource {
+            path,
+            last_loaded: None,
+        }
+    }
+
+    fn read(&mut self) -> Result<Option<SamplingStrategyResponse>, String> {
+        let signature = std::fs::metadata(&self.path)
+            .and_then(|metadata| Ok((metadata.modified()?, metadata.len())))
+            .map_err(|err| format!("cannot read {}, {}", self.path.display(), err))?;
+        if self.last_loaded == Some(signature) {
+            return Ok(None);
+        }
+
+        let content = std::fs::read(&self.path)
+            .map_err(|err| format!("cannot read {}, {}", self.path.display(), err))?;
+        let strategy = serde_json::from_slice(&content)
+            .map_err(|err| format!("cannot deserialize {}, {}", self.path.display(), err))?;
+        // Only remembered once valid, as a file being written may be read
+        // before its modification time changes again.
+        self.last_loaded = Some(signature);
+        Ok(Some(strategy))
+    }
+}
+
+impl StrategySource for FileStrategySource {
+    fn fetch(&mut self) -> BoxFuture<'_, Result<Option<SamplingStrategyResponse>, String>> {
+        // The strategy file is expected to be small, so it is read without
+        // offloading the blocking IO from the runtime.
+        Box::pin(async move { self.read() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace::sampler::jaeger_remote::remote::SamplingStrategyType;
+    use std::fs::File;
+    use std::io::Write;
+    use std::time::Duration;
+
+    fn write_strategy(path: &PathBuf, content: &str, modified: SystemTime) {
+        let mut file = File::create(path).unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        file.set_modified(modified).unwrap();
+    }
+
+    #[test]
+    fn file_strategy_source_reloads_on_change() {
+        let path = std::env::temp_dir().join(format!(
+            "jaeger-remote-strategy-{}.json",
+            std::process::id()
+        ));
+        let mut source = FileStrategySource::new(path.clone());
+        assert!(source.read().is_err());
+
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        write_strategy(
+            &path,
+            r#"{"strategyType":"PROBABILISTIC","probabilisticSampling":{"samplingRate":0.5}}"#,
+            modified,
+        );
+        let strategy = source.read().unwrap().unwrap();
+        assert!(strategy.strategy_type == SamplingStrategyType::Probabilistic);
+        assert_eq!(strategy.probabilistic_sampling.unwrap().sampling_rate, 0.5);
+
+        // unchanged
+        assert!(source.read().unwrap().is_none());
+
+        // invalid content is reported until the file is fixed
+        write_strategy(&path, "{", modified + Duration::from_secs(1));
+        assert!(source.read().is_err());
+        assert!(source.read().is_err());
+
+        write_strategy(
+            &path,
+            r#"{"strategyType":"RATELIMITING","rateLimitingSampling":{"maxTracesPerSecond":10}}"#,
+            modified + Duration::from_secs(2),
+        );
+        let strategy = source.read().unwrap().unwrap();
+        assert_eq!(
+            strategy
+                .rate_limiting_sampling
+                .unwrap()
+                .max_traces_per_second,
+            10
+        );
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn file_strategy_source_reloads_after_invalid_content() {
+        let path = std::env::temp_dir().join(format!(
+            "jaeger-remote-strategy-invalid-{}.json",
+            std::process::id()
+        ));
+        let mut source = FileStrategySource::new(path.clone());
+
+        // a partially written file, completed within the same modification time
+        let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        write_strategy(&path, r#"{"strategyType":"PROBABILISTIC","#, modified);
+        assert!(source.read().is_err());
+        write_strategy(
+            &path,
+            r#"{"strategyType":"PROBABILISTIC","probabilisticSampling":{"samplingRate":0.5}}"#,
+            modified,
+        );
+        let strategy = source.read().unwrap().unwrap();
+        assert_eq!(strategy.probabilistic_sampling.unwrap().sampling_rate, 0.5);
+        assert!(source.read().unwrap().is_none());
+
+        // a rewrite of another length within the same modification time
+        write_strategy(
+            &path,
+            r#"{"strategyType":"PROBABILISTIC","probabilisticSampling":{"samplingRate":0.25}}"#,
+            modified,
+        );
+        let strategy = source.read().unwrap().unwrap();
+        assert_eq!(strategy.probabilistic_sampling.unwrap().sampling_rate, 0.25);
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,263 @@
+use crate::trace::ShouldSample;
+use opentelemetry::time::now;
+use opentelemetry::trace::{
+    Link, SamplingDecision, SamplingResult, SpanKind, TraceContextExt, TraceId, TraceState,
+};
+use opentelemetry::{otel_debug, Context, KeyValue};
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// A sampler sampling at most a given number of traces per second.
+///
+/// The sampler allows bursts of up to one second worth of traces, and samples
+/// the following traces as the allowance is replenished. Clones of the
+/// sampler share the same allowance.
+///
+/// The sampler does not take the parent span into account: wrap it in
+/// [`Sampler::ParentBased`] to only rate limit root spans and respect the
+/// decision of the parent for the others.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry_sdk::trace::{RateLimitingSampler, Sampler, SdkTracerProvider};
+///
+/// let provider = SdkTracerProvider::builder()
+///     .with_sampler(Sampler::ParentBased(Box::new(Sampler::RateLimited(
+///         RateLimitingSampler::new(100.0),
+///     ))))
+///     .build();
+/// # drop(provider);
+/// ```
+///
+/// [`Sampler::ParentBased`]: crate::trace::Sampler::ParentBased
+#[derive(Clone)]
+pub struct RateLimitingSampler {
+    traces_per_second: f64,
+    leaky_bucket: Arc<Mutex<LeakyBucket>>,
+}
+
+impl RateLimitingSampler {
+    /// Create a sampler sampling at most `traces_per_second` traces per second.
+    ///
+    /// Negative and NaN rates sample no traces.
+    pub fn new(traces_per_second: f64) -> Self {
+        let traces_per_second = traces_per_second.max(0.0);
+        let bucket_size = if traces_per_second > 0.0 {
+            traces_per_second.max(1.0)
+        } else {
+            0.0
+        };
+        RateLimitingSampler {
+            traces_per_second,
+            leaky_bucket: Arc::new(Mutex::new(LeakyBucket::new(bucket_size, traces_per_second))),
+        }
+    }
+
+    /// The maximum number of traces sampled per second.
+    pub fn traces_per_second(&self) -> f64 {
+        self.traces_per_second
+    }
+
+    /// Take one trace from the allowance, if available.
+    pub(crate) fn try_acquire(&self) -> bool {
+        self.leaky_bucket
+            .lock()
+            .map(|mut leaky_bucket| leaky_bucket.should_sample())
+            .unwrap_or(false)
+    }
+}
+
+impl Debug for RateLimitingSampler {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RateLimitingSampler")
+            .field("traces_per_second", &self.traces_per_second)
+            .finish()
+    }
+}
+
+impl ShouldSample for RateLimitingSampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&Context>,
+        _trace_id: TraceId,
+        _name: &str,
+        _span_kind: &SpanKind,
+        _attributes: &[KeyValue],
+        _links: &[Link],
+    ) -> SamplingResult {
+        SamplingResult {
+            decision: if self.try_acquire() {
+                SamplingDecision::RecordAndSample
+            } else {
+                SamplingDecision::Drop
+            },
+            attributes: Vec::new(),
+            trace_state: match parent_context {
+                Some(ctx) => ctx.span().span_context().trace_state().clone(),
+                None => TraceState::default(),
+            },
+        }
+    }
+}
+
+// leaky bucket based rate limit
+// should be Send+Sync
+pub(crate) struct LeakyBucket {
+    span_per_sec: f64,
+    available: f64,
+    bucket_size: f64,
+    last_time: SystemTime,
+}
+
+impl LeakyBucket {
+    pub(crate) fn new(bucket_size: f64, span_per_sec: f64) -> LeakyBucket {
+        LeakyBucket {
+            span_per_sec,
+            available: bucket_size,
+            bucket_size,
+            last_time: now(),
+        }
+    }
+
+    #[cfg(feature = "jaeger_remote_sampler")]
+    pub(crate) fn update(&mut self, span_per_sec: f64) {
+        self.span_per_sec = span_per_sec;
+    }
+
+    pub(crate) fn should_sample(&mut self) -> bool {
+        self.check_availability(now)
+    }
+
+    fn check_availability<F>(&mut self, now: F) -> bool
+    where
+        F: Fn() -> SystemTime,
+    {
+        if self.available >= 1.0 {
+            self.available -= 1.0;
+            true
+        } else {
+            let cur_time = now();
+            let elapsed = cur_time.duration_since(self.last_time);
+            match elapsed {
+                Ok(dur) => {
+                    self.last_time = cur_time;
+                    self.available = f64::min(
+                        dur.as_secs_f64() * self.span_per_sec + self.available,
+                        self.bucket_size,
+                    );
+
+                    if self.available >= 1.0 {
+                        self.available -= 1.0;
+                        true
+                    } else {
+                        false
+                    }
+                }
+                Err(err) => {
+                    otel_debug!(
+                        name: "Sampler.LeakyBucket.ClockAdjustment",
+                        message = "Rate limiting sampler detected a rewind in system clock",
+                        reason = format!("{:?}", err),
+                    );
+                    true
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LeakyBucket, RateLimitingSampler};
+    use crate::trace::ShouldSample;
+    use opentelemetry::time::now;
+    use opentelemetry::trace::{SamplingDecision, SpanKind, TraceId};
+    use std::ops::{Add, Sub};
+    use std::time::Duration;
+
+    #[test]
+    fn test_leaky_bucket() {
+        // maximum bucket size 2, add 1 allowance every 10 seconds
+        let mut leaky_bucket = LeakyBucket::new(2.0, 0.1);
+        let current_time = now();
+        leaky_bucket.last_time = current_time;
+
+        let test_cases = vec![
+            (0, vec![true, true, false]),
+            (1, vec![false]),
+            (5, vec![false]),
+            (10, vec![true, false]),
+            (60, vec![true, true, false]), // maximum allowance is 2
+        ];
+
+        for (elapsed_sec, cases) in test_cases.into_iter() {
+            for should_pass in cases {
+                assert_eq!(
+                    should_pass,
+                    leaky_bucket.check_availability(|| {
+                        current_time.add(Duration::from_secs(elapsed_sec))
+                    })
+                )
+            }
+        }
+    }
+
+    #[test]
+    fn test_rewind_clock_should_pass() {
+        let mut leaky_bucket = LeakyBucket::new(2.0, 0.1);
+        let current_time = now();
+        leaky_bucket.last_time = current_time;
+
+        assert!(leaky_bucket.check_availability(|| { current_time.sub(Duration::from_secs(10)) }))
+    }
+
+    #[test]
+    fn test_leaky_bucket_sub_second_refill() {
+        let mut leaky_bucket = LeakyBucket::new(1.0, 10.0);
+        let current_time = now();
+        leaky_bucket.last_time = current_time;
+
+        assert!(leaky_bucket.check_availability(|| current_time));
+        assert!(!leaky_bucket.check_availability(|| current_time));
+        // 10 traces per second add one allowance every 100ms
+        assert!(!leaky_bucket.check_availability(|| current_time.add(Duration::from_millis(50))));
+        assert!(leaky_bucket.check_availability(|| current_time.add(Duration::from_millis(150))));
+    }
+
+    #[test]
+    fn test_rate_limiting_sampler() {
+        let sample = |sampler: &RateLimitingSampler| {
+            sampler
+                .should_sample(
+                    None,
+                    TraceId::from_u128(1),
+                    "span",
+                    &SpanKind::Internal,
+                    &[],
+                    &[],
+                )
+                .decision
+        };
+
+        // the initial allowance is one second worth of traces
+        let sampler = RateLimitingSampler::new(2.0);
+        let cloned = sampler.clone();
+        assert_eq!(sample(&sampler), SamplingDecision::RecordAndSample);
+        assert_eq!(sample(&cloned), SamplingDecision::RecordAndSample);
+        assert_eq!(sample(&sampler), SamplingDecision::Drop);
+        assert_eq!(sample(&cloned), SamplingDecision::Drop);
+
+        // the allowance is at least one trace
+        let sampler = RateLimitingSampler::new(0.5);
+        assert_eq!(sample(&sampler), SamplingDecision::RecordAndSample);
+        assert_eq!(sample(&sampler), SamplingDecision::Drop);
+
+        for traces_per_second in [0.0, -1.0, f64::NAN] {
+            let sampler = RateLimitingSampler::new(traces_per_second);
+            assert_eq!(sampler.traces_per_second(), 0.0);
+            assert_eq!(sample(&sampler), SamplingDecision::Drop);
+        }
+    }
+}
@@ -0,0 +1,586 @@
+use crate::trace::{Sampler, ShouldSample};
+use glob::Pattern;
+use opentelemetry::trace::{Link, SamplingResult, SpanKind, TraceError, TraceId};
+use opentelemetry::{Context, Key, KeyValue, Value};
+use regex::Regex;
+
+/// A pattern matching span names or attribute values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum MatchPattern {
+    /// Matches strings equal to the given string.
+    Exact(String),
+    /// Matches strings with a glob pattern, where `*` matches zero or more
+    /// characters, `?` matches exactly one character and `[...]` matches a
+    /// character range.
+    Glob(String),
+    /// Matches strings containing a match of the given regular expression. Use
+    /// `^` and `$` to match the whole string.
+    Regex(String),
+}
+
+impl MatchPattern {
+    fn compile(&self) -> Result<Matcher, TraceError> {
+        match self {
+            MatchPattern::Exact(value) => Ok(Matcher::Exact(value.clone())),
+            MatchPattern::Glob(pattern) => Pattern::new(pattern)
+                .map(Matcher::Glob)
+                .map_err(|err| format!("invalid glob pattern {pattern:?}: {err}").into()),
+            MatchPattern::Regex(regex) => Regex::new(regex)
+                .map(Matcher::Regex)
+                .map_err(|err| format!("invalid regex {regex:?}: {err}").into()),
+        }
+    }
+}
+
+/// A condition on the attributes of a span, evaluated with the attributes
+/// available when the span is created.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum AttributeCondition {
+    /// The attribute is set.
+    Exists(Key),
+    /// The attribute is set to the given value.
+    Equals(Key, Value),
+    /// The attribute is set to a value whose string representation matches the pattern.
+    Matches(Key, MatchPattern),
+}
+
+impl AttributeCondition {
+    fn compile(&self) -> Result<AttributeMatcher, TraceError> {
+        Ok(match self {
+            AttributeCondition::Exists(key) => AttributeMatcher::Exists(key.clone()),
+            AttributeCondition::Equals(key, value) => {
+                AttributeMatcher::Equals(key.clone(), value.clone())
+            }
+            AttributeCondition::Matches(key, pattern) => {
+                AttributeMatcher::Matches(key.clone(), pattern.compile()?)
+            }
+        })
+    }
+}
+
+/// A rule of a [`RuleBasedSampler`], routing the spans it matches to a sampler.
+///
+/// A span matches a rule if it matches all of its criteria:
+/// - its name matches the name pattern, if any.
+/// - its kind is one of the span kinds, if any.
+/// - its attributes satisfy all the attribute conditions.
+#[derive(Clone, Debug)]
+pub struct SamplingRule {
+    name: Option<MatchPattern>,
+    span_kinds: Vec<SpanKind>,
+    attributes: Vec<AttributeCondition>,
+    sampler: Box<dyn ShouldSample>,
+}
+
+impl SamplingRule {
+    /// Create a rule matching all spans, and sampling them with `sampler`.
+    pub fn new<S: ShouldSample + 'static>(sampler: S) -> Self {
+        SamplingRule {
+            name: None,
+            span_kinds: Vec::new(),
+            attributes: Vec::new(),
+            sampler: Box::new(sampler),
+        }
+    }
+
+    /// Only match spans whose name matches `pattern`.
+    pub fn with_name(self, pattern: MatchPattern) -> Self {
+        SamplingRule {
+            name: Some(pattern),
+            ..self
+        }
+    }
+
+    /// Only match spans of the given kind. Spans match if they are of any of
+    /// the kinds added to the rule.
+    pub fn with_span_kind(mut self, span_kind: SpanKind) -> Self {
+        self.span_kinds.push(span_kind);
+        self
+    }
+
+    /// Only match spans whose attributes satisfy `condition`, in addition to
+    /// the other conditions added to the rule.
+    pub fn with_attribute(mut self, condition: AttributeCondition) -> Self {
+        self.attributes.push(condition);
+        self
+    }
+}
+
+/// Builder for [`RuleBasedSampler`].
+#[derive(Clone, Debug)]
+pub struct RuleBasedSamplerBuilder {
+    rules: Vec<SamplingRule>,
+    fallback: Box<dyn ShouldSample>,
+}
+
+impl RuleBasedSamplerBuilder {
+    /// Add a rule, evaluated after the rules already added.
+    pub fn with_rule(mut self, rule: SamplingRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// The sampler of spans matching none of the rules.
+    ///
+    /// By default it's `Sampler::ParentBased(Box::new(Sampler::AlwaysOn))`.
+    pub fn with_fallback<S: ShouldSample + 'static>(self, sampler: S) -> Self {
+        RuleBasedSamplerBuilder {
+            fallback: Box::new(sampler),
+            ..self
+        }
+    }
+
+    /// Build a [`RuleBasedSampler`] using the provided rules.
+    ///
+    /// Return errors if any of the glob patterns or regular expressions is invalid.
+    pub fn build(self) -> Result<RuleBasedSampler, TraceError> {
+        let rules = self
+            .rules
+            .into_iter()
+            .map(|rule| {
+                Ok(CompiledRule {
+                    name: rule.name.as_ref().map(MatchPattern::compile).transpose()?,
+                    span_kinds: rule.span_kinds,
+                    attributes: rule
+                        .attributes
+                        .iter()
+                        .map(AttributeCondition::compile)
+                        .collect::<Result<_, TraceError>>()?,
+                    sampler: rule.sampler,
+                })
+            })
+            .collect::<Result<_, TraceError>>()?;
+
+        Ok(RuleBasedSampler {
+            rules,
+            fallback: self.fallback,
+        })
+    }
+}
+
+/// A sampler routing spans to other samplers, depending on their name, kind
+/// and attributes.
+///
+/// The rules are evaluated in order, and the first rule matching a span makes
+/// the sampling decision with its sampler. Spans matching none of the rules are
+/// sampled by the fallback sampler.
+///
+/// # Examples
+///
+/// Drop health checks, sample checkouts and 5% of the other traces:
+///
+/// ```
+/// use opentelemetry::trace::SpanKind;
+/// use opentelemetry_sdk::trace::{
+///     AttributeCondition, MatchPattern, RuleBasedSampler, Sampler, SamplingRule,
+/// };
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let sampler = RuleBasedSampler::builder()
+///     .with_rule(
+///         SamplingRule::new(Sampler::AlwaysOff)
+///             .with_span_kind(SpanKind::Server)
+///             .with_attribute(AttributeCondition::Matches(
+///                 "url.path".into(),
+///                 MatchPattern::Glob("/health*".into()),
+///             )),
+///     )
+///     .with_rule(
+///         SamplingRule::new(Sampler::AlwaysOn).with_name(MatchPattern::Exact("/checkout".into())),
+///     )
+///     .with_fallback(Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(0.05))))
+///     .build()?;
+/// # drop(sampler);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct RuleBasedSampler {
+    rules: Vec<CompiledRule>,
+    fallback: Box<dyn ShouldSample>,
+}
+
+impl RuleBasedSampler {
+    /// Create a builder of [`RuleBasedSampler`] without rules.
+    pub fn builder() -> RuleBasedSamplerBuilder {
+        RuleBasedSamplerBuilder {
+            rules: Vec::new(),
+            fallback: Box::new(Sampler::ParentBased(Box::new(Sampler::AlwaysOn))),
+        }
+    }
+
+    /// Create a [`RuleBasedSampler`] from its declarative configuration.
+    ///
+    /// Return errors if any of the glob patterns or regular expressions is invalid.
+    pub fn from_config(config: RuleBasedSamplerConfig) -> Result<Self, TraceError> {
+        config
+            .rules
+            .into_iter()
+            .fold(
+                RuleBasedSampler::builder().with_fallback(Sampler::from(config.fallback)),
+                |builder, rule| {
+                    builder.with_rule(SamplingRule {
+                        name: rule.name,
+                        span_kinds: rule.span_kinds,
+                        attributes: rule.attributes,
+                        sampler: Box::new(Sampler::from(rule.sampler)),
+                    })
+                },
+            )
+            .build()
+    }
+}
+
+impl ShouldSample for RuleBasedSampler {
+    fn should_sample(
+        &self,
+        parent_context: Option<&Context>,
+        trace_id: TraceId,
+        name: &str,
+        span_kind: &SpanKind,
+        attributes: &[KeyValue],
+        links: &[Link],
+    ) -> SamplingResult {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(name, span_kind, attributes))
+            .map_or(&self.fallback, |rule| &rule.sampler)
+            .should_sample(parent_context, trace_id, name, span_kind, attributes, links)
+    }
+}
+
+/// Declarative configuration of a [`RuleBasedSampler`].
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry_sdk::trace::{
+///     MatchPattern, RuleBasedSampler, RuleBasedSamplerConfig, SamplerConfig, SamplingRuleConfig,
+/// };
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let sampler = RuleBasedSampler::from_config(RuleBasedSamplerConfig {
+///     rules: vec![SamplingRuleConfig {
+///         name: Some(MatchPattern::Regex("^GET /(health|ready)$".into())),
+///         sampler: SamplerConfig::AlwaysOff,
+///         ..Default::default()
+///     }],
+///     fallback: SamplerConfig::ParentBased(Box::new(SamplerConfig::TraceIdRatioBased(0.05))),
+/// })?;
+/// # drop(sampler);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RuleBasedSamplerConfig {
+    /// The rules, in order of evaluation.
+    pub rules: Vec<SamplingRuleConfig>,
+    /// The sampler of spans matching none of the rules.
+    pub fallback: SamplerConfig,
+}
+
+/// Declarative configuration of a [`SamplingRule`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SamplingRuleConfig {
+    /// The pattern span names must match, if any.
+    pub name: Option<MatchPattern>,
+    /// The span kinds spans must be of, if any.
+    pub span_kinds: Vec<SpanKind>,
+    /// The conditions the attributes of spans must all satisfy.
+    pub attributes: Vec<AttributeCondition>,
+    /// The sampler of the spans matching the rule.
+    pub sampler: SamplerConfig,
+}
+
+/// Declarative configuration of the built-in [`Sampler`]s.
+#[derive(Clone, Debug, PartialEq)]
+#[non_exhaustive]
+pub enum SamplerConfig {
+    /// [`Sampler::AlwaysOn`].
+    AlwaysOn,
+    /// [`Sampler::AlwaysOff`].
+    AlwaysOff,
+    /// [`Sampler::TraceIdRatioBased`].
+    TraceIdRatioBased(f64),
+    /// [`Sampler::ParentBased`], with the given root sampler.
+    ParentBased(Box<SamplerConfig>),
+}
+
+impl Default for SamplerConfig {
+    /// `ParentBased(AlwaysOn)`, the default sampler of the SDK.
+    fn default() -> Self {
+        SamplerConfig::ParentBased(Box::new(SamplerConfig::AlwaysOn))
+    }
+}
+
+impl From<SamplerConfig> for Sampler {
+    fn from(config: SamplerConfig) -> Self {
+        match config {
+            SamplerConfig::AlwaysOn => Sampler::AlwaysOn,
+            SamplerConfig::AlwaysOff => Sampler::AlwaysOff,
+            SamplerConfig::TraceIdRatioBased(ratio) => Sampler::TraceIdRatioBased(ratio),
+            SamplerConfig::ParentBased(root) => {
+                Sampler::ParentBased(Box::new(Sampler::from(*root)))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Matcher {
+    Exact(String),
+    Glob(Pattern),
+    Regex(Regex),
+}
+
+impl Matcher {
+    fn matches(&self, value: &str) -> bool {
+        match self {
+            Matcher::Exact(expected) => expected == value,
+            Matcher::Glob(pattern) => pattern.matches(value),
+            Matcher::Regex(regex) => regex.is_match(value),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum AttributeMatcher {
+    Exists(Key),
+    Equals(Key, Value),
+    Matches(Key, Matcher),
+}
+
+impl AttributeMatcher {
+    fn matches(&self, attributes: &[KeyValue]) -> bool {
+        let find = |key: &Key| attributes.iter().find(|kv| &kv.key == key);
+        match self {
+            AttributeMatcher::Exists(key) => find(key).is_some(),
+            AttributeMatcher::Equals(key, value) => find(key).is_some_and(|kv| &kv.value == value),
+            AttributeMatcher::Matches(key, matcher) => {
+                find(key).is_some_and(|kv| matcher.matches(&kv.value.as_str()))
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct CompiledRule {
+    name: Option<Matcher>,
+    span_kinds: Vec<SpanKind>,
+    attributes: Vec<AttributeMatcher>,
+    sampler: Box<dyn ShouldSample>,
+}
+
+impl CompiledRule {
+    fn matches(&self, name: &str, span_kind: &SpanKind, attributes: &[KeyValue]) -> bool {
+        self.name
+            .as_ref()
+            .map_or(true, |matcher| matcher.matches(name))
+            && (self.span_kinds.is_empty() || self.span_kinds.contains(span_kind))
+            && self
+                .attributes
+                .iter()
+                .all(|matcher| matcher.matches(attributes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::trace::SamplingDecision;
+
+    fn decision(
+        sampler: &RuleBasedSampler,
+        name: &str,
+        span_kind: SpanKind,
+        attributes: &[KeyValue],
+    ) -> SamplingDecision {
+        sampler
+            .should_sample(
+                None,
+                TraceId::from_u128(1),
+                name,
+                &span_kind,
+                attributes,
+                &[],
+            )
+            .decision
+    }
+
+    #[test]
+    fn name_patterns() {
+        let sampler = RuleBasedSampler::builder()
+            .with_rule(
+                SamplingRule::new(Sampler::AlwaysOn)
+                    .with_name(MatchPattern::Exact("/checkout".into())),
+            )
+            .with_rule(
+                SamplingRule::new(Sampler::AlwaysOn).with_name(MatchPattern::Glob("GET /*".into())),
+            )
+            .with_rule(
+                SamplingRule::new(Sampler::AlwaysOn)
+                    .with_name(MatchPattern::Regex("^POST /(cart|order)$".into())),
+            )
+            .with_fallback(Sampler::AlwaysOff)
+            .build()
+            .unwrap();
+
+        let sampled = SamplingDecision::RecordAndSample;
+        for (name, expected) in [
+            ("/checkout", sampled.clone()),
+            ("/checkout/confirm", SamplingDecision::Drop),
+            ("GET /users", sampled.clone()),
+            ("PUT /users", SamplingDecision::Drop),
+            ("POST /cart", sampled.clone()),
+            ("POST /cart/items", SamplingDecision::Drop),
+        ] {
+            assert_eq!(
+                decision(&sampler, name, SpanKind::Server, &[]),
+                expected,
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn span_kinds_and_attributes() {
+        let sampler = RuleBasedSampler::builder()
+            .with_rule(
+                SamplingRule::new(Sampler::AlwaysOff)
+                    .with_span_kind(SpanKind::Server)
+                    .with_span_kind(SpanKind::Consumer)
+                    .with_attribute(AttributeCondition::Matches(
+                        "url.path".into(),
+                        MatchPattern::Glob("/health*".into()),
+                    ))
+                    .with_attribute(AttributeCondition::Equals(
+                        "http.request.method".into(),
+                        "GET".into(),
+                    )),
+            )
+            .with_rule(
+                SamplingRule::new(Sampler::AlwaysOff)
+                    .with_attribute(AttributeCondition::Exists("synthetic".into())),
+            )
+            .with_fallback(Sampler::AlwaysOn)
+            .build()
+            .unwrap();
+
+        let health_check = [
+            KeyValue::new("url.path", "/healthz"),
+            KeyValue::new("http.request.method", "GET"),
+        ];
+        assert_eq!(
+            decision(&sampler, "GET", SpanKind::Server, &health_check),
+            SamplingDecision::Drop
+        );
+        assert_eq!(
+            decision(&sampler, "GET", SpanKind::Consumer, &health_check),
+            SamplingDecision::Drop
+        );
+        // wrong span kind
+        assert_eq!(
+            decision(&sampler, "GET", SpanKind::Client, &health_check),
+            SamplingDecision::RecordAndSample
+        );
+        // not all attribute conditions are satisfied
+        assert_eq!(
+            decision(
+                &sampler,
+                "GET",
+                SpanKind::Server,
+                &[KeyValue::new("url.path", "/healthz")]
+            ),
+            SamplingDecision::RecordAndSample
+        );
+        assert_eq!(
+            decision(
+                &sampler,
+                "GET",
+                SpanKind::Internal,
+                &[KeyValue::new("synthetic", true)]
+            ),
+            SamplingDecision::Drop
+        );
+    }
+
+    #[test]
+    fn first_match_wins() {
+        let sampler = RuleBasedSampler::builder()
+            .with_rule(
+                SamplingRule::new(Sampler::AlwaysOn).with_name(MatchPattern::Glob("*".into())),
+            )
+            .with_rule(SamplingRule::new(Sampler::AlwaysOff))
+            .with_fallback(Sampler::AlwaysOff)
+            .build()
+            .unwrap();
+        assert_eq!(
+            decision(&sampler, "any", SpanKind::Internal, &[]),
+            SamplingDecision::RecordAndSample
+        );
+
+        // without rules, the fallback decides
+        let sampler = RuleBasedSampler::builder()
+            .with_fallback(Sampler::AlwaysOff)
+            .build()
+            .unwrap();
+        assert_eq!(
+            decision(&sampler, "any", SpanKind::Internal, &[]),
+            SamplingDecision::Drop
+        );
+    }
+
+    #[test]
+    fn invalid_patterns() {
+        for pattern in [
+            MatchPattern::Glob("[".into()),
+            MatchPattern::Regex("(".into()),
+        ] {
+            let result = RuleBasedSampler::builder()
+                .with_rule(SamplingRule::new(Sampler::AlwaysOn).with_name(pattern.clone()))
+                .build();
+            assert!(result.is_err(), "{pattern:?}");
+        }
+    }
+
+    #[test]
+    fn from_config() {
+        let sampler = RuleBasedSampler::from_config(RuleBasedSamplerConfig {
+            rules: vec![
+                SamplingRuleConfig {
+                    name: Some(MatchPattern::Exact("/checkout".into())),
+                    sampler: SamplerConfig::AlwaysOn,
+                    ..Default::default()
+                },
+                SamplingRuleConfig {
+                    span_kinds: vec![SpanKind::Client],
+                    attributes: vec![AttributeCondition::Exists("db.system".into())],
+                    sampler: SamplerConfig::ParentBased(Box::new(SamplerConfig::AlwaysOff)),
+                    ..Default::default()
+                },
+            ],
+            fallback: SamplerConfig::TraceIdRatioBased(0.0),
+        })
+        .unwrap();
+
+        assert_eq!(
+            decision(&sampler, "/checkout", SpanKind::Server, &[]),
+            SamplingDecision::RecordAndSample
+        );
+        assert_eq!(
+            decision(
+                &sampler,
+                "SELECT",
+                SpanKind::Client,
+                &[KeyValue::new("db.system", "postgresql")]
+            ),
+            SamplingDecision::Drop
+        );
+        assert_eq!(
+            decision(&sampler, "other", SpanKind::Server, &[]),
+            SamplingDecision::Drop
+        );
+    }
+}
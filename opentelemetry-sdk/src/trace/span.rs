@@ -97,13 +97,15 @@ impl opentelemetry::trace::Span for Span {
     ) where
         T: Into<Cow<'static, str>>,
     {
-        let span_events_limit = self.span_limits.max_events_per_span as usize;
-        let event_attributes_limit = self.span_limits.max_attributes_per_event as usize;
+        let span_limits = self.span_limits;
+        let span_events_limit = span_limits.max_events_per_span as usize;
+        let event_attributes_limit = span_limits.max_attributes_per_event as usize;
         self.with_data(|data| {
             if data.events.len() < span_events_limit {
                 let dropped_attributes_count =
                     attributes.len().saturating_sub(event_attributes_limit);
                 attributes.truncate(event_attributes_limit);
+                span_limits.truncate_attribute_values(&mut attributes);
 
                 data.events.add_event(Event::new(
                     name,
@@ -134,10 +136,12 @@ impl opentelemetry::trace::Span for Span {
     /// Note that the OpenTelemetry project documents certain ["standard
     /// attributes"](https://github.com/open-telemetry/opentelemetry-specification/tree/v0.5.0/specification/trace/semantic_conventions/README.md)
     /// that have prescribed semantic meanings.
-    fn set_attribute(&mut self, attribute: KeyValue) {
-        let span_attribute_limit = self.span_limits.max_attributes_per_span as usize;
+    fn set_attribute(&mut self, mut attribute: KeyValue) {
+        let span_limits = self.span_limits;
+        let span_attribute_limit = span_limits.max_attributes_per_span as usize;
         self.with_data(|data| {
             if data.attributes.len() < span_attribute_limit {
+                span_limits.truncate_attribute_values(std::slice::from_mut(&mut attribute));
                 data.attributes.push(attribute);
             } else {
                 data.dropped_attributes_count += 1;
@@ -171,14 +175,16 @@ impl opentelemetry::trace::Span for Span {
     /// Add `Link` to this `Span`
     ///
     fn add_link(&mut self, span_context: SpanContext, attributes: Vec<KeyValue>) {
-        let span_links_limit = self.span_limits.max_links_per_span as usize;
-        let link_attributes_limit = self.span_limits.max_attributes_per_link as usize;
+        let span_limits = self.span_limits;
+        let span_links_limit = span_limits.max_links_per_span as usize;
+        let link_attributes_limit = span_limits.max_attributes_per_link as usize;
         self.with_data(|data| {
             if data.links.links.len() < span_links_limit {
                 let dropped_attributes_count =
                     attributes.len().saturating_sub(link_attributes_limit);
                 let mut attributes = attributes;
                 attributes.truncate(link_attributes_limit);
+                span_limits.truncate_attribute_values(&mut attributes);
                 data.links.add_link(Link::new(
                     span_context,
                     attributes,
@@ -566,6 +572,60 @@ mod tests {
         );
     }
 
+    #[test]
+    fn exceed_attribute_value_length_limit() {
+        let exporter = NoopSpanExporter::new();
+        let provider = crate::trace::SdkTracerProvider::builder()
+            .with_simple_exporter(exporter)
+            .with_max_attribute_value_length(3)
+            .build();
+        let tracer = provider.tracer("opentelemetry-test");
+
+        let link = Link::with_context(SpanContext::new(
+            TraceId::from_u128(12),
+            SpanId::from_u64(12),
+            TraceFlags::default(),
+            false,
+            Default::default(),
+        ));
+        let mut link_with_attributes = link.clone();
+        link_with_attributes
+            .attributes
+            .push(KeyValue::new("link", "abcdef"));
+        let mut event = Event::with_name("builder event");
+        event.attributes.push(KeyValue::new("event", "abcdef"));
+        let span_builder = tracer
+            .span_builder("test")
+            .with_attributes(vec![KeyValue::new("builder", "abcdef")])
+            .with_links(vec![link_with_attributes])
+            .with_events(vec![event]);
+
+        let mut span = tracer.build(span_builder);
+        span.set_attribute(KeyValue::new("set", "abcdef"));
+        span.add_event("event", vec![KeyValue::new("event", "abcdef")]);
+        span.add_link(
+            link.span_context.clone(),
+            vec![KeyValue::new("link", "abcdef")],
+        );
+
+        let data = span
+            .data
+            .clone()
+            .expect("span data should not be empty as we already set it before");
+        assert_eq!(
+            data.attributes,
+            vec![KeyValue::new("builder", "abc"), KeyValue::new("set", "abc")]
+        );
+        for event in data.events.iter() {
+            assert_eq!(event.attributes, vec![KeyValue::new("event", "abc")]);
+        }
+        for link in data.links.iter() {
+            assert_eq!(link.attributes, vec![KeyValue::new("link", "abc")]);
+        }
+        assert_eq!(data.events.len(), 2);
+        assert_eq!(data.links.len(), 2);
+    }
+
     #[test]
     fn exceed_event_attributes_limit() {
         let exporter = NoopSpanExporter::new();
@@ -0,0 +1,382 @@
+use crate::error::OTelSdkResult;
+use crate::resource::Resource;
+use crate::trace::{Span, SpanData, SpanProcessor};
+use glob::Pattern;
+use opentelemetry::trace::{SpanKind, Status, TraceError};
+use opentelemetry::{Context, InstrumentationScope, KeyValue};
+use std::fmt::Debug;
+use std::time::Duration;
+
+/// A predicate on the finished spans, deciding which spans the
+/// [`FilteringSpanProcessor`] drops.
+pub trait SpanFilter: Send + Sync + Debug {
+    /// Returns `true` if `span`, created by the instrumentation `scope`,
+    /// matches the filter.
+    fn matches(&self, span: &SpanData, scope: &InstrumentationScope) -> bool;
+}
+
+/// The built-in predicates of the [`FilteringSpanProcessor`].
+#[derive(Debug)]
+pub enum SpanPredicate {
+    /// The span name matches the glob pattern, where `*` matches zero or more
+    /// characters, `?` matches exactly one character and `[...]` matches a
+    /// character range.
+    NameGlob(String),
+    /// The name of the instrumentation scope of the span is equal to the given
+    /// name.
+    ScopeName(String),
+    /// The span lasted less than the given duration.
+    ShorterThan(Duration),
+    /// The span has the given status. Error statuses match regardless of their
+    /// description.
+    Status(Status),
+    /// The span is of the given kind.
+    Kind(SpanKind),
+    /// The span has the given attribute.
+    Attribute(KeyValue),
+    /// The span matches all the predicates.
+    All(Vec<SpanPredicate>),
+    /// The span matches any of the predicates.
+    Any(Vec<SpanPredicate>),
+    /// The span does not match the predicate.
+    Not(Box<SpanPredicate>),
+    /// The span matches a custom filter.
+    Custom(Box<dyn SpanFilter>),
+}
+
+impl SpanPredicate {
+    fn compile(self) -> Result<CompiledPredicate, TraceError> {
+        Ok(match self {
+            SpanPredicate::NameGlob(pattern) => Pattern::new(&pattern)
+                .map(CompiledPredicate::NameGlob)
+                .map_err(|err| format!("invalid glob pattern {pattern:?}: {err}"))?,
+            SpanPredicate::ScopeName(name) => CompiledPredicate::ScopeName(name),
+            SpanPredicate::ShorterThan(duration) => CompiledPredicate::ShorterThan(duration),
+            SpanPredicate::Status(status) => CompiledPredicate::Status(status),
+            SpanPredicate::Kind(kind) => CompiledPredicate::Kind(kind),
+            SpanPredicate::Attribute(attribute) => CompiledPredicate::Attribute(attribute),
+            SpanPredicate::All(predicates) => CompiledPredicate::All(
+                predicates
+                    .into_iter()
+                    .map(SpanPredicate::compile)
+                    .collect::<Result<_, _>>()?,
+            ),
+            SpanPredicate::Any(predicates) => CompiledPredicate::Any(
+                predicates
+                    .into_iter()
+                    .map(SpanPredicate::compile)
+                    .collect::<Result<_, _>>()?,
+            ),
+            SpanPredicate::Not(predicate) => CompiledPredicate::Not(Box::new(predicate.compile()?)),
+            SpanPredicate::Custom(filter) => CompiledPredicate::Custom(filter),
+        })
+    }
+}
+
+#[derive(Debug)]
+enum CompiledPredicate {
+    NameGlob(Pattern),
+    ScopeName(String),
+    ShorterThan(Duration),
+    Status(Status),
+    Kind(SpanKind),
+    Attribute(KeyValue),
+    All(Vec<CompiledPredicate>),
+    Any(Vec<CompiledPredicate>),
+    Not(Box<CompiledPredicate>),
+    Custom(Box<dyn SpanFilter>),
+}
+
+impl SpanFilter for CompiledPredicate {
+    fn matches(&self, span: &SpanData, scope: &InstrumentationScope) -> bool {
+        match self {
+            CompiledPredicate::NameGlob(pattern) => pattern.matches(&span.name),
+            CompiledPredicate::ScopeName(name) => scope.name() == name,
+            CompiledPredicate::ShorterThan(threshold) => span
+                .end_time
+                .duration_since(span.start_time)
+                .map_or(true, |duration| duration < *threshold),
+            CompiledPredicate::Status(status) => match (status, &span.status) {
+                (Status::Error { .. }, Status::Error { .. }) => true,
+                (status, span_status) => status == span_status,
+            },
+            CompiledPredicate::Kind(kind) => span.span_kind == *kind,
+            CompiledPredicate::Attribute(attribute) => span.attributes.contains(attribute),
+            CompiledPredicate::All(predicates) => predicates
+                .iter()
+                .all(|predicate| predicate.matches(span, scope)),
+            CompiledPredicate::Any(predicates) => predicates
+                .iter()
+                .any(|predicate| predicate.matches(span, scope)),
+            CompiledPredicate::Not(predicate) => !predicate.matches(span, scope),
+            CompiledPredicate::Custom(filter) => filter.matches(span, scope),
+        }
+    }
+}
+
+/// A [`SpanProcessor`] dropping the finished spans matching any of its
+/// filters, and forwarding the other spans to another span processor.
+///
+/// The spans are filtered once they ended, so dropping a span does not change
+/// the sampling decision of its children: they are still sampled and exported
+/// unless they are filtered as well, and may then refer to a parent span which
+/// was not exported.
+///
+/// # Examples
+///
+/// Drop health checks, and internal spans shorter than 1ms:
+///
+/// ```
+/// use opentelemetry::trace::SpanKind;
+/// use opentelemetry_sdk::testing::trace::NoopSpanExporter;
+/// use opentelemetry_sdk::trace::{
+///     BatchSpanProcessor, FilteringSpanProcessor, SdkTracerProvider, SpanPredicate,
+/// };
+/// use std::time::Duration;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let processor = FilteringSpanProcessor::builder(
+///     BatchSpanProcessor::builder(NoopSpanExporter::new()).build(),
+/// )
+/// .with_filter(SpanPredicate::NameGlob("GET /health*".into()))
+/// .with_filter(SpanPredicate::All(vec![
+///     SpanPredicate::Kind(SpanKind::Internal),
+///     SpanPredicate::ShorterThan(Duration::from_millis(1)),
+/// ]))
+/// .build()?;
+///
+/// let provider = SdkTracerProvider::builder()
+///     .with_span_processor(processor)
+///     .build();
+/// # drop(provider);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct FilteringSpanProcessor {
+    filters: Vec<CompiledPredicate>,
+    processor: Box<dyn SpanProcessor>,
+}
+
+impl FilteringSpanProcessor {
+    /// Create a builder of a processor forwarding the spans it does not drop to
+    /// the given span processor.
+    pub fn builder<P>(processor: P) -> FilteringSpanProcessorBuilder
+    where
+        P: SpanProcessor + 'static,
+    {
+        FilteringSpanProcessorBuilder {
+            processor: Box::new(processor),
+            filters: Vec::new(),
+        }
+    }
+}
+
+impl SpanProcessor for FilteringSpanProcessor {
+    fn on_start(&self, span: &mut Span, cx: &Context) {
+        self.processor.on_start(span, cx);
+    }
+
+    fn on_end(&self, span: SpanData) {
+        let scope = &span.instrumentation_scope;
+        if self
+            .filters
+            .iter()
+            .any(|filter| filter.matches(&span, scope))
+        {
+            return;
+        }
+        self.processor.on_end(span);
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        self.processor.force_flush()
+    }
+
+    fn shutdown(&self) -> OTelSdkResult {
+        self.processor.shutdown()
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        self.processor.set_resource(resource);
+    }
+}
+
+/// Builder for [`FilteringSpanProcessor`].
+#[derive(Debug)]
+pub struct FilteringSpanProcessorBuilder {
+    processor: Box<dyn SpanProcessor>,
+    filters: Vec<SpanPredicate>,
+}
+
+impl FilteringSpanProcessorBuilder {
+    /// Drop the spans matching `predicate`, in addition to the spans matching
+    /// the filters already added.
+    pub fn with_filter(mut self, predicate: SpanPredicate) -> Self {
+        self.filters.push(predicate);
+        self
+    }
+
+    /// Build a [`FilteringSpanProcessor`] using the provided filters.
+    ///
+    /// Return errors if any of the glob patterns is invalid.
+    pub fn build(self) -> Result<FilteringSpanProcessor, TraceError> {
+        Ok(FilteringSpanProcessor {
+            filters: self
+                .filters
+                .into_iter()
+                .map(SpanPredicate::compile)
+                .collect::<Result<_, _>>()?,
+            processor: self.processor,
+        })
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::testing::trace::new_test_export_span_data;
+    use crate::trace::{InMemorySpanExporterBuilder, SimpleSpanProcessor};
+    use std::time::SystemTime;
+
+    fn span(name: &'static str, kind: SpanKind, duration: Duration) -> SpanData {
+        let start_time = SystemTime::now();
+        SpanData {
+            name: name.into(),
+            span_kind: kind,
+            start_time,
+            end_time: start_time + duration,
+            ..new_test_export_span_data()
+        }
+    }
+
+    fn matches(predicate: SpanPredicate, span: &SpanData) -> bool {
+        predicate
+            .compile()
+            .unwrap()
+            .matches(span, &span.instrumentation_scope)
+    }
+
+    #[test]
+    fn predicates() {
+        let mut data = span(
+            "GET /health/live",
+            SpanKind::Server,
+            Duration::from_millis(5),
+        );
+        data.attributes = vec![KeyValue::new("http.response.status_code", 200)];
+        data.instrumentation_scope = InstrumentationScope::builder("axum").build();
+
+        assert!(matches(
+            SpanPredicate::NameGlob("GET /health*".into()),
+            &data
+        ));
+        assert!(!matches(SpanPredicate::NameGlob("/health*".into()), &data));
+        assert!(matches(SpanPredicate::ScopeName("axum".into()), &data));
+        assert!(!matches(SpanPredicate::ScopeName("reqwest".into()), &data));
+        assert!(matches(
+            SpanPredicate::ShorterThan(Duration::from_millis(10)),
+            &data
+        ));
+        assert!(!matches(
+            SpanPredicate::ShorterThan(Duration::from_millis(5)),
+            &data
+        ));
+        assert!(matches(SpanPredicate::Kind(SpanKind::Server), &data));
+        assert!(!matches(SpanPredicate::Kind(SpanKind::Internal), &data));
+        assert!(matches(
+            SpanPredicate::Attribute(KeyValue::new("http.response.status_code", 200)),
+            &data
+        ));
+        assert!(!matches(
+            SpanPredicate::Attribute(KeyValue::new("http.response.status_code", 500)),
+            &data
+        ));
+
+        assert!(matches(SpanPredicate::Status(Status::Unset), &data));
+        data.status = Status::error("timeout");
+        assert!(matches(SpanPredicate::Status(Status::error("")), &data));
+        assert!(!matches(SpanPredicate::Status(Status::Ok), &data));
+
+        assert!(matches(
+            SpanPredicate::All(vec![
+                SpanPredicate::Kind(SpanKind::Server),
+                SpanPredicate::ScopeName("axum".into()),
+            ]),
+            &data
+        ));
+        assert!(!matches(
+            SpanPredicate::All(vec![
+                SpanPredicate::Kind(SpanKind::Server),
+                SpanPredicate::ScopeName("reqwest".into()),
+            ]),
+            &data
+        ));
+        assert!(matches(
+            SpanPredicate::Any(vec![
+                SpanPredicate::Kind(SpanKind::Internal),
+                SpanPredicate::ScopeName("axum".into()),
+            ]),
+            &data
+        ));
+        assert!(matches(
+            SpanPredicate::Not(Box::new(SpanPredicate::Kind(SpanKind::Internal))),
+            &data
+        ));
+    }
+
+    #[test]
+    fn drops_matching_spans() {
+        #[derive(Debug)]
+        struct NameLength(usize);
+
+        impl SpanFilter for NameLength {
+            fn matches(&self, span: &SpanData, _scope: &InstrumentationScope) -> bool {
+                span.name.len() > self.0
+            }
+        }
+
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor =
+            FilteringSpanProcessor::builder(SimpleSpanProcessor::new(Box::new(exporter.clone())))
+                .with_filter(SpanPredicate::NameGlob("GET /health*".into()))
+                .with_filter(SpanPredicate::All(vec![
+                    SpanPredicate::Kind(SpanKind::Internal),
+                    SpanPredicate::ShorterThan(Duration::from_millis(1)),
+                ]))
+                .with_filter(SpanPredicate::Custom(Box::new(NameLength(20))))
+                .build()
+                .unwrap();
+
+        processor.on_end(span("GET /health", SpanKind::Server, Duration::ZERO));
+        processor.on_end(span("GET /users", SpanKind::Server, Duration::ZERO));
+        processor.on_end(span("parse", SpanKind::Internal, Duration::ZERO));
+        processor.on_end(span("query", SpanKind::Internal, Duration::from_millis(2)));
+        processor.on_end(span(
+            "a very long span name",
+            SpanKind::Client,
+            Duration::ZERO,
+        ));
+
+        let names = exporter
+            .get_finished_spans()
+            .unwrap()
+            .into_iter()
+            .map(|span| span.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["GET /users", "query"]);
+    }
+
+    #[test]
+    fn invalid_glob() {
+        let err = FilteringSpanProcessor::builder(SimpleSpanProcessor::new(Box::new(
+            InMemorySpanExporterBuilder::new().build(),
+        )))
+        .with_filter(SpanPredicate::Not(Box::new(SpanPredicate::NameGlob(
+            "[".into(),
+        ))))
+        .build()
+        .unwrap_err();
+        assert!(err.to_string().contains("invalid glob pattern"));
+    }
+}
@@ -1,3 +1,6 @@
+use crate::attribute_limits::truncate_value;
+use opentelemetry::KeyValue;
+
 /// # Span limit
 /// Erroneous code can add unintended attributes, events, and links to a span. If these collections
 /// are unbounded, they can quickly exhaust available memory, resulting in crashes that are
@@ -9,9 +12,12 @@
 ///  - Maximum allowed span link count
 ///  - Maximum allowed attribute per span event count
 ///  - Maximum allowed attribute per span link count
+///  - Maximum allowed attribute value length
 ///
 /// If the limit has been breached. The attributes, events or links will be dropped based on their
-/// index in the collection. The one added to collections later will be dropped first.
+/// index in the collection. The one added to collections later will be dropped first. String
+/// attribute values, including the elements of string arrays, longer than the value length limit
+/// are truncated.
 pub(crate) const DEFAULT_MAX_EVENT_PER_SPAN: u32 = 128;
 pub(crate) const DEFAULT_MAX_ATTRIBUTES_PER_SPAN: u32 = 128;
 pub(crate) const DEFAULT_MAX_LINKS_PER_SPAN: u32 = 128;
@@ -31,6 +37,9 @@ pub struct SpanLimits {
     pub max_attributes_per_event: u32,
     /// The max attributes that can be added into a `Link`
     pub max_attributes_per_link: u32,
+    /// The max length, in characters, of string attribute values of a `Span`,
+    /// its `Event`s and `Link`s. `None` means there is no limit.
+    pub max_attribute_value_length: Option<u32>,
 }
 
 impl Default for SpanLimits {
@@ -41,6 +50,61 @@ impl Default for SpanLimits {
             max_links_per_span: DEFAULT_MAX_LINKS_PER_SPAN,
             max_attributes_per_link: DEFAULT_MAX_ATTRIBUTES_PER_LINK,
             max_attributes_per_event: DEFAULT_MAX_ATTRIBUTES_PER_EVENT,
+            max_attribute_value_length: None,
+        }
+    }
+}
+
+impl SpanLimits {
+    /// Truncates the string values of `attributes` to the max attribute value length.
+    pub(crate) fn truncate_attribute_values(&self, attributes: &mut [KeyValue]) {
+        if let Some(max_length) = self.max_attribute_value_length {
+            for attribute in attributes {
+                truncate_value(&mut attribute.value, max_length as usize);
+            }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use opentelemetry::{StringValue, Value};
+
+    #[test]
+    fn truncate_attribute_values() {
+        let limits = SpanLimits {
+            max_attribute_value_length: Some(3),
+            ..SpanLimits::default()
+        };
+        let mut attributes = vec![
+            KeyValue::new("short", "abc"),
+            KeyValue::new("long", "abcdef"),
+            KeyValue::new("multibyte", "héllo"),
+            KeyValue::new("int", 123456),
+            KeyValue::new(
+                "array",
+                Value::Array(vec![StringValue::from("abcdef"), StringValue::from("a")].into()),
+            ),
+        ];
+        limits.truncate_attribute_values(&mut attributes);
+
+        assert_eq!(
+            attributes,
+            vec![
+                KeyValue::new("short", "abc"),
+                KeyValue::new("long", "abc"),
+                KeyValue::new("multibyte", "hél"),
+                KeyValue::new("int", 123456),
+                KeyValue::new(
+                    "array",
+                    Value::Array(vec![StringValue::from("abc"), StringValue::from("a")].into()),
+                ),
+            ]
+        );
+
+        let mut attributes = vec![KeyValue::new("long", "abcdef")];
+        SpanLimits::default().truncate_attribute_values(&mut attributes);
+        assert_eq!(attributes, vec![KeyValue::new("long", "abcdef")]);
+    }
+}
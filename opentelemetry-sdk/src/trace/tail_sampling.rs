@@ -0,0 +1,1019 @@
+//! # Tail-based sampling
+//!
+//! Samplers decide whether a span is sampled when it starts, before anything
+//! is known about its outcome. The [`TailSamplingSpanProcessor`] instead
+//! buffers the finished spans of each trace, and decides whether to keep the
+//! whole trace once it had time to complete, based on all its spans. This
+//! allows to keep, for instance, every trace containing an error or a slow
+//! span, and only a fraction of the others.
+//!
+//! ```ascii
+//!   +-----+--------------+   +---------------------------+   +-------------------+
+//!   |     |              |   |                           |   |                   |
+//!   | SDK | Span.end()   +---> TailSamplingSpanProcessor +---> (Batch)Processor  |
+//!   |     |              |   |  buffer, wait, decide     |   | or SpanExporter   |
+//!   |     |              |   |                           |   |                   |
+//!   +-----+--------------+   +---------------------------+   +-------------------+
+//! ```
+
+use crate::error::{OTelSdkError, OTelSdkResult};
+use crate::resource::Resource;
+use crate::trace::sampler::sample_based_on_probability;
+use crate::trace::{RateLimitingSampler, Span, SpanData, SpanExporter, SpanProcessor};
+use opentelemetry::trace::{SamplingDecision, Status, TraceId};
+use opentelemetry::{otel_debug, otel_error, otel_info, otel_warn, Context, KeyValue};
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Debug;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{sync_channel, Receiver, RecvTimeoutError, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Default time to wait for the spans of a trace before deciding.
+const DEFAULT_DECISION_WAIT: Duration = Duration::from_secs(30);
+/// Default maximum number of traces waiting for a decision.
+const DEFAULT_MAX_TRACES: usize = 10_000;
+/// Default maximum number of spans buffered per trace.
+const DEFAULT_MAX_SPANS_PER_TRACE: usize = 1_000;
+/// Default maximum number of ended spans queued for the background thread.
+const DEFAULT_MAX_QUEUE_SIZE: usize = 2_048;
+
+/// Decides whether a whole trace is kept by the [`TailSamplingSpanProcessor`].
+pub trait ShouldKeepTrace: Send + Sync + Debug {
+    /// Returns `true` if the trace made of `spans` should be kept.
+    ///
+    /// `spans` holds the spans of the trace which ended before the decision,
+    /// in the order they ended. It is never empty.
+    fn should_keep(&self, spans: &[SpanData]) -> bool;
+}
+
+/// The built-in policies of the [`TailSamplingSpanProcessor`].
+#[derive(Debug)]
+pub enum TailSamplingPolicy {
+    /// Keep the traces with at least one span with an error status.
+    StatusError,
+    /// Keep the traces with at least one span lasting at least the given
+    /// duration.
+    Latency(Duration),
+    /// Keep the traces with at least one span with the given attribute.
+    Attribute(KeyValue),
+    /// Keep the given ratio of the traces, based on their trace id like
+    /// [`Sampler::TraceIdRatioBased`].
+    ///
+    /// [`Sampler::TraceIdRatioBased`]: crate::trace::Sampler::TraceIdRatioBased
+    Probabilistic(f64),
+    /// Keep at most the given number of traces per second.
+    RateLimited(RateLimitingSampler),
+    /// Keep the traces kept by a custom policy.
+    Custom(Box<dyn ShouldKeepTrace>),
+}
+
+impl ShouldKeepTrace for TailSamplingPolicy {
+    fn should_keep(&self, spans: &[SpanData]) -> bool {
+        match self {
+            TailSamplingPolicy::StatusError => spans
+                .iter()
+                .any(|span| matches!(span.status, Status::Error { .. })),
+            TailSamplingPolicy::Latency(threshold) => spans.iter().any(|span| {
+                span.end_time
+                    .duration_since(span.start_time)
+                    .is_ok_and(|duration| duration >= *threshold)
+            }),
+            TailSamplingPolicy::Attribute(attribute) => {
+                spans.iter().any(|span| span.attributes.contains(attribute))
+            }
+            TailSamplingPolicy::Probabilistic(prob) => spans.first().is_some_and(|span| {
+                sample_based_on_probability(prob, span.span_context.trace_id())
+                    == SamplingDecision::RecordAndSample
+            }),
+            TailSamplingPolicy::RateLimited(rate_limiter) => rate_limiter.try_acquire(),
+            TailSamplingPolicy::Custom(policy) => policy.should_keep(spans),
+        }
+    }
+}
+
+/// Counts of the traces and spans handled by a [`TailSamplingSpanProcessor`].
+///
+/// The counts are shared by all the clones of the stats, and keep being
+/// updated by the processor once it is registered in a tracer provider.
+#[derive(Clone, Debug, Default)]
+pub struct TailSamplingStats {
+    inner: Arc<StatsCounters>,
+}
+
+#[derive(Debug, Default)]
+struct StatsCounters {
+    kept_traces: AtomicU64,
+    kept_spans: AtomicU64,
+    dropped_traces: AtomicU64,
+    dropped_spans: AtomicU64,
+    evicted_traces: AtomicU64,
+    evicted_spans: AtomicU64,
+}
+
+impl TailSamplingStats {
+    /// The number of traces kept by the policies.
+    pub fn kept_traces(&self) -> u64 {
+        self.inner.kept_traces.load(Ordering::Relaxed)
+    }
+
+    /// The number of spans forwarded, as part of a kept trace.
+    pub fn kept_spans(&self) -> u64 {
+        self.inner.kept_spans.load(Ordering::Relaxed)
+    }
+
+    /// The number of traces not kept by any policy.
+    pub fn dropped_traces(&self) -> u64 {
+        self.inner.dropped_traces.load(Ordering::Relaxed)
+    }
+
+    /// The number of spans dropped, as part of a trace not kept by any policy.
+    pub fn dropped_spans(&self) -> u64 {
+        self.inner.dropped_spans.load(Ordering::Relaxed)
+    }
+
+    /// The number of traces evicted before their decision, to stay within the
+    /// maximum number of buffered traces.
+    pub fn evicted_traces(&self) -> u64 {
+        self.inner.evicted_traces.load(Ordering::Relaxed)
+    }
+
+    /// The number of spans lost to stay within the memory bounds, either as
+    /// part of an evicted trace, beyond the maximum number of spans per trace,
+    /// or because the queue of ended spans was full.
+    pub fn evicted_spans(&self) -> u64 {
+        self.inner.evicted_spans.load(Ordering::Relaxed)
+    }
+
+    fn add_evicted_spans(&self, count: usize) {
+        if self
+            .inner
+            .evicted_spans
+            .fetch_add(count as u64, Ordering::Relaxed)
+            == 0
+        {
+            otel_warn!(name: "TailSamplingSpanProcessor.SpanEvictionStarted",
+                message = "TailSamplingSpanProcessor evicted spans to stay within its memory bounds. No further internal log will be emitted for further evictions until Shutdown. During Shutdown time, a log will be emitted with exact count of total Spans evicted.");
+        }
+    }
+}
+
+/// Where the spans of the kept traces are forwarded.
+#[derive(Debug)]
+enum Downstream {
+    Processor(Box<dyn SpanProcessor>),
+    Exporter(Box<dyn SpanExporter>),
+}
+
+impl Downstream {
+    fn forward(&mut self, spans: Vec<SpanData>) -> OTelSdkResult {
+        if spans.is_empty() {
+            return Ok(());
+        }
+        match self {
+            Downstream::Processor(processor) => {
+                spans.into_iter().for_each(|span| processor.on_end(span));
+                Ok(())
+            }
+            Downstream::Exporter(exporter) => {
+                let result = futures_executor::block_on(exporter.export(spans));
+                if let Err(err) = &result {
+                    otel_error!(
+                        name: "TailSamplingSpanProcessor.ExportError",
+                        error = format!("{}", err)
+                    );
+                }
+                result
+            }
+        }
+    }
+
+    fn force_flush(&mut self) -> OTelSdkResult {
+        match self {
+            Downstream::Processor(processor) => processor.force_flush(),
+            Downstream::Exporter(exporter) => exporter.force_flush(),
+        }
+    }
+
+    fn shutdown(&mut self) -> OTelSdkResult {
+        match self {
+            Downstream::Processor(processor) => processor.shutdown(),
+            Downstream::Exporter(exporter) => exporter.shutdown(),
+        }
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        match self {
+            Downstream::Processor(processor) => processor.set_resource(resource),
+            Downstream::Exporter(exporter) => exporter.set_resource(resource),
+        }
+    }
+}
+
+/// The spans waiting for a decision, and the recent decisions.
+#[derive(Debug, Default)]
+struct TraceBuffer {
+    /// Spans of the traces waiting for a decision.
+    traces: HashMap<TraceId, Vec<SpanData>>,
+    /// Traces waiting for a decision, in the order they were first seen.
+    pending: VecDeque<(TraceId, Instant)>,
+    /// Decisions of the recent traces, for their spans ending afterwards.
+    decisions: HashMap<TraceId, bool>,
+    decision_order: VecDeque<TraceId>,
+    /// Spans of kept traces which ended after the decision, forwarded with
+    /// the next decisions.
+    late_spans: Vec<SpanData>,
+}
+
+/// Messages exchanged between the processor and the background thread.
+#[derive(Debug)]
+enum TailSamplingMessage {
+    /// The span queue is filling up, the flag is reset once it was drained.
+    ReceiveSpans(Arc<AtomicBool>),
+    ForceFlush(SyncSender<OTelSdkResult>),
+    Shutdown(SyncSender<OTelSdkResult>),
+    SetResource(Arc<Resource>),
+}
+
+/// The decision side of the processor, running on the background thread.
+///
+/// The worker owns the buffered spans: ended spans reach it through a bounded
+/// queue, so that ending a span never waits on the decisions.
+#[derive(Debug)]
+struct DecisionWorker {
+    buffer: TraceBuffer,
+    span_receiver: Receiver<SpanData>,
+    queued_spans: Arc<AtomicUsize>,
+    policies: Vec<Box<dyn ShouldKeepTrace>>,
+    downstream: Downstream,
+    stats: TailSamplingStats,
+    decision_wait: Duration,
+    max_traces: usize,
+    max_spans_per_trace: usize,
+    max_queue_size: usize,
+}
+
+impl DecisionWorker {
+    /// Buffer the spans queued since the last call.
+    ///
+    /// At most a queue worth of spans is received, so that the late spans
+    /// forwarded with the next decisions stay within the same bound.
+    fn receive_spans(&mut self) {
+        let mut received = 0;
+        while received < self.max_queue_size {
+            let Ok(span) = self.span_receiver.try_recv() else {
+                break;
+            };
+            received += 1;
+            self.buffer_span(span);
+        }
+        self.queued_spans.fetch_sub(received, Ordering::Relaxed);
+    }
+
+    fn buffer_span(&mut self, span: SpanData) {
+        let trace_id = span.span_context.trace_id();
+        let counters = &self.stats.inner;
+        let buffer = &mut self.buffer;
+
+        if let Some(&keep) = buffer.decisions.get(&trace_id) {
+            if keep {
+                counters.kept_spans.fetch_add(1, Ordering::Relaxed);
+                buffer.late_spans.push(span);
+            } else {
+                counters.dropped_spans.fetch_add(1, Ordering::Relaxed);
+            }
+            return;
+        }
+
+        if let Some(spans) = buffer.traces.get_mut(&trace_id) {
+            if spans.len() < self.max_spans_per_trace {
+                spans.push(span);
+            } else {
+                self.stats.add_evicted_spans(1);
+            }
+            return;
+        }
+
+        if buffer.traces.len() >= self.max_traces {
+            // Evict the oldest trace to make room for the new one.
+            while let Some((oldest, _)) = buffer.pending.pop_front() {
+                if let Some(evicted) = buffer.traces.remove(&oldest) {
+                    counters.evicted_traces.fetch_add(1, Ordering::Relaxed);
+                    self.stats.add_evicted_spans(evicted.len());
+                    break;
+                }
+            }
+        }
+        buffer.pending.push_back((trace_id, Instant::now()));
+        buffer.traces.insert(trace_id, vec![span]);
+    }
+
+    /// Decide the traces waiting for longer than the decision wait, or all of
+    /// them with `all`, and forward the kept spans.
+    fn decide_and_forward(&mut self, all: bool) -> OTelSdkResult {
+        let kept_spans = self.decide(all);
+        self.downstream.forward(kept_spans)
+    }
+
+    fn decide(&mut self, all: bool) -> Vec<SpanData> {
+        let now = Instant::now();
+        let buffer = &mut self.buffer;
+        let mut kept_spans = std::mem::take(&mut buffer.late_spans);
+        while let Some(&(trace_id, first_seen)) = buffer.pending.front() {
+            if !all && now.saturating_duration_since(first_seen) < self.decision_wait {
+                break;
+            }
+            buffer.pending.pop_front();
+            let Some(spans) = buffer.traces.remove(&trace_id) else {
+                continue;
+            };
+
+            // Policies are evaluated in order, the first one keeping the trace
+            // wins, so that rate limits only apply to the remaining traces.
+            let keep = self
+                .policies
+                .iter()
+                .any(|policy| policy.should_keep(&spans));
+
+            buffer.decisions.insert(trace_id, keep);
+            buffer.decision_order.push_back(trace_id);
+            if buffer.decision_order.len() > self.max_traces {
+                if let Some(oldest) = buffer.decision_order.pop_front() {
+                    buffer.decisions.remove(&oldest);
+                }
+            }
+
+            let counters = &self.stats.inner;
+            if keep {
+                counters.kept_traces.fetch_add(1, Ordering::Relaxed);
+                counters
+                    .kept_spans
+                    .fetch_add(spans.len() as u64, Ordering::Relaxed);
+                kept_spans.extend(spans);
+            } else {
+                counters.dropped_traces.fetch_add(1, Ordering::Relaxed);
+                counters
+                    .dropped_spans
+                    .fetch_add(spans.len() as u64, Ordering::Relaxed);
+            }
+        }
+        kept_spans
+    }
+}
+
+/// A [`SpanProcessor`] sampling whole traces, once they ended.
+///
+/// The processor buffers the finished spans of each trace for the decision
+/// wait, 30 seconds by default, counted from the end of the first span of the
+/// trace it received. It then applies its policies: the trace is kept if any
+/// policy keeps it, and all its spans are forwarded to the downstream
+/// processor or exporter. Otherwise, they are dropped. The spans of the trace
+/// ending after the decision follow the same fate.
+///
+/// Spans are only buffered once sampled: use a sampler sampling all the traces
+/// tail sampling should apply to, like the default
+/// `Sampler::ParentBased(Box::new(Sampler::AlwaysOn))`.
+///
+/// The memory used by the processor is bounded: when the maximum number of
+/// traces waiting for a decision is reached, the oldest trace is evicted, and
+/// the spans beyond the maximum number of spans per trace are evicted as well.
+/// Ended spans are handed to the background thread through a queue, and spans
+/// ending while the queue is full are evicted too. Evicted spans are lost.
+/// [`TailSamplingStats`] counts the kept, dropped and evicted traces and spans,
+/// and the evictions are reported at shutdown through internal logs.
+///
+/// `force_flush` and `shutdown` decide all the buffered traces without
+/// waiting.
+///
+/// The decisions are made and the spans forwarded on a **dedicated background
+/// thread**. Spans are forwarded in the same way as by the
+/// [`BatchSpanProcessor`], so the same exporter features are supported when
+/// forwarding to an exporter directly.
+///
+/// # Examples
+///
+/// ```
+/// use opentelemetry_sdk::testing::trace::NoopSpanExporter;
+/// use opentelemetry_sdk::trace::{
+///     BatchSpanProcessor, RateLimitingSampler, SdkTracerProvider, TailSamplingPolicy,
+///     TailSamplingSpanProcessor,
+/// };
+/// use std::time::Duration;
+///
+/// // Keep all the traces with an error or a span slower than 2 seconds, and
+/// // up to 10 other traces per second.
+/// let processor = TailSamplingSpanProcessor::builder(
+///     BatchSpanProcessor::builder(NoopSpanExporter::new()).build(),
+/// )
+/// .with_policy(TailSamplingPolicy::StatusError)
+/// .with_policy(TailSamplingPolicy::Latency(Duration::from_secs(2)))
+/// .with_policy(TailSamplingPolicy::RateLimited(RateLimitingSampler::new(10.0)))
+/// .with_decision_wait(Duration::from_secs(10))
+/// .build();
+/// let stats = processor.stats();
+///
+/// let provider = SdkTracerProvider::builder()
+///     .with_span_processor(processor)
+///     .build();
+///
+/// // ...
+///
+/// provider.shutdown().unwrap();
+/// println!("{} spans evicted", stats.evicted_spans());
+/// ```
+///
+/// [`BatchSpanProcessor`]: crate::trace::BatchSpanProcessor
+#[derive(Debug)]
+pub struct TailSamplingSpanProcessor {
+    span_sender: SyncSender<SpanData>,
+    message_sender: SyncSender<TailSamplingMessage>,
+    queued_spans: Arc<AtomicUsize>,
+    receive_spans_message_sent: Arc<AtomicBool>,
+    handle: Mutex<Option<thread::JoinHandle<()>>>,
+    stats: TailSamplingStats,
+    max_traces: usize,
+    max_spans_per_trace: usize,
+    max_queue_size: usize,
+    forceflush_timeout: Duration,
+    shutdown_timeout: Duration,
+    is_shutdown: AtomicBool,
+}
+
+impl TailSamplingSpanProcessor {
+    /// Create a builder of a processor forwarding the kept traces to the given
+    /// span processor.
+    pub fn builder<P>(processor: P) -> TailSamplingSpanProcessorBuilder
+    where
+        P: SpanProcessor + 'static,
+    {
+        TailSamplingSpanProcessorBuilder::new(Downstream::Processor(Box::new(processor)))
+    }
+
+    /// Create a builder of a processor exporting the kept traces with the
+    /// given exporter, one export per decision round.
+    pub fn builder_with_exporter<E>(exporter: E) -> TailSamplingSpanProcessorBuilder
+    where
+        E: SpanExporter + 'static,
+    {
+        TailSamplingSpanProcessorBuilder::new(Downstream::Exporter(Box::new(exporter)))
+    }
+
+    /// The counts of the traces and spans handled by this processor.
+    pub fn stats(&self) -> TailSamplingStats {
+        self.stats.clone()
+    }
+
+    fn new(builder: TailSamplingSpanProcessorBuilder) -> Self {
+        let (span_sender, span_receiver) = sync_channel::<SpanData>(builder.max_queue_size);
+        let (message_sender, message_receiver) = sync_channel::<TailSamplingMessage>(64);
+        let queued_spans = Arc::new(AtomicUsize::new(0));
+        let stats = TailSamplingStats::default();
+        let decision_wait = builder.decision_wait;
+        // Decisions are checked a few times per decision wait.
+        let tick = (decision_wait / 4).clamp(Duration::from_millis(10), Duration::from_secs(1));
+
+        let mut worker = DecisionWorker {
+            buffer: TraceBuffer::default(),
+            span_receiver,
+            queued_spans: queued_spans.clone(),
+            policies: builder.policies,
+            downstream: builder.downstream,
+            stats: stats.clone(),
+            decision_wait,
+            max_traces: builder.max_traces,
+            max_spans_per_trace: builder.max_spans_per_trace,
+            max_queue_size: builder.max_queue_size,
+        };
+
+        let handle = thread::Builder::new()
+            .name("OpenTelemetry.Traces.TailSamplingProcessor".to_string())
+            .spawn(move || {
+                otel_info!(
+                    name: "TailSamplingSpanProcessor.ThreadStarted",
+                    decision_wait_in_millisecs = decision_wait.as_millis(),
+                    max_traces = worker.max_traces,
+                    max_queue_size = worker.max_queue_size,
+                );
+                loop {
+                    match message_receiver.recv_timeout(tick) {
+                        Ok(TailSamplingMessage::ReceiveSpans(message_sent)) => {
+                            // Reset the flag now the spans are about to be received.
+                            message_sent.store(false, Ordering::Relaxed);
+                        }
+                        Ok(TailSamplingMessage::ForceFlush(sender)) => {
+                            otel_debug!(name: "TailSamplingSpanProcessor.DecidingDueToForceFlush");
+                            worker.receive_spans();
+                            let result = worker.decide_and_forward(true);
+                            let _ = sender.send(result.and(worker.downstream.force_flush()));
+                        }
+                        Ok(TailSamplingMessage::Shutdown(sender)) => {
+                            otel_debug!(name: "TailSamplingSpanProcessor.DecidingDueToShutdown");
+                            worker.receive_spans();
+                            let result = worker.decide_and_forward(true);
+                            let _ = sender.send(result.and(worker.downstream.shutdown()));
+
+                            otel_debug!(
+                                name: "TailSamplingSpanProcessor.ThreadExiting",
+                                reason = "ShutdownRequested"
+                            );
+                            break;
+                        }
+                        Ok(TailSamplingMessage::SetResource(resource)) => {
+                            worker.downstream.set_resource(&resource);
+                        }
+                        Err(RecvTimeoutError::Timeout) => {}
+                        Err(RecvTimeoutError::Disconnected) => {
+                            otel_debug!(
+                                name: "TailSamplingSpanProcessor.ThreadExiting",
+                                reason = "MessageSenderDisconnected"
+                            );
+                            break;
+                        }
+                    }
+                    worker.receive_spans();
+                    let _ = worker.decide_and_forward(false);
+                }
+                otel_info!(name: "TailSamplingSpanProcessor.ThreadStopped");
+            })
+            .expect("Failed to spawn thread");
+
+        TailSamplingSpanProcessor {
+            span_sender,
+            message_sender,
+            queued_spans,
+            receive_spans_message_sent: Arc::new(AtomicBool::new(false)),
+            handle: Mutex::new(Some(handle)),
+            stats,
+            max_traces: builder.max_traces,
+            max_spans_per_trace: builder.max_spans_per_trace,
+            max_queue_size: builder.max_queue_size,
+            forceflush_timeout: Duration::from_secs(5),
+            shutdown_timeout: Duration::from_secs(5),
+            is_shutdown: AtomicBool::new(false),
+        }
+    }
+}
+
+impl SpanProcessor for TailSamplingSpanProcessor {
+    fn on_start(&self, _span: &mut Span, _cx: &Context) {
+        // Ignored
+    }
+
+    fn on_end(&self, span: SpanData) {
+        if !span.span_context.is_sampled() {
+            return;
+        }
+        if self.is_shutdown.load(Ordering::Relaxed) {
+            otel_warn!(
+                name: "TailSamplingSpanProcessor.Emit.ProcessorShutdown",
+                message = "TailSamplingSpanProcessor has been shutdown. No further spans will be emitted."
+            );
+            return;
+        }
+
+        if self.span_sender.try_send(span).is_err() {
+            self.stats.add_evicted_spans(1);
+            return;
+        }
+
+        // Ask the background thread to receive the queued spans before the
+        // next decision round once the queue is half full.
+        if self.queued_spans.fetch_add(1, Ordering::Relaxed) + 1 >= self.max_queue_size / 2
+            && !self.receive_spans_message_sent.load(Ordering::Relaxed)
+            && !self
+                .receive_spans_message_sent
+                .swap(true, Ordering::Relaxed)
+            && self
+                .message_sender
+                .try_send(TailSamplingMessage::ReceiveSpans(
+                    self.receive_spans_message_sent.clone(),
+                ))
+                .is_err()
+        {
+            self.receive_spans_message_sent
+                .store(false, Ordering::Relaxed);
+        }
+    }
+
+    fn force_flush(&self) -> OTelSdkResult {
+        if self.is_shutdown.load(Ordering::Relaxed) {
+            return Err(OTelSdkError::AlreadyShutdown);
+        }
+        let (sender, receiver) = sync_channel(1);
+        self.message_sender
+            .try_send(TailSamplingMessage::ForceFlush(sender))
+            .map_err(|e| OTelSdkError::InternalFailure(e.to_string()))?;
+
+        receiver
+            .recv_timeout(self.forceflush_timeout)
+            .map_err(|_| OTelSdkError::Timeout(self.forceflush_timeout))?
+    }
+
+    fn shutdown(&self) -> OTelSdkResult {
+        if self.is_shutdown.swap(true, Ordering::Relaxed) {
+            return Err(OTelSdkError::AlreadyShutdown);
+        }
+
+        let (sender, receiver) = sync_channel(1);
+        self.message_sender
+            .try_send(TailSamplingMessage::Shutdown(sender))
+            .map_err(|e| OTelSdkError::InternalFailure(e.to_string()))?;
+
+        let result = receiver
+            .recv_timeout(self.shutdown_timeout)
+            .map_err(|_| OTelSdkError::Timeout(self.shutdown_timeout))?;
+        if let Some(handle) = self.handle.lock().unwrap().take() {
+            if let Err(err) = handle.join() {
+                return Err(OTelSdkError::InternalFailure(format!(
+                    "Background thread failed to join during shutdown. This may indicate a panic or unexpected termination: {:?}",
+                    err
+                )));
+            }
+        }
+
+        let evicted_spans = self.stats.evicted_spans();
+        if evicted_spans > 0 {
+            otel_warn!(
+                name: "TailSamplingSpanProcessor.SpansEvicted",
+                evicted_span_count = evicted_spans,
+                evicted_trace_count = self.stats.evicted_traces(),
+                max_traces = self.max_traces,
+                max_spans_per_trace = self.max_spans_per_trace,
+                message = "Spans were evicted to stay within the memory bounds of the processor. The counts represent the total count of spans and traces evicted in the lifetime of this TailSamplingSpanProcessor. Consider increasing the maximum number of traces and/or decreasing the decision wait."
+            );
+        }
+        otel_debug!(
+            name: "TailSamplingSpanProcessor.Stats",
+            kept_trace_count = self.stats.kept_traces(),
+            kept_span_count = self.stats.kept_spans(),
+            dropped_trace_count = self.stats.dropped_traces(),
+            dropped_span_count = self.stats.dropped_spans(),
+        );
+        result
+    }
+
+    fn set_resource(&mut self, resource: &Resource) {
+        let resource = Arc::new(resource.clone());
+        let _ = self
+            .message_sender
+            .try_send(TailSamplingMessage::SetResource(resource));
+    }
+}
+
+/// Builder for [`TailSamplingSpanProcessor`].
+#[derive(Debug)]
+pub struct TailSamplingSpanProcessorBuilder {
+    downstream: Downstream,
+    policies: Vec<Box<dyn ShouldKeepTrace>>,
+    decision_wait: Duration,
+    max_traces: usize,
+    max_spans_per_trace: usize,
+    max_queue_size: usize,
+}
+
+impl TailSamplingSpanProcessorBuilder {
+    fn new(downstream: Downstream) -> Self {
+        TailSamplingSpanProcessorBuilder {
+            downstream,
+            policies: Vec::new(),
+            decision_wait: DEFAULT_DECISION_WAIT,
+            max_traces: DEFAULT_MAX_TRACES,
+            max_spans_per_trace: DEFAULT_MAX_SPANS_PER_TRACE,
+            max_queue_size: DEFAULT_MAX_QUEUE_SIZE,
+        }
+    }
+
+    /// Add a policy. A trace is kept if any of the policies keeps it, and no
+    /// trace is kept without policies.
+    ///
+    /// Policies are evaluated in the order they were added, until one of them
+    /// keeps the trace.
+    pub fn with_policy<P>(mut self, policy: P) -> Self
+    where
+        P: ShouldKeepTrace + 'static,
+    {
+        self.policies.push(Box::new(policy));
+        self
+    }
+
+    /// Set the time to wait for the spans of a trace before deciding, counted
+    /// from the end of its first span. The default value is 30 seconds.
+    pub fn with_decision_wait(mut self, decision_wait: Duration) -> Self {
+        self.decision_wait = decision_wait;
+        self
+    }
+
+    /// Set the maximum number of traces waiting for a decision. The oldest
+    /// trace is evicted to make room for a new one. The default value is
+    /// 10000.
+    pub fn with_max_traces(mut self, max_traces: usize) -> Self {
+        self.max_traces = max_traces.max(1);
+        self
+    }
+
+    /// Set the maximum number of spans buffered per trace, further spans are
+    /// evicted. The default value is 1000.
+    pub fn with_max_spans_per_trace(mut self, max_spans_per_trace: usize) -> Self {
+        self.max_spans_per_trace = max_spans_per_trace.max(1);
+        self
+    }
+
+    /// Set the maximum number of ended spans queued for the background thread,
+    /// spans ending while the queue is full are evicted. The default value is
+    /// 2048.
+    pub fn with_max_queue_size(mut self, max_queue_size: usize) -> Self {
+        self.max_queue_size = max_queue_size.max(1);
+        self
+    }
+
+    /// Build a new instance of [`TailSamplingSpanProcessor`].
+    pub fn build(self) -> TailSamplingSpanProcessor {
+        TailSamplingSpanProcessor::new(self)
+    }
+}
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::testing::trace::new_test_export_span_data;
+    use crate::trace::{InMemorySpanExporter, InMemorySpanExporterBuilder, SimpleSpanProcessor};
+    use opentelemetry::trace::{SpanContext, SpanId, TraceFlags, TraceState};
+    use std::time::SystemTime;
+
+    fn span(trace_id: u128, span_id: u64) -> SpanData {
+        SpanData {
+            span_context: SpanContext::new(
+                TraceId::from_u128(trace_id),
+                SpanId::from_u64(span_id),
+                TraceFlags::SAMPLED,
+                false,
+                TraceState::default(),
+            ),
+            ..new_test_export_span_data()
+        }
+    }
+
+    fn exported_span_ids(exporter: &InMemorySpanExporter) -> Vec<(u128, u64)> {
+        let mut ids = exporter
+            .get_finished_spans()
+            .unwrap()
+            .into_iter()
+            .map(|span| {
+                (
+                    u128::from_be_bytes(span.span_context.trace_id().to_bytes()),
+                    u64::from_be_bytes(span.span_context.span_id().to_bytes()),
+                )
+            })
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids
+    }
+
+    fn wait_for_spans(exporter: &InMemorySpanExporter, count: usize) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while exporter.get_finished_spans().unwrap().len() < count {
+            assert!(Instant::now() < deadline, "timed out waiting for spans");
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    #[test]
+    fn keeps_traces_matching_any_policy() {
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor = TailSamplingSpanProcessor::builder_with_exporter(exporter.clone())
+            .with_policy(TailSamplingPolicy::StatusError)
+            .with_policy(TailSamplingPolicy::Latency(Duration::from_secs(2)))
+            .with_policy(TailSamplingPolicy::Attribute(KeyValue::new("debug", true)))
+            .with_decision_wait(Duration::from_secs(3600))
+            .build();
+        let stats = processor.stats();
+
+        // ok trace
+        processor.on_end(span(1, 1));
+        processor.on_end(span(1, 2));
+        // trace with an error
+        processor.on_end(span(2, 1));
+        processor.on_end(SpanData {
+            status: Status::error("failed"),
+            ..span(2, 2)
+        });
+        // trace with a slow span
+        let start_time = SystemTime::now();
+        processor.on_end(SpanData {
+            start_time,
+            end_time: start_time + Duration::from_secs(3),
+            ..span(3, 1)
+        });
+        // trace with a matching attribute
+        processor.on_end(SpanData {
+            attributes: vec![KeyValue::new("debug", true)],
+            ..span(4, 1)
+        });
+        processor.on_end(SpanData {
+            attributes: vec![KeyValue::new("debug", false)],
+            ..span(5, 1)
+        });
+        // unsampled spans are ignored
+        processor.on_end(SpanData {
+            status: Status::error("failed"),
+            span_context: SpanContext::new(
+                TraceId::from_u128(6),
+                SpanId::from_u64(1),
+                TraceFlags::default(),
+                false,
+                TraceState::default(),
+            ),
+            ..new_test_export_span_data()
+        });
+
+        assert!(exporter.get_finished_spans().unwrap().is_empty());
+        processor.force_flush().unwrap();
+        assert_eq!(
+            exported_span_ids(&exporter),
+            vec![(2, 1), (2, 2), (3, 1), (4, 1)]
+        );
+        assert_eq!(stats.kept_traces(), 3);
+        assert_eq!(stats.kept_spans(), 4);
+        assert_eq!(stats.dropped_traces(), 2);
+        assert_eq!(stats.dropped_spans(), 3);
+        assert_eq!(stats.evicted_spans(), 0);
+
+        processor.shutdown().unwrap();
+    }
+
+    #[test]
+    fn decides_after_decision_wait() {
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor = TailSamplingSpanProcessor::builder(SimpleSpanProcessor::new(Box::new(
+            exporter.clone(),
+        )))
+        .with_policy(TailSamplingPolicy::StatusError)
+        .with_decision_wait(Duration::from_millis(50))
+        .build();
+        let stats = processor.stats();
+
+        processor.on_end(SpanData {
+            status: Status::error("failed"),
+            ..span(1, 1)
+        });
+        processor.on_end(span(2, 1));
+        wait_for_spans(&exporter, 1);
+        assert_eq!(exported_span_ids(&exporter), vec![(1, 1)]);
+
+        // spans ending after the decision follow the decision of their trace
+        while stats.dropped_traces() == 0 {
+            thread::sleep(Duration::from_millis(5));
+        }
+        processor.on_end(span(1, 2));
+        processor.on_end(span(2, 2));
+        wait_for_spans(&exporter, 2);
+        assert_eq!(exported_span_ids(&exporter), vec![(1, 1), (1, 2)]);
+        assert_eq!(stats.kept_spans(), 2);
+        assert_eq!(stats.dropped_spans(), 2);
+
+        processor.shutdown().unwrap();
+        // the downstream processor is shut down as well
+        assert!(exporter.get_finished_spans().unwrap().is_empty());
+    }
+
+    #[test]
+    fn evicts_to_stay_within_bounds() {
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor = TailSamplingSpanProcessor::builder_with_exporter(exporter.clone())
+            .with_policy(TailSamplingPolicy::Probabilistic(1.0))
+            .with_decision_wait(Duration::from_secs(3600))
+            .with_max_traces(2)
+            .with_max_spans_per_trace(2)
+            .build();
+        let stats = processor.stats();
+
+        processor.on_end(span(1, 1));
+        processor.on_end(span(1, 2));
+        processor.on_end(span(2, 1));
+        processor.on_end(span(2, 2));
+        processor.on_end(span(2, 3)); // beyond the maximum number of spans per trace
+        processor.on_end(span(3, 1)); // evicts the oldest trace
+
+        processor.force_flush().unwrap();
+        assert_eq!(exported_span_ids(&exporter), vec![(2, 1), (2, 2), (3, 1)]);
+        assert_eq!(stats.evicted_traces(), 1);
+        assert_eq!(stats.evicted_spans(), 3);
+        assert_eq!(stats.kept_traces(), 2);
+
+        processor.shutdown().unwrap();
+        processor.on_end(span(4, 1));
+        assert_eq!(stats.kept_spans(), 3);
+    }
+
+    #[test]
+    fn ending_spans_does_not_wait_for_decisions() {
+        #[derive(Debug)]
+        struct Blocking {
+            deciding: SyncSender<()>,
+            release: Mutex<Receiver<()>>,
+        }
+
+        impl ShouldKeepTrace for Blocking {
+            fn should_keep(&self, _spans: &[SpanData]) -> bool {
+                let _ = self.deciding.send(());
+                let _ = self.release.lock().unwrap().recv();
+                true
+            }
+        }
+
+        let (deciding, deciding_receiver) = sync_channel(8);
+        let (release, release_receiver) = sync_channel(8);
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor = Arc::new(
+            TailSamplingSpanProcessor::builder_with_exporter(exporter.clone())
+                .with_policy(TailSamplingPolicy::Custom(Box::new(Blocking {
+                    deciding,
+                    release: Mutex::new(release_receiver),
+                })))
+                .with_decision_wait(Duration::from_secs(3600))
+                .with_max_queue_size(2)
+                .build(),
+        );
+        let stats = processor.stats();
+
+        processor.on_end(span(1, 1));
+        let flushing = {
+            let processor = processor.clone();
+            thread::spawn(move || processor.force_flush())
+        };
+        deciding_receiver.recv().unwrap();
+
+        // the policy is still deciding, spans are queued until the queue is full
+        processor.on_end(span(2, 1));
+        processor.on_end(span(2, 2));
+        processor.on_end(span(2, 3));
+        assert_eq!(stats.evicted_spans(), 1);
+
+        release.send(()).unwrap();
+        flushing.join().unwrap().unwrap();
+        release.send(()).unwrap();
+        processor.force_flush().unwrap();
+        assert_eq!(exported_span_ids(&exporter), vec![(1, 1), (2, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn fallback_policies() {
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor = TailSamplingSpanProcessor::builder_with_exporter(exporter.clone())
+            .with_policy(TailSamplingPolicy::StatusError)
+            .with_policy(TailSamplingPolicy::RateLimited(RateLimitingSampler::new(
+                1.0,
+            )))
+            .with_decision_wait(Duration::from_secs(3600))
+            .build();
+
+        processor.on_end(span(1, 1));
+        processor.on_end(span(2, 1));
+        processor.on_end(SpanData {
+            status: Status::error("failed"),
+            ..span(3, 1)
+        });
+        processor.force_flush().unwrap();
+        // errors do not consume the rate limit
+        assert_eq!(exported_span_ids(&exporter), vec![(1, 1), (3, 1)]);
+
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor = TailSamplingSpanProcessor::builder_with_exporter(exporter.clone())
+            .with_policy(TailSamplingPolicy::Probabilistic(0.5))
+            .with_decision_wait(Duration::from_secs(3600))
+            .build();
+        // the ratio is applied to the low 63 bits of the trace id
+        processor.on_end(span(1, 1));
+        processor.on_end(span(u64::MAX as u128, 1));
+        processor.force_flush().unwrap();
+        assert_eq!(exported_span_ids(&exporter), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn custom_policy() {
+        #[derive(Debug)]
+        struct MinSpans(usize);
+
+        impl ShouldKeepTrace for MinSpans {
+            fn should_keep(&self, spans: &[SpanData]) -> bool {
+                spans.len() >= self.0
+            }
+        }
+
+        let exporter = InMemorySpanExporterBuilder::new().build();
+        let processor = TailSamplingSpanProcessor::builder_with_exporter(exporter.clone())
+            .with_policy(TailSamplingPolicy::Custom(Box::new(MinSpans(2))))
+            .build();
+
+        processor.on_end(span(1, 1));
+        processor.on_end(span(2, 1));
+        processor.on_end(span(2, 2));
+        processor.shutdown().unwrap();
+        // the exporter is shut down after the last export, which clears it
+        assert!(exporter.get_finished_spans().unwrap().is_empty());
+        assert_eq!(processor.stats().kept_spans(), 2);
+        assert_eq!(processor.stats().dropped_spans(), 1);
+    }
+}
@@ -69,6 +69,7 @@ impl SdkTracer {
             .len()
             .saturating_sub(span_attributes_limit);
         attribute_options.truncate(span_attributes_limit);
+        span_limits.truncate_attribute_values(&mut attribute_options);
         let dropped_attributes_count = dropped_attributes_count as u32;
 
         // Links are available as Option<Vec<Link>> in the builder
@@ -89,6 +90,7 @@ impl SdkTracer {
                 let dropped_attributes_count =
                     link.attributes.len().saturating_sub(link_attributes_limit);
                 link.attributes.truncate(link_attributes_limit);
+                span_limits.truncate_attribute_values(&mut link.attributes);
                 link.dropped_attributes_count = dropped_attributes_count as u32;
             }
             SpanLinks {
@@ -121,6 +123,7 @@ impl SdkTracer {
                     .len()
                     .saturating_sub(event_attributes_limit);
                 event.attributes.truncate(event_attributes_limit);
+                span_limits.truncate_attribute_values(&mut event.attributes);
                 event.dropped_attributes_count = dropped_attributes_count as u32;
             }
             SpanEvents {
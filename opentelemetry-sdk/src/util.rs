@@ -1,5 +1,8 @@
 //! Internal utilities
 
+#[doc(hidden)]
+pub mod timer;
+
 /// Helper which wraps `tokio::time::interval` and makes it return a stream
 #[cfg(any(feature = "rt-tokio", feature = "rt-tokio-current-thread"))]
 pub fn tokio_interval_stream(
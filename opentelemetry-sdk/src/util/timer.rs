@@ -0,0 +1,254 @@
+//! Runtime agnostic timers used by the exporters.
+//!
+//! The exporters can be driven by any async runtime (or none at all, when used
+//! from the batch processors' background threads or with blocking HTTP
+//! clients), so timers don't rely on a runtime provided timer. Instead, all the
+//! timers of the process are driven by a single, lazily started thread, which
+//! sleeps until the earliest deadline.
+//!
+//! These timers are only public for the exporter crates of this repository,
+//! they are not part of the public API and may change in any release.
+
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex, OnceLock};
+use std::task::{Context, Poll, Waker};
+use std::time::{Duration, Instant};
+
+/// Returns a future that completes once `duration` has elapsed.
+///
+/// The timer is unregistered if the future is dropped before completing.
+#[doc(hidden)]
+pub fn sleep(duration: Duration) -> Delay {
+    let shared = Arc::new(Mutex::new(DelayState::default()));
+    let key = Instant::now()
+        .checked_add(duration)
+        .and_then(|deadline| TimerThread::get()?.register(deadline, &shared));
+    if key.is_none() && TimerThread::get().is_none() {
+        // without a timer thread, don't wait forever
+        if let Ok(mut state) = shared.lock() {
+            state.completed = true;
+        }
+    }
+    Delay { shared, key }
+}
+
+/// Error returned by [`timeout`] when the future did not complete in time.
+#[doc(hidden)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Elapsed;
+
+/// Runs `future`, giving up once `duration` has elapsed.
+#[doc(hidden)]
+pub async fn timeout<F: Future>(duration: Duration, future: F) -> Result<F::Output, Elapsed> {
+    let mut future = std::pin::pin!(future);
+    let mut delay = sleep(duration);
+    std::future::poll_fn(|cx| {
+        if let Poll::Ready(output) = future.as_mut().poll(cx) {
+            return Poll::Ready(Ok(output));
+        }
+        Pin::new(&mut delay).poll(cx).map(|_| Err(Elapsed))
+    })
+    .await
+}
+
+#[derive(Debug, Default)]
+struct DelayState {
+    completed: bool,
+    waker: Option<Waker>,
+}
+
+/// The deadline of a registered timer, made unique by a sequence number.
+type TimerKey = (Instant, u64);
+
+/// The thread completing the timers once their deadline is reached.
+#[derive(Debug, Default)]
+struct TimerThread {
+    timers: Mutex<Timers>,
+    changed: Condvar,
+}
+
+#[derive(Debug, Default)]
+struct Timers {
+    pending: BTreeMap<TimerKey, Arc<Mutex<DelayState>>>,
+    next_seq: u64,
+}
+
+impl TimerThread {
+    /// The timer thread of the process, started on first use, or `None` if it
+    /// could not be started.
+    fn get() -> Option<&'static TimerThread> {
+        static TIMER_THREAD: OnceLock<Option<&'static TimerThread>> = OnceLock::new();
+        *TIMER_THREAD.get_or_init(|| {
+            let timer_thread: &'static TimerThread = Box::leak(Box::default());
+            std::thread::Builder::new()
+                .name("OpenTelemetry.Timer".to_string())
+                .spawn(move || timer_thread.run())
+                .ok()
+                .map(|_| timer_thread)
+        })
+    }
+
+    fn register(&self, deadline: Instant, shared: &Arc<Mutex<DelayState>>) -> Option<TimerKey> {
+        let mut timers = self.timers.lock().ok()?;
+        let key = (deadline, timers.next_seq);
+        timers.next_seq += 1;
+        let earliest = timers
+            .pending
+            .keys()
+            .next()
+            .map_or(true, |first| key < *first);
+        timers.pending.insert(key, Arc::clone(shared));
+        drop(timers);
+        if earliest {
+            self.changed.notify_one();
+        }
+        Some(key)
+    }
+
+    fn unregister(&self, key: &TimerKey) {
+        if let Ok(mut timers) = self.timers.lock() {
+            timers.pending.remove(key);
+        }
+    }
+
+    fn run(&self) {
+        let Ok(mut timers) = self.timers.lock() else {
+            return;
+        };
+        loop {
+            let now = Instant::now();
+            let mut expired = Vec::new();
+            while let Some(entry) = timers.pending.first_entry() {
+                if entry.key().0 > now {
+                    break;
+                }
+                expired.push(entry.remove());
+            }
+
+            if !expired.is_empty() {
+                // wake the tasks without holding the lock, they may register new timers
+                drop(timers);
+                for shared in expired {
+                    let waker = shared.lock().ok().and_then(|mut state| {
+                        state.completed = true;
+                        state.waker.take()
+                    });
+                    if let Some(waker) = waker {
+                        waker.wake();
+                    }
+                }
+                timers = match self.timers.lock() {
+                    Ok(timers) => timers,
+                    Err(_) => return,
+                };
+                continue;
+            }
+
+            let wait = match timers.pending.keys().next() {
+                Some((deadline, _)) => {
+                    let timeout = deadline.saturating_duration_since(now);
+                    self.changed
+                        .wait_timeout(timers, timeout)
+                        .ok()
+                        .map(|(timers, _)| timers)
+                }
+                None => self.changed.wait(timers).ok(),
+            };
+            let Some(guard) = wait else {
+                return;
+            };
+            timers = guard;
+        }
+    }
+}
+
+/// Future returned by [`sleep`].
+#[doc(hidden)]
+#[derive(Debug)]
+pub struct Delay {
+    shared: Arc<Mutex<DelayState>>,
+    key: Option<TimerKey>,
+}
+
+impl Future for Delay {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.shared.lock() {
+            Ok(mut state) if !state.completed => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+            // a poisoned lock means the timer thread is gone, don't wait forever
+            _ => Poll::Ready(()),
+        }
+    }
+}
+
+impl Drop for Delay {
+    fn drop(&mut self) {
+        if let (Some(key), Some(timer_thread)) = (&self.key, TimerThread::get()) {
+            timer_thread.unregister(key);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sleep() {
+        let start = Instant::now();
+        futures_executor::block_on(sleep(Duration::from_millis(50)));
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[test]
+    fn test_concurrent_sleeps() {
+        let start = Instant::now();
+        futures_executor::block_on(futures_util::future::join_all(
+            [300, 100, 200]
+                .into_iter()
+                .map(|millis| sleep(Duration::from_millis(millis))),
+        ));
+        assert!(start.elapsed() >= Duration::from_millis(300));
+    }
+
+    #[test]
+    fn test_dropped_sleeps_are_unregistered() {
+        let delay = sleep(Duration::from_secs(60));
+        let key = delay.key.unwrap();
+        let timer_thread = TimerThread::get().unwrap();
+        assert!(timer_thread
+            .timers
+            .lock()
+            .unwrap()
+            .pending
+            .contains_key(&key));
+
+        drop(delay);
+        assert!(!timer_thread
+            .timers
+            .lock()
+            .unwrap()
+            .pending
+            .contains_key(&key));
+    }
+
+    #[test]
+    fn test_timeout() {
+        let result = futures_executor::block_on(timeout(Duration::from_secs(5), async { 42 }));
+        assert_eq!(result, Ok(42));
+
+        let start = Instant::now();
+        let result = futures_executor::block_on(timeout(
+            Duration::from_millis(50),
+            sleep(Duration::from_secs(5)),
+        ));
+        assert_eq!(result, Err(Elapsed));
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -11,8 +11,10 @@ cargo_feature() {
 
 if rustup component add clippy; then
  crates=( "opentelemetry"
+                "opentelemetry-aws"
                 "opentelemetry-http"
                 "opentelemetry-jaeger-propagator"
+                "opentelemetry-ottrace-propagator"
                 "opentelemetry-appender-log"
                 "opentelemetry-appender-tracing"
                 "opentelemetry-otlp"
@@ -39,8 +41,12 @@ if rustup component add clippy; then
   cargo_feature opentelemetry-otlp "http-proto, reqwest-rustls"
   cargo_feature opentelemetry-otlp "metrics"
 
+  cargo_feature opentelemetry-aws "default"
+
   cargo_feature opentelemetry-jaeger-propagator "default"
 
+  cargo_feature opentelemetry-ottrace-propagator "default"
+
   cargo_feature opentelemetry-proto "default"
   cargo_feature opentelemetry-proto "full"
   cargo_feature opentelemetry-proto "gen-tonic,trace"
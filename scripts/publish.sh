@@ -5,8 +5,10 @@ packages=(
     "opentelemetry-http"
     "opentelemetry-semantic-conventions"
     "opentelemetry-jaeger-propagator"
+    "opentelemetry-ottrace-propagator"
     "opentelemetry-sdk"    
     "opentelemetry-proto"
+    "opentelemetry-aws"
     "opentelemetry-otlp"
     "opentelemetry-stdout"
     "opentelemetry-zipkin"
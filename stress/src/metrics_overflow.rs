@@ -20,9 +20,14 @@ use std::cell::RefCell;
 
 mod throughput;
 
+/// Cardinality limit of the stress test, lower than the SDK default so that
+/// measurements overflow early.
+const CARDINALITY_LIMIT: usize = 100;
+
 lazy_static! {
     static ref PROVIDER: SdkMeterProvider = SdkMeterProvider::builder()
         .with_reader(ManualReader::builder().build())
+        .with_cardinality_limit(CARDINALITY_LIMIT)
         .build();
     static ref COUNTER: Counter<u64> = PROVIDER.meter("test").u64_counter("hello").build();
 }